@@ -30,6 +30,8 @@
 //! Criterion benchmarks for rust_container_system
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_container_system::core::container_view::ContainerView;
+use rust_container_system::core::value_data::ValueData;
 use rust_container_system::prelude::*;
 use std::sync::Arc;
 
@@ -107,6 +109,19 @@ fn bench_container_add_values(c: &mut Criterion) {
                 black_box(container)
             });
         });
+
+        // Inline storage: no Arc allocation per value
+        group.bench_with_input(BenchmarkId::new("inline", size), size, |b, &size| {
+            b.iter(|| {
+                let mut container = ValueContainer::new_inline();
+                for i in 0..size {
+                    container
+                        .add_data(format!("key_{}", i), ValueData::Int(i))
+                        .unwrap();
+                }
+                black_box(container)
+            });
+        });
     }
 
     group.finish();
@@ -158,6 +173,28 @@ fn bench_container_get_value(c: &mut Criterion) {
                 });
             },
         );
+
+        let mut inline = ValueContainer::new_inline();
+        for i in 0..size {
+            inline
+                .add_data(format!("key_{}", i), ValueData::Int(i))
+                .unwrap();
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("inline_middle", size),
+            &inline,
+            |b, container| {
+                b.iter(|| {
+                    let value = container
+                        .with_data(black_box(&format!("key_{}", size / 2)), |data| {
+                            matches!(data, ValueData::Int(_))
+                        })
+                        .unwrap();
+                    black_box(value)
+                });
+            },
+        );
     }
 
     group.finish();
@@ -206,6 +243,107 @@ fn bench_json_serialization(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_binary_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary_serialization");
+
+    for size in [10, 50, 100].iter() {
+        let container = serialization_container(*size);
+
+        group.throughput(Throughput::Elements(*size as u64));
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &container,
+            |b, container| {
+                b.iter(|| {
+                    let bytes = container.to_binary().unwrap();
+                    black_box(bytes)
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+#[allow(deprecated)]
+fn bench_deserialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialization");
+
+    for size in [10, 50, 100].iter() {
+        let container = serialization_container(*size);
+        let json = container.to_json().unwrap();
+        let binary = container.to_binary().unwrap();
+
+        group.throughput(Throughput::Elements(*size as u64));
+
+        group.bench_with_input(BenchmarkId::new("json", size), &json, |b, json| {
+            b.iter(|| {
+                let restored = ValueContainer::from_json(black_box(json)).unwrap();
+                black_box(restored)
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("binary", size), &binary, |b, binary| {
+            b.iter(|| {
+                let restored = ValueContainer::from_binary(black_box(binary)).unwrap();
+                black_box(restored)
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_binary_view(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary_partial_read");
+
+    // 10,000 values; read two of them
+    let binary = serialization_container(5_000).to_binary().unwrap();
+
+    group.bench_function("deserialize", |b| {
+        b.iter(|| {
+            let container = ValueContainer::from_binary(black_box(&binary)).unwrap();
+            let first = container.get_value("int_10").unwrap().to_int().unwrap();
+            let second = container.get_value("str_4000").unwrap().to_string();
+            black_box((first, second))
+        });
+    });
+
+    group.bench_function("view", |b| {
+        b.iter(|| {
+            let view = ContainerView::new(black_box(&binary)).unwrap();
+            let first = view.get_int("int_10").unwrap();
+            let second = view.get_str("str_4000").unwrap();
+            black_box((first, second))
+        });
+    });
+
+    group.finish();
+}
+
+/// Container with `size` int and `size` string values, as used by the JSON benchmark
+fn serialization_container(size: i32) -> ValueContainer {
+    let mut container = ValueContainer::new();
+    container.set_source("client", "123");
+    container.set_target("server", "main");
+    container.set_message_type("test_message");
+
+    for i in 0..size {
+        container
+            .add_value(Arc::new(IntValue::new(format!("int_{}", i), i)))
+            .unwrap();
+        container
+            .add_value(Arc::new(StringValue::new(
+                format!("str_{}", i),
+                format!("value_{}", i),
+            )))
+            .unwrap();
+    }
+
+    container
+}
+
 #[allow(deprecated)]
 fn bench_xml_serialization(c: &mut Criterion) {
     let mut group = c.benchmark_group("xml_serialization");
@@ -324,6 +462,9 @@ criterion_group!(
     bench_container_add_values,
     bench_container_get_value,
     bench_json_serialization,
+    bench_binary_serialization,
+    bench_deserialization,
+    bench_binary_view,
     bench_xml_serialization,
     bench_container_clone,
     bench_value_conversions
@@ -0,0 +1,381 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Derive macro for `rust_container_system` messages.
+//!
+//! `#[derive(ContainerMessage)]` implements
+//! `rust_container_system::messaging::ContainerMessage` for a struct with named
+//! fields, so it can be turned into a `ValueContainer` with `to_container()` and read
+//! back with `from_container()`. Every field type must implement
+//! `rust_container_system::messaging::MessageField`.
+//!
+//! Use it through the `derive` feature of `rust_container_system` rather than
+//! depending on this crate directly.
+//!
+//! # Attributes
+//!
+//! On the struct:
+//!
+//! - `#[container(message_type = "...")]` sets the `message_type` header. Defaults to
+//!   the struct name.
+//!
+//! On fields:
+//!
+//! - `#[container(name = "...")]` sets the value name. Defaults to the field name.
+//! - `#[container(value_type = "...")]` picks the value type, e.g. `"long"` to send an
+//!   `i64` as a 32-bit `long_value` instead of the default `llong_value`. Accepts the
+//!   `ValueType` names in lower case, with or without the `_value` suffix.
+//!
+//! `Option` fields are written as `null_value` when `None` and read as `None` when
+//! the value is null or missing.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::messaging::ContainerMessage;
+//! use rust_container_system::ValueType;
+//!
+//! #[derive(Debug, PartialEq, ContainerMessage)]
+//! #[container(message_type = "user_profile")]
+//! struct UserProfile {
+//!     #[container(name = "uid", value_type = "long")]
+//!     user_id: i64,
+//!     display_name: String,
+//!     avatar: Option<Vec<u8>>,
+//! }
+//!
+//! let profile = UserProfile {
+//!     user_id: 42,
+//!     display_name: "Alice".to_string(),
+//!     avatar: None,
+//! };
+//!
+//! let container = profile.to_container().unwrap();
+//! assert_eq!(container.message_type(), "user_profile");
+//! assert_eq!(container.get_value("uid").unwrap().value_type(), ValueType::Long);
+//! assert!(container.get_value("avatar").unwrap().is_null());
+//!
+//! assert_eq!(UserProfile::from_container(&container).unwrap(), profile);
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Derive `rust_container_system::messaging::ContainerMessage` for a struct.
+///
+/// See the [crate documentation](crate) for the supported attributes.
+#[proc_macro_derive(ContainerMessage, attributes(container))]
+pub fn derive_container_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A field after its `#[container(...)]` attributes have been applied
+struct MessageField {
+    ident: syn::Ident,
+    name: String,
+    value_type: Option<syn::Ident>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let message_type = message_type(&input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "ContainerMessage can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "ContainerMessage can only be derived for structs",
+            ))
+        }
+    };
+    let fields = fields
+        .iter()
+        .map(message_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let to_values = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        let value_type = match &field.value_type {
+            Some(variant) => quote! {
+                ::std::option::Option::Some(::rust_container_system::ValueType::#variant)
+            },
+            None => quote!(::std::option::Option::None),
+        };
+        quote! {
+            ::rust_container_system::messaging::MessageField::to_value(
+                &self.#ident,
+                #name,
+                #value_type,
+            )?
+        }
+    });
+    let from_values = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        quote! {
+            #ident: ::rust_container_system::messaging::read_field(values, #name)?
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rust_container_system::messaging::ContainerMessage
+            for #ident #ty_generics #where_clause
+        {
+            const MESSAGE_TYPE: &'static str = #message_type;
+
+            fn to_values(
+                &self,
+            ) -> ::rust_container_system::Result<
+                ::std::vec::Vec<::std::sync::Arc<dyn ::rust_container_system::Value>>,
+            > {
+                ::std::result::Result::Ok(::std::vec![#(#to_values),*])
+            }
+
+            fn from_values(
+                values: &[::std::sync::Arc<dyn ::rust_container_system::Value>],
+            ) -> ::rust_container_system::Result<Self> {
+                ::std::result::Result::Ok(Self {
+                    #(#from_values),*
+                })
+            }
+        }
+    })
+}
+
+/// Read `#[container(message_type = "...")]` from the struct, defaulting to its name.
+fn message_type(input: &DeriveInput) -> syn::Result<String> {
+    let mut message_type = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("container")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("message_type") {
+                let value: LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("message_type must not be empty"));
+                }
+                message_type = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown container attribute, expected `message_type`"))
+            }
+        })?;
+    }
+    Ok(message_type.unwrap_or_else(|| input.ident.to_string()))
+}
+
+/// Apply `#[container(name = "...", value_type = "...")]` to a named field.
+fn message_field(field: &syn::Field) -> syn::Result<MessageField> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new_spanned(field, "expected a named field"))?;
+    let mut name = None;
+    let mut value_type = None;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("container")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("name must not be empty"));
+                }
+                name = Some(value.value());
+                Ok(())
+            } else if meta.path.is_ident("value_type") {
+                let value: LitStr = meta.value()?.parse()?;
+                let variant = value_type_variant(&value.value()).ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &value,
+                        format!("unknown value_type `{}`", value.value()),
+                    )
+                })?;
+                value_type = Some(syn::Ident::new(variant, value.span()));
+                Ok(())
+            } else {
+                Err(meta.error("unknown container attribute, expected `name` or `value_type`"))
+            }
+        })?;
+    }
+
+    let name = name.unwrap_or_else(|| {
+        let ident = ident.to_string();
+        ident.strip_prefix("r#").unwrap_or(&ident).to_string()
+    });
+    Ok(MessageField {
+        ident,
+        name,
+        value_type,
+    })
+}
+
+/// Map a `value_type` attribute to the matching `ValueType` variant.
+fn value_type_variant(name: &str) -> Option<&'static str> {
+    let variant = match name.strip_suffix("_value").unwrap_or(name) {
+        "null" => "Null",
+        "bool" => "Bool",
+        "short" => "Short",
+        "ushort" => "UShort",
+        "int" => "Int",
+        "uint" => "UInt",
+        "long" => "Long",
+        "ulong" => "ULong",
+        "llong" => "LLong",
+        "ullong" => "ULLong",
+        "float" => "Float",
+        "double" => "Double",
+        "string" => "String",
+        "bytes" => "Bytes",
+        "container" => "Container",
+        "array" => "Array",
+        _ => return None,
+    };
+    Some(variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expand_err(input: DeriveInput) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_value_type_names() {
+        assert_eq!(value_type_variant("llong"), Some("LLong"));
+        assert_eq!(value_type_variant("long_value"), Some("Long"));
+        assert_eq!(value_type_variant("ullong"), Some("ULLong"));
+        assert_eq!(value_type_variant("Long"), None);
+        assert_eq!(value_type_variant("i64"), None);
+    }
+
+    #[test]
+    fn test_attributes_are_applied() {
+        let input: DeriveInput = parse_quote! {
+            #[container(message_type = "login")]
+            struct Login {
+                #[container(name = "uid", value_type = "long")]
+                user_id: i64,
+                r#type: String,
+            }
+        };
+        assert_eq!(message_type(&input).unwrap(), "login");
+
+        let Data::Struct(data) = &input.data else {
+            unreachable!()
+        };
+        let fields = data
+            .fields
+            .iter()
+            .map(message_field)
+            .collect::<syn::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(fields[0].name, "uid");
+        assert_eq!(fields[0].value_type.as_ref().unwrap(), "Long");
+        assert_eq!(fields[1].name, "type");
+        assert!(fields[1].value_type.is_none());
+    }
+
+    #[test]
+    fn test_message_type_defaults_to_struct_name() {
+        let input: DeriveInput = parse_quote! {
+            struct Heartbeat {
+                sequence: u64,
+            }
+        };
+        assert_eq!(message_type(&input).unwrap(), "Heartbeat");
+        assert!(expand(input).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_shapes() {
+        let tuple: DeriveInput = parse_quote!(struct Pair(i32, i32););
+        assert!(expand_err(tuple).contains("named fields"));
+
+        let unit: DeriveInput = parse_quote!(struct Marker;);
+        assert!(expand_err(unit).contains("named fields"));
+
+        let enumeration: DeriveInput = parse_quote!(
+            enum Command {
+                Start,
+            }
+        );
+        assert!(expand_err(enumeration).contains("only be derived for structs"));
+    }
+
+    #[test]
+    fn test_rejects_bad_attributes() {
+        let unknown_type: DeriveInput = parse_quote! {
+            struct Message {
+                #[container(value_type = "int64")]
+                id: i64,
+            }
+        };
+        assert!(expand_err(unknown_type).contains("unknown value_type `int64`"));
+
+        let unknown_key: DeriveInput = parse_quote! {
+            struct Message {
+                #[container(rename = "id")]
+                id: i64,
+            }
+        };
+        assert!(expand_err(unknown_key).contains("unknown container attribute"));
+
+        let empty_name: DeriveInput = parse_quote! {
+            struct Message {
+                #[container(name = "")]
+                id: i64,
+            }
+        };
+        assert!(expand_err(empty_name).contains("must not be empty"));
+
+        let bad_struct_attr: DeriveInput = parse_quote! {
+            #[container(name = "x")]
+            struct Message {
+                id: i64,
+            }
+        };
+        assert!(expand_err(bad_struct_attr).contains("expected `message_type`"));
+    }
+}
@@ -0,0 +1,597 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Binary Container Format
+//!
+//! This module implements a compact binary encoding of a complete `ValueContainer`:
+//! the header block, the value list and any nested containers or arrays.
+//!
+//! # Layout
+//!
+//! All integers are little-endian.
+//!
+//! ```text
+//! [magic:4 = "CNTB"][format_version:1 = 1]
+//! [header_count:1] { [field_id:1][len:4][UTF-8 bytes] } * header_count
+//! [value_count:4]  { value } * value_count
+//! ```
+//!
+//! Header field IDs are the C++ wire protocol IDs (1 = target_id, 2 = target_sub_id,
+//! 3 = source_id, 4 = source_sub_id, 5 = message_type, 6 = version). Unknown IDs are
+//! skipped when decoding.
+//!
+//! Every value uses the C++ container_system value layout
+//! (`[type:1][name_len:4][name][value_size:4][value]`, see [`value_codec`](crate::core::value_codec)),
+//! with nested containers and arrays encoded recursively.
+//!
+//! # Streaming
+//!
+//! `write_binary` and `read_binary` encode to any `std::io::Write` and decode from any
+//! `std::io::Read` without holding the whole container in an intermediate buffer.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::prelude::*;
+//! use rust_container_system::core::binary_protocol;
+//! use std::sync::Arc;
+//!
+//! let mut container = ValueContainer::new();
+//! container.set_message_type("user_data");
+//! container.add_value(Arc::new(IntValue::new("count", 42))).unwrap();
+//!
+//! let bytes = binary_protocol::serialize_binary(&container).unwrap();
+//! let restored = binary_protocol::deserialize_binary(&bytes).unwrap();
+//!
+//! assert_eq!(restored.message_type(), "user_data");
+//! assert_eq!(restored.get_value("count").unwrap().to_int().unwrap(), 42);
+//! ```
+
+use crate::core::value_codec::{
+    encode_value_into, write_len, write_value, BinaryReader, StreamReader, ValueDecoder,
+};
+use crate::core::{Result, ValueContainer};
+use std::io::{Read, Write};
+
+/// Magic bytes at the start of every binary container
+pub const BINARY_MAGIC: [u8; 4] = *b"CNTB";
+
+/// Current binary container format version
+pub const BINARY_FORMAT_VERSION: u8 = 1;
+
+// Header field IDs (shared with the C++ wire protocol)
+const TARGET_ID: u8 = 1;
+const TARGET_SUB_ID: u8 = 2;
+const SOURCE_ID: u8 = 3;
+const SOURCE_SUB_ID: u8 = 4;
+const MESSAGE_TYPE: u8 = 5;
+const MESSAGE_VERSION: u8 = 6;
+
+/// Serialize a ValueContainer to the binary container format
+///
+/// # Errors
+///
+/// Returns `ContainerError::SerializationError` naming the value if a value reports a
+/// type it cannot be encoded as (e.g. a custom `Value` claiming to be a container), or if
+/// a name or payload exceeds 4 GiB.
+pub fn serialize_binary(container: &ValueContainer) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(256);
+    write_header(container, &mut out)?;
+
+    container.with_values(|values| -> Result<()> {
+        write_len(&mut out, values.len(), "value count")?;
+        for value in values {
+            encode_value_into(value, &mut out)?;
+        }
+        Ok(())
+    })?;
+
+    Ok(out)
+}
+
+/// Write a ValueContainer in the binary container format to `writer`
+///
+/// Produces the same bytes as [`serialize_binary`] without building them in memory:
+/// nested sizes are computed up front and string and byte payloads are copied straight
+/// from each value into the writer. Values are written in several small pieces, so
+/// wrap unbuffered writers such as files or sockets in a [`std::io::BufWriter`].
+///
+/// # Example
+///
+/// ```
+/// use rust_container_system::prelude::*;
+/// use rust_container_system::core::binary_protocol::{read_binary, write_binary};
+/// use std::sync::Arc;
+///
+/// let mut container = ValueContainer::new();
+/// container.add_value(Arc::new(BytesValue::new("blob", vec![1; 1 << 20]))).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_binary(&container, &mut out).unwrap();
+///
+/// let restored = read_binary(&mut out.as_slice()).unwrap();
+/// assert_eq!(restored.get_value("blob").unwrap().size(), 1 << 20);
+/// ```
+///
+/// # Errors
+///
+/// Same as [`serialize_binary`], plus `ContainerError::IoError` if the writer fails.
+/// After an error the writer may hold a partial container.
+pub fn write_binary<W: Write + ?Sized>(container: &ValueContainer, writer: &mut W) -> Result<()> {
+    write_header(container, writer)?;
+
+    // Snapshot the values so the container is not locked while writing
+    let values = container.values();
+    write_len(writer, values.len(), "value count")?;
+    for value in &values {
+        write_value(value, writer)?;
+    }
+    Ok(())
+}
+
+/// Write the magic, format version and header block
+fn write_header<W: Write + ?Sized>(container: &ValueContainer, writer: &mut W) -> Result<()> {
+    let mut out = Vec::with_capacity(128);
+    out.extend_from_slice(&BINARY_MAGIC);
+    out.push(BINARY_FORMAT_VERSION);
+
+    let header = [
+        (TARGET_ID, container.target_id()),
+        (TARGET_SUB_ID, container.target_sub_id()),
+        (SOURCE_ID, container.source_id()),
+        (SOURCE_SUB_ID, container.source_sub_id()),
+        (MESSAGE_TYPE, container.message_type()),
+        (MESSAGE_VERSION, container.version()),
+    ];
+    out.push(header.len() as u8);
+    for (id, text) in &header {
+        out.push(*id);
+        write_len(&mut out, text.len(), "header field")?;
+        out.extend_from_slice(text.as_bytes());
+    }
+
+    writer.write_all(&out)?;
+    Ok(())
+}
+
+/// Deserialize a ValueContainer from the binary container format
+///
+/// # Errors
+///
+/// Returns `ContainerError::WireFormat` with the byte offset of the problem if the
+/// magic or format version is wrong, the data is truncated, a length or count points
+/// past the end of the input, a type byte is unknown, a fixed-size payload has the
+/// wrong size, a string is not valid UTF-8, or nesting exceeds
+/// [`MAX_NESTING_DEPTH`](crate::core::wire_protocol::MAX_NESTING_DEPTH).
+pub fn deserialize_binary(data: &[u8]) -> Result<ValueContainer> {
+    deserialize_binary_with_decoder(data, &ValueDecoder::new())
+}
+
+/// Deserialize a ValueContainer, rebuilding values through `decoder`
+///
+/// Use this to reconstruct custom `Value` implementations registered with a
+/// [`ValueDecoder`].
+///
+/// # Errors
+///
+/// Same as [`deserialize_binary`], plus any error returned by a registered decoder.
+pub fn deserialize_binary_with_decoder(
+    data: &[u8],
+    decoder: &ValueDecoder,
+) -> Result<ValueContainer> {
+    let mut reader = BinaryReader::new(data);
+    let mut container = read_header(&mut reader)?.into_owned().into_container();
+
+    let count_offset = reader.pos;
+    let count = reader.read_len()?;
+    reader.check_count(count, count_offset)?;
+    for _ in 0..count {
+        let value = decoder.decode_from(&mut reader, 0)?;
+        container.add_value(value)?;
+    }
+
+    if reader.pos != data.len() {
+        return Err(BinaryReader::error(
+            reader.pos,
+            format!(
+                "{} trailing bytes after last value",
+                data.len() - reader.pos
+            ),
+        ));
+    }
+
+    Ok(container)
+}
+
+/// Parse the magic, format version and header fields, borrowing the field text
+pub(crate) fn read_header<'a>(reader: &mut BinaryReader<'a>) -> Result<Header<&'a str>> {
+    let magic = reader.read_bytes(BINARY_MAGIC.len())?;
+    if magic != BINARY_MAGIC {
+        return Err(BinaryReader::error(0, "not a binary container (bad magic)"));
+    }
+    let version_offset = reader.pos;
+    let format_version = reader.read_u8()?;
+    if format_version != BINARY_FORMAT_VERSION {
+        return Err(BinaryReader::error(
+            version_offset,
+            format!(
+                "unsupported binary format version {} (expected {})",
+                format_version, BINARY_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    let mut header = Header::default();
+    let header_count = reader.read_u8()?;
+    for _ in 0..header_count {
+        let id = reader.read_u8()?;
+        let len = reader.read_len()?;
+        header.set(id, reader.read_str(len)?);
+    }
+    Ok(header)
+}
+
+/// Read a ValueContainer in the binary container format from `reader`
+///
+/// Reads exactly one container and leaves the reader positioned after it, so
+/// containers written back to back can be read one at a time. Values are rebuilt as
+/// they arrive: nested containers and arrays child by child, and at most one scalar
+/// payload is buffered at a time.
+///
+/// # Errors
+///
+/// Returns `ContainerError::WireFormat` (with offsets counted from where reading
+/// started) for the same problems as [`deserialize_binary`], including input that ends
+/// early, and `ContainerError::IoError` if the reader fails.
+pub fn read_binary<R: Read + ?Sized>(reader: &mut R) -> Result<ValueContainer> {
+    read_binary_with_decoder(reader, &ValueDecoder::new())
+}
+
+/// Read a ValueContainer from `reader`, rebuilding values through `decoder`
+///
+/// Values whose type has a registered decoder are buffered whole and passed to it.
+///
+/// # Errors
+///
+/// Same as [`read_binary`], plus any error returned by a registered decoder.
+pub fn read_binary_with_decoder<R: Read + ?Sized>(
+    reader: &mut R,
+    decoder: &ValueDecoder,
+) -> Result<ValueContainer> {
+    let mut reader = StreamReader::new(reader);
+
+    let magic = reader.read_bytes(BINARY_MAGIC.len())?;
+    if magic != BINARY_MAGIC {
+        return Err(BinaryReader::error(0, "not a binary container (bad magic)"));
+    }
+    let version_offset = reader.pos;
+    let format_version = reader.read_u8()?;
+    if format_version != BINARY_FORMAT_VERSION {
+        return Err(BinaryReader::error(
+            version_offset,
+            format!(
+                "unsupported binary format version {} (expected {})",
+                format_version, BINARY_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    let mut header = Header::default();
+    let header_count = reader.read_u8()?;
+    for _ in 0..header_count {
+        let id = reader.read_u8()?;
+        let len = reader.read_len()?;
+        header.set(id, reader.read_string(len)?);
+    }
+    let mut container = header.into_container();
+
+    // The count is not checked up front: there is no known input length to check it against
+    let count = reader.read_len()?;
+    for _ in 0..count {
+        let value = decoder.read_from(&mut reader, 0)?;
+        container.add_value(value)?;
+    }
+
+    Ok(container)
+}
+
+/// Header fields collected while decoding (owned, or borrowed from the input)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Header<T = String> {
+    pub(crate) source: (T, T),
+    pub(crate) target: (T, T),
+    pub(crate) message_type: Option<T>,
+    pub(crate) version: Option<T>,
+}
+
+impl<T> Header<T> {
+    pub(crate) fn set(&mut self, id: u8, text: T) {
+        match id {
+            TARGET_ID => self.target.0 = text,
+            TARGET_SUB_ID => self.target.1 = text,
+            SOURCE_ID => self.source.0 = text,
+            SOURCE_SUB_ID => self.source.1 = text,
+            MESSAGE_TYPE => self.message_type = Some(text),
+            MESSAGE_VERSION => self.version = Some(text),
+            _ => {} // Skip fields added by newer writers
+        }
+    }
+}
+
+impl Header<&str> {
+    pub(crate) fn into_owned(self) -> Header {
+        Header {
+            source: (self.source.0.to_string(), self.source.1.to_string()),
+            target: (self.target.0.to_string(), self.target.1.to_string()),
+            message_type: self.message_type.map(str::to_string),
+            version: self.version.map(str::to_string),
+        }
+    }
+}
+
+impl Header {
+    pub(crate) fn into_container(self) -> ValueContainer {
+        let mut container = ValueContainer::new();
+        container.set_source(self.source.0, self.source.1);
+        container.set_target(self.target.0, self.target.1);
+        if let Some(message_type) = self.message_type {
+            container.set_message_type(message_type);
+        }
+        if let Some(version) = self.version {
+            container.set_version(version);
+        }
+        container
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::Trickle;
+    use crate::core::{ContainerError, Value};
+    use crate::values::*;
+    use std::sync::Arc;
+
+    fn sample_container() -> ValueContainer {
+        let mut container = ValueContainer::builder()
+            .source("client", "session")
+            .target("server", "handler")
+            .message_type("binary_test")
+            .version("2.0.1")
+            .build();
+
+        let values: Vec<Arc<dyn Value>> = vec![
+            Arc::new(NullValue::new("nothing")),
+            Arc::new(BoolValue::new("flag", true)),
+            Arc::new(ShortValue::new("short", -12)),
+            Arc::new(UShortValue::new("ushort", 65000)),
+            Arc::new(IntValue::new("int", -42)),
+            Arc::new(UIntValue::new("uint", 4_000_000_000)),
+            Arc::new(LongValue::new("long", -2_000_000_000).unwrap()),
+            Arc::new(ULongValue::new("ulong", 4_000_000_000).unwrap()),
+            Arc::new(LLongValue::new("llong", i64::MIN)),
+            Arc::new(ULLongValue::new("ullong", u64::MAX)),
+            Arc::new(FloatValue::new("float", 1.5)),
+            Arc::new(DoubleValue::new("double", std::f64::consts::E)),
+            Arc::new(StringValue::new("text", "안녕 ];,")),
+            Arc::new(BytesValue::new("raw", vec![0, 1, 0xFF])),
+        ];
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+
+        let array = ArrayValue::new(
+            "list",
+            vec![
+                Arc::new(IntValue::new("", 1)) as Arc<dyn Value>,
+                Arc::new(StringValue::new("", "two")),
+            ],
+        );
+        let nested = ContainerValue::new(
+            "nested",
+            vec![
+                Arc::new(IntValue::new("id", 7)) as Arc<dyn Value>,
+                Arc::new(array),
+            ],
+        );
+        container.add_value(Arc::new(nested)).unwrap();
+        container
+    }
+
+    #[test]
+    fn test_roundtrip_all_types() {
+        let original = sample_container();
+        let bytes = serialize_binary(&original).unwrap();
+        let restored = deserialize_binary(&bytes).unwrap();
+
+        assert_eq!(restored.source_id(), "client");
+        assert_eq!(restored.source_sub_id(), "session");
+        assert_eq!(restored.target_id(), "server");
+        assert_eq!(restored.target_sub_id(), "handler");
+        assert_eq!(restored.message_type(), "binary_test");
+        assert_eq!(restored.version(), "2.0.1");
+        assert_eq!(restored.value_count(), original.value_count());
+
+        for (a, b) in original.values().iter().zip(restored.values().iter()) {
+            assert_eq!(a.name(), b.name());
+            assert_eq!(a.value_type(), b.value_type());
+            assert_eq!(a.to_string(), b.to_string(), "{}", a.name());
+        }
+
+        // Encoding is deterministic
+        assert_eq!(serialize_binary(&restored).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_value_layout_matches_cpp() {
+        let mut container = ValueContainer::new();
+        container
+            .add_value(Arc::new(IntValue::new("test", 42)))
+            .unwrap();
+        let bytes = serialize_binary(&container).unwrap();
+
+        // The last value is encoded exactly like IntValue::to_bytes()
+        let expected = IntValue::new("test", 42).to_bytes();
+        assert!(bytes.ends_with(&expected));
+        assert_eq!(&bytes[..5], b"CNTB\x01");
+    }
+
+    #[test]
+    fn test_nested_value_layout() {
+        let array = ArrayValue::new(
+            "arr",
+            vec![Arc::new(BoolValue::new("", true)) as Arc<dyn Value>],
+        );
+        let mut container = ValueContainer::new();
+        container.add_value(Arc::new(array.clone())).unwrap();
+        let bytes = serialize_binary(&container).unwrap();
+
+        // Arrays share ArrayValue::to_binary_bytes()'s layout
+        assert!(bytes.ends_with(&array.to_binary_bytes()));
+    }
+
+    #[test]
+    fn test_empty_container() {
+        let bytes = serialize_binary(&ValueContainer::new()).unwrap();
+        let restored = deserialize_binary(&bytes).unwrap();
+        assert_eq!(restored.value_count(), 0);
+        assert_eq!(restored.message_type(), "data_container");
+        assert_eq!(restored.version(), "1.0.0.0");
+    }
+
+    #[test]
+    fn test_rejects_bad_magic_and_version() {
+        assert!(matches!(
+            deserialize_binary(b"JSON\x01"),
+            Err(ContainerError::WireFormat { offset: 0, .. })
+        ));
+
+        let mut bytes = serialize_binary(&ValueContainer::new()).unwrap();
+        bytes[4] = 99;
+        assert!(matches!(
+            deserialize_binary(&bytes),
+            Err(ContainerError::WireFormat { offset: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncated_input_fails_at_every_length() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        for len in 0..bytes.len() {
+            assert!(
+                deserialize_binary(&bytes[..len]).is_err(),
+                "truncated at {} should fail",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_oversized_counts_and_payloads() {
+        let mut bytes = serialize_binary(&ValueContainer::new()).unwrap();
+        let count_offset = bytes.len() - 4;
+        bytes[count_offset..].copy_from_slice(&u32::MAX.to_le_bytes());
+        match deserialize_binary(&bytes) {
+            Err(ContainerError::WireFormat { offset, .. }) => assert_eq!(offset, count_offset),
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+
+        // Int with the wrong value_size
+        let mut container = ValueContainer::new();
+        container
+            .add_value(Arc::new(IntValue::new("n", 1)))
+            .unwrap();
+        let mut bytes = serialize_binary(&container).unwrap();
+        let size_offset = bytes.len() - 8;
+        bytes[size_offset] = 2;
+        assert!(deserialize_binary(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_rejects_trailing_bytes() {
+        let mut bytes = serialize_binary(&ValueContainer::new()).unwrap();
+        bytes.push(0);
+        assert!(deserialize_binary(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_write_binary_matches_serialize() {
+        let container = sample_container();
+        let mut out = Vec::new();
+        write_binary(&container, &mut out).unwrap();
+        assert_eq!(out, serialize_binary(&container).unwrap());
+    }
+
+    #[test]
+    fn test_read_binary_back_to_back() {
+        let first = sample_container();
+        let mut second = ValueContainer::new();
+        second.set_message_type("second");
+
+        let mut stream = Vec::new();
+        write_binary(&first, &mut stream).unwrap();
+        write_binary(&second, &mut stream).unwrap();
+
+        let mut reader = Trickle(&stream);
+        let restored = read_binary(&mut reader).unwrap();
+        assert_eq!(
+            serialize_binary(&restored).unwrap(),
+            serialize_binary(&first).unwrap()
+        );
+        let restored = read_binary(&mut reader).unwrap();
+        assert_eq!(restored.message_type(), "second");
+        assert!(reader.0.is_empty());
+    }
+
+    #[test]
+    fn test_read_binary_errors() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        for len in 0..bytes.len() {
+            assert!(
+                read_binary(&mut Trickle(&bytes[..len])).is_err(),
+                "truncated at {} should fail",
+                len
+            );
+        }
+
+        assert!(matches!(
+            read_binary(&mut &b"JSON\x01"[..]),
+            Err(ContainerError::WireFormat { offset: 0, .. })
+        ));
+
+        // A huge value count fails at the end of the input
+        let mut bytes = serialize_binary(&ValueContainer::new()).unwrap();
+        let count_offset = bytes.len() - 4;
+        bytes[count_offset..].copy_from_slice(&u32::MAX.to_le_bytes());
+        match read_binary(&mut bytes.as_slice()) {
+            Err(ContainerError::WireFormat { offset, .. }) => assert_eq!(offset, bytes.len()),
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
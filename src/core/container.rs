@@ -29,12 +29,25 @@
 
 //! Value container implementation with header information.
 
+use super::diff::ContainerDiff;
 use super::error::Result;
+use super::merge::{MergePolicy, MergeReport};
+use super::patch::Patch;
+use super::query::{Query, QueryMatch};
+use super::schema::{Schema, ValidationReport};
 use super::value::Value;
+use super::value_data::{NamedValue, ValueData};
+use super::value_path::{PathLevel, ValuePath};
+use super::value_types::TypeSupport;
+use crate::values::time_values::{duration_of, timestamp_of};
+use crate::values::uuid_value::uuid_of;
+use crate::values::{ArrayValue, ContainerValue, StringValue, Timestamp, Uuid};
 use indexmap::IndexMap;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Default maximum number of values per container (prevents memory exhaustion)
 pub const DEFAULT_MAX_VALUES: usize = 10_000;
@@ -65,8 +78,8 @@ pub struct ValueContainer {
 }
 
 /// Internal container data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ContainerInner {
+#[derive(Debug, Clone)]
+pub(crate) struct ContainerInner {
     source_id: String,
     source_sub_id: String,
     target_id: String,
@@ -74,19 +87,288 @@ struct ContainerInner {
     message_type: String,
     version: String,
 
-    #[serde(skip)]
-    values: Vec<Arc<dyn Value>>,
-
-    #[serde(skip)]
-    // Improved: Direct Arc references instead of indices for O(1) removal
-    // Using IndexMap for insertion-order preservation and efficient removal
-    value_map: IndexMap<String, Vec<Arc<dyn Value>>>,
+    storage: Storage,
 
-    #[serde(skip)]
     /// Maximum number of values allowed in this container
     max_values: usize,
 }
 
+impl ContainerInner {
+    /// Header field by its serialized name (`source_id`, ..., `version`)
+    pub(crate) fn header_mut(&mut self, field: &str) -> Option<&mut String> {
+        match field {
+            "source_id" => Some(&mut self.source_id),
+            "source_sub_id" => Some(&mut self.source_sub_id),
+            "target_id" => Some(&mut self.target_id),
+            "target_sub_id" => Some(&mut self.target_sub_id),
+            "message_type" => Some(&mut self.message_type),
+            "version" => Some(&mut self.version),
+            _ => None,
+        }
+    }
+}
+
+impl PathLevel for ContainerInner {
+    fn count(&self, name: &str) -> usize {
+        self.storage.count(name)
+    }
+
+    fn nth(&self, name: &str, nth: usize) -> Option<Arc<dyn Value>> {
+        self.storage.nth(name, nth)
+    }
+
+    fn replace_nth(&mut self, name: &str, nth: usize, value: Arc<dyn Value>) -> Result<()> {
+        self.storage.replace_nth(name, nth, value)
+    }
+
+    fn push(&mut self, value: Arc<dyn Value>) -> Result<()> {
+        ValueContainer::check_limit(self)?;
+        self.storage.push_value(value)
+    }
+
+    fn remove_nth(&mut self, name: &str, nth: usize) -> Option<Arc<dyn Value>> {
+        self.storage.remove_nth(name, nth)
+    }
+}
+
+/// How a container keeps its values
+#[derive(Debug, Clone)]
+enum Storage {
+    /// One `Arc<dyn Value>` per value (the default)
+    Shared {
+        values: Vec<Arc<dyn Value>>,
+        // Improved: Direct Arc references instead of indices for O(1) removal
+        // Using IndexMap for insertion-order preservation and efficient removal
+        value_map: IndexMap<String, Vec<Arc<dyn Value>>>,
+    },
+    /// `NamedValue`s stored in place (see `ValueContainer::new_inline`)
+    Inline {
+        values: Vec<NamedValue>,
+        /// Indices into `values` per name, in insertion order
+        positions: IndexMap<String, Vec<usize>>,
+    },
+}
+
+impl Storage {
+    fn shared() -> Self {
+        Storage::Shared {
+            values: Vec::new(),
+            value_map: IndexMap::new(),
+        }
+    }
+
+    fn inline() -> Self {
+        Storage::Inline {
+            values: Vec::new(),
+            positions: IndexMap::new(),
+        }
+    }
+
+    /// An empty storage of the same kind
+    fn empty_like(&self) -> Self {
+        match self {
+            Storage::Shared { .. } => Self::shared(),
+            Storage::Inline { .. } => Self::inline(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Storage::Shared { values, .. } => values.len(),
+            Storage::Inline { values, .. } => values.len(),
+        }
+    }
+
+    fn push_value(&mut self, value: Arc<dyn Value>) -> Result<()> {
+        match self {
+            Storage::Shared { values, value_map } => {
+                let name = value.name().to_string();
+
+                // Store in both Vec and HashMap for dual access patterns
+                values.push(Arc::clone(&value));
+                value_map.entry(name).or_default().push(value);
+            }
+            Storage::Inline { .. } => self.push_data(NamedValue::from_value(value.as_ref())?),
+        }
+        Ok(())
+    }
+
+    fn push_data(&mut self, value: NamedValue) {
+        match self {
+            Storage::Shared { .. } => {
+                // Cannot fail: the storage is shared
+                let _ = self.push_value(value.into_value());
+            }
+            Storage::Inline { values, positions } => {
+                match positions.get_mut(value.name.as_str()) {
+                    Some(indices) => indices.push(values.len()),
+                    None => {
+                        positions.insert(value.name.clone(), vec![values.len()]);
+                    }
+                }
+                values.push(value);
+            }
+        }
+    }
+
+    /// Values called `name`; inline storage builds them on demand
+    fn named(&self, name: &str) -> Option<Cow<'_, [Arc<dyn Value>]>> {
+        match self {
+            Storage::Shared { value_map, .. } => value_map.get(name).map(|v| Cow::Borrowed(&v[..])),
+            Storage::Inline { values, positions } => positions
+                .get(name)
+                .map(|indices| Cow::Owned(indices.iter().map(|&i| values[i].to_value()).collect())),
+        }
+    }
+
+    /// All values in insertion order; inline storage builds them on demand
+    fn all(&self) -> Cow<'_, [Arc<dyn Value>]> {
+        match self {
+            Storage::Shared { values, .. } => Cow::Borrowed(values),
+            Storage::Inline { values, .. } => {
+                Cow::Owned(values.iter().map(NamedValue::to_value).collect())
+            }
+        }
+    }
+
+    fn remove(&mut self, name: &str) -> bool {
+        match self {
+            Storage::Shared { values, value_map } => {
+                // Remove from HashMap - O(1)
+                if let Some(removed_values) = value_map.shift_remove(name) {
+                    // Build HashSet of pointers to remove - O(m) where m is number of values with this name
+                    use std::collections::HashSet;
+                    let removed_ptrs: HashSet<*const dyn Value> =
+                        removed_values.iter().map(Arc::as_ptr).collect();
+
+                    // Remove from Vec by filtering with HashSet lookup - O(n) single pass
+                    values.retain(|value| !removed_ptrs.contains(&Arc::as_ptr(value)));
+                    true
+                } else {
+                    false
+                }
+            }
+            Storage::Inline { values, positions } => {
+                if positions.shift_remove(name).is_none() {
+                    return false;
+                }
+                values.retain(|value| value.name != name);
+                Self::reindex(values, positions);
+                true
+            }
+        }
+    }
+
+    /// Indices after removed values have moved; rebuild them in one pass
+    fn reindex(values: &[NamedValue], positions: &mut IndexMap<String, Vec<usize>>) {
+        for indices in positions.values_mut() {
+            indices.clear();
+        }
+        for (index, value) in values.iter().enumerate() {
+            if let Some(indices) = positions.get_mut(value.name.as_str()) {
+                indices.push(index);
+            }
+        }
+    }
+
+    /// Number of values called `name`
+    fn count(&self, name: &str) -> usize {
+        match self {
+            Storage::Shared { value_map, .. } => value_map.get(name).map_or(0, Vec::len),
+            Storage::Inline { positions, .. } => positions.get(name).map_or(0, Vec::len),
+        }
+    }
+
+    /// The nth value called `name`
+    fn nth(&self, name: &str, nth: usize) -> Option<Arc<dyn Value>> {
+        match self {
+            Storage::Shared { value_map, .. } => value_map.get(name)?.get(nth).cloned(),
+            Storage::Inline { values, positions } => {
+                let index = *positions.get(name)?.get(nth)?;
+                Some(values[index].to_value())
+            }
+        }
+    }
+
+    /// Position in insertion order of the nth value called `name`
+    fn position(&self, name: &str, nth: usize) -> Option<usize> {
+        match self {
+            Storage::Shared { values, .. } => values
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| value.name() == name)
+                .nth(nth)
+                .map(|(position, _)| position),
+            Storage::Inline { positions, .. } => positions.get(name)?.get(nth).copied(),
+        }
+    }
+
+    /// Replace the nth value called `name` with `value` (which has the same name)
+    fn replace_nth(&mut self, name: &str, nth: usize, value: Arc<dyn Value>) -> Result<()> {
+        let position = self
+            .position(name, nth)
+            .ok_or_else(|| crate::core::ContainerError::ValueNotFound(name.to_string()))?;
+        match self {
+            Storage::Shared { values, value_map } => {
+                if let Some(slot) = value_map.get_mut(name).and_then(|v| v.get_mut(nth)) {
+                    *slot = Arc::clone(&value);
+                }
+                values[position] = value;
+            }
+            Storage::Inline { values, .. } => {
+                values[position] = NamedValue::from_value(value.as_ref())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove and return the nth value called `name`
+    fn remove_nth(&mut self, name: &str, nth: usize) -> Option<Arc<dyn Value>> {
+        let position = self.position(name, nth)?;
+        match self {
+            Storage::Shared { values, value_map } => {
+                if let Some(named) = value_map.get_mut(name) {
+                    named.remove(nth);
+                    if named.is_empty() {
+                        value_map.shift_remove(name);
+                    }
+                }
+                Some(values.remove(position))
+            }
+            Storage::Inline { values, positions } => {
+                let removed = values.remove(position);
+                if positions
+                    .get(name)
+                    .is_some_and(|indices| indices.len() == 1)
+                {
+                    positions.shift_remove(name);
+                }
+                Self::reindex(values, positions);
+                Some(removed.into_value())
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = self.empty_like();
+    }
+
+    /// Deep copy whose values share nothing with this storage
+    fn deep_copy(&self) -> Self {
+        match self {
+            Storage::Shared { values, .. } => {
+                let mut copy = Self::shared();
+                for value in values {
+                    // Cannot fail: the storage is shared
+                    let _ = copy.push_value(value.clone_value());
+                }
+                copy
+            }
+            Storage::Inline { .. } => self.clone(),
+        }
+    }
+}
+
 impl ValueContainer {
     /// Create a new empty container
     ///
@@ -101,13 +383,46 @@ impl ValueContainer {
                 target_sub_id: String::new(),
                 message_type: "data_container".to_string(),
                 version: "1.0.0.0".to_string(),
-                values: Vec::new(),
-                value_map: IndexMap::new(),
+                storage: Storage::shared(),
                 max_values: DEFAULT_MAX_VALUES,
             })),
         }
     }
 
+    /// Create an empty container that stores values inline as [`NamedValue`]s
+    ///
+    /// Adding values with [`add_data`](Self::add_data) and reading them with
+    /// [`with_data`](Self::with_data) allocates no `Arc` per value. Methods that
+    /// return `Arc<dyn Value>` (`get_value`, `values`, `with_values`, the serializers,
+    /// ...) still work but build those values on every call, so prefer the data
+    /// methods on inline containers.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::core::value_data::ValueData;
+    /// use rust_container_system::prelude::*;
+    ///
+    /// let mut container = ValueContainer::new_inline();
+    /// container.add_data("count", ValueData::Int(42)).unwrap();
+    ///
+    /// let count = container.with_data("count", |data| match data {
+    ///     ValueData::Int(n) => *n,
+    ///     _ => 0,
+    /// });
+    /// assert_eq!(count.unwrap(), Some(42));
+    /// assert_eq!(container.get_value("count").unwrap().to_int().unwrap(), 42);
+    /// ```
+    pub fn new_inline() -> Self {
+        let container = Self::new();
+        container.inner.write().storage = Storage::inline();
+        container
+    }
+
+    /// Whether values are stored inline (see [`new_inline`](Self::new_inline))
+    pub fn is_inline(&self) -> bool {
+        matches!(self.inner.read().storage, Storage::Inline { .. })
+    }
+
     /// Create a container with a custom maximum value count
     ///
     /// # Arguments
@@ -156,6 +471,24 @@ impl ValueContainer {
         inner.message_type = message_type.into();
     }
 
+    /// Set message version
+    ///
+    /// The version string is carried verbatim in the header (wire protocol field 6)
+    /// and restored by every deserializer, so it can be used to version message schemas.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::prelude::*;
+    ///
+    /// let mut container = ValueContainer::new();
+    /// container.set_version("2.1.0");
+    /// assert_eq!(container.version(), "2.1.0");
+    /// ```
+    pub fn set_version(&mut self, version: impl Into<String>) {
+        let mut inner = self.inner.write();
+        inner.version = version.into();
+    }
+
     /// Swap source and target (useful for creating response messages)
     pub fn swap_header(&mut self) {
         let mut inner = self.inner.write();
@@ -256,28 +589,42 @@ impl ValueContainer {
     /// Add a value to the container
     ///
     /// Values are stored with direct Arc references for efficient access and removal.
+    /// Inline containers copy the value into a [`NamedValue`] instead.
     ///
     /// # Errors
     ///
-    /// Returns an error if the container has reached its maximum value count.
+    /// Returns an error if the container has reached its maximum value count, or if
+    /// the container is inline and the value cannot be represented as `ValueData`.
     pub fn add_value(&mut self, value: Arc<dyn Value>) -> Result<()> {
         let mut inner = self.inner.write();
+        Self::check_limit(&inner)?;
+        inner.storage.push_value(value)
+    }
 
-        // Check value limit to prevent memory exhaustion
-        if inner.values.len() >= inner.max_values {
+    /// Add a value given as [`ValueData`]
+    ///
+    /// Inline containers store it as is; other containers build the matching
+    /// built-in value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container has reached its maximum value count.
+    pub fn add_data(&mut self, name: impl Into<String>, data: ValueData) -> Result<()> {
+        let mut inner = self.inner.write();
+        Self::check_limit(&inner)?;
+        inner.storage.push_data(NamedValue::new(name, data));
+        Ok(())
+    }
+
+    /// Check value limit to prevent memory exhaustion
+    fn check_limit(inner: &ContainerInner) -> Result<()> {
+        if inner.storage.len() >= inner.max_values {
             return Err(crate::core::ContainerError::InvalidDataFormat(format!(
                 "Container value limit reached ({}/{})",
-                inner.values.len(),
+                inner.storage.len(),
                 inner.max_values
             )));
         }
-
-        let name = value.name().to_string();
-
-        // Store in both Vec and HashMap for dual access patterns
-        inner.values.push(Arc::clone(&value));
-        inner.value_map.entry(name).or_default().push(value);
-
         Ok(())
     }
 
@@ -290,17 +637,300 @@ impl ValueContainer {
     #[inline]
     pub fn get_value(&self, name: &str) -> Option<Arc<dyn Value>> {
         let inner = self.inner.read();
-        inner
-            .value_map
-            .get(name)
-            .and_then(|values| values.first())
-            .cloned()
+        match &inner.storage {
+            Storage::Shared { value_map, .. } => value_map
+                .get(name)
+                .and_then(|values| values.first())
+                .cloned(),
+            Storage::Inline { values, positions } => positions
+                .get(name)
+                .and_then(|indices| indices.first())
+                .map(|&index| values[index].to_value()),
+        }
+    }
+
+    /// Run `f` on the data of the first value with the specified name
+    ///
+    /// Inline containers pass a reference to the stored data without allocating;
+    /// other containers convert the value first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTypeConversion` if a stored `Arc<dyn Value>` cannot be
+    /// represented as `ValueData` (see [`ValueData::from_value`]).
+    pub fn with_data<F, R>(&self, name: &str, f: F) -> Result<Option<R>>
+    where
+        F: FnOnce(&ValueData) -> R,
+    {
+        let inner = self.inner.read();
+        match &inner.storage {
+            Storage::Shared { value_map, .. } => {
+                match value_map.get(name).and_then(|values| values.first()) {
+                    Some(value) => Ok(Some(f(&ValueData::from_value(value.as_ref())?))),
+                    None => Ok(None),
+                }
+            }
+            Storage::Inline { values, positions } => Ok(positions
+                .get(name)
+                .and_then(|indices| indices.first())
+                .map(|&index| f(&values[index].data))),
+        }
+    }
+
+    /// Get a copy of the data of the first value with the specified name
+    ///
+    /// See [`with_data`](Self::with_data).
+    pub fn get_data(&self, name: &str) -> Result<Option<ValueData>> {
+        self.with_data(name, ValueData::clone)
+    }
+
+    /// Get the first value with the specified name as a timestamp
+    ///
+    /// Reads a `timestamp_value`, or the RFC 3339 `string_value` that a peer
+    /// without timestamp support sends instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTypeConversion` if the value has another type and
+    /// `ParseError` if a string is not a timestamp.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::prelude::*;
+    /// use rust_container_system::values::Timestamp;
+    /// # use std::sync::Arc;
+    ///
+    /// let mut container = ValueContainer::new();
+    /// let sent = Timestamp::from_unix_millis(1_700_000_000_123).unwrap();
+    /// container.add_value(Arc::new(TimestampValue::new("sent_at", sent))).unwrap();
+    ///
+    /// assert_eq!(container.get_timestamp("sent_at").unwrap(), Some(sent));
+    /// assert_eq!(container.get_timestamp("missing").unwrap(), None);
+    /// ```
+    pub fn get_timestamp(&self, name: &str) -> Result<Option<Timestamp>> {
+        self.get_value(name)
+            .map(|value| timestamp_of(value.as_ref()))
+            .transpose()
+    }
+
+    /// Get the first value with the specified name as a duration
+    ///
+    /// Reads a `duration_value`, or the ISO 8601 `string_value` that a peer
+    /// without duration support sends instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTypeConversion` if the value has another type and
+    /// `ParseError` if a string is not a duration.
+    pub fn get_duration(&self, name: &str) -> Result<Option<Duration>> {
+        self.get_value(name)
+            .map(|value| duration_of(value.as_ref()))
+            .transpose()
+    }
+
+    /// Get the first value with the specified name as a UUID
+    ///
+    /// Reads a `uuid_value`, or the `string_value` that a peer without UUID
+    /// support sends instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTypeConversion` if the value has another type and
+    /// `ParseError` if a string is not a UUID.
+    pub fn get_uuid(&self, name: &str) -> Result<Option<Uuid>> {
+        self.get_value(name)
+            .map(|value| uuid_of(value.as_ref()))
+            .transpose()
     }
 
     /// Get all values with the specified name
     pub fn get_value_array(&self, name: &str) -> Vec<Arc<dyn Value>> {
         let inner = self.inner.read();
-        inner.value_map.get(name).cloned().unwrap_or_default()
+        inner
+            .storage
+            .named(name)
+            .map(Cow::into_owned)
+            .unwrap_or_default()
+    }
+
+    /// Get a nested value by path, e.g. `order.items[3].price`
+    ///
+    /// See [`value_path`](crate::core::value_path) for the path syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` naming the segment that is malformed or does not match.
+    ///
+    /// # Example
+    /// ```
+    /// # use rust_container_system::prelude::*;
+    /// # use std::sync::Arc;
+    /// let mut container = ValueContainer::new();
+    /// container.add_value(Arc::new(IntValue::new("tag", 1))).unwrap();
+    /// container.add_value(Arc::new(IntValue::new("tag", 2))).unwrap();
+    ///
+    /// assert_eq!(container.get_path("tag{1}").unwrap().to_int().unwrap(), 2);
+    /// assert!(container.get_path("tag{2}").is_err());
+    /// ```
+    pub fn get_path(&self, path: &str) -> Result<Arc<dyn Value>> {
+        ValuePath::parse(path)?.get(self)
+    }
+
+    /// Store a value at a path, returning the value it replaced
+    ///
+    /// Missing containers along the path are created; nested values are copied
+    /// rather than modified in place. See [`ValuePath::set`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` naming the segment that failed, or an error if the
+    /// container has reached its maximum value count. The container is left
+    /// unchanged on error.
+    pub fn set_path(
+        &mut self,
+        path: &str,
+        value: Arc<dyn Value>,
+    ) -> Result<Option<Arc<dyn Value>>> {
+        ValuePath::parse(path)?.set(self, value)
+    }
+
+    /// Remove the value at a path and return it
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` naming the segment that is malformed or does not match.
+    pub fn remove_path(&mut self, path: &str) -> Result<Arc<dyn Value>> {
+        ValuePath::parse(path)?.remove(self)
+    }
+
+    /// Select every value matching a JSONPath-style query
+    ///
+    /// Compiles `query` on every call; compile a [`Query`] once to reuse it. See
+    /// [`query`](crate::core::query) for the syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the query is malformed.
+    ///
+    /// # Example
+    /// ```
+    /// # use rust_container_system::prelude::*;
+    /// # use std::sync::Arc;
+    /// let mut container = ValueContainer::new();
+    /// container.add_value(Arc::new(IntValue::new("a", 1))).unwrap();
+    /// container.add_value(Arc::new(IntValue::new("b", 7))).unwrap();
+    ///
+    /// let matches = container.query("$[?(@ > 5)]").unwrap();
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].path, "b");
+    /// ```
+    pub fn query(&self, query: &str) -> Result<Vec<QueryMatch>> {
+        Ok(Query::compile(query)?.select(self))
+    }
+
+    /// List what changed from this container to `other`
+    ///
+    /// See [`diff`](crate::core::diff) for how values are paired.
+    ///
+    /// # Example
+    /// ```
+    /// # use rust_container_system::prelude::*;
+    /// # use std::sync::Arc;
+    /// let mut old = ValueContainer::new();
+    /// old.add_value(Arc::new(IntValue::new("count", 1))).unwrap();
+    /// let mut new = old.copy(true);
+    /// new.add_value(Arc::new(IntValue::new("extra", 2))).unwrap();
+    ///
+    /// let changes = old.diff(&new);
+    /// assert_eq!(changes.report(), "+ extra: int_value 2\n");
+    /// ```
+    pub fn diff(&self, other: &ValueContainer) -> ContainerDiff {
+        super::diff::diff(self, other)
+    }
+
+    /// Run `f` on the top level of values under a read lock
+    pub(crate) fn with_path_level<R>(&self, f: impl FnOnce(&dyn PathLevel) -> R) -> R {
+        f(&*self.inner.read())
+    }
+
+    /// Merge the values of `other` into this container
+    ///
+    /// Header fields are kept. See [`merge`](crate::core::merge) for how values
+    /// are paired and combined.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MergeConflict` under `ConflictPolicy::Error`, or an error if the
+    /// merged container would exceed its maximum value count. The container is
+    /// left unchanged on error.
+    ///
+    /// # Example
+    /// ```
+    /// # use rust_container_system::prelude::*;
+    /// # use std::sync::Arc;
+    /// use rust_container_system::core::merge::{ArrayPolicy, ConflictPolicy, MergePolicy};
+    ///
+    /// let mut defaults = ValueContainer::new();
+    /// defaults.add_value(Arc::new(IntValue::new("port", 80))).unwrap();
+    /// let mut overrides = ValueContainer::new();
+    /// overrides.add_value(Arc::new(IntValue::new("port", 8080))).unwrap();
+    ///
+    /// let policy = MergePolicy::new(ConflictPolicy::KeepExisting).arrays(ArrayPolicy::Append);
+    /// let report = defaults.merge(&overrides, policy).unwrap();
+    /// assert_eq!(defaults.get_value("port").unwrap().to_int().unwrap(), 80);
+    /// assert_eq!(report.conflicts().len(), 1);
+    /// ```
+    pub fn merge(&mut self, other: &ValueContainer, policy: MergePolicy) -> Result<MergeReport> {
+        super::merge::merge(self, other, policy)
+    }
+
+    /// Validate the values of this container against a schema
+    ///
+    /// See [`schema`](crate::core::schema) for the available constraints.
+    ///
+    /// # Example
+    /// ```
+    /// # use rust_container_system::prelude::*;
+    /// # use std::sync::Arc;
+    /// use rust_container_system::core::schema::{Schema, ValueSchema};
+    ///
+    /// let schema = Schema::new().required("age", ValueSchema::new(ValueType::Int).min(0));
+    /// let mut container = ValueContainer::new();
+    /// container.add_value(Arc::new(IntValue::new("age", -1))).unwrap();
+    ///
+    /// let report = container.validate(&schema);
+    /// assert_eq!(report.to_string(), "age: -1 is out of range >= 0\n");
+    /// ```
+    pub fn validate(&self, schema: &Schema) -> ValidationReport {
+        schema.validate(self)
+    }
+
+    /// Apply a patch atomically (see [`Patch::apply`])
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatchError` naming the operation that failed; the container is
+    /// left unchanged.
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<()> {
+        patch.apply(self)
+    }
+
+    /// Run `f` on a copy of the contents under the write lock, and keep the copy
+    /// only if `f` succeeds
+    pub(crate) fn transact<R>(
+        &mut self,
+        f: impl FnOnce(&mut ContainerInner) -> Result<R>,
+    ) -> Result<R> {
+        let mut inner = self.inner.write();
+        let mut copy = inner.clone();
+        let result = f(&mut copy)?;
+        *inner = copy;
+        Ok(result)
+    }
+
+    /// Run `f` on the top level of values under a write lock
+    pub(crate) fn with_path_level_mut<R>(&mut self, f: impl FnOnce(&mut dyn PathLevel) -> R) -> R {
+        f(&mut *self.inner.write())
     }
 
     /// Zero-copy access to values with the specified name via callback
@@ -320,12 +950,12 @@ impl ValueContainer {
         F: FnOnce(&[Arc<dyn Value>]) -> R,
     {
         let inner = self.inner.read();
-        inner.value_map.get(name).map(|values| f(values))
+        inner.storage.named(name).map(|values| f(&values))
     }
 
     /// Get all values
     pub fn values(&self) -> Vec<Arc<dyn Value>> {
-        self.inner.read().values.clone()
+        self.inner.read().storage.all().into_owned()
     }
 
     /// Zero-copy access to all values via callback
@@ -348,7 +978,7 @@ impl ValueContainer {
         F: FnOnce(&[Arc<dyn Value>]) -> R,
     {
         let inner = self.inner.read();
-        f(&inner.values)
+        f(&inner.storage.all())
     }
 
     /// Remove all values with the specified name
@@ -358,42 +988,24 @@ impl ValueContainer {
     /// Time complexity: O(n) where n is total number of values
     /// (Optimized using HashSet for O(n) instead of O(n × m))
     pub fn remove_value(&mut self, name: &str) -> bool {
-        let mut inner = self.inner.write();
-
-        // Remove from HashMap - O(1)
-        if let Some(removed_values) = inner.value_map.shift_remove(name) {
-            // Build HashSet of pointers to remove - O(m) where m is number of values with this name
-            use std::collections::HashSet;
-            let removed_ptrs: HashSet<*const dyn Value> =
-                removed_values.iter().map(Arc::as_ptr).collect();
-
-            // Remove from Vec by filtering with HashSet lookup - O(n) single pass
-            inner
-                .values
-                .retain(|value| !removed_ptrs.contains(&Arc::as_ptr(value)));
-            true
-        } else {
-            false
-        }
+        self.inner.write().storage.remove(name)
     }
 
     /// Clear all values (header information is preserved)
     pub fn clear_values(&mut self) {
-        let mut inner = self.inner.write();
-        inner.values.clear();
-        inner.value_map.clear();
+        self.inner.write().storage.clear();
     }
 
     /// Get the number of values
     #[inline]
     pub fn value_count(&self) -> usize {
-        self.inner.read().values.len()
+        self.inner.read().storage.len()
     }
 
     /// Check if container is empty
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.inner.read().values.is_empty()
+        self.inner.read().storage.len() == 0
     }
 
     /// Copy this container
@@ -402,44 +1014,20 @@ impl ValueContainer {
     /// - `including_values`: If true, copy values; if false, copy header only
     pub fn copy(&self, including_values: bool) -> Self {
         let inner = self.inner.read();
-        let new_inner = if including_values {
-            // Clone all values
-            let cloned_values: Vec<Arc<dyn Value>> =
-                inner.values.iter().map(|v| v.clone_value()).collect();
-
-            // Rebuild value_map from freshly cloned values to avoid sharing Arc refs
-            let mut new_value_map: IndexMap<String, Vec<Arc<dyn Value>>> = IndexMap::new();
-            for value in &cloned_values {
-                let name = value.name().to_string();
-                new_value_map
-                    .entry(name)
-                    .or_default()
-                    .push(Arc::clone(value));
-            }
-
-            ContainerInner {
-                source_id: inner.source_id.clone(),
-                source_sub_id: inner.source_sub_id.clone(),
-                target_id: inner.target_id.clone(),
-                target_sub_id: inner.target_sub_id.clone(),
-                message_type: inner.message_type.clone(),
-                version: inner.version.clone(),
-                values: cloned_values,
-                value_map: new_value_map,
-                max_values: inner.max_values,
-            }
-        } else {
-            ContainerInner {
-                source_id: inner.source_id.clone(),
-                source_sub_id: inner.source_sub_id.clone(),
-                target_id: inner.target_id.clone(),
-                target_sub_id: inner.target_sub_id.clone(),
-                message_type: inner.message_type.clone(),
-                version: inner.version.clone(),
-                values: Vec::new(),
-                value_map: IndexMap::new(),
-                max_values: inner.max_values,
-            }
+        let new_inner = ContainerInner {
+            source_id: inner.source_id.clone(),
+            source_sub_id: inner.source_sub_id.clone(),
+            target_id: inner.target_id.clone(),
+            target_sub_id: inner.target_sub_id.clone(),
+            message_type: inner.message_type.clone(),
+            version: inner.version.clone(),
+            // Clone all values so that the copy shares no Arc refs with this container
+            storage: if including_values {
+                inner.storage.deep_copy()
+            } else {
+                inner.storage.empty_like()
+            },
+            max_values: inner.max_values,
         };
 
         Self {
@@ -447,6 +1035,41 @@ impl ValueContainer {
         }
     }
 
+    /// Copy of this container that a peer with the given type support can decode
+    ///
+    /// Values of extended types the peer does not support, including those nested
+    /// in containers and arrays, are replaced by a `string_value` of the same name
+    /// holding their text (`to_string()`). Everything else is kept as is.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::prelude::*;
+    /// use rust_container_system::core::value_types::TypeSupport;
+    /// # use std::sync::Arc;
+    ///
+    /// let mut container = ValueContainer::new();
+    /// container
+    ///     .add_value(Arc::new(DecimalValue::parse("amount", "12.50").unwrap()))
+    ///     .unwrap();
+    ///
+    /// let legacy = container.for_peer(TypeSupport::CORE);
+    /// let amount = legacy.get_value("amount").unwrap();
+    /// assert_eq!(amount.value_type(), ValueType::String);
+    /// assert_eq!(amount.to_string(), "12.50");
+    /// ```
+    pub fn for_peer(&self, support: TypeSupport) -> Self {
+        let peer = self.copy(false);
+        {
+            let mut inner = peer.inner.write();
+            for value in self.values() {
+                // Cannot fail: the copy has the same limit, and every value is either
+                // kept or a string
+                let _ = inner.storage.push_value(downgrade(&value, support));
+            }
+        }
+        peer
+    }
+
     /// Serialize to JSON with type-preserving value serialization
     ///
     /// # Deprecation Notice
@@ -482,7 +1105,7 @@ impl ValueContainer {
         });
 
         if let Some(values_array) = json_obj["values"].as_array_mut() {
-            for value in &inner.values {
+            for value in inner.storage.all().iter() {
                 // Call each value's to_json() to preserve type-specific formatting
                 // (e.g., BytesValue uses base64, not "<n bytes>" string representation)
                 let value_json_str = value.to_json()?;
@@ -534,7 +1157,7 @@ impl ValueContainer {
 
         // Pre-allocate with reasonable capacity to reduce reallocations
         // Estimate: ~200 bytes header + ~100 bytes per value
-        let estimated_size = 200 + (inner.values.len() * 100);
+        let estimated_size = 200 + (inner.storage.len() * 100);
         let mut xml = String::with_capacity(estimated_size);
 
         xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
@@ -594,7 +1217,7 @@ impl ValueContainer {
         xml.push_str("  </header>\n");
         xml.push_str("  <values>\n");
 
-        for value in &inner.values {
+        for value in inner.storage.all().iter() {
             // Call value.to_xml() to get type-specific formatting (e.g., base64 for BytesValue)
             // then extract the content by stripping the outer type tags
             let xml_str = value.to_xml()?;
@@ -702,10 +1325,7 @@ impl ValueContainer {
         container.set_source(source_id, source_sub_id);
         container.set_target(target_id, target_sub_id);
         container.set_message_type(message_type);
-        {
-            let mut inner = container.inner.write();
-            inner.version = version;
-        }
+        container.set_version(version);
 
         // Parse values array
         if let Some(values_array) = json_value["values"].as_array() {
@@ -866,6 +1486,42 @@ impl ValueContainer {
 
                         Arc::new(BytesValue::new(name, bytes))
                     }
+                    super::value_types::ValueType::Decimal => {
+                        let text = value_data.as_str().ok_or_else(|| {
+                            crate::core::ContainerError::InvalidDataFormat(format!(
+                                "Invalid decimal value for '{}'",
+                                name
+                            ))
+                        })?;
+                        Arc::new(crate::values::DecimalValue::parse(name, text)?)
+                    }
+                    super::value_types::ValueType::Timestamp => {
+                        let text = value_data.as_str().ok_or_else(|| {
+                            crate::core::ContainerError::InvalidDataFormat(format!(
+                                "Invalid timestamp value for '{}'",
+                                name
+                            ))
+                        })?;
+                        Arc::new(crate::values::TimestampValue::parse(name, text)?)
+                    }
+                    super::value_types::ValueType::Duration => {
+                        let text = value_data.as_str().ok_or_else(|| {
+                            crate::core::ContainerError::InvalidDataFormat(format!(
+                                "Invalid duration value for '{}'",
+                                name
+                            ))
+                        })?;
+                        Arc::new(crate::values::DurationValue::parse(name, text)?)
+                    }
+                    super::value_types::ValueType::Uuid => {
+                        let text = value_data.as_str().ok_or_else(|| {
+                            crate::core::ContainerError::InvalidDataFormat(format!(
+                                "Invalid uuid value for '{}'",
+                                name
+                            ))
+                        })?;
+                        Arc::new(crate::values::UuidValue::parse(name, text)?)
+                    }
                     super::value_types::ValueType::Null
                     | super::value_types::ValueType::Container
                     | super::value_types::ValueType::Array => {
@@ -932,6 +1588,75 @@ impl ValueContainer {
     pub fn deserialize_cpp_wire(wire_data: &str) -> Result<Self> {
         crate::core::wire_protocol::deserialize_cpp_wire(wire_data)
     }
+
+    /// Serialize to the binary container format
+    ///
+    /// Values use the C++ container_system binary value layout; see
+    /// [`binary_protocol`](crate::core::binary_protocol) for the full format.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// let mut container = ValueContainer::new();
+    /// container.add_value(Arc::new(IntValue::new("count", 42))).unwrap();
+    ///
+    /// let bytes = container.to_binary().unwrap();
+    /// let restored = ValueContainer::from_binary(&bytes).unwrap();
+    /// assert_eq!(restored.get_value("count").unwrap().to_int().unwrap(), 42);
+    /// ```
+    pub fn to_binary(&self) -> Result<Vec<u8>> {
+        crate::core::binary_protocol::serialize_binary(self)
+    }
+
+    /// Deserialize from the binary container format produced by [`to_binary`](Self::to_binary)
+    pub fn from_binary(data: &[u8]) -> Result<Self> {
+        crate::core::binary_protocol::deserialize_binary(data)
+    }
+
+    /// Write in C++ wire protocol format to `writer` without building the message in memory
+    ///
+    /// See [`wire_protocol::write_cpp_wire`](crate::core::wire_protocol::write_cpp_wire).
+    pub fn write_cpp_wire<W: std::io::Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        crate::core::wire_protocol::write_cpp_wire(self, writer)
+    }
+
+    /// Read C++ wire protocol data from `reader` until the end of the input
+    ///
+    /// See [`wire_protocol::read_cpp_wire`](crate::core::wire_protocol::read_cpp_wire).
+    pub fn read_cpp_wire<R: std::io::Read + ?Sized>(reader: &mut R) -> Result<Self> {
+        crate::core::wire_protocol::read_cpp_wire(reader)
+    }
+
+    /// Write in the binary container format to `writer` without building it in memory
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::prelude::*;
+    /// use std::io::{BufReader, BufWriter};
+    /// use std::sync::Arc;
+    ///
+    /// let mut container = ValueContainer::new();
+    /// container.add_value(Arc::new(BytesValue::new("blob", vec![0; 4096]))).unwrap();
+    ///
+    /// let mut writer = BufWriter::new(Vec::new());
+    /// container.write_binary(&mut writer).unwrap();
+    /// let bytes = writer.into_inner().unwrap();
+    ///
+    /// let restored = ValueContainer::read_binary(&mut BufReader::new(bytes.as_slice())).unwrap();
+    /// assert_eq!(restored.get_value("blob").unwrap().size(), 4096);
+    /// ```
+    pub fn write_binary<W: std::io::Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        crate::core::binary_protocol::write_binary(self, writer)
+    }
+
+    /// Read one container in the binary container format from `reader`
+    ///
+    /// See [`binary_protocol::read_binary`](crate::core::binary_protocol::read_binary).
+    pub fn read_binary<R: std::io::Read + ?Sized>(reader: &mut R) -> Result<Self> {
+        crate::core::binary_protocol::read_binary(reader)
+    }
 }
 
 impl Default for ValueContainer {
@@ -940,6 +1665,88 @@ impl Default for ValueContainer {
     }
 }
 
+/// Serializes the header fields and every value with its exact `ValueType`
+///
+/// See [`value_serde`](crate::core::value_serde) for the value representation.
+impl Serialize for ValueContainer {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        struct Values<'a>(&'a [Arc<dyn Value>]);
+
+        impl Serialize for Values<'_> {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                super::value_serde::values::serialize(self.0, serializer)
+            }
+        }
+
+        let inner = self.inner.read();
+        let mut state = serializer.serialize_struct("ValueContainer", 7)?;
+        state.serialize_field("source_id", &inner.source_id)?;
+        state.serialize_field("source_sub_id", &inner.source_sub_id)?;
+        state.serialize_field("target_id", &inner.target_id)?;
+        state.serialize_field("target_sub_id", &inner.target_sub_id)?;
+        state.serialize_field("message_type", &inner.message_type)?;
+        state.serialize_field("version", &inner.version)?;
+        state.serialize_field("values", &Values(&inner.storage.all()))?;
+        state.end()
+    }
+}
+
+/// Serde representation of a container, see `impl Serialize for ValueContainer`
+#[derive(Deserialize)]
+#[serde(rename = "ValueContainer")]
+struct ContainerRepr {
+    source_id: String,
+    source_sub_id: String,
+    target_id: String,
+    target_sub_id: String,
+    message_type: String,
+    version: String,
+    #[serde(with = "super::value_serde::values")]
+    values: Vec<Arc<dyn Value>>,
+}
+
+/// Rebuilds the header and values; the value limit is `DEFAULT_MAX_VALUES`
+impl<'de> Deserialize<'de> for ValueContainer {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let ContainerRepr {
+            source_id,
+            source_sub_id,
+            target_id,
+            target_sub_id,
+            message_type,
+            version,
+            values,
+        } = ContainerRepr::deserialize(deserializer)?;
+
+        let mut container = Self::new();
+        {
+            let mut inner = container.inner.write();
+            inner.source_id = source_id;
+            inner.source_sub_id = source_sub_id;
+            inner.target_id = target_id;
+            inner.target_sub_id = target_sub_id;
+            inner.message_type = message_type;
+            inner.version = version;
+        }
+        for value in values {
+            container
+                .add_value(value)
+                .map_err(serde::de::Error::custom)?;
+        }
+        Ok(container)
+    }
+}
+
 /// Builder for constructing ValueContainer with a fluent API
 ///
 /// # Example
@@ -950,6 +1757,7 @@ impl Default for ValueContainer {
 ///     .source("sender", "session_1")
 ///     .target("receiver", "main")
 ///     .message_type("user_event")
+///     .version("2.0.0")
 ///     .max_values(1000)
 ///     .build();
 /// ```
@@ -960,7 +1768,9 @@ pub struct ValueContainerBuilder {
     target_id: String,
     target_sub_id: String,
     message_type: String,
+    version: String,
     max_values: usize,
+    inline: bool,
 }
 
 impl ValueContainerBuilder {
@@ -972,7 +1782,9 @@ impl ValueContainerBuilder {
             target_id: String::new(),
             target_sub_id: String::new(),
             message_type: "data_container".to_string(),
+            version: "1.0.0.0".to_string(),
             max_values: DEFAULT_MAX_VALUES,
+            inline: false,
         }
     }
 
@@ -999,6 +1811,13 @@ impl ValueContainerBuilder {
         self
     }
 
+    /// Set message version
+    #[must_use = "builder methods return a new value"]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
     /// Set maximum number of values
     #[must_use = "builder methods return a new value"]
     pub fn max_values(mut self, max: usize) -> Self {
@@ -1006,12 +1825,23 @@ impl ValueContainerBuilder {
         self
     }
 
+    /// Store values inline (see [`ValueContainer::new_inline`])
+    #[must_use = "builder methods return a new value"]
+    pub fn inline_values(mut self) -> Self {
+        self.inline = true;
+        self
+    }
+
     /// Build the ValueContainer
     pub fn build(self) -> ValueContainer {
         let mut container = ValueContainer::with_max_values(self.max_values);
+        if self.inline {
+            container.inner.write().storage = Storage::inline();
+        }
         container.set_source(&self.source_id, &self.source_sub_id);
         container.set_target(&self.target_id, &self.target_sub_id);
         container.set_message_type(&self.message_type);
+        container.set_version(self.version);
         container
     }
 }
@@ -1041,6 +1871,31 @@ impl ValueContainer {
     }
 }
 
+/// `value` as a peer with `support` can decode it (see [`ValueContainer::for_peer`])
+fn downgrade(value: &Arc<dyn Value>, support: TypeSupport) -> Arc<dyn Value> {
+    if !support.supports(value.value_type()) {
+        return Arc::new(StringValue::new(value.name(), value.to_string()));
+    }
+    let any = value.as_any();
+    if let Some(container) = any.downcast_ref::<ContainerValue>() {
+        let children = container.children().iter();
+        Arc::new(ContainerValue::new(
+            value.name(),
+            children.map(|child| downgrade(child, support)).collect(),
+        ))
+    } else if let Some(array) = any.downcast_ref::<ArrayValue>() {
+        let elements = array.elements().iter();
+        Arc::new(ArrayValue::new(
+            value.name(),
+            elements
+                .map(|element| downgrade(element, support))
+                .collect(),
+        ))
+    } else {
+        Arc::clone(value)
+    }
+}
+
 /// Iterator over values in a ValueContainer
 ///
 /// # Example
@@ -1099,6 +1954,7 @@ impl IntoIterator for &ValueContainer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::ValueType;
     use crate::values::*;
 
     #[test]
@@ -1218,6 +2074,7 @@ mod tests {
             .source("sender", "session_1")
             .target("receiver", "main")
             .message_type("user_event")
+            .version("3.2.1")
             .max_values(500)
             .build();
 
@@ -1226,9 +2083,16 @@ mod tests {
         assert_eq!(container.target_id(), "receiver");
         assert_eq!(container.target_sub_id(), "main");
         assert_eq!(container.message_type(), "user_event");
+        assert_eq!(container.version(), "3.2.1");
         assert_eq!(container.inner.read().max_values, 500);
     }
 
+    #[test]
+    fn test_builder_default_version() {
+        let container = ValueContainer::builder().build();
+        assert_eq!(container.version(), "1.0.0.0");
+    }
+
     #[test]
     fn test_builder_with_values() {
         let mut container = ValueContainer::builder()
@@ -1570,4 +2434,238 @@ mod tests {
         assert_eq!(tags[1].to_int().unwrap(), 2);
         assert_eq!(tags[2].to_int().unwrap(), 3);
     }
+
+    #[test]
+    fn test_inline_storage() {
+        use crate::core::value_data::ValueData;
+
+        let mut container = ValueContainer::builder()
+            .inline_values()
+            .max_values(4)
+            .build();
+        assert!(container.is_inline());
+
+        container.add_data("tag", ValueData::Int(1)).unwrap();
+        container
+            .add_value(Arc::new(StringValue::new("name", "alice")))
+            .unwrap();
+        container.add_data("tag", ValueData::Int(2)).unwrap();
+        container.add_data("flag", ValueData::Bool(true)).unwrap();
+        assert!(container.add_data("extra", ValueData::Null).is_err());
+
+        assert_eq!(
+            container.with_data("tag", |data| data.clone()).unwrap(),
+            Some(ValueData::Int(1))
+        );
+        assert_eq!(container.get_value("name").unwrap().to_string(), "alice");
+        let names: Vec<String> = container
+            .values()
+            .iter()
+            .map(|v| v.name().to_string())
+            .collect();
+        assert_eq!(names, ["tag", "name", "tag", "flag"]);
+
+        // Positions stay correct after removing a name in the middle
+        assert!(container.remove_value("name"));
+        assert_eq!(container.value_count(), 3);
+        let tags: Vec<i32> = container
+            .get_value_array("tag")
+            .iter()
+            .map(|v| v.to_int().unwrap())
+            .collect();
+        assert_eq!(tags, [1, 2]);
+        assert_eq!(
+            container.get_data("flag").unwrap(),
+            Some(ValueData::Bool(true))
+        );
+
+        let copy = container.copy(true);
+        assert!(copy.is_inline());
+        assert_eq!(copy.value_count(), 3);
+        assert!(container.copy(false).is_empty());
+
+        container.clear_values();
+        assert!(container.is_empty());
+        assert_eq!(container.get_data("tag").unwrap(), None);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_inline_matches_shared() {
+        use crate::core::value_data::ValueData;
+
+        let mut inline = ValueContainer::new_inline();
+        let mut shared = ValueContainer::new();
+        for container in [&mut inline, &mut shared] {
+            container.set_message_type("inline_test");
+            container.add_data("id", ValueData::LLong(7)).unwrap();
+            container
+                .add_data("payload", ValueData::Bytes(vec![1, 2, 3]))
+                .unwrap();
+        }
+        assert!(!shared.is_inline());
+
+        assert_eq!(inline.to_json().unwrap(), shared.to_json().unwrap());
+        assert_eq!(inline.to_xml().unwrap(), shared.to_xml().unwrap());
+        assert_eq!(
+            serde_json::to_string(&inline).unwrap(),
+            serde_json::to_string(&shared).unwrap()
+        );
+        assert_eq!(
+            shared.get_data("payload").unwrap(),
+            Some(ValueData::Bytes(vec![1, 2, 3]))
+        );
+    }
+
+    fn billing() -> ValueContainer {
+        let mut container = ValueContainer::with_message_type("invoice");
+        container
+            .add_value(Arc::new(
+                DecimalValue::parse("amount", "1234567890.123456789").unwrap(),
+            ))
+            .unwrap();
+        container
+            .add_value(Arc::new(ContainerValue::new(
+                "line",
+                vec![Arc::new(DecimalValue::parse("tax", "-0.07").unwrap())],
+            )))
+            .unwrap();
+        container
+    }
+
+    #[test]
+    fn test_decimal_roundtrip() {
+        use crate::core::json_v2_adapter::JsonV2Adapter;
+
+        let container = billing();
+        let wire = container.serialize_cpp_wire().unwrap();
+        let binary = container.to_binary().unwrap();
+        let v2 = JsonV2Adapter::to_v2_json(&container, false).unwrap();
+        assert!(v2.contains(r#""data":"1234567890.123456789""#));
+
+        for restored in [
+            ValueContainer::deserialize_cpp_wire(&wire).unwrap(),
+            ValueContainer::from_binary(&binary).unwrap(),
+            JsonV2Adapter::from_v2_json(&v2).unwrap(),
+            serde_json::from_str(&serde_json::to_string(&container).unwrap()).unwrap(),
+        ] {
+            let amount = restored.get_value("amount").unwrap();
+            assert_eq!(amount.value_type(), ValueType::Decimal);
+            assert_eq!(amount.to_string(), "1234567890.123456789");
+            assert_eq!(restored.get_path("line.tax").unwrap().to_string(), "-0.07");
+        }
+    }
+
+    #[test]
+    fn test_for_peer_downgrades_extended_types() {
+        let container = billing();
+
+        let legacy = container.for_peer(TypeSupport::CORE);
+        assert_eq!(legacy.message_type(), "invoice");
+        let amount = legacy.get_value("amount").unwrap();
+        assert_eq!(amount.value_type(), ValueType::String);
+        assert_eq!(amount.to_string(), "1234567890.123456789");
+        let tax = legacy.get_path("line.tax").unwrap();
+        assert_eq!(tax.value_type(), ValueType::String);
+        assert!(legacy
+            .serialize_cpp_wire()
+            .unwrap()
+            .contains("string_value"));
+
+        let current = container.for_peer(TypeSupport::ALL);
+        assert_eq!(
+            current.get_path("line.tax").unwrap().value_type(),
+            ValueType::Decimal
+        );
+        assert_eq!(
+            container.get_value("amount").unwrap().value_type(),
+            ValueType::Decimal
+        );
+    }
+
+    #[test]
+    fn test_time_values_roundtrip_and_getters() {
+        use crate::core::json_v2_adapter::JsonV2Adapter;
+
+        let sent: Timestamp = "2024-03-01T09:30:00.123456789+09:00".parse().unwrap();
+        let mut container = ValueContainer::with_message_type("heartbeat");
+        container
+            .add_value(Arc::new(TimestampValue::new("sent_at", sent)))
+            .unwrap();
+        container
+            .add_value(Arc::new(DurationValue::new(
+                "interval",
+                Duration::from_millis(1500),
+            )))
+            .unwrap();
+
+        let v2 = JsonV2Adapter::to_v2_json(&container, false).unwrap();
+        assert!(v2.contains(r#""data":"2024-03-01T09:30:00.123456789+09:00""#));
+        assert!(v2.contains(r#""data":"PT1.5S""#));
+
+        for restored in [
+            ValueContainer::deserialize_cpp_wire(&container.serialize_cpp_wire().unwrap()).unwrap(),
+            ValueContainer::from_binary(&container.to_binary().unwrap()).unwrap(),
+            JsonV2Adapter::from_v2_json(&v2).unwrap(),
+            serde_json::from_str(&serde_json::to_string(&container).unwrap()).unwrap(),
+            container.for_peer(TypeSupport::CORE),
+        ] {
+            assert_eq!(restored.get_timestamp("sent_at").unwrap(), Some(sent));
+            assert_eq!(
+                restored.get_duration("interval").unwrap(),
+                Some(Duration::from_millis(1500))
+            );
+        }
+
+        let legacy = container.for_peer(TypeSupport::CORE);
+        assert_eq!(
+            legacy.get_value("sent_at").unwrap().value_type(),
+            ValueType::String
+        );
+        assert_eq!(container.get_duration("missing").unwrap(), None);
+        assert!(container.get_timestamp("interval").is_err());
+    }
+
+    #[test]
+    fn test_uuid_roundtrip_and_fallback() {
+        use crate::core::json_v2_adapter::JsonV2Adapter;
+
+        const ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let id: Uuid = ID.parse().unwrap();
+        let mut container = ValueContainer::with_message_type("order");
+        container
+            .add_value(Arc::new(UuidValue::new("order_id", id)))
+            .unwrap();
+
+        let v2 = JsonV2Adapter::to_v2_json(&container, false).unwrap();
+        assert!(v2.contains(&format!(r#""data":"{}""#, ID)));
+        assert!(container.serialize_cpp_wire().unwrap().contains(ID));
+
+        let legacy = container.for_peer(TypeSupport::CORE);
+        assert_eq!(
+            legacy.get_value("order_id").unwrap().value_type(),
+            ValueType::String
+        );
+        // 16 raw bytes instead of 36 characters of text
+        assert_eq!(
+            legacy.to_binary().unwrap().len() - container.to_binary().unwrap().len(),
+            20
+        );
+
+        for restored in [
+            ValueContainer::deserialize_cpp_wire(&container.serialize_cpp_wire().unwrap()).unwrap(),
+            ValueContainer::from_binary(&container.to_binary().unwrap()).unwrap(),
+            JsonV2Adapter::from_v2_json(&v2).unwrap(),
+            serde_json::from_str(&serde_json::to_string(&container).unwrap()).unwrap(),
+            legacy,
+        ] {
+            assert_eq!(restored.get_uuid("order_id").unwrap(), Some(id));
+        }
+
+        let mut malformed = ValueContainer::new();
+        malformed
+            .add_value(Arc::new(StringValue::new("order_id", "not-a-uuid")))
+            .unwrap();
+        assert!(malformed.get_uuid("order_id").is_err());
+    }
 }
@@ -0,0 +1,751 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Zero-Copy Container View
+//!
+//! `ContainerView` reads a container in the binary container format (see
+//! `binary_protocol`) without building a `ValueContainer`. Header fields, names, strings
+//! and byte payloads are borrowed straight from the input buffer, and no `Arc<dyn Value>`
+//! is created unless asked for. This makes it cheap to pick a few fields out of a large
+//! message.
+//!
+//! Creating a view checks the header and the layout of every top-level value (type
+//! bytes, lengths, names and fixed payload sizes) in one pass without allocating per
+//! value. The name index is built on the first lookup by name. Nested containers and
+//! arrays are only parsed when their children are requested.
+//!
+//! Use `to_container` to turn a view into an owned `ValueContainer`.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::prelude::*;
+//! use rust_container_system::core::container_view::ContainerView;
+//! use std::sync::Arc;
+//!
+//! let mut container = ValueContainer::new();
+//! container.set_message_type("user_data");
+//! container.add_value(Arc::new(IntValue::new("count", 42))).unwrap();
+//! container.add_value(Arc::new(StringValue::new("name", "Alice"))).unwrap();
+//! let bytes = container.to_binary().unwrap();
+//!
+//! let view = ContainerView::new(&bytes).unwrap();
+//! assert_eq!(view.message_type(), "user_data");
+//! assert_eq!(view.get_int("count").unwrap(), 42);
+//! assert_eq!(view.get_str("name").unwrap(), "Alice");
+//!
+//! let owned = view.to_container().unwrap();
+//! assert_eq!(owned.value_count(), 2);
+//! ```
+
+use crate::core::binary_protocol::{deserialize_binary_with_decoder, read_header, Header};
+use crate::core::value_codec::{array, fixed_payload_size, offset_by, BinaryReader, ValueDecoder};
+use crate::core::{ContainerError, Result, Value, ValueContainer, ValueType};
+use crate::values::Decimal;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// Message type reported when the header has none (as for `ValueContainer::new`)
+const DEFAULT_MESSAGE_TYPE: &str = "data_container";
+
+/// Version reported when the header has none (as for `ValueContainer::new`)
+const DEFAULT_VERSION: &str = "1.0.0.0";
+
+/// Read-only view of a binary container, borrowing from the input buffer
+#[derive(Debug, Clone)]
+pub struct ContainerView<'a> {
+    data: &'a [u8],
+    header: Header<&'a str>,
+    values: Vec<ValueView<'a>>,
+    /// Index of the first value with each name, built on the first lookup
+    index: OnceLock<HashMap<&'a str, usize>>,
+}
+
+impl<'a> ContainerView<'a> {
+    /// Create a view over one container in the binary container format
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::WireFormat` with the byte offset of the problem if the
+    /// magic or format version is wrong, the data is truncated or has trailing bytes, a
+    /// type byte is unknown, a name is not valid UTF-8, or a fixed-size payload has the
+    /// wrong size. Nested values and string payloads are checked when they are read.
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        let mut reader = BinaryReader::new(data);
+        let header = read_header(&mut reader)?;
+
+        let count_offset = reader.pos;
+        let count = reader.read_len()?;
+        reader.check_count(count, count_offset)?;
+        let values = scan_values(&mut reader, count)?;
+
+        if reader.pos != data.len() {
+            return Err(BinaryReader::error(
+                reader.pos,
+                format!(
+                    "{} trailing bytes after last value",
+                    data.len() - reader.pos
+                ),
+            ));
+        }
+
+        Ok(Self {
+            data,
+            header,
+            values,
+            index: OnceLock::new(),
+        })
+    }
+
+    /// Get the source ID
+    pub fn source_id(&self) -> &'a str {
+        self.header.source.0
+    }
+
+    /// Get the source sub-ID
+    pub fn source_sub_id(&self) -> &'a str {
+        self.header.source.1
+    }
+
+    /// Get the target ID
+    pub fn target_id(&self) -> &'a str {
+        self.header.target.0
+    }
+
+    /// Get the target sub-ID
+    pub fn target_sub_id(&self) -> &'a str {
+        self.header.target.1
+    }
+
+    /// Get the message type ("data_container" if the header has none)
+    pub fn message_type(&self) -> &'a str {
+        self.header.message_type.unwrap_or(DEFAULT_MESSAGE_TYPE)
+    }
+
+    /// Get the message version ("1.0.0.0" if the header has none)
+    pub fn version(&self) -> &'a str {
+        self.header.version.unwrap_or(DEFAULT_VERSION)
+    }
+
+    /// All top-level values, in order
+    pub fn values(&self) -> &[ValueView<'a>] {
+        &self.values
+    }
+
+    /// Number of top-level values
+    pub fn value_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if there are no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the first value with the specified name
+    pub fn get(&self, name: &str) -> Option<ValueView<'a>> {
+        let index = self.index.get_or_init(|| {
+            let mut index = HashMap::with_capacity(self.values.len());
+            for (position, value) in self.values.iter().enumerate() {
+                index.entry(value.name).or_insert(position);
+            }
+            index
+        });
+        index.get(name).map(|&position| self.values[position])
+    }
+
+    /// Get a boolean value by name
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::ValueNotFound` if there is no such value and
+    /// `ContainerError::InvalidTypeConversion` if it is not a bool.
+    pub fn get_bool(&self, name: &str) -> Result<bool> {
+        self.require(name)?.to_bool()
+    }
+
+    /// Get an integer value by name as i32
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::ValueNotFound` if there is no such value and
+    /// `ContainerError::InvalidTypeConversion` if it is not an integer or does not fit.
+    pub fn get_int(&self, name: &str) -> Result<i32> {
+        self.require(name)?.to_int()
+    }
+
+    /// Get an integer value by name as i64
+    ///
+    /// # Errors
+    ///
+    /// Same as [`get_int`](Self::get_int).
+    pub fn get_long(&self, name: &str) -> Result<i64> {
+        self.require(name)?.to_long()
+    }
+
+    /// Get an integer value by name as u64
+    ///
+    /// # Errors
+    ///
+    /// Same as [`get_int`](Self::get_int).
+    pub fn get_ulong(&self, name: &str) -> Result<u64> {
+        self.require(name)?.to_ulong()
+    }
+
+    /// Get a numeric value by name as f64
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::ValueNotFound` if there is no such value and
+    /// `ContainerError::InvalidTypeConversion` if it is not numeric.
+    pub fn get_double(&self, name: &str) -> Result<f64> {
+        self.require(name)?.to_double()
+    }
+
+    /// Get a string value by name, borrowed from the buffer
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::ValueNotFound` if there is no such value,
+    /// `ContainerError::InvalidTypeConversion` if it is not a string and
+    /// `ContainerError::WireFormat` if it is not valid UTF-8.
+    pub fn get_str(&self, name: &str) -> Result<&'a str> {
+        self.require(name)?.as_str()
+    }
+
+    /// Get a bytes value by name, borrowed from the buffer
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::ValueNotFound` if there is no such value and
+    /// `ContainerError::InvalidTypeConversion` if it is not a bytes value.
+    pub fn get_bytes(&self, name: &str) -> Result<&'a [u8]> {
+        self.require(name)?.as_bytes()
+    }
+
+    /// Build an owned `ValueContainer` with the same header and values
+    ///
+    /// # Errors
+    ///
+    /// Same as [`deserialize_binary`](crate::core::binary_protocol::deserialize_binary).
+    pub fn to_container(&self) -> Result<ValueContainer> {
+        self.to_container_with_decoder(&ValueDecoder::new())
+    }
+
+    /// Build an owned `ValueContainer`, rebuilding values through `decoder`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_container`](Self::to_container), plus any error returned by a
+    /// registered decoder.
+    pub fn to_container_with_decoder(&self, decoder: &ValueDecoder) -> Result<ValueContainer> {
+        deserialize_binary_with_decoder(self.data, decoder)
+    }
+
+    fn require(&self, name: &str) -> Result<ValueView<'a>> {
+        self.get(name)
+            .ok_or_else(|| ContainerError::ValueNotFound(name.to_string()))
+    }
+}
+
+/// Read-only view of one encoded value
+///
+/// Scalars are read from the payload on each call; names, strings and bytes are
+/// borrowed from the buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct ValueView<'a> {
+    /// The whole buffer the view was created from, for offsets in errors
+    data: &'a [u8],
+    value_type: ValueType,
+    name: &'a str,
+    /// Offset of the value's type byte in `data`
+    start: usize,
+    payload_at: usize,
+    payload_len: usize,
+}
+
+impl<'a> ValueView<'a> {
+    /// Get the value name
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get the value type
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    /// Get the raw payload (the value layout's data part)
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[self.payload_at..self.payload_at + self.payload_len]
+    }
+
+    /// Read a bool value
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidTypeConversion` if this is not a bool.
+    pub fn to_bool(&self) -> Result<bool> {
+        match self.value_type {
+            ValueType::Bool => Ok(self.payload()[0] != 0),
+            _ => Err(self.conversion_error("bool")),
+        }
+    }
+
+    /// Read an integer value as i32
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidTypeConversion` if this is not an integer or the
+    /// value does not fit in an i32.
+    pub fn to_int(&self) -> Result<i32> {
+        self.integer("i32")
+    }
+
+    /// Read an integer value as i64
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_int`](Self::to_int), for i64.
+    pub fn to_long(&self) -> Result<i64> {
+        self.integer("i64")
+    }
+
+    /// Read an integer value as u64
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_int`](Self::to_int), for u64.
+    pub fn to_ulong(&self) -> Result<u64> {
+        self.integer("u64")
+    }
+
+    /// Read a numeric value as f64
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidTypeConversion` if this is not numeric.
+    pub fn to_double(&self) -> Result<f64> {
+        let payload = self.payload();
+        match self.value_type {
+            ValueType::Float => Ok(f32::from_le_bytes(array(payload)) as f64),
+            ValueType::Double => Ok(f64::from_le_bytes(array(payload))),
+            ValueType::Decimal => Decimal::from_payload(payload)
+                .map(|decimal| decimal.to_f64())
+                .map_err(|_| self.conversion_error("f64")),
+            _ => match self.integer_value() {
+                Some(value) => Ok(value as f64),
+                None => Err(self.conversion_error("f64")),
+            },
+        }
+    }
+
+    /// Borrow a string value
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidTypeConversion` if this is not a string and
+    /// `ContainerError::WireFormat` if it is not valid UTF-8.
+    pub fn as_str(&self) -> Result<&'a str> {
+        if self.value_type != ValueType::String {
+            return Err(self.conversion_error("string"));
+        }
+        std::str::from_utf8(self.payload()).map_err(|e| {
+            BinaryReader::error(
+                self.payload_at,
+                format!("invalid UTF-8 in '{}': {}", self.name, e),
+            )
+        })
+    }
+
+    /// Borrow a bytes value
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidTypeConversion` if this is not a bytes value.
+    pub fn as_bytes(&self) -> Result<&'a [u8]> {
+        match self.value_type {
+            ValueType::Bytes => Ok(self.payload()),
+            _ => Err(self.conversion_error("bytes")),
+        }
+    }
+
+    /// Parse the children of a nested container or array
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidTypeConversion` if this is not a container or
+    /// array, and `ContainerError::WireFormat` if the children are malformed.
+    pub fn children(&self) -> Result<Vec<ValueView<'a>>> {
+        if !matches!(self.value_type, ValueType::Container | ValueType::Array) {
+            return Err(self.conversion_error("container"));
+        }
+
+        let mut reader = BinaryReader {
+            data: self.data,
+            pos: self.payload_at,
+            end: self.payload_at + self.payload_len,
+        };
+        let count_offset = reader.pos;
+        let count = reader.read_len()?;
+        reader.check_count(count, count_offset)?;
+        let children = scan_values(&mut reader, count)?;
+        if reader.pos != reader.end {
+            return Err(BinaryReader::error(
+                reader.pos,
+                format!("{} bytes after the last child", reader.end - reader.pos),
+            ));
+        }
+        Ok(children)
+    }
+
+    /// Build an owned `Value`, including any nested values
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::WireFormat` if the payload is malformed.
+    pub fn to_value(&self) -> Result<Arc<dyn Value>> {
+        self.to_value_with_decoder(&ValueDecoder::new())
+    }
+
+    /// Build an owned `Value` through `decoder`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_value`](Self::to_value), plus any error returned by a registered
+    /// decoder.
+    pub fn to_value_with_decoder(&self, decoder: &ValueDecoder) -> Result<Arc<dyn Value>> {
+        let end = self.payload_at + self.payload_len;
+        let (value, _) = decoder
+            .decode(&self.data[self.start..end])
+            .map_err(|e| offset_by(e, self.start))?;
+        Ok(value)
+    }
+
+    /// Read any integer type, widened to i128
+    fn integer_value(&self) -> Option<i128> {
+        let payload = self.payload();
+        Some(match self.value_type {
+            ValueType::Short => i16::from_le_bytes(array(payload)) as i128,
+            ValueType::UShort => u16::from_le_bytes(array(payload)) as i128,
+            // Long and ULong travel as 32-bit values, as in the C++ layout
+            ValueType::Int | ValueType::Long => i32::from_le_bytes(array(payload)) as i128,
+            ValueType::UInt | ValueType::ULong => u32::from_le_bytes(array(payload)) as i128,
+            ValueType::LLong => i64::from_le_bytes(array(payload)) as i128,
+            ValueType::ULLong => u64::from_le_bytes(array(payload)) as i128,
+            _ => return None,
+        })
+    }
+
+    fn integer<T: TryFrom<i128>>(&self, target: &str) -> Result<T> {
+        self.integer_value()
+            .and_then(|value| T::try_from(value).ok())
+            .ok_or_else(|| self.conversion_error(target))
+    }
+
+    fn conversion_error(&self, target: &str) -> ContainerError {
+        ContainerError::InvalidTypeConversion {
+            from: self.value_type.to_string(),
+            to: target.to_string(),
+        }
+    }
+}
+
+/// Check the layout of `count` values at the reader's position and record where each is
+fn scan_values<'a>(reader: &mut BinaryReader<'a>, count: usize) -> Result<Vec<ValueView<'a>>> {
+    let mut values = Vec::with_capacity(count);
+    for _ in 0..count {
+        let start = reader.pos;
+        let type_byte = reader.read_u8()?;
+        let value_type = ValueType::from_u8(type_byte).ok_or_else(|| {
+            BinaryReader::error(start, format!("unknown type byte {}", type_byte))
+        })?;
+        let name_len = reader.read_len()?;
+        let name = reader.read_str(name_len)?;
+        let value_size = reader.read_len()?;
+        let payload_at = reader.pos;
+        reader.read_bytes(value_size)?;
+
+        if let Some(expected) = fixed_payload_size(value_type) {
+            if value_size != expected {
+                return Err(BinaryReader::error(
+                    payload_at,
+                    format!(
+                        "{} '{}' has value_size {} (expected {})",
+                        value_type, name, value_size, expected
+                    ),
+                ));
+            }
+        }
+
+        values.push(ValueView {
+            data: reader.data,
+            value_type,
+            name,
+            start,
+            payload_at,
+            payload_len: value_size,
+        });
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::binary_protocol::{deserialize_binary, serialize_binary};
+    use crate::values::*;
+
+    fn sample_container() -> ValueContainer {
+        let mut container = ValueContainer::builder()
+            .source("client", "session")
+            .target("server", "handler")
+            .message_type("view_test")
+            .version("2.0.1")
+            .build();
+
+        let values: Vec<Arc<dyn Value>> = vec![
+            Arc::new(BoolValue::new("flag", true)),
+            Arc::new(ShortValue::new("short", -12)),
+            Arc::new(UShortValue::new("ushort", 65000)),
+            Arc::new(IntValue::new("int", -42)),
+            Arc::new(UIntValue::new("uint", 4_000_000_000)),
+            Arc::new(LongValue::new("long", -2_000_000_000).unwrap()),
+            Arc::new(ULongValue::new("ulong", 4_000_000_000).unwrap()),
+            Arc::new(LLongValue::new("llong", i64::MIN)),
+            Arc::new(ULLongValue::new("ullong", u64::MAX)),
+            Arc::new(FloatValue::new("float", 1.5)),
+            Arc::new(DoubleValue::new("double", std::f64::consts::E)),
+            Arc::new(StringValue::new("text", "안녕 ];,")),
+            Arc::new(BytesValue::new("raw", vec![0, 1, 0xFF])),
+            Arc::new(IntValue::new("int", 99)),
+        ];
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+
+        let nested = ContainerValue::new(
+            "nested",
+            vec![
+                Arc::new(IntValue::new("id", 7)) as Arc<dyn Value>,
+                Arc::new(ArrayValue::new(
+                    "list",
+                    vec![
+                        Arc::new(IntValue::new("", 1)) as Arc<dyn Value>,
+                        Arc::new(StringValue::new("", "two")),
+                    ],
+                )),
+            ],
+        );
+        container.add_value(Arc::new(nested)).unwrap();
+        container
+    }
+
+    fn error_offset(error: ContainerError) -> usize {
+        match error {
+            ContainerError::WireFormat { offset, .. } => offset,
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_header_and_values() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        let view = ContainerView::new(&bytes).unwrap();
+
+        assert_eq!(view.source_id(), "client");
+        assert_eq!(view.source_sub_id(), "session");
+        assert_eq!(view.target_id(), "server");
+        assert_eq!(view.target_sub_id(), "handler");
+        assert_eq!(view.message_type(), "view_test");
+        assert_eq!(view.version(), "2.0.1");
+        assert_eq!(view.value_count(), 15);
+        assert!(!view.is_empty());
+        assert_eq!(view.values()[0].name(), "flag");
+        assert_eq!(view.values()[14].value_type(), ValueType::Container);
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        let view = ContainerView::new(&bytes).unwrap();
+
+        assert!(view.get_bool("flag").unwrap());
+        assert_eq!(view.get_int("short").unwrap(), -12);
+        assert_eq!(view.get_int("ushort").unwrap(), 65000);
+        assert_eq!(view.get_long("uint").unwrap(), 4_000_000_000);
+        assert_eq!(view.get_int("long").unwrap(), -2_000_000_000);
+        assert_eq!(view.get_ulong("ulong").unwrap(), 4_000_000_000);
+        assert_eq!(view.get_long("llong").unwrap(), i64::MIN);
+        assert_eq!(view.get_ulong("ullong").unwrap(), u64::MAX);
+        assert_eq!(view.get_double("float").unwrap(), 1.5);
+        assert_eq!(view.get_double("double").unwrap(), std::f64::consts::E);
+        assert_eq!(view.get_double("int").unwrap(), -42.0);
+        assert_eq!(view.get_str("text").unwrap(), "안녕 ];,");
+        assert_eq!(view.get_bytes("raw").unwrap(), &[0, 1, 0xFF]);
+
+        // The first value wins when names repeat, as with ValueContainer::get_value
+        assert_eq!(view.get_int("int").unwrap(), -42);
+    }
+
+    #[test]
+    fn test_getters_borrow_from_buffer() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        let view = ContainerView::new(&bytes).unwrap();
+        let range = bytes.as_ptr_range();
+
+        let text = view.get_str("text").unwrap();
+        assert!(range.contains(&text.as_ptr()));
+        let raw = view.get_bytes("raw").unwrap();
+        assert!(range.contains(&raw.as_ptr()));
+        assert!(range.contains(&view.message_type().as_ptr()));
+    }
+
+    #[test]
+    fn test_getter_errors() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        let view = ContainerView::new(&bytes).unwrap();
+
+        assert!(matches!(
+            view.get_int("missing"),
+            Err(ContainerError::ValueNotFound(name)) if name == "missing"
+        ));
+        for result in [
+            view.get_int("uint").map(|_| ()),
+            view.get_int("ullong").map(|_| ()),
+            view.get_ulong("llong").map(|_| ()),
+            view.get_int("text").map(|_| ()),
+            view.get_bool("int").map(|_| ()),
+            view.get_str("raw").map(|_| ()),
+            view.get_bytes("text").map(|_| ()),
+            view.get_double("flag").map(|_| ()),
+        ] {
+            assert!(matches!(
+                result,
+                Err(ContainerError::InvalidTypeConversion { .. })
+            ));
+        }
+        assert!(view.get("int").unwrap().children().is_err());
+    }
+
+    #[test]
+    fn test_nested_children() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        let view = ContainerView::new(&bytes).unwrap();
+
+        let nested = view.get("nested").unwrap().children().unwrap();
+        assert_eq!(nested.len(), 2);
+        assert_eq!(nested[0].name(), "id");
+        assert_eq!(nested[0].to_int().unwrap(), 7);
+
+        let list = nested[1].children().unwrap();
+        assert_eq!(list[0].to_int().unwrap(), 1);
+        assert_eq!(list[1].as_str().unwrap(), "two");
+
+        let owned = nested[1].to_value().unwrap();
+        assert_eq!(owned.value_type(), ValueType::Array);
+        assert_eq!(owned.name(), "list");
+    }
+
+    #[test]
+    fn test_to_container_matches_deserialize() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+        let view = ContainerView::new(&bytes).unwrap();
+
+        let owned = view.to_container().unwrap();
+        assert_eq!(serialize_binary(&owned).unwrap(), bytes);
+        let expected = deserialize_binary(&bytes).unwrap();
+        assert_eq!(owned.message_type(), expected.message_type());
+        assert_eq!(owned.value_count(), expected.value_count());
+    }
+
+    #[test]
+    fn test_missing_header_fields_use_defaults() {
+        let mut bytes = b"CNTB\x01\x00".to_vec();
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+
+        let view = ContainerView::new(&bytes).unwrap();
+        assert_eq!(view.message_type(), "data_container");
+        assert_eq!(view.version(), "1.0.0.0");
+        assert_eq!(view.source_id(), "");
+        assert!(view.is_empty());
+
+        let owned = view.to_container().unwrap();
+        assert_eq!(owned.message_type(), view.message_type());
+        assert_eq!(owned.version(), view.version());
+    }
+
+    #[test]
+    fn test_malformed_input_matches_deserialize() {
+        let bytes = serialize_binary(&sample_container()).unwrap();
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        for input in [&bytes[..bytes.len() - 1], &trailing, &bad_magic] {
+            assert_eq!(
+                error_offset(ContainerView::new(input).unwrap_err()),
+                error_offset(deserialize_binary(input).unwrap_err())
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_and_string_errors_are_lazy() {
+        let mut container = ValueContainer::new();
+        container
+            .add_value(Arc::new(StringValue::new("text", "ok")))
+            .unwrap();
+        container
+            .add_value(Arc::new(ContainerValue::new(
+                "nested",
+                vec![Arc::new(IntValue::new("id", 7)) as Arc<dyn Value>],
+            )))
+            .unwrap();
+        let mut bytes = serialize_binary(&container).unwrap();
+
+        // Corrupt the string payload and the nested child's type byte
+        let text_at = bytes.windows(2).position(|w| w == b"ok").unwrap();
+        bytes[text_at] = 0xFF;
+        let child_at = bytes.len() - (1 + 4 + 2 + 4 + 4);
+        bytes[child_at] = 99;
+
+        let view = ContainerView::new(&bytes).unwrap();
+        assert_eq!(error_offset(view.get_str("text").unwrap_err()), text_at);
+        assert_eq!(
+            error_offset(view.get("nested").unwrap().children().unwrap_err()),
+            child_at
+        );
+        assert_eq!(
+            error_offset(view.get("nested").unwrap().to_value().unwrap_err()),
+            child_at
+        );
+    }
+}
@@ -0,0 +1,501 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Structural Diff
+//!
+//! [`diff`] compares two containers and lists what changed from the first to the
+//! second: header fields, then values in tree order, descending into
+//! `ContainerValue` children and `ArrayValue` elements.
+//!
+//! - Values are paired by name; repeated names are paired by position, so
+//!   reordering values with the same name is a change. The order of values with
+//!   different names is not compared.
+//! - Array elements are paired by index.
+//! - A value whose type differs is reported once as [`Change::TypeChanged`]
+//!   without comparing what is inside it.
+//!
+//! Paths use [`value_path`] syntax; a repeated name gets an index on both sides
+//! (`tag{1}`) when either container repeats it.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::diff::{diff, Change};
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let mut old = ValueContainer::new();
+//! old.add_value(Arc::new(IntValue::new("count", 1))).unwrap();
+//! old.add_value(Arc::new(StringValue::new("note", "hi"))).unwrap();
+//!
+//! let mut new = old.copy(true);
+//! new.set_message_type("update");
+//! new.set_path("count", Arc::new(IntValue::new("count", 2))).unwrap();
+//! new.remove_value("note");
+//!
+//! let changes = diff(&old, &new);
+//! assert_eq!(changes.len(), 3);
+//! assert!(matches!(&changes.changes()[1], Change::Modified { path, .. } if path == "count"));
+//! println!("{}", changes);
+//! // header message_type: "data_container" -> "update"
+//! // ~ count: 1 -> 2
+//! // - note: string_value "hi"
+//! ```
+//!
+//! [`diff`]: crate::core::diff::diff
+//! [`Change::TypeChanged`]: crate::core::diff::Change::TypeChanged
+//! [`value_path`]: crate::core::value_path
+
+use super::container::ValueContainer;
+use super::value_data::ValueData;
+use super::value_path::{child_path, element_path};
+use super::{Value, ValueType};
+use crate::values::{ArrayValue, ContainerValue};
+use indexmap::IndexMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// One difference between two containers
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// A header field differs (`field` is e.g. `message_type`)
+    Header {
+        field: &'static str,
+        old: String,
+        new: String,
+    },
+    /// The value exists only in the second container
+    Added { path: String, value: Arc<dyn Value> },
+    /// The value exists only in the first container
+    Removed { path: String, value: Arc<dyn Value> },
+    /// Same type, different value
+    Modified {
+        path: String,
+        old: Arc<dyn Value>,
+        new: Arc<dyn Value>,
+    },
+    /// Different `ValueType`
+    TypeChanged {
+        path: String,
+        old: Arc<dyn Value>,
+        new: Arc<dyn Value>,
+    },
+}
+
+impl Change {
+    /// Path of the value, or the header field name
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Header { field, .. } => field,
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Modified { path, .. }
+            | Change::TypeChanged { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Header { field, old, new } => {
+                write!(f, "header {}: {:?} -> {:?}", field, old, new)
+            }
+            Change::Added { path, value } => {
+                write!(f, "+ {}: {} {}", path, value.value_type(), Describe(value))
+            }
+            Change::Removed { path, value } => {
+                write!(f, "- {}: {} {}", path, value.value_type(), Describe(value))
+            }
+            Change::Modified { path, old, new } => {
+                write!(f, "~ {}: {} -> {}", path, Describe(old), Describe(new))
+            }
+            Change::TypeChanged { path, old, new } => write!(
+                f,
+                "! {}: {} {} -> {} {}",
+                path,
+                old.value_type(),
+                Describe(old),
+                new.value_type(),
+                Describe(new)
+            ),
+        }
+    }
+}
+
+/// Short text for a value in a report
+struct Describe<'a>(&'a Arc<dyn Value>);
+
+impl fmt::Display for Describe<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let any = self.0.as_any();
+        if let Some(container) = any.downcast_ref::<ContainerValue>() {
+            write!(f, "({} children)", container.child_count())
+        } else if let Some(array) = any.downcast_ref::<ArrayValue>() {
+            write!(f, "({} elements)", array.count())
+        } else if self.0.value_type() == ValueType::String {
+            write!(f, "{:?}", self.0.to_string())
+        } else {
+            f.write_str(&self.0.to_string())
+        }
+    }
+}
+
+/// The differences between two containers, see [`diff`]
+#[derive(Debug, Clone, Default)]
+pub struct ContainerDiff {
+    changes: Vec<Change>,
+}
+
+impl ContainerDiff {
+    /// Whether the containers are equal
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Number of changes
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Header changes first, then value changes in tree order
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Iterate over the changes
+    pub fn iter(&self) -> std::slice::Iter<'_, Change> {
+        self.changes.iter()
+    }
+
+    /// Human-readable report, one change per line
+    ///
+    /// Lines start with `header`, `+` (added), `-` (removed), `~` (modified) or
+    /// `!` (type changed); equal containers give an empty string.
+    pub fn report(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for ContainerDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for ContainerDiff {
+    type Item = Change;
+    type IntoIter = std::vec::IntoIter<Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ContainerDiff {
+    type Item = &'a Change;
+    type IntoIter = std::slice::Iter<'a, Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+type HeaderGetter = fn(&ValueContainer) -> String;
+
+/// Values with one name, from the old and the new side
+type Pairs<'a> = (Vec<&'a Arc<dyn Value>>, Vec<&'a Arc<dyn Value>>);
+
+/// Compare two containers
+pub fn diff(old: &ValueContainer, new: &ValueContainer) -> ContainerDiff {
+    let mut changes = diff_headers(old, new);
+    diff_named(&old.values(), &new.values(), "", &mut changes);
+    ContainerDiff { changes }
+}
+
+/// `Change::Header` for each header field that differs
+pub(crate) fn diff_headers(old: &ValueContainer, new: &ValueContainer) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let headers: [(&'static str, HeaderGetter); 6] = [
+        ("source_id", ValueContainer::source_id),
+        ("source_sub_id", ValueContainer::source_sub_id),
+        ("target_id", ValueContainer::target_id),
+        ("target_sub_id", ValueContainer::target_sub_id),
+        ("message_type", ValueContainer::message_type),
+        ("version", ValueContainer::version),
+    ];
+    for (field, get) in headers {
+        let (old, new) = (get(old), get(new));
+        if old != new {
+            changes.push(Change::Header { field, old, new });
+        }
+    }
+    changes
+}
+
+/// Compare two values (e.g. two `ContainerValue`s or `ArrayValue`s)
+///
+/// Paths are relative to the values; below arrays they start with an index.
+pub fn diff_values(old: &Arc<dyn Value>, new: &Arc<dyn Value>) -> ContainerDiff {
+    let mut changes = Vec::new();
+    diff_value(old, new, String::new(), &mut changes);
+    ContainerDiff { changes }
+}
+
+/// Compare levels of named values, pairing repeated names by position
+fn diff_named(
+    old: &[Arc<dyn Value>],
+    new: &[Arc<dyn Value>],
+    parent: &str,
+    changes: &mut Vec<Change>,
+) {
+    let mut names: IndexMap<&str, Pairs<'_>> = IndexMap::new();
+    for value in old {
+        names.entry(value.name()).or_default().0.push(value);
+    }
+    for value in new {
+        names.entry(value.name()).or_default().1.push(value);
+    }
+
+    for (name, (old, new)) in names {
+        let repeated = old.len() > 1 || new.len() > 1;
+        for nth in 0..old.len().max(new.len()) {
+            let path = child_path(parent, name, repeated.then_some(nth));
+            match (old.get(nth), new.get(nth)) {
+                (Some(&old), Some(&new)) => diff_value(old, new, path, changes),
+                (Some(&old), None) => changes.push(Change::Removed {
+                    path,
+                    value: Arc::clone(old),
+                }),
+                (None, Some(&new)) => changes.push(Change::Added {
+                    path,
+                    value: Arc::clone(new),
+                }),
+                (None, None) => unreachable!("nth is below one of the lengths"),
+            }
+        }
+    }
+}
+
+fn diff_value(old: &Arc<dyn Value>, new: &Arc<dyn Value>, path: String, changes: &mut Vec<Change>) {
+    if old.value_type() != new.value_type() {
+        changes.push(Change::TypeChanged {
+            path,
+            old: Arc::clone(old),
+            new: Arc::clone(new),
+        });
+        return;
+    }
+
+    let (old_any, new_any) = (old.as_any(), new.as_any());
+    if let (Some(old), Some(new)) = (
+        old_any.downcast_ref::<ContainerValue>(),
+        new_any.downcast_ref::<ContainerValue>(),
+    ) {
+        diff_named(old.children(), new.children(), &path, changes);
+    } else if let (Some(old), Some(new)) = (
+        old_any.downcast_ref::<ArrayValue>(),
+        new_any.downcast_ref::<ArrayValue>(),
+    ) {
+        diff_elements(old.elements(), new.elements(), &path, changes);
+    } else if !same_payload(old.as_ref(), new.as_ref()) {
+        changes.push(Change::Modified {
+            path,
+            old: Arc::clone(old),
+            new: Arc::clone(new),
+        });
+    }
+}
+
+fn diff_elements(
+    old: &[Arc<dyn Value>],
+    new: &[Arc<dyn Value>],
+    parent: &str,
+    changes: &mut Vec<Change>,
+) {
+    for index in 0..old.len().max(new.len()) {
+        let path = element_path(parent, index);
+        match (old.get(index), new.get(index)) {
+            (Some(old), Some(new)) => diff_value(old, new, path, changes),
+            (Some(old), None) => changes.push(Change::Removed {
+                path,
+                value: Arc::clone(old),
+            }),
+            (None, Some(new)) => changes.push(Change::Added {
+                path,
+                value: Arc::clone(new),
+            }),
+            (None, None) => unreachable!("index is below one of the lengths"),
+        }
+    }
+}
+
+/// Whether two values of the same type hold the same payload (names are not compared)
+pub(crate) fn same_payload(old: &dyn Value, new: &dyn Value) -> bool {
+    match (ValueData::from_value(old), ValueData::from_value(new)) {
+        // NaN equals NaN here, so an unchanged NaN is not reported
+        (Ok(ValueData::Float(a)), Ok(ValueData::Float(b))) => a.to_bits() == b.to_bits(),
+        (Ok(ValueData::Double(a)), Ok(ValueData::Double(b))) => a.to_bits() == b.to_bits(),
+        (Ok(a), Ok(b)) => a == b,
+        // Values outside the built-in types: compare their text and binary forms
+        _ => old.to_string() == new.to_string() && old.to_bytes() == new.to_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::{DoubleValue, IntValue, LLongValue, StringValue};
+
+    fn order(qty: &[i32]) -> Arc<dyn Value> {
+        let items = qty
+            .iter()
+            .map(|&q| -> Arc<dyn Value> {
+                Arc::new(ContainerValue::new(
+                    "",
+                    vec![Arc::new(IntValue::new("qty", q))],
+                ))
+            })
+            .collect();
+        Arc::new(ContainerValue::new(
+            "order",
+            vec![
+                Arc::new(ArrayValue::new("items", items)),
+                Arc::new(DoubleValue::new("total", 1.5)),
+            ],
+        ))
+    }
+
+    fn container(values: Vec<Arc<dyn Value>>) -> ValueContainer {
+        let mut container = ValueContainer::new();
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+        container
+    }
+
+    fn paths(changes: &ContainerDiff) -> Vec<&str> {
+        changes.iter().map(Change::path).collect()
+    }
+
+    #[test]
+    fn test_equal_containers() {
+        let old = container(vec![order(&[1, 2]), Arc::new(IntValue::new("n", 1))]);
+        // Different order of different names is not a change
+        let new = container(vec![Arc::new(IntValue::new("n", 1)), order(&[1, 2])]);
+        let changes = diff(&old, &new);
+        assert!(changes.is_empty(), "{}", changes);
+        assert_eq!(changes.report(), "");
+    }
+
+    #[test]
+    fn test_headers() {
+        let old = ValueContainer::new();
+        let mut new = ValueContainer::new();
+        new.set_target("server", "main");
+        new.set_version("2.0");
+
+        let changes = diff(&old, &new);
+        assert_eq!(paths(&changes), ["target_id", "target_sub_id", "version"]);
+        assert_eq!(
+            changes.report(),
+            "header target_id: \"\" -> \"server\"\n\
+             header target_sub_id: \"\" -> \"main\"\n\
+             header version: \"1.0.0.0\" -> \"2.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_changes() {
+        let old = container(vec![order(&[1, 2, 3])]);
+        let new = container(vec![order(&[1, 5])]);
+        let changes = diff(&old, &new);
+
+        assert_eq!(paths(&changes), ["order.items[1].qty", "order.items[2]"]);
+        assert!(
+            matches!(&changes.changes()[0], Change::Modified { old, new, .. }
+            if old.to_int().unwrap() == 2 && new.to_int().unwrap() == 5)
+        );
+        assert!(matches!(&changes.changes()[1], Change::Removed { .. }));
+        assert_eq!(
+            changes.report(),
+            "~ order.items[1].qty: 2 -> 5\n- order.items[2]: container_value (1 children)\n"
+        );
+    }
+
+    #[test]
+    fn test_type_changes() {
+        let old = container(vec![
+            Arc::new(IntValue::new("id", 7)),
+            Arc::new(StringValue::new("name", "a")),
+        ]);
+        let new = container(vec![
+            Arc::new(LLongValue::new("id", 7)),
+            Arc::new(StringValue::new("name", "b")),
+        ]);
+        let changes = diff(&old, &new);
+
+        assert!(matches!(&changes.changes()[0], Change::TypeChanged { path, .. } if path == "id"));
+        assert_eq!(
+            changes.report(),
+            "! id: int_value 7 -> llong_value 7\n~ name: \"a\" -> \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn test_repeated_names() {
+        let tag = |s: &str| -> Arc<dyn Value> { Arc::new(StringValue::new("tag", s)) };
+        let old = container(vec![tag("x"), tag("y")]);
+
+        // Swapping values with the same name changes both positions
+        let swapped = container(vec![tag("y"), tag("x")]);
+        assert_eq!(paths(&diff(&old, &swapped)), ["tag{0}", "tag{1}"]);
+
+        // A repeated name keeps its index even when one side has a single value
+        let single = container(vec![tag("x")]);
+        let changes = diff(&old, &single);
+        assert_eq!(changes.report(), "- tag{1}: string_value \"y\"\n");
+
+        let added = diff(&single, &old);
+        assert!(matches!(&added.changes()[0], Change::Added { path, .. } if path == "tag{1}"));
+    }
+
+    #[test]
+    fn test_diff_values() {
+        let changes = diff_values(&order(&[1]), &order(&[1, 2]));
+        assert_eq!(paths(&changes), ["items[1]"]);
+        assert!(diff_values(&order(&[1]), &order(&[1])).is_empty());
+
+        let nan: Arc<dyn Value> = Arc::new(DoubleValue::new("x", f64::NAN));
+        assert!(diff_values(&nan, &nan.clone_value()).is_empty());
+    }
+}
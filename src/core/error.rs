@@ -73,6 +73,46 @@ pub enum ContainerError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    /// Malformed wire protocol or binary input (offset is in bytes from the start of the input)
+    #[error("Wire format error at byte {offset}: {message}")]
+    WireFormat { offset: usize, message: String },
+
+    /// Path expression that is malformed or does not match the container
+    /// (`segment` is the part of the path that failed)
+    #[error("Path error in '{path}' at '{segment}': {message}")]
+    PathError {
+        path: String,
+        segment: String,
+        message: String,
+    },
+
+    /// A patch operation or its precondition failed (nothing was applied)
+    #[error("Patch operation {index} ({op}) failed: {message}")]
+    PatchError {
+        index: usize,
+        op: &'static str,
+        message: String,
+    },
+
+    /// Both sides of a merge hold a different value (nothing was merged)
+    #[error("Merge conflict at '{path}': existing {existing}, incoming {incoming}")]
+    MergeConflict {
+        path: String,
+        existing: String,
+        incoming: String,
+    },
+
+    /// Invalid schema definition, or no schema for a message type
+    #[error("Schema error: {0}")]
+    SchemaError(String),
+
+    /// A container does not match its schema (one entry per violation, with its path)
+    #[error("Container '{message_type}' violates its schema: {}", violations.join("; "))]
+    SchemaViolation {
+        message_type: String,
+        violations: Vec<String>,
+    },
+
     /// Thread safety error
     #[error("Thread safety error: {0}")]
     ThreadSafetyError(String),
@@ -95,3 +135,17 @@ impl From<quick_xml::DeError> for ContainerError {
         ContainerError::XmlError(err.to_string())
     }
 }
+
+/// Allow ContainerError as the error type of serde serializers
+impl serde::ser::Error for ContainerError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ContainerError::SerializationError(msg.to_string())
+    }
+}
+
+/// Allow ContainerError as the error type of serde deserializers
+impl serde::de::Error for ContainerError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ContainerError::DeserializationError(msg.to_string())
+    }
+}
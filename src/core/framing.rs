@@ -0,0 +1,689 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Container Framing
+//!
+//! This module puts serialized containers back to back on a byte stream (a file, pipe
+//! or socket) and splits them again. Every container travels in a frame that names its
+//! serialization format and length, so a reader never has to parse a payload to find
+//! where the next one starts.
+//!
+//! # Layout
+//!
+//! All integers are little-endian.
+//!
+//! ```text
+//! [magic:4 = "CNTF"][format:1][flags:1][payload_len:4][crc32:4, if flags & 1][payload]
+//! ```
+//!
+//! | Format tag | Payload |
+//! |------------|---------|
+//! | 1 | C++ wire protocol text (`SerializationFormat::WireProtocol`) |
+//! | 2 | Binary container format (`SerializationFormat::Binary`) |
+//! | 3 | JSON v2.0 (`SerializationFormat::JsonV2`) |
+//!
+//! The optional checksum is the CRC-32 (IEEE) of the payload. Flag bits other than
+//! bit 0 are reserved and rejected.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::prelude::*;
+//! use rust_container_system::core::framing::{FrameReader, FrameWriter};
+//! use std::sync::Arc;
+//!
+//! let mut writer = FrameWriter::new(Vec::new(), SerializationFormat::Binary)
+//!     .unwrap()
+//!     .with_checksum(true);
+//! for id in 0..3 {
+//!     let mut container = ValueContainer::new();
+//!     container.add_value(Arc::new(IntValue::new("id", id))).unwrap();
+//!     writer.write_container(&container).unwrap();
+//! }
+//! let stream = writer.into_inner();
+//!
+//! let reader = FrameReader::new(stream.as_slice());
+//! let ids: Vec<i32> = reader
+//!     .map(|container| container.unwrap().get_value("id").unwrap().to_int().unwrap())
+//!     .collect();
+//! assert_eq!(ids, [0, 1, 2]);
+//! ```
+
+use crate::core::binary_protocol::{deserialize_binary, serialize_binary};
+use crate::core::json_v2_adapter::{JsonV2Adapter, SerializationFormat};
+use crate::core::value_codec::{offset_by, BinaryReader};
+use crate::core::wire_protocol::{read_cpp_wire, serialize_cpp_wire};
+use crate::core::{ContainerError, Result, ValueContainer};
+use std::io::{ErrorKind, Read, Write};
+
+/// Magic bytes at the start of every frame
+pub const FRAME_MAGIC: [u8; 4] = *b"CNTF";
+
+/// Size of a frame header without the optional checksum
+pub const FRAME_HEADER_LEN: usize = 10;
+
+/// Default upper bound on the payload length accepted by a [`FrameReader`] (16 MiB)
+pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Flag bit marking that a CRC-32 of the payload follows the length
+const FLAG_CHECKSUM: u8 = 0x01;
+
+const TAG_WIRE: u8 = 1;
+const TAG_BINARY: u8 = 2;
+const TAG_JSON_V2: u8 = 3;
+
+fn format_tag(format: SerializationFormat) -> Option<u8> {
+    match format {
+        SerializationFormat::WireProtocol => Some(TAG_WIRE),
+        SerializationFormat::Binary => Some(TAG_BINARY),
+        SerializationFormat::JsonV2 => Some(TAG_JSON_V2),
+        _ => None,
+    }
+}
+
+fn format_from_tag(tag: u8) -> Option<SerializationFormat> {
+    match tag {
+        TAG_WIRE => Some(SerializationFormat::WireProtocol),
+        TAG_BINARY => Some(SerializationFormat::Binary),
+        TAG_JSON_V2 => Some(SerializationFormat::JsonV2),
+        _ => None,
+    }
+}
+
+fn unsupported_format(format: SerializationFormat) -> ContainerError {
+    ContainerError::InvalidDataFormat(format!(
+        "format '{}' cannot be framed (use wire, binary or v2.0)",
+        format
+    ))
+}
+
+/// One serialized container together with its format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Serialization format of the payload
+    pub format: SerializationFormat,
+    /// The serialized container
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Serialize `container` in `format`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidDataFormat` if `format` is not wire, binary or
+    /// JSON v2.0, or any error from the serializer.
+    pub fn encode(container: &ValueContainer, format: SerializationFormat) -> Result<Self> {
+        let payload = match format {
+            SerializationFormat::WireProtocol => serialize_cpp_wire(container)?.into_bytes(),
+            SerializationFormat::Binary => serialize_binary(container)?,
+            SerializationFormat::JsonV2 => {
+                JsonV2Adapter::to_v2_json(container, false)?.into_bytes()
+            }
+            other => return Err(unsupported_format(other)),
+        };
+        Ok(Self { format, payload })
+    }
+
+    /// Deserialize the payload
+    ///
+    /// # Errors
+    ///
+    /// Returns the payload format's deserialization error. `ContainerError::WireFormat`
+    /// offsets are relative to the start of the payload.
+    pub fn decode(&self) -> Result<ValueContainer> {
+        match self.format {
+            SerializationFormat::WireProtocol => read_cpp_wire(&mut self.payload.as_slice()),
+            SerializationFormat::Binary => deserialize_binary(&self.payload),
+            SerializationFormat::JsonV2 => {
+                let json = std::str::from_utf8(&self.payload).map_err(|e| {
+                    BinaryReader::error(e.valid_up_to(), format!("invalid UTF-8: {}", e))
+                })?;
+                JsonV2Adapter::from_v2_json(json)
+            }
+            other => Err(unsupported_format(other)),
+        }
+    }
+}
+
+/// Writes containers to any `Write` as a sequence of frames
+///
+/// Each frame is written with a single `write_all` of its header followed by one of
+/// its payload; wrap unbuffered writers in a `BufWriter` if that matters.
+#[derive(Debug)]
+pub struct FrameWriter<W> {
+    writer: W,
+    format: SerializationFormat,
+    checksum: bool,
+}
+
+impl<W: Write> FrameWriter<W> {
+    /// Create a writer that serializes containers in `format`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidDataFormat` if `format` is not wire, binary or
+    /// JSON v2.0.
+    pub fn new(writer: W, format: SerializationFormat) -> Result<Self> {
+        format_tag(format).ok_or_else(|| unsupported_format(format))?;
+        Ok(Self {
+            writer,
+            format,
+            checksum: false,
+        })
+    }
+
+    /// Enable or disable the CRC-32 checksum on frames written from now on
+    #[must_use]
+    pub fn with_checksum(mut self, enabled: bool) -> Self {
+        self.checksum = enabled;
+        self
+    }
+
+    /// Serialize `container` and write it as one frame
+    ///
+    /// # Errors
+    ///
+    /// Returns any serialization error, `ContainerError::InvalidDataFormat` if the
+    /// payload exceeds 4 GiB, or `ContainerError::IoError` if writing fails.
+    pub fn write_container(&mut self, container: &ValueContainer) -> Result<()> {
+        self.write_frame(&Frame::encode(container, self.format)?)
+    }
+
+    /// Write an already serialized frame, in its own format
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::InvalidDataFormat` if the frame's format cannot be
+    /// framed or its payload exceeds 4 GiB, or `ContainerError::IoError` if writing
+    /// fails.
+    pub fn write_frame(&mut self, frame: &Frame) -> Result<()> {
+        let tag = format_tag(frame.format).ok_or_else(|| unsupported_format(frame.format))?;
+        let len = u32::try_from(frame.payload.len()).map_err(|_| {
+            ContainerError::InvalidDataFormat(format!(
+                "frame payload of {} bytes exceeds the 4 GiB limit",
+                frame.payload.len()
+            ))
+        })?;
+
+        let mut header = Vec::with_capacity(FRAME_HEADER_LEN + 4);
+        header.extend_from_slice(&FRAME_MAGIC);
+        header.push(tag);
+        header.push(if self.checksum { FLAG_CHECKSUM } else { 0 });
+        header.extend_from_slice(&len.to_le_bytes());
+        if self.checksum {
+            header.extend_from_slice(&crc32(&frame.payload).to_le_bytes());
+        }
+
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&frame.payload)?;
+        Ok(())
+    }
+
+    /// Flush the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::IoError` if flushing fails.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Get a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying writer
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Unwrap the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads frames from any `Read`
+///
+/// Iterating yields one decoded container per frame and stops at the end of the
+/// stream. The stream may only end between frames. After the first error the
+/// iterator is fused, since the position of the next frame is no longer known.
+///
+/// Error offsets count from where reading started; `ContainerError::WireFormat`
+/// errors from a payload are shifted by the payload's position in the stream.
+#[derive(Debug)]
+pub struct FrameReader<R> {
+    reader: R,
+    max_frame_len: usize,
+    /// Bytes consumed so far
+    offset: usize,
+    failed: bool,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Create a reader accepting payloads up to [`DEFAULT_MAX_FRAME_LEN`]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            offset: 0,
+            failed: false,
+        }
+    }
+
+    /// Set the largest payload length accepted
+    ///
+    /// Longer frames are rejected before any of their payload is read.
+    #[must_use]
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Read the next frame without decoding its payload
+    ///
+    /// Returns `Ok(None)` if the stream ends cleanly before a new frame starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ContainerError::WireFormat` if the magic, format tag or flags are
+    /// invalid, the payload length exceeds the limit, the checksum does not match or
+    /// the stream ends inside a frame, and `ContainerError::IoError` if reading fails.
+    pub fn read_frame(&mut self) -> Result<Option<Frame>> {
+        let result = self.next_frame();
+        if result.is_err() {
+            self.failed = true;
+        }
+        result
+    }
+
+    /// Read and decode the next container
+    ///
+    /// Returns `Ok(None)` if the stream ends cleanly before a new frame starts.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`read_frame`](Self::read_frame), plus the payload format's
+    /// deserialization error.
+    pub fn read_container(&mut self) -> Result<Option<ValueContainer>> {
+        let Some(frame) = self.read_frame()? else {
+            return Ok(None);
+        };
+        // The payload is the last part of the frame
+        let payload_offset = self.offset - frame.payload.len();
+        frame
+            .decode()
+            .map(Some)
+            .map_err(|e| offset_by(e, payload_offset))
+    }
+
+    /// Get a reference to the underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwrap the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn next_frame(&mut self) -> Result<Option<Frame>> {
+        let start = self.offset;
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        match self.fill(&mut header)? {
+            0 => return Ok(None),
+            FRAME_HEADER_LEN => {}
+            read => return Err(truncated(start + read, FRAME_HEADER_LEN - read)),
+        }
+
+        if header[..4] != FRAME_MAGIC {
+            return Err(BinaryReader::error(
+                start,
+                "not a container frame (bad magic)",
+            ));
+        }
+        let format = format_from_tag(header[4]).ok_or_else(|| {
+            BinaryReader::error(start + 4, format!("unknown frame format tag {}", header[4]))
+        })?;
+        let flags = header[5];
+        if flags & !FLAG_CHECKSUM != 0 {
+            return Err(BinaryReader::error(
+                start + 5,
+                format!("unsupported frame flags {:#04x}", flags),
+            ));
+        }
+        let len = u32::from_le_bytes([header[6], header[7], header[8], header[9]]) as usize;
+        if len > self.max_frame_len {
+            return Err(BinaryReader::error(
+                start + 6,
+                format!(
+                    "frame payload of {} bytes exceeds the limit of {}",
+                    len, self.max_frame_len
+                ),
+            ));
+        }
+
+        let expected_crc = if flags & FLAG_CHECKSUM != 0 {
+            let mut crc = [0u8; 4];
+            let read = self.fill(&mut crc)?;
+            if read < crc.len() {
+                return Err(truncated(self.offset, crc.len() - read));
+            }
+            Some(u32::from_le_bytes(crc))
+        } else {
+            None
+        };
+
+        // The payload is read through `take` so a bogus length never allocates up front
+        let payload_offset = self.offset;
+        let mut payload = Vec::new();
+        let read = (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut payload)?;
+        self.offset += read;
+        if read < len {
+            return Err(truncated(self.offset, len - read));
+        }
+
+        if let Some(expected) = expected_crc {
+            let actual = crc32(&payload);
+            if actual != expected {
+                return Err(BinaryReader::error(
+                    payload_offset,
+                    format!(
+                        "frame checksum mismatch (expected {:08x}, got {:08x})",
+                        expected, actual
+                    ),
+                ));
+            }
+        }
+
+        Ok(Some(Frame { format, payload }))
+    }
+
+    /// Read until `buf` is full or the stream ends, returning the bytes read
+    fn fill(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.offset += filled;
+        Ok(filled)
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = Result<ValueContainer>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read_container();
+        if result.is_err() {
+            self.failed = true;
+        }
+        result.transpose()
+    }
+}
+
+fn truncated(offset: usize, missing: usize) -> ContainerError {
+    BinaryReader::error(
+        offset,
+        format!(
+            "stream ended inside a frame ({} more bytes expected)",
+            missing
+        ),
+    )
+}
+
+/// CRC-32 (IEEE 802.3, reflected) lookup table
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::Trickle;
+    use crate::values::*;
+    use std::sync::Arc;
+
+    const FORMATS: [SerializationFormat; 3] = [
+        SerializationFormat::WireProtocol,
+        SerializationFormat::Binary,
+        SerializationFormat::JsonV2,
+    ];
+
+    fn sample_container(id: i32) -> ValueContainer {
+        let mut container = ValueContainer::builder()
+            .source("client", "session")
+            .message_type("framed")
+            .build();
+        container
+            .add_value(Arc::new(IntValue::new("id", id)))
+            .unwrap();
+        container
+            .add_value(Arc::new(StringValue::new("text", "안녕 ];,")))
+            .unwrap();
+        container
+            .add_value(Arc::new(BytesValue::new("raw", vec![0, 1, 0xFF])))
+            .unwrap();
+        container
+    }
+
+    fn framed(format: SerializationFormat, checksum: bool, count: i32) -> Vec<u8> {
+        let mut writer = FrameWriter::new(Vec::new(), format)
+            .unwrap()
+            .with_checksum(checksum);
+        for id in 0..count {
+            writer.write_container(&sample_container(id)).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    fn error_offset(error: ContainerError) -> usize {
+        match error {
+            ContainerError::WireFormat { offset, .. } => offset,
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_roundtrip_every_format() {
+        for format in FORMATS {
+            for checksum in [false, true] {
+                let stream = framed(format, checksum, 3);
+                let mut reader = FrameReader::new(Trickle(&stream));
+                for id in 0..3 {
+                    let container = reader.read_container().unwrap().unwrap();
+                    assert_eq!(container.message_type(), "framed");
+                    assert_eq!(container.get_value("id").unwrap().to_int().unwrap(), id);
+                    assert_eq!(container.get_value("text").unwrap().to_string(), "안녕 ];,");
+                }
+                assert!(reader.read_container().unwrap().is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_frame_layout() {
+        let container = sample_container(1);
+        let stream = framed(SerializationFormat::Binary, true, 2);
+        let payload = serialize_binary(&container).unwrap();
+
+        assert_eq!(&stream[..4], b"CNTF");
+        assert_eq!(stream[4], TAG_BINARY);
+        assert_eq!(stream[5], FLAG_CHECKSUM);
+        let first_len = u32::from_le_bytes(stream[6..10].try_into().unwrap()) as usize;
+        assert_eq!(stream.len(), 2 * (FRAME_HEADER_LEN + 4 + first_len));
+
+        let mut reader = FrameReader::new(stream.as_slice());
+        reader.read_frame().unwrap();
+        let frame = reader.read_frame().unwrap().unwrap();
+        assert_eq!(frame.format, SerializationFormat::Binary);
+        assert_eq!(frame.payload, payload);
+    }
+
+    #[test]
+    fn test_mixed_formats_on_one_stream() {
+        let mut stream = Vec::new();
+        for (id, format) in FORMATS.into_iter().enumerate() {
+            FrameWriter::new(&mut stream, format)
+                .unwrap()
+                .write_container(&sample_container(id as i32))
+                .unwrap();
+        }
+
+        let mut reader = FrameReader::new(stream.as_slice());
+        for format in FORMATS {
+            assert_eq!(reader.read_frame().unwrap().unwrap().format, format);
+        }
+        assert!(reader.read_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unsupported_writer_format() {
+        for format in [SerializationFormat::CppJson, SerializationFormat::Unknown] {
+            assert!(matches!(
+                FrameWriter::new(Vec::new(), format),
+                Err(ContainerError::InvalidDataFormat(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_checksum_mismatch_detected() {
+        let mut stream = framed(SerializationFormat::Binary, true, 1);
+        let last = stream.len() - 1;
+        stream[last] ^= 0x01;
+
+        let error = FrameReader::new(stream.as_slice())
+            .read_frame()
+            .unwrap_err();
+        assert_eq!(error_offset(error), FRAME_HEADER_LEN + 4);
+    }
+
+    #[test]
+    fn test_malformed_headers() {
+        let good = framed(SerializationFormat::Binary, false, 1);
+
+        let mut bad_magic = good.clone();
+        bad_magic[0] = b'X';
+        let mut bad_tag = good.clone();
+        bad_tag[4] = 9;
+        let mut bad_flags = good.clone();
+        bad_flags[5] = 0x80;
+        for (stream, offset) in [(bad_magic, 0), (bad_tag, 4), (bad_flags, 5)] {
+            let error = FrameReader::new(stream.as_slice())
+                .read_frame()
+                .unwrap_err();
+            assert_eq!(error_offset(error), offset);
+        }
+
+        let error = FrameReader::new(good.as_slice())
+            .with_max_frame_len(8)
+            .read_frame()
+            .unwrap_err();
+        assert_eq!(error_offset(error), 6);
+    }
+
+    #[test]
+    fn test_truncated_stream() {
+        let stream = framed(SerializationFormat::WireProtocol, true, 2);
+        let first_len = stream.len() / 2;
+
+        for cut in [
+            first_len + 3,
+            first_len + FRAME_HEADER_LEN + 2,
+            stream.len() - 1,
+        ] {
+            let mut reader = FrameReader::new(&stream[..cut]);
+            assert!(reader.read_container().unwrap().is_some());
+            let error = reader.read_container().unwrap_err();
+            assert_eq!(error_offset(error), cut);
+        }
+    }
+
+    #[test]
+    fn test_payload_error_offset_is_shifted() {
+        let first = framed(SerializationFormat::Binary, false, 1);
+        let mut stream = first.clone();
+        stream.extend_from_slice(&first);
+        // Corrupt the format version byte inside the second payload
+        stream[first.len() + FRAME_HEADER_LEN + 4] = 99;
+
+        let mut reader = FrameReader::new(stream.as_slice());
+        assert!(reader.next().unwrap().is_ok());
+        let error = reader.next().unwrap().unwrap_err();
+        assert_eq!(error_offset(error), first.len() + FRAME_HEADER_LEN + 4);
+        assert!(reader.next().is_none());
+    }
+}
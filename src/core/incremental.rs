@@ -0,0 +1,678 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Incremental Decoders
+//!
+//! Push-based decoders for containers that arrive in pieces, such as reads from a
+//! non-blocking socket. Each chunk is passed to `feed` as soon as it is received; the
+//! decoder consumes what it can, keeps the rest buffered and reports a
+//! `DecodeStatus`:
+//!
+//! - `NeedMoreData(n)`: the message is incomplete and at least `n` more bytes are needed
+//! - `Complete(container)`: a whole container was decoded
+//!
+//! Bytes after a completed container stay buffered for the next one, so messages sent
+//! back to back can be decoded from one stream. Call `feed(&[])` to decode a further
+//! message that is already buffered.
+//!
+//! `WireDecoder` handles the C++ wire protocol text format and `BinaryDecoder` the
+//! binary container format. Both decode value by value, so only the value currently
+//! being received is kept in the buffer. Error offsets count from the first byte fed
+//! to the decoder. After an error, call `reset` before feeding a new stream.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::prelude::*;
+//! use rust_container_system::core::incremental::{DecodeStatus, WireDecoder};
+//! use std::sync::Arc;
+//!
+//! let mut container = ValueContainer::new();
+//! container.set_message_type("user_data");
+//! container.add_value(Arc::new(IntValue::new("count", 42))).unwrap();
+//! let wire = container.serialize_cpp_wire().unwrap();
+//! let (first, second) = wire.as_bytes().split_at(20);
+//!
+//! let mut decoder = WireDecoder::new();
+//! assert!(matches!(decoder.feed(first).unwrap(), DecodeStatus::NeedMoreData(_)));
+//! match decoder.feed(second).unwrap() {
+//!     DecodeStatus::Complete(restored) => {
+//!         assert_eq!(restored.message_type(), "user_data");
+//!         assert_eq!(restored.get_value("count").unwrap().to_int().unwrap(), 42);
+//!     }
+//!     DecodeStatus::NeedMoreData(_) => unreachable!(),
+//! }
+//! ```
+
+use crate::core::binary_protocol::{Header, BINARY_FORMAT_VERSION, BINARY_MAGIC};
+use crate::core::value_codec::{offset_by, BinaryReader, ValueDecoder};
+use crate::core::wire_protocol::{
+    WireMessageBuilder, WireMode, WireProgress, WireReport, WireTokenizer,
+};
+use crate::core::{Result, ValueContainer};
+
+/// Bytes before the header fields: magic, format version and header count
+const PREAMBLE_LEN: usize = BINARY_MAGIC.len() + 2;
+
+/// Bytes before a header field's text: field ID and text length
+const FIELD_PREFIX_LEN: usize = 5;
+
+/// Bytes before a value's name: type byte and name length
+const NAME_PREFIX_LEN: usize = 5;
+
+/// Outcome of feeding a chunk to an incremental decoder
+#[derive(Debug)]
+pub enum DecodeStatus {
+    /// The message is incomplete; at least this many more bytes are needed
+    NeedMoreData(usize),
+    /// A whole container was decoded
+    Complete(ValueContainer),
+}
+
+impl DecodeStatus {
+    /// Get the decoded container, if the message is complete
+    pub fn into_complete(self) -> Option<ValueContainer> {
+        match self {
+            DecodeStatus::Complete(container) => Some(container),
+            DecodeStatus::NeedMoreData(_) => None,
+        }
+    }
+}
+
+/// Incremental decoder for the C++ wire protocol text format
+///
+/// A message is complete when its `@data` section closes, so the header section must
+/// come first (as `serialize_cpp_wire` writes it). The wire format carries no
+/// length, so `NeedMoreData` always asks for at least one more byte.
+pub struct WireDecoder {
+    mode: WireMode,
+    tokenizer: WireTokenizer,
+    builder: WireMessageBuilder,
+    buf: Vec<u8>,
+    report: WireReport,
+}
+
+impl WireDecoder {
+    /// Create a decoder in [`WireMode::Lenient`]
+    pub fn new() -> Self {
+        Self::with_mode(WireMode::Lenient)
+    }
+
+    /// Create a decoder that handles unknown header IDs according to `mode`
+    pub fn with_mode(mode: WireMode) -> Self {
+        Self {
+            mode,
+            tokenizer: WireTokenizer::new(),
+            builder: WireMessageBuilder::new(mode),
+            buf: Vec::new(),
+            report: WireReport::default(),
+        }
+    }
+
+    /// Append `chunk` to the buffered input and decode as far as possible
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as
+    /// [`deserialize_cpp_wire_with_mode`](crate::core::wire_protocol::deserialize_cpp_wire_with_mode),
+    /// with offsets counted from the first byte fed.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<DecodeStatus> {
+        self.buf.extend_from_slice(chunk);
+        let progress = self.tokenizer.advance(&self.buf, false, &mut self.builder);
+        self.tokenizer.compact(&mut self.buf);
+
+        match progress? {
+            WireProgress::DataClosed => {
+                let builder =
+                    std::mem::replace(&mut self.builder, WireMessageBuilder::new(self.mode));
+                let (container, report) = builder.finish()?;
+                self.report = report;
+                Ok(DecodeStatus::Complete(container))
+            }
+            WireProgress::NeedMoreData | WireProgress::End => Ok(DecodeStatus::NeedMoreData(1)),
+        }
+    }
+
+    /// Warnings recorded while decoding the most recently completed container
+    pub fn report(&self) -> &WireReport {
+        &self.report
+    }
+
+    /// Number of bytes received but not yet decoded
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Check whether the decoder is between messages, with nothing but whitespace buffered
+    pub fn is_idle(&self) -> bool {
+        !self.tokenizer.in_message(&self.buf)
+    }
+
+    /// Discard all buffered input and partial state, e.g. after an error
+    pub fn reset(&mut self) {
+        *self = Self::with_mode(self.mode);
+    }
+}
+
+impl Default for WireDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a [`BinaryDecoder`] is within the current message
+enum BinaryState {
+    /// Waiting for the magic, format version and header count
+    Preamble,
+    /// Reading header fields
+    Header { remaining: u8, header: Header },
+    /// Waiting for the value count
+    Count(Header),
+    /// Reading top-level values
+    Values {
+        remaining: usize,
+        container: ValueContainer,
+    },
+}
+
+/// Incremental decoder for the binary container format
+///
+/// Because every field of the binary layout is length-prefixed, `NeedMoreData`
+/// reports exactly how many bytes are missing from the field or top-level value
+/// being received. Each top-level value is decoded once it has fully arrived.
+pub struct BinaryDecoder {
+    decoder: ValueDecoder,
+    buf: Vec<u8>,
+    /// Offset of the start of `buf` from the first byte fed
+    base: usize,
+    state: BinaryState,
+}
+
+impl BinaryDecoder {
+    /// Create a decoder using only the built-in value decoders
+    pub fn new() -> Self {
+        Self::with_decoder(ValueDecoder::new())
+    }
+
+    /// Create a decoder that rebuilds values through `decoder`
+    pub fn with_decoder(decoder: ValueDecoder) -> Self {
+        Self {
+            decoder,
+            buf: Vec::new(),
+            base: 0,
+            state: BinaryState::Preamble,
+        }
+    }
+
+    /// Append `chunk` to the buffered input and decode as far as possible
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as
+    /// [`deserialize_binary_with_decoder`](crate::core::binary_protocol::deserialize_binary_with_decoder),
+    /// with offsets counted from the first byte fed. Trailing bytes are not an error:
+    /// they are kept as the start of the next message.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<DecodeStatus> {
+        self.buf.extend_from_slice(chunk);
+        let mut pos = 0;
+        let status = self.advance(&mut pos);
+        self.buf.drain(..pos);
+        self.base += pos;
+        status
+    }
+
+    /// Number of bytes received but not yet decoded
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Check whether the decoder is between messages with nothing buffered
+    pub fn is_idle(&self) -> bool {
+        matches!(self.state, BinaryState::Preamble) && self.buf.is_empty()
+    }
+
+    /// Discard all buffered input and partial state, e.g. after an error
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.base = 0;
+        self.state = BinaryState::Preamble;
+    }
+
+    /// Decode from `buf[*pos..]`, advancing `pos` past everything consumed
+    fn advance(&mut self, pos: &mut usize) -> Result<DecodeStatus> {
+        loop {
+            let rest = &self.buf[*pos..];
+            let offset = self.base + *pos;
+            match &mut self.state {
+                BinaryState::Preamble => {
+                    if rest.len() < PREAMBLE_LEN {
+                        return Ok(DecodeStatus::NeedMoreData(PREAMBLE_LEN - rest.len()));
+                    }
+                    if rest[..BINARY_MAGIC.len()] != BINARY_MAGIC {
+                        return Err(BinaryReader::error(
+                            offset,
+                            "not a binary container (bad magic)",
+                        ));
+                    }
+                    let format_version = rest[BINARY_MAGIC.len()];
+                    if format_version != BINARY_FORMAT_VERSION {
+                        return Err(BinaryReader::error(
+                            offset + BINARY_MAGIC.len(),
+                            format!(
+                                "unsupported binary format version {} (expected {})",
+                                format_version, BINARY_FORMAT_VERSION
+                            ),
+                        ));
+                    }
+                    self.state = BinaryState::Header {
+                        remaining: rest[PREAMBLE_LEN - 1],
+                        header: Header::default(),
+                    };
+                    *pos += PREAMBLE_LEN;
+                }
+                BinaryState::Header {
+                    remaining: 0,
+                    header,
+                } => {
+                    self.state = BinaryState::Count(std::mem::take(header));
+                }
+                BinaryState::Header { remaining, header } => {
+                    if rest.len() < FIELD_PREFIX_LEN {
+                        return Ok(DecodeStatus::NeedMoreData(FIELD_PREFIX_LEN - rest.len()));
+                    }
+                    let field_len = FIELD_PREFIX_LEN.saturating_add(read_u32(&rest[1..]));
+                    if rest.len() < field_len {
+                        return Ok(DecodeStatus::NeedMoreData(field_len - rest.len()));
+                    }
+                    let mut reader = BinaryReader::new(&rest[..field_len]);
+                    reader.pos = FIELD_PREFIX_LEN;
+                    let text = reader
+                        .read_str(field_len - FIELD_PREFIX_LEN)
+                        .map_err(|e| offset_by(e, offset))?;
+                    header.set(rest[0], text.to_string());
+                    *remaining -= 1;
+                    *pos += field_len;
+                }
+                BinaryState::Count(header) => {
+                    if rest.len() < 4 {
+                        return Ok(DecodeStatus::NeedMoreData(4 - rest.len()));
+                    }
+                    self.state = BinaryState::Values {
+                        remaining: read_u32(rest),
+                        container: std::mem::take(header).into_container(),
+                    };
+                    *pos += 4;
+                }
+                BinaryState::Values {
+                    remaining: 0,
+                    container,
+                } => {
+                    let container = std::mem::take(container);
+                    self.state = BinaryState::Preamble;
+                    return Ok(DecodeStatus::Complete(container));
+                }
+                BinaryState::Values {
+                    remaining,
+                    container,
+                } => {
+                    // [type:1][name_len:4][name][value_size:4][value]
+                    if rest.len() < NAME_PREFIX_LEN {
+                        return Ok(DecodeStatus::NeedMoreData(NAME_PREFIX_LEN - rest.len()));
+                    }
+                    let size_at = NAME_PREFIX_LEN.saturating_add(read_u32(&rest[1..]));
+                    let payload_at = size_at.saturating_add(4);
+                    if rest.len() < payload_at {
+                        return Ok(DecodeStatus::NeedMoreData(payload_at - rest.len()));
+                    }
+                    let total = payload_at.saturating_add(read_u32(&rest[size_at..]));
+                    if rest.len() < total {
+                        return Ok(DecodeStatus::NeedMoreData(total - rest.len()));
+                    }
+                    let (value, _) = self
+                        .decoder
+                        .decode(&rest[..total])
+                        .map_err(|e| offset_by(e, offset))?;
+                    container.add_value(value)?;
+                    *remaining -= 1;
+                    *pos += total;
+                }
+            }
+        }
+    }
+}
+
+impl Default for BinaryDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read a little-endian u32 length from the start of `bytes`
+fn read_u32(bytes: &[u8]) -> usize {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::binary_protocol::{deserialize_binary, serialize_binary};
+    use crate::core::value_types::ValueType;
+    use crate::core::wire_protocol::{deserialize_cpp_wire, serialize_cpp_wire, WireWarning};
+    use crate::core::{ContainerError, Value};
+    use crate::values::*;
+    use std::sync::Arc;
+
+    fn sample_container(message_type: &str) -> ValueContainer {
+        let mut container = ValueContainer::builder()
+            .source("client", "session")
+            .target("server", "handler")
+            .message_type(message_type)
+            .build();
+        container
+            .add_value(Arc::new(IntValue::new("count", 42)))
+            .unwrap();
+        container
+            .add_value(Arc::new(StringValue::new("text", "안녕 ];,")))
+            .unwrap();
+        container
+            .add_value(Arc::new(BytesValue::new("raw", vec![0, 1, 0xFF])))
+            .unwrap();
+        let nested = ContainerValue::new(
+            "nested",
+            vec![
+                Arc::new(IntValue::new("id", 7)) as Arc<dyn Value>,
+                Arc::new(ArrayValue::new(
+                    "list",
+                    vec![Arc::new(StringValue::new("", "two")) as Arc<dyn Value>],
+                )),
+            ],
+        );
+        container.add_value(Arc::new(nested)).unwrap();
+        container
+    }
+
+    fn wire_of(message_type: &str) -> Vec<u8> {
+        serialize_cpp_wire(&sample_container(message_type))
+            .unwrap()
+            .into_bytes()
+    }
+
+    fn binary_of(message_type: &str) -> Vec<u8> {
+        serialize_binary(&sample_container(message_type)).unwrap()
+    }
+
+    fn error_offset(error: ContainerError) -> usize {
+        match error {
+            ContainerError::WireFormat { offset, .. } => offset,
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wire_split_at_every_position() {
+        let wire = wire_of("split");
+        for split in 0..wire.len() {
+            let mut decoder = WireDecoder::new();
+            assert!(matches!(
+                decoder.feed(&wire[..split]).unwrap(),
+                DecodeStatus::NeedMoreData(1)
+            ));
+            let restored = decoder.feed(&wire[split..]).unwrap().into_complete();
+            let restored = restored.unwrap_or_else(|| panic!("incomplete at split {}", split));
+            assert_eq!(serialize_cpp_wire(&restored).unwrap().as_bytes(), wire);
+            assert!(decoder.is_idle());
+        }
+    }
+
+    #[test]
+    fn test_wire_back_to_back_byte_by_byte() {
+        let mut input = wire_of("first");
+        input.extend_from_slice(b"\n");
+        input.extend_from_slice(&wire_of("second"));
+
+        let mut decoder = WireDecoder::new();
+        let mut decoded = Vec::new();
+        for byte in &input {
+            if let DecodeStatus::Complete(container) = decoder.feed(&[*byte]).unwrap() {
+                decoded.push(container.message_type());
+            }
+        }
+        assert_eq!(decoded, ["first", "second"]);
+        assert!(decoder.is_idle());
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[test]
+    fn test_wire_buffered_message_decoded_by_empty_feed() {
+        let mut input = wire_of("first");
+        input.extend_from_slice(&wire_of("second"));
+
+        let mut decoder = WireDecoder::new();
+        let first = decoder.feed(&input).unwrap().into_complete().unwrap();
+        assert_eq!(first.message_type(), "first");
+        assert!(!decoder.is_idle());
+        let second = decoder.feed(&[]).unwrap().into_complete().unwrap();
+        assert_eq!(second.message_type(), "second");
+        assert!(decoder.is_idle());
+        assert!(matches!(
+            decoder.feed(&[]).unwrap(),
+            DecodeStatus::NeedMoreData(1)
+        ));
+    }
+
+    #[test]
+    fn test_wire_idle_tracks_partial_header() {
+        let wire = wire_of("partial");
+        let header_end = wire.windows(3).position(|w| w == b"}};").unwrap() + 3;
+
+        let mut decoder = WireDecoder::new();
+        decoder.feed(&wire[..header_end]).unwrap();
+        assert_eq!(decoder.buffered_len(), 0);
+        assert!(!decoder.is_idle());
+        assert!(decoder
+            .feed(&wire[header_end..])
+            .unwrap()
+            .into_complete()
+            .is_some());
+        assert!(decoder.is_idle());
+    }
+
+    #[test]
+    fn test_wire_large_value_in_small_chunks() {
+        let mut container = ValueContainer::new();
+        container
+            .add_value(Arc::new(BytesValue::new("blob", vec![0xAB; 100_000])))
+            .unwrap();
+        let wire = serialize_cpp_wire(&container).unwrap().into_bytes();
+
+        let mut decoder = WireDecoder::new();
+        let mut restored = None;
+        for chunk in wire.chunks(3) {
+            if let DecodeStatus::Complete(container) = decoder.feed(chunk).unwrap() {
+                restored = Some(container);
+            }
+        }
+        let blob = restored.unwrap().get_value("blob").unwrap();
+        let blob = blob.as_any().downcast_ref::<BytesValue>().unwrap();
+        assert_eq!(blob.data(), &[0xAB; 100_000][..]);
+    }
+
+    #[test]
+    fn test_wire_error_offset_counts_from_first_byte() {
+        let first = wire_of("first");
+        let bad = b"@header={{[5,bad];}};@data={{[count,int_value,oops];}};";
+        let expected =
+            error_offset(deserialize_cpp_wire(std::str::from_utf8(bad).unwrap()).unwrap_err());
+
+        let mut decoder = WireDecoder::new();
+        assert!(decoder.feed(&first).unwrap().into_complete().is_some());
+        let error = decoder.feed(bad).unwrap_err();
+        assert_eq!(error_offset(error), first.len() + expected);
+
+        decoder.reset();
+        assert!(decoder.feed(&first).unwrap().into_complete().is_some());
+    }
+
+    #[test]
+    fn test_wire_modes_and_report() {
+        let input = b"@header={{[5,t];[42,x];}};@data={{[count,int_value,1];}};";
+
+        let mut strict = WireDecoder::with_mode(WireMode::Strict);
+        assert!(strict.feed(input).is_err());
+
+        let mut lenient = WireDecoder::new();
+        let container = lenient.feed(input).unwrap().into_complete().unwrap();
+        assert_eq!(container.message_type(), "t");
+        assert!(matches!(
+            lenient.report().warnings(),
+            [WireWarning::UnknownHeaderId { id: 42, .. }]
+        ));
+
+        let clean = wire_of("clean");
+        assert!(lenient.feed(&clean).unwrap().into_complete().is_some());
+        assert!(lenient.report().is_clean());
+    }
+
+    #[test]
+    fn test_binary_split_at_every_position() {
+        let bytes = binary_of("split");
+        for split in 0..bytes.len() {
+            let mut decoder = BinaryDecoder::new();
+            match decoder.feed(&bytes[..split]).unwrap() {
+                DecodeStatus::NeedMoreData(n) => assert!(n >= 1 && n <= bytes.len() - split),
+                DecodeStatus::Complete(_) => panic!("complete at split {}", split),
+            }
+            let restored = decoder.feed(&bytes[split..]).unwrap().into_complete();
+            let restored = restored.unwrap_or_else(|| panic!("incomplete at split {}", split));
+            assert_eq!(serialize_binary(&restored).unwrap(), bytes);
+            assert!(decoder.is_idle());
+        }
+    }
+
+    #[test]
+    fn test_binary_reports_exact_missing_bytes() {
+        let mut container = ValueContainer::builder().message_type("t").build();
+        container
+            .add_value(Arc::new(StringValue::new("name", "value")))
+            .unwrap();
+        let bytes = serialize_binary(&container).unwrap();
+
+        // Feeding exactly the reported number of bytes always makes progress
+        let mut decoder = BinaryDecoder::new();
+        let mut fed = 0;
+        let mut requests = Vec::new();
+        let mut status = decoder.feed(&[]).unwrap();
+        while let DecodeStatus::NeedMoreData(n) = status {
+            requests.push(n);
+            status = decoder.feed(&bytes[fed..fed + n]).unwrap();
+            fed += n;
+        }
+        assert_eq!(fed, bytes.len());
+        assert_eq!(status.into_complete().unwrap().message_type(), "t");
+        // preamble; empty target/source fields; message type and version (prefix, text);
+        // count; one value (prefix, name and size, payload)
+        assert_eq!(requests, [6, 5, 5, 5, 5, 5, 1, 5, 7, 4, 5, 8, 5]);
+    }
+
+    #[test]
+    fn test_binary_back_to_back_byte_by_byte() {
+        let mut input = binary_of("first");
+        input.extend_from_slice(&binary_of("second"));
+
+        let mut decoder = BinaryDecoder::new();
+        let mut decoded = Vec::new();
+        for byte in &input {
+            if let DecodeStatus::Complete(container) = decoder.feed(&[*byte]).unwrap() {
+                decoded.push(container.message_type());
+            }
+        }
+        assert_eq!(decoded, ["first", "second"]);
+        assert!(decoder.is_idle());
+
+        let first = decoder.feed(&input).unwrap().into_complete().unwrap();
+        assert_eq!(first.message_type(), "first");
+        assert_eq!(decoder.buffered_len(), binary_of("second").len());
+        let second = decoder.feed(&[]).unwrap().into_complete().unwrap();
+        assert_eq!(second.message_type(), "second");
+    }
+
+    #[test]
+    fn test_binary_error_offsets_match_slice_decoder() {
+        let first = binary_of("first");
+
+        let mut bad_magic = binary_of("second");
+        bad_magic[0] = b'X';
+        let mut decoder = BinaryDecoder::new();
+        decoder.feed(&first).unwrap();
+        assert_eq!(
+            error_offset(decoder.feed(&bad_magic).unwrap_err()),
+            first.len()
+        );
+
+        // Corrupt the type byte of the first value
+        let mut bad_type = binary_of("second");
+        let value_start = bad_type.len() - (first.len() - first_value_offset(&first));
+        bad_type[value_start] = 99;
+        let expected = error_offset(deserialize_binary(&bad_type).unwrap_err());
+        decoder.reset();
+        decoder.feed(&first).unwrap();
+        assert_eq!(
+            error_offset(decoder.feed(&bad_type).unwrap_err()),
+            first.len() + expected
+        );
+    }
+
+    /// Offset of the first value in a container whose message types are the same length
+    fn first_value_offset(bytes: &[u8]) -> usize {
+        let mut pos = PREAMBLE_LEN;
+        for _ in 0..bytes[PREAMBLE_LEN - 1] {
+            pos += FIELD_PREFIX_LEN + read_u32(&bytes[pos + 1..]);
+        }
+        pos + 4
+    }
+
+    #[test]
+    fn test_binary_custom_decoder() {
+        let mut values = ValueDecoder::new();
+        values.register(ValueType::String, |name, payload, _ctx| {
+            let text = String::from_utf8_lossy(payload).to_uppercase();
+            Ok(Arc::new(StringValue::new(name, text)) as Arc<dyn Value>)
+        });
+        let mut decoder = BinaryDecoder::with_decoder(values);
+        let restored = decoder
+            .feed(&binary_of("custom"))
+            .unwrap()
+            .into_complete()
+            .unwrap();
+        assert_eq!(
+            restored.get_value("text").unwrap().to_string(),
+            "안녕 ];,".to_uppercase()
+        );
+    }
+}
@@ -0,0 +1,647 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! JSON Schema Export and Import
+//!
+//! [`to_json_schema`] turns the [`Schema`] of a message type into a JSON
+//! Schema (draft 2020-12) document describing what
+//! [`JsonV2Adapter::to_v2_json`] produces for it, so frontend and Python code
+//! can validate messages with standard tooling. [`from_json_schema`] reads such
+//! a document back into a [`Schema`].
+//!
+//! Each value in the v2 `values` array is an object with `name`, `type`,
+//! `type_name` and `data`. The document lists one entry per field with:
+//!
+//! - `type` / `type_name` restricted to the accepted types, and one `anyOf`
+//!   branch per type giving the shape of `data`;
+//! - integer ranges from the width of the type, narrowed by the field's
+//!   `min` / `max`; float ranges from `min` / `max`;
+//! - strings with `minLength`, `maxLength` and `pattern`;
+//! - bytes as base64 strings (`contentEncoding`), with `"encoding": "base64"`;
+//! - decimals as strings matching the decimal text form;
+//! - timestamps, durations and UUIDs as `date-time`, `duration` and `uuid`
+//!   formatted strings;
+//! - arrays with `minItems` / `maxItems` and the element entry as `items`;
+//! - nested containers as arrays of values described the same way.
+//!
+//! Required fields become `contains` constraints on the `values` array; an
+//! open schema also admits entries with any other name. Byte lengths, field
+//! aliases, defaults and decimal ranges have no JSON Schema equivalent and are not
+//! exported.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::json_schema::{from_json_schema, to_json_schema};
+//! use rust_container_system::core::schema::{Schema, ValueSchema};
+//! use rust_container_system::ValueType;
+//!
+//! let login = Schema::new()
+//!     .required("user_id", ValueSchema::new(ValueType::LLong).min(1))
+//!     .optional("device", ValueSchema::new(ValueType::String).max_length(64));
+//!
+//! let document = to_json_schema("user_login", &login);
+//! assert_eq!(document["title"], "user_login");
+//!
+//! let imported = from_json_schema(&document).unwrap();
+//! assert!(imported.get("user_id").unwrap().is_required());
+//! assert_eq!(to_json_schema("user_login", &imported), document);
+//! ```
+//!
+//! [`to_json_schema`]: crate::core::json_schema::to_json_schema
+//! [`Schema`]: crate::core::schema::Schema
+//! [`from_json_schema`]: crate::core::json_schema::from_json_schema
+//! [`JsonV2Adapter::to_v2_json`]: crate::core::json_v2_adapter::JsonV2Adapter::to_v2_json
+
+use super::json_v2_adapter::{type_name_from_value_type, value_type_from_name, V2_FORMAT_VERSION};
+use super::schema::{FieldSchema, Limit, Schema, ValueSchema};
+use super::{ContainerError, Result, ValueType};
+use serde_json::{json, Map, Value as JsonValue};
+
+/// The JSON Schema dialect of exported documents
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Text form of a `decimal_value` in JSON v2.0
+const DECIMAL_PATTERN: &str = r"^[-+]?[0-9]+(\.[0-9]+)?$";
+
+/// JSON Schema for the JSON v2.0 form of a message type
+pub fn to_json_schema(message_type: &str, schema: &Schema) -> JsonValue {
+    export(message_type, None, schema)
+}
+
+/// JSON Schema for a message type, optionally pinning `protocol_version`
+pub(crate) fn export(message_type: &str, version: Option<&str>, schema: &Schema) -> JsonValue {
+    let protocol_version = match version {
+        Some(version) => json!({ "const": version }),
+        None => json!({ "type": "string" }),
+    };
+    let endpoint = json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string" },
+            "sub_id": { "type": "string" }
+        }
+    });
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": message_type,
+        "type": "object",
+        "required": ["container"],
+        "properties": {
+            "container": {
+                "type": "object",
+                "required": ["version", "metadata", "values"],
+                "properties": {
+                    "version": { "const": V2_FORMAT_VERSION },
+                    "metadata": {
+                        "type": "object",
+                        "required": ["message_type"],
+                        "properties": {
+                            "message_type": { "const": message_type },
+                            "protocol_version": protocol_version,
+                            "source": endpoint.clone(),
+                            "target": endpoint
+                        }
+                    },
+                    "values": values_schema(schema)
+                }
+            }
+        }
+    })
+}
+
+/// Schema of a list of v2 value objects
+fn values_schema(schema: &Schema) -> JsonValue {
+    let mut entries: Vec<JsonValue> = schema.fields().iter().map(field_entry).collect();
+    if !schema.denies_unknown() {
+        let mut other = json!({ "type": "object", "required": ["name"] });
+        if !entries.is_empty() {
+            let names: Vec<&str> = schema.fields().iter().map(FieldSchema::name).collect();
+            other["properties"] = json!({ "name": { "not": { "enum": names } } });
+        }
+        entries.push(other);
+    }
+
+    let mut values = json!({ "type": "array" });
+    values["items"] = match entries.len() {
+        0 => JsonValue::Bool(false),
+        _ => json!({ "anyOf": entries }),
+    };
+    let required: Vec<JsonValue> = schema
+        .fields()
+        .iter()
+        .filter(|field| field.is_required())
+        .map(|field| {
+            json!({ "contains": {
+                "type": "object",
+                "required": ["name"],
+                "properties": { "name": { "const": field.name() } }
+            } })
+        })
+        .collect();
+    if !required.is_empty() {
+        values["allOf"] = json!(required);
+    }
+    values
+}
+
+fn field_entry(field: &FieldSchema) -> JsonValue {
+    let mut entry = value_entry(field.schema());
+    entry["properties"]["name"] = json!({ "const": field.name() });
+    entry
+}
+
+/// Schema of one v2 value object (without its name)
+fn value_entry(schema: &ValueSchema) -> JsonValue {
+    let mut entry = json!({
+        "type": "object",
+        "required": ["name", "type", "type_name", "data"],
+        "properties": {}
+    });
+    let types = schema.types();
+    if types.is_empty() {
+        return entry;
+    }
+
+    let codes: Vec<u8> = types.iter().map(|&t| t as u8).collect();
+    let names: Vec<&str> = types
+        .iter()
+        .map(|&t| type_name_from_value_type(t))
+        .collect();
+    entry["properties"] = json!({
+        "type": { "enum": codes },
+        "type_name": { "enum": names }
+    });
+    let branches: Vec<JsonValue> = types
+        .iter()
+        .map(|&value_type| {
+            let mut properties = json!({
+                "type": { "const": value_type as u8 },
+                "data": data_schema(schema, value_type)
+            });
+            if value_type == ValueType::Bytes {
+                properties["encoding"] = json!({ "const": "base64" });
+            }
+            json!({ "properties": properties })
+        })
+        .collect();
+    entry["anyOf"] = json!(branches);
+    entry
+}
+
+/// Schema of the `data` member for one accepted type
+fn data_schema(schema: &ValueSchema, value_type: ValueType) -> JsonValue {
+    let (min, max) = schema.range();
+    let (min_length, max_length) = schema.length();
+    let mut data = Map::new();
+    let mut set = |key: &str, value: JsonValue| {
+        data.insert(key.to_string(), value);
+    };
+
+    match value_type {
+        ValueType::Null => set("type", json!("null")),
+        ValueType::Bool => set("type", json!("boolean")),
+        ValueType::Float | ValueType::Double => {
+            set("type", json!("number"));
+            if let Some(min) = min {
+                set("minimum", limit_json(min));
+            }
+            if let Some(max) = max {
+                set("maximum", limit_json(max));
+            }
+        }
+        ValueType::String => {
+            set("type", json!("string"));
+            if let Some(min) = min_length {
+                set("minLength", json!(min));
+            }
+            if let Some(max) = max_length {
+                set("maxLength", json!(max));
+            }
+            if let Some(pattern) = schema.pattern_str() {
+                set("pattern", json!(pattern));
+            }
+        }
+        ValueType::Bytes => {
+            set("type", json!("string"));
+            set("contentEncoding", json!("base64"));
+        }
+        // Decimals travel as exact text, which JSON Schema cannot bound numerically
+        ValueType::Decimal => {
+            set("type", json!("string"));
+            set("pattern", json!(DECIMAL_PATTERN));
+        }
+        ValueType::Timestamp => {
+            set("type", json!("string"));
+            set("format", json!("date-time"));
+        }
+        ValueType::Duration => {
+            set("type", json!("string"));
+            set("format", json!("duration"));
+        }
+        ValueType::Uuid => {
+            set("type", json!("string"));
+            set("format", json!("uuid"));
+        }
+        ValueType::Container => match schema.field_schema() {
+            Some(fields) => return values_schema(fields),
+            None => set("type", json!("array")),
+        },
+        ValueType::Array => {
+            set("type", json!("array"));
+            if let Some(min) = min_length {
+                set("minItems", json!(min));
+            }
+            if let Some(max) = max_length {
+                set("maxItems", json!(max));
+            }
+            if let Some(elements) = schema.element_schema() {
+                set("items", value_entry(elements));
+            }
+        }
+        integer => {
+            let (low, high) = int_range(integer).expect("remaining types are integers");
+            let min = min.map_or(low, |min| low.max(int_bound(min, f64::ceil)));
+            let max = max.map_or(high, |max| high.min(int_bound(max, f64::floor)));
+            set("type", json!("integer"));
+            set("minimum", limit_json(Limit::Int(min)));
+            set("maximum", limit_json(Limit::Int(max)));
+        }
+    }
+    JsonValue::Object(data)
+}
+
+/// Values an integer type can hold (as stored in `ValueData`)
+fn int_range(value_type: ValueType) -> Option<(i128, i128)> {
+    Some(match value_type {
+        ValueType::Short => (i16::MIN.into(), i16::MAX.into()),
+        ValueType::UShort => (0, u16::MAX.into()),
+        ValueType::Int | ValueType::Long => (i32::MIN.into(), i32::MAX.into()),
+        ValueType::UInt | ValueType::ULong => (0, u32::MAX.into()),
+        ValueType::LLong => (i64::MIN.into(), i64::MAX.into()),
+        ValueType::ULLong => (0, u64::MAX.into()),
+        _ => return None,
+    })
+}
+
+/// An integer bound, rounding float bounds inwards with `round`
+fn int_bound(limit: Limit, round: fn(f64) -> f64) -> i128 {
+    match limit {
+        Limit::Int(n) => n,
+        Limit::Float(x) => round(x) as i128,
+    }
+}
+
+fn limit_json(limit: Limit) -> JsonValue {
+    match limit {
+        Limit::Int(n) => match (i64::try_from(n), u64::try_from(n)) {
+            (Ok(n), _) => json!(n),
+            (_, Ok(n)) => json!(n),
+            _ => json!(n as f64),
+        },
+        Limit::Float(x) => json!(x),
+    }
+}
+
+fn json_limit(value: &JsonValue) -> Option<Limit> {
+    value
+        .as_i64()
+        .map(Limit::from)
+        .or_else(|| value.as_u64().map(Limit::from))
+        .or_else(|| value.as_f64().map(Limit::Float))
+}
+
+/// Read a [`Schema`] from a JSON Schema document
+///
+/// Accepts documents in the shape [`to_json_schema`] produces, either whole or
+/// just the schema of the `values` array. Unknown keywords are ignored.
+///
+/// # Errors
+///
+/// Returns `SchemaError` if a value entry has no recognizable type or a
+/// pattern does not compile.
+pub fn from_json_schema(document: &JsonValue) -> Result<Schema> {
+    let values = document
+        .pointer("/properties/container/properties/values")
+        .unwrap_or(document);
+    import_values(values, "")
+}
+
+fn invalid(path: &str, message: &str) -> ContainerError {
+    let path = if path.is_empty() { "values" } else { path };
+    ContainerError::SchemaError(format!("invalid JSON Schema at '{}': {}", path, message))
+}
+
+fn import_values(values: &JsonValue, parent: &str) -> Result<Schema> {
+    let (entries, mut open) = match values.get("items") {
+        None => (&[][..], true),
+        Some(JsonValue::Bool(allowed)) => (&[][..], *allowed),
+        Some(items) => match items.get("anyOf").and_then(JsonValue::as_array) {
+            Some(entries) => (entries.as_slice(), false),
+            None => (std::slice::from_ref(items), false),
+        },
+    };
+
+    let required: Vec<&str> = values
+        .get("allOf")
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.pointer("/contains/properties/name/const")?.as_str())
+        .collect();
+
+    let mut schema = Schema::new();
+    for entry in entries {
+        let Some(name) = entry
+            .pointer("/properties/name/const")
+            .and_then(|n| n.as_str())
+        else {
+            open = true;
+            continue;
+        };
+        let path = super::value_path::child_path(parent, name, None);
+        let value = import_value(entry, &path)?;
+        schema = if required.contains(&name) {
+            schema.required(name, value)
+        } else {
+            schema.optional(name, value)
+        };
+    }
+    Ok(if open { schema } else { schema.deny_unknown() })
+}
+
+/// Read a [`ValueSchema`] from the schema of one v2 value object
+fn import_value(entry: &JsonValue, path: &str) -> Result<ValueSchema> {
+    let branches = entry
+        .get("anyOf")
+        .and_then(JsonValue::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut typed = Vec::with_capacity(branches.len());
+    for branch in branches {
+        let code = branch.pointer("/properties/type/const");
+        let name = branch.pointer("/properties/type_name/const");
+        let value_type = code
+            .and_then(JsonValue::as_u64)
+            .and_then(|code| u8::try_from(code).ok())
+            .and_then(ValueType::from_u8)
+            .or_else(|| {
+                name.and_then(JsonValue::as_str)
+                    .and_then(value_type_from_name)
+            })
+            .ok_or_else(|| invalid(path, "value branch without a known type"))?;
+        typed.push((value_type, branch.pointer("/properties/data")));
+    }
+
+    let mut schema = ValueSchema::any_of(typed.iter().map(|&(t, _)| t));
+    for (value_type, data) in typed {
+        if let Some(data) = data {
+            schema = import_data(schema, value_type, data, path)?;
+        }
+    }
+    Ok(schema)
+}
+
+/// Add the constraints of a `data` schema that are not set yet
+fn import_data(
+    mut schema: ValueSchema,
+    value_type: ValueType,
+    data: &JsonValue,
+    path: &str,
+) -> Result<ValueSchema> {
+    let number = |key: &str| data.get(key).and_then(json_limit);
+    let count = |key: &str| {
+        data.get(key)
+            .and_then(JsonValue::as_u64)
+            .and_then(|n| usize::try_from(n).ok())
+    };
+    let (min, max) = schema.range();
+    let (min_length, max_length) = schema.length();
+
+    let (low, high) = match int_range(value_type) {
+        Some((low, high)) => (Some(Limit::Int(low)), Some(Limit::Int(high))),
+        None => (None, None),
+    };
+    if value_type.is_numeric() {
+        if let (None, Some(bound)) = (min, number("minimum").filter(|&b| Some(b) != low)) {
+            schema = schema.min(bound);
+        }
+        if let (None, Some(bound)) = (max, number("maximum").filter(|&b| Some(b) != high)) {
+            schema = schema.max(bound);
+        }
+    }
+
+    let (min_key, max_key) = match value_type {
+        ValueType::String => ("minLength", "maxLength"),
+        ValueType::Array => ("minItems", "maxItems"),
+        _ => ("", ""),
+    };
+    if let (None, Some(n)) = (min_length, count(min_key)) {
+        schema = schema.min_length(n);
+    }
+    if let (None, Some(n)) = (max_length, count(max_key)) {
+        schema = schema.max_length(n);
+    }
+
+    if value_type == ValueType::String && schema.pattern_str().is_none() {
+        if let Some(pattern) = data.get("pattern").and_then(JsonValue::as_str) {
+            schema = schema.pattern(pattern)?;
+        }
+    }
+    if value_type == ValueType::Array && schema.element_schema().is_none() {
+        if let Some(items) = data.get("items").filter(|items| items.is_object()) {
+            let elements = import_value(items, &format!("{}[*]", path))?;
+            schema = schema.elements(elements);
+        }
+    }
+    if value_type == ValueType::Container
+        && schema.field_schema().is_none()
+        && data.get("items").is_some()
+    {
+        schema = schema.fields(import_values(data, path)?);
+    }
+    Ok(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::json_v2_adapter::JsonV2Adapter;
+    use crate::core::schema::SchemaRegistry;
+    use crate::core::ValueContainer;
+    use crate::values::{ArrayValue, BytesValue, ContainerValue, IntValue, LLongValue};
+    use std::sync::Arc;
+
+    fn order() -> Schema {
+        let item = Schema::new()
+            .required(
+                "sku",
+                ValueSchema::new(ValueType::String)
+                    .pattern("^[A-Z]+$")
+                    .unwrap(),
+            )
+            .optional("qty", ValueSchema::new(ValueType::Int).min(1).max(99));
+        Schema::new()
+            .required("id", ValueSchema::new(ValueType::LLong))
+            .optional(
+                "items",
+                ValueSchema::new(ValueType::Array)
+                    .max_length(10)
+                    .elements(ValueSchema::new(ValueType::Container).fields(item.deny_unknown())),
+            )
+            .optional(
+                "payload",
+                ValueSchema::any_of([ValueType::Bytes, ValueType::Null]),
+            )
+            .optional("price", ValueSchema::new(ValueType::Double).min(0))
+            .deny_unknown()
+    }
+
+    fn values(document: &JsonValue) -> &JsonValue {
+        document
+            .pointer("/properties/container/properties/values")
+            .unwrap()
+    }
+
+    fn data(document: &JsonValue, field: usize, branch: usize) -> &JsonValue {
+        &values(document)["items"]["anyOf"][field]["anyOf"][branch]["properties"]["data"]
+    }
+
+    #[test]
+    fn test_export_shapes() {
+        let document = to_json_schema("order", &order());
+        assert_eq!(document["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(
+            document.pointer("/properties/container/properties/metadata/properties/message_type"),
+            Some(&json!({ "const": "order" }))
+        );
+
+        let id = data(&document, 0, 0);
+        assert_eq!(id["type"], "integer");
+        assert_eq!(id["minimum"], json!(i64::MIN));
+        assert_eq!(id["maximum"], json!(i64::MAX));
+
+        let item = &data(&document, 1, 0)["items"]["anyOf"][0]["properties"]["data"];
+        let qty = &item["items"]["anyOf"][1]["anyOf"][0]["properties"]["data"];
+        assert_eq!((&qty["minimum"], &qty["maximum"]), (&json!(1), &json!(99)));
+        assert_eq!(data(&document, 1, 0)["maxItems"], 10);
+
+        let payload = &values(&document)["items"]["anyOf"][2];
+        assert_eq!(
+            payload["properties"]["type_name"]["enum"],
+            json!(["bytes", "null"])
+        );
+        assert_eq!(data(&document, 2, 0)["contentEncoding"], "base64");
+
+        // Closed: no catch-all entry; "id" is required
+        assert_eq!(
+            values(&document)["items"]["anyOf"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+        assert_eq!(
+            values(&document)["allOf"][0]["contains"]["properties"]["name"]["const"],
+            "id"
+        );
+    }
+
+    #[test]
+    fn test_export_matches_v2_output() {
+        let mut container = ValueContainer::new();
+        container.set_message_type("order");
+        container
+            .add_value(Arc::new(LLongValue::new("id", 5)))
+            .unwrap();
+        let item = ContainerValue::new("", vec![Arc::new(IntValue::new("qty", 2))]);
+        container
+            .add_value(Arc::new(ArrayValue::new("items", vec![Arc::new(item)])))
+            .unwrap();
+        container
+            .add_value(Arc::new(BytesValue::new("payload", vec![1, 2])))
+            .unwrap();
+        let output: JsonValue =
+            serde_json::from_str(&JsonV2Adapter::to_v2_json(&container, false).unwrap()).unwrap();
+
+        let document = to_json_schema("order", &order());
+        let entries = values(&document)["items"]["anyOf"].as_array().unwrap();
+        for value in output["container"]["values"].as_array().unwrap() {
+            let entry = entries
+                .iter()
+                .find(|e| e["properties"]["name"]["const"] == value["name"])
+                .unwrap();
+            let codes = entry["properties"]["type"]["enum"].as_array().unwrap();
+            assert!(codes.contains(&value["type"]));
+            let names = entry["properties"]["type_name"]["enum"].as_array().unwrap();
+            assert!(names.contains(&value["type_name"]));
+            for key in entry["required"].as_array().unwrap() {
+                assert!(value.get(key.as_str().unwrap()).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_import_roundtrip() {
+        let document = to_json_schema("order", &order());
+        let imported = from_json_schema(&document).unwrap();
+        assert_eq!(to_json_schema("order", &imported), document);
+        assert!(imported.denies_unknown());
+        assert!(imported.get("id").unwrap().is_required());
+        assert_eq!(imported.get("id").unwrap().schema().range(), (None, None));
+        assert_eq!(
+            imported.get("price").unwrap().schema().range(),
+            (Some(Limit::Int(0)), None)
+        );
+
+        let open = Schema::new().optional("note", ValueSchema::any());
+        let imported = from_json_schema(values(&to_json_schema("x", &open))).unwrap();
+        assert!(!imported.denies_unknown());
+        assert!(imported.get("note").unwrap().schema().types().is_empty());
+    }
+
+    #[test]
+    fn test_import_errors_and_registry() {
+        let broken = json!({ "items": { "anyOf": [
+            { "properties": { "name": { "const": "x" } }, "anyOf": [ { "properties": {} } ] }
+        ] } });
+        let err = from_json_schema(&broken).unwrap_err();
+        assert!(err.to_string().contains("'x'"));
+
+        let mut registry = SchemaRegistry::new();
+        registry.register("order", "2.0", order()).unwrap();
+        let document = registry.to_json_schema("order").unwrap();
+        assert_eq!(
+            document
+                .pointer("/properties/container/properties/metadata/properties/protocol_version"),
+            Some(&json!({ "const": "2.0" }))
+        );
+        assert!(registry.to_json_schema("missing").is_none());
+    }
+}
@@ -93,6 +93,8 @@ pub enum SerializationFormat {
     PythonJson,
     /// C++ Wire Protocol (text-based)
     WireProtocol,
+    /// Binary container format (see `binary_protocol`); never detected from text
+    Binary,
     /// Unknown or invalid format
     Unknown,
 }
@@ -104,13 +106,14 @@ impl std::fmt::Display for SerializationFormat {
             SerializationFormat::CppJson => write!(f, "cpp"),
             SerializationFormat::PythonJson => write!(f, "python"),
             SerializationFormat::WireProtocol => write!(f, "wire"),
+            SerializationFormat::Binary => write!(f, "binary"),
             SerializationFormat::Unknown => write!(f, "unknown"),
         }
     }
 }
 
 /// Type name mapping for human-readable type names (matches C++/Python/.NET)
-fn type_name_from_value_type(vt: ValueType) -> &'static str {
+pub(crate) fn type_name_from_value_type(vt: ValueType) -> &'static str {
     match vt {
         ValueType::Null => "null",
         ValueType::Bool => "bool",
@@ -128,11 +131,15 @@ fn type_name_from_value_type(vt: ValueType) -> &'static str {
         ValueType::Bytes => "bytes",
         ValueType::Container => "container",
         ValueType::Array => "array",
+        ValueType::Decimal => "decimal",
+        ValueType::Timestamp => "timestamp",
+        ValueType::Duration => "duration",
+        ValueType::Uuid => "uuid",
     }
 }
 
 /// Reverse mapping from type name to ValueType
-fn value_type_from_name(name: &str) -> Option<ValueType> {
+pub(crate) fn value_type_from_name(name: &str) -> Option<ValueType> {
     match name {
         "null" => Some(ValueType::Null),
         "bool" => Some(ValueType::Bool),
@@ -150,6 +157,10 @@ fn value_type_from_name(name: &str) -> Option<ValueType> {
         "bytes" => Some(ValueType::Bytes),
         "container" => Some(ValueType::Container),
         "array" => Some(ValueType::Array),
+        "decimal" => Some(ValueType::Decimal),
+        "timestamp" => Some(ValueType::Timestamp),
+        "duration" => Some(ValueType::Duration),
+        "uuid" => Some(ValueType::Uuid),
         _ => None,
     }
 }
@@ -279,6 +290,9 @@ impl JsonV2Adapter {
         container.set_source(source_id, source_sub_id);
         container.set_target(target_id, target_sub_id);
         container.set_message_type(message_type);
+        if let Some(protocol_version) = metadata.get("protocol_version").and_then(|v| v.as_str()) {
+            container.set_version(protocol_version);
+        }
 
         // Parse values
         if let Some(values_array) = container_data.get("values").and_then(|v| v.as_array()) {
@@ -328,6 +342,9 @@ impl JsonV2Adapter {
         container.set_source(source_id, source_sub_id);
         container.set_target(target_id, target_sub_id);
         container.set_message_type(message_type);
+        if let Some(version) = header.get("version").and_then(|v| v.as_str()) {
+            container.set_version(version);
+        }
 
         // Parse values (C++ format: values is an object with keys)
         if let Some(values_obj) = data.get("values").and_then(|v| v.as_object()) {
@@ -456,7 +473,7 @@ impl JsonV2Adapter {
                 // Python flat format - parse manually
                 Self::from_python_json(data)?
             }
-            SerializationFormat::Unknown => {
+            SerializationFormat::Binary | SerializationFormat::Unknown => {
                 return Err(ContainerError::InvalidDataFormat(format!(
                     "Unsupported source format: {}",
                     source_format
@@ -472,6 +489,10 @@ impl JsonV2Adapter {
                 crate::core::wire_protocol::serialize_cpp_wire(&container)
             }
             SerializationFormat::PythonJson => Self::to_python_json(&container, pretty),
+            SerializationFormat::Binary => Err(ContainerError::InvalidDataFormat(
+                "Cannot convert to binary format as text; use binary_protocol::serialize_binary"
+                    .to_string(),
+            )),
             SerializationFormat::Unknown => Err(ContainerError::InvalidDataFormat(
                 "Cannot convert to unknown format".to_string(),
             )),
@@ -531,6 +552,9 @@ impl JsonV2Adapter {
         container.set_source(source_id, source_sub_id);
         container.set_target(target_id, target_sub_id);
         container.set_message_type(message_type);
+        if let Some(version) = data.get("version").and_then(|v| v.as_str()) {
+            container.set_version(version);
+        }
 
         // Parse values array
         if let Some(values_array) = data.get("values").and_then(|v| v.as_array()) {
@@ -588,6 +612,17 @@ impl JsonV2Adapter {
             ValueType::String => {
                 obj.insert("data".to_string(), json!(value.to_string()));
             }
+            ValueType::Decimal => {
+                // Exact text; a JSON number would round through f64
+                obj.insert("data".to_string(), json!(value.to_string()));
+            }
+            ValueType::Timestamp | ValueType::Duration => {
+                // RFC 3339 timestamps, ISO 8601 durations
+                obj.insert("data".to_string(), json!(value.to_string()));
+            }
+            ValueType::Uuid => {
+                obj.insert("data".to_string(), json!(value.to_string()));
+            }
             ValueType::Bytes => {
                 // Base64 encode binary data (matches Python/.NET)
                 // Must downcast to BytesValue to get raw data (to_bytes() returns serialized format)
@@ -664,6 +699,10 @@ impl JsonV2Adapter {
             13 => ValueType::Bytes,
             14 => ValueType::Container,
             15 => ValueType::Array,
+            16 => ValueType::Decimal,
+            17 => ValueType::Timestamp,
+            18 => ValueType::Duration,
+            19 => ValueType::Uuid,
             _ => {
                 // Try type_name if type ID is invalid
                 let type_name = value_data.get("type_name").and_then(|v| v.as_str())?;
@@ -727,6 +766,20 @@ impl JsonV2Adapter {
                 let val = data?.as_str().unwrap_or("");
                 Some(Arc::new(StringValue::new(name, val)))
             }
+            ValueType::Decimal => {
+                let val = data?.as_str()?.parse::<Decimal>().ok()?;
+                Some(Arc::new(DecimalValue::new(name, val)))
+            }
+            ValueType::Timestamp => {
+                let val = data?.as_str()?.parse::<Timestamp>().ok()?;
+                Some(Arc::new(TimestampValue::new(name, val)))
+            }
+            ValueType::Duration => DurationValue::parse(name, data?.as_str()?)
+                .ok()
+                .map(|v| Arc::new(v) as Arc<dyn Value>),
+            ValueType::Uuid => UuidValue::parse(name, data?.as_str()?)
+                .ok()
+                .map(|v| Arc::new(v) as Arc<dyn Value>),
             ValueType::Bytes => {
                 // Decode base64
                 let encoding = value_data
@@ -1013,6 +1066,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_version_roundtrip_all_formats() {
+        let mut original = ValueContainer::new();
+        original.set_message_type("schema_msg");
+        original.set_version("2.4.1-beta");
+        original.add_value(Arc::new(IntValue::new("x", 1))).unwrap();
+
+        let v2 = JsonV2Adapter::to_v2_json(&original, false).unwrap();
+        assert_eq!(
+            JsonV2Adapter::from_v2_json(&v2).unwrap().version(),
+            "2.4.1-beta"
+        );
+
+        let cpp = JsonV2Adapter::to_cpp_json(&original, false).unwrap();
+        assert_eq!(
+            JsonV2Adapter::from_cpp_json(&cpp).unwrap().version(),
+            "2.4.1-beta"
+        );
+
+        let python = JsonV2Adapter::to_python_json(&original, false).unwrap();
+        assert_eq!(
+            JsonV2Adapter::from_python_json(&python).unwrap().version(),
+            "2.4.1-beta"
+        );
+
+        // Conversions between formats keep the version as well
+        let converted =
+            JsonV2Adapter::convert_format(&cpp, SerializationFormat::JsonV2, false).unwrap();
+        assert_eq!(
+            JsonV2Adapter::from_v2_json(&converted).unwrap().version(),
+            "2.4.1-beta"
+        );
+    }
+
+    #[test]
+    fn test_missing_version_uses_default() {
+        let cpp = r#"{"header": {"message_type": "t"}, "values": {}}"#;
+        assert_eq!(
+            JsonV2Adapter::from_cpp_json(cpp).unwrap().version(),
+            "1.0.0.0"
+        );
+    }
+
     #[test]
     fn test_convert_format() {
         // Create a container and convert to different formats
@@ -1039,6 +1135,16 @@ mod tests {
         assert_eq!(restored.message_type(), "test");
     }
 
+    #[test]
+    fn test_convert_format_rejects_binary_target() {
+        let v2_json = JsonV2Adapter::to_v2_json(&ValueContainer::new(), false).unwrap();
+        assert!(matches!(
+            JsonV2Adapter::convert_format(&v2_json, SerializationFormat::Binary, false),
+            Err(ContainerError::InvalidDataFormat(_))
+        ));
+        assert_eq!(SerializationFormat::Binary.to_string(), "binary");
+    }
+
     #[test]
     fn test_nested_container() {
         let mut container = ValueContainer::new();
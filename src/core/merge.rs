@@ -0,0 +1,459 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Deep Merge
+//!
+//! [`ValueContainer::merge`] copies the values of another container into this
+//! one, for example to lay overrides on top of defaults. Header fields of the
+//! target are kept.
+//!
+//! Values are paired by name, and repeated names by position, as in
+//! [`diff`]. Incoming values without a counterpart are appended.
+//! When both sides hold a different value, the [`MergePolicy`] decides:
+//!
+//! - two `ContainerValue`s are merged child by child (unless the policy is
+//!   shallow);
+//! - two `ArrayValue`s are replaced or concatenated ([`ArrayPolicy`]); with
+//!   [`ArrayPolicy::Append`] they are concatenated even when they are equal;
+//! - anything else, including a change of type, is a conflict resolved by
+//!   [`ConflictPolicy`].
+//!
+//! The merge runs under the container's write lock and is all or nothing: a
+//! conflict under [`ConflictPolicy::Error`] or reaching `max_values` leaves the
+//! container unchanged.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::merge::{ConflictPolicy, MergePolicy};
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let mut config = ValueContainer::new();
+//! config.add_value(Arc::new(IntValue::new("timeout", 30))).unwrap();
+//! config.add_value(Arc::new(IntValue::new("retries", 3))).unwrap();
+//!
+//! let mut overrides = ValueContainer::new();
+//! overrides.add_value(Arc::new(IntValue::new("timeout", 5))).unwrap();
+//! overrides.add_value(Arc::new(BoolValue::new("verbose", true))).unwrap();
+//!
+//! let report = config.merge(&overrides, MergePolicy::default()).unwrap();
+//! assert_eq!(config.get_value("timeout").unwrap().to_int().unwrap(), 5);
+//! assert_eq!(config.value_count(), 3);
+//! assert_eq!(report.conflicts()[0].path, "timeout");
+//!
+//! let strict = MergePolicy::new(ConflictPolicy::Error);
+//! assert!(config.merge(&overrides.copy(true), strict).is_ok()); // same values now
+//! ```
+//!
+//! [`ValueContainer::merge`]: crate::core::ValueContainer::merge
+//! [`diff`]: crate::core::diff
+//! [`MergePolicy`]: crate::core::merge::MergePolicy
+//! [`ArrayPolicy`]: crate::core::merge::ArrayPolicy
+//! [`ArrayPolicy::Append`]: crate::core::merge::ArrayPolicy::Append
+//! [`ConflictPolicy`]: crate::core::merge::ConflictPolicy
+//! [`ConflictPolicy::Error`]: crate::core::merge::ConflictPolicy::Error
+
+use super::container::ValueContainer;
+use super::diff::same_payload;
+use super::value_path::{child_path, PathLevel};
+use super::{ContainerError, Result, Value};
+use crate::values::{ArrayValue, ContainerValue};
+use indexmap::IndexMap;
+use std::sync::Arc;
+
+/// What to do when both sides hold a different value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Take the incoming value
+    #[default]
+    LastWriterWins,
+    /// Keep the existing value
+    KeepExisting,
+    /// Fail the merge with `ContainerError::MergeConflict`
+    Error,
+}
+
+/// What to do when both sides hold an array
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayPolicy {
+    /// Treat differing arrays like any other value (see [`ConflictPolicy`])
+    #[default]
+    Replace,
+    /// Append the incoming elements to the existing ones, even when both arrays
+    /// are equal (an empty incoming array changes nothing)
+    Append,
+}
+
+/// How [`ValueContainer::merge`](crate::core::ValueContainer::merge) combines values
+///
+/// The default takes incoming values, replaces arrays and merges nested
+/// containers recursively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergePolicy {
+    conflicts: ConflictPolicy,
+    arrays: ArrayPolicy,
+    recursive: bool,
+}
+
+impl MergePolicy {
+    /// Policy with the given conflict handling, replacing arrays and merging
+    /// nested containers
+    pub fn new(conflicts: ConflictPolicy) -> Self {
+        Self {
+            conflicts,
+            arrays: ArrayPolicy::Replace,
+            recursive: true,
+        }
+    }
+
+    /// Set how arrays are combined
+    #[must_use = "builder methods return a new value"]
+    pub fn arrays(mut self, arrays: ArrayPolicy) -> Self {
+        self.arrays = arrays;
+        self
+    }
+
+    /// Whether nested `ContainerValue`s are merged child by child (default) or
+    /// treated as single values
+    #[must_use = "builder methods return a new value"]
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        Self::new(ConflictPolicy::default())
+    }
+}
+
+/// How a conflict was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The incoming value replaced the existing one
+    TookIncoming,
+    /// The existing value was kept
+    KeptExisting,
+    /// The incoming array elements were appended to the existing array
+    Appended,
+}
+
+/// A place where both containers held a different value
+#[derive(Debug, Clone)]
+pub struct MergeConflict {
+    /// Path to the value (see [`value_path`](super::value_path))
+    pub path: String,
+    /// The value before the merge
+    pub existing: Arc<dyn Value>,
+    /// The value from the other container
+    pub incoming: Arc<dyn Value>,
+    /// What the merge did
+    pub resolution: Resolution,
+}
+
+/// What a merge did
+#[derive(Debug, Clone, Default)]
+pub struct MergeReport {
+    conflicts: Vec<MergeConflict>,
+    added: Vec<String>,
+}
+
+impl MergeReport {
+    /// Every conflict, in tree order
+    pub fn conflicts(&self) -> &[MergeConflict] {
+        &self.conflicts
+    }
+
+    /// Paths of the values that were appended because the target had no
+    /// counterpart
+    pub fn added(&self) -> &[String] {
+        &self.added
+    }
+
+    /// Whether the merge changed nothing
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self
+                .conflicts
+                .iter()
+                .all(|conflict| conflict.resolution == Resolution::KeptExisting)
+    }
+}
+
+/// Merge `other` into `target`, see the module documentation
+pub(crate) fn merge(
+    target: &mut ValueContainer,
+    other: &ValueContainer,
+    policy: MergePolicy,
+) -> Result<MergeReport> {
+    // Read first: `other` may share its lock with `target`
+    let incoming = other.values();
+    target.transact(|inner| {
+        let mut report = MergeReport::default();
+        merge_level(inner, &incoming, "", policy, &mut report)?;
+        Ok(report)
+    })
+}
+
+/// Values with one name, in order
+type Named<'a> = Vec<&'a Arc<dyn Value>>;
+
+fn merge_level(
+    level: &mut dyn PathLevel,
+    incoming: &[Arc<dyn Value>],
+    parent: &str,
+    policy: MergePolicy,
+    report: &mut MergeReport,
+) -> Result<()> {
+    let mut names: IndexMap<&str, Named<'_>> = IndexMap::new();
+    for value in incoming {
+        names.entry(value.name()).or_default().push(value);
+    }
+
+    for (name, incoming) in names {
+        let existing = level.count(name);
+        let repeated = existing > 1 || incoming.len() > 1;
+        for (nth, value) in incoming.into_iter().enumerate() {
+            let path = child_path(parent, name, repeated.then_some(nth));
+            if nth >= existing {
+                level.push(Arc::clone(value))?;
+                report.added.push(path);
+                continue;
+            }
+
+            let current = level.nth(name, nth).expect("nth is below the count");
+            if let Some(merged) = merge_value(&current, value, &path, policy, report)? {
+                level.replace_nth(name, nth, merged)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The merged value, or `None` to keep `existing`
+fn merge_value(
+    existing: &Arc<dyn Value>,
+    incoming: &Arc<dyn Value>,
+    path: &str,
+    policy: MergePolicy,
+    report: &mut MergeReport,
+) -> Result<Option<Arc<dyn Value>>> {
+    if existing.value_type() == incoming.value_type() {
+        let (existing_any, incoming_any) = (existing.as_any(), incoming.as_any());
+        if let (true, Some(current), Some(other)) = (
+            policy.recursive,
+            existing_any.downcast_ref::<ContainerValue>(),
+            incoming_any.downcast_ref::<ContainerValue>(),
+        ) {
+            let mut merged = current.clone();
+            merge_level(&mut merged, other.children(), path, policy, report)?;
+            return Ok(Some(Arc::new(merged)));
+        }
+        if let (ArrayPolicy::Append, Some(current), Some(other)) = (
+            policy.arrays,
+            existing_any.downcast_ref::<ArrayValue>(),
+            incoming_any.downcast_ref::<ArrayValue>(),
+        ) {
+            if other.is_empty() {
+                return Ok(None);
+            }
+            let mut merged = current.clone();
+            for element in other.elements() {
+                merged.push(Arc::clone(element));
+            }
+            report.conflict(path, existing, incoming, Resolution::Appended);
+            return Ok(Some(Arc::new(merged)));
+        }
+        if same_payload(existing.as_ref(), incoming.as_ref()) {
+            return Ok(None);
+        }
+    }
+
+    match policy.conflicts {
+        ConflictPolicy::LastWriterWins => {
+            report.conflict(path, existing, incoming, Resolution::TookIncoming);
+            Ok(Some(Arc::clone(incoming)))
+        }
+        ConflictPolicy::KeepExisting => {
+            report.conflict(path, existing, incoming, Resolution::KeptExisting);
+            Ok(None)
+        }
+        ConflictPolicy::Error => Err(ContainerError::MergeConflict {
+            path: path.to_string(),
+            existing: format!("{} {}", existing.value_type(), existing.to_string()),
+            incoming: format!("{} {}", incoming.value_type(), incoming.to_string()),
+        }),
+    }
+}
+
+impl MergeReport {
+    fn conflict(
+        &mut self,
+        path: &str,
+        existing: &Arc<dyn Value>,
+        incoming: &Arc<dyn Value>,
+        resolution: Resolution,
+    ) {
+        self.conflicts.push(MergeConflict {
+            path: path.to_string(),
+            existing: Arc::clone(existing),
+            incoming: Arc::clone(incoming),
+            resolution,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::{IntValue, StringValue};
+
+    fn int(name: &str, value: i32) -> Arc<dyn Value> {
+        Arc::new(IntValue::new(name, value))
+    }
+
+    fn container(values: Vec<Arc<dyn Value>>) -> ValueContainer {
+        let mut container = ValueContainer::new();
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+        container
+    }
+
+    fn server(port: i32, tags: &[i32]) -> Arc<dyn Value> {
+        let tags = tags.iter().map(|&t| int("", t)).collect();
+        Arc::new(ContainerValue::new(
+            "server",
+            vec![int("port", port), Arc::new(ArrayValue::new("tags", tags))],
+        ))
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let defaults = container(vec![int("a", 1), int("b", 2)]);
+        let overrides = container(vec![int("b", 20), Arc::new(StringValue::new("c", "new"))]);
+
+        let mut merged = defaults.copy(true);
+        let report = merged.merge(&overrides, MergePolicy::default()).unwrap();
+        assert_eq!(merged.get_value("b").unwrap().to_int().unwrap(), 20);
+        assert_eq!(merged.value_count(), 3);
+        assert_eq!(report.added(), ["c"]);
+        assert_eq!(report.conflicts().len(), 1);
+        assert_eq!(report.conflicts()[0].resolution, Resolution::TookIncoming);
+
+        let mut kept = defaults.copy(true);
+        let policy = MergePolicy::new(ConflictPolicy::KeepExisting);
+        let report = kept.merge(&overrides, policy).unwrap();
+        assert_eq!(kept.get_value("b").unwrap().to_int().unwrap(), 2);
+        assert_eq!(report.conflicts()[0].resolution, Resolution::KeptExisting);
+
+        let mut strict = defaults.copy(true);
+        let err = strict
+            .merge(&overrides, MergePolicy::new(ConflictPolicy::Error))
+            .unwrap_err();
+        assert!(matches!(err, ContainerError::MergeConflict { ref path, .. } if path == "b"));
+        // Nothing was merged, not even the value before the conflict
+        assert_eq!(strict.value_count(), 2);
+        assert!(strict.diff(&defaults).is_empty());
+    }
+
+    #[test]
+    fn test_recursive_and_arrays() {
+        let mut target = container(vec![server(80, &[1, 2])]);
+        let incoming = container(vec![server(8080, &[3])]);
+
+        let policy = MergePolicy::default().arrays(ArrayPolicy::Append);
+        let report = target.merge(&incoming, policy).unwrap();
+        assert_eq!(
+            target.get_path("server.port").unwrap().to_int().unwrap(),
+            8080
+        );
+        let tags = target.get_path("server.tags").unwrap();
+        let tags = tags.as_any().downcast_ref::<ArrayValue>().unwrap();
+        assert_eq!(tags.count(), 3);
+        let paths: Vec<_> = report.conflicts().iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["server.port", "server.tags"]);
+        assert_eq!(report.conflicts()[1].resolution, Resolution::Appended);
+
+        // Equal arrays are appended too; an empty one changes nothing
+        let mut same = container(vec![server(80, &[1, 2])]);
+        let report = same
+            .merge(&container(vec![server(80, &[1, 2])]), policy)
+            .unwrap();
+        let tags = same.get_path("server.tags").unwrap();
+        let tags = tags.as_any().downcast_ref::<ArrayValue>().unwrap();
+        assert_eq!(tags.count(), 4);
+        assert_eq!(report.conflicts()[0].resolution, Resolution::Appended);
+        let report = same
+            .merge(&container(vec![server(80, &[])]), policy)
+            .unwrap();
+        assert!(report.conflicts().is_empty());
+        assert_eq!(
+            same.get_path("server.tags[3]").unwrap().to_int().unwrap(),
+            2
+        );
+
+        let mut shallow = container(vec![server(80, &[1, 2])]);
+        let report = shallow
+            .merge(&incoming, MergePolicy::default().recursive(false))
+            .unwrap();
+        assert_eq!(report.conflicts()[0].path, "server");
+        assert!(shallow.diff(&incoming).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_names_and_limits() {
+        let mut target = container(vec![int("x", 1)]);
+        let incoming = container(vec![int("x", 1), int("x", 2)]);
+        let report = target.merge(&incoming, MergePolicy::default()).unwrap();
+        assert_eq!(report.added(), ["x{1}"]);
+        assert!(report.conflicts().is_empty());
+        assert_eq!(target.get_value_array("x").len(), 2);
+
+        // Merging identical content changes nothing
+        let again = target.merge(&incoming, MergePolicy::default()).unwrap();
+        assert!(again.is_empty());
+
+        let mut limited = ValueContainer::with_max_values(2);
+        limited.add_value(int("a", 1)).unwrap();
+        let incoming = container(vec![int("b", 2), int("c", 3)]);
+        assert!(limited.merge(&incoming, MergePolicy::default()).is_err());
+        assert_eq!(limited.value_count(), 1);
+    }
+
+    #[test]
+    fn test_merge_with_itself() {
+        let mut target = container(vec![int("a", 1)]);
+        let same = target.clone();
+        let report = target.merge(&same, MergePolicy::default()).unwrap();
+        assert!(report.is_empty());
+    }
+}
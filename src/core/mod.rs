@@ -65,12 +65,64 @@ pub mod container;
 /// C++ wire protocol implementation for cross-language compatibility
 pub mod wire_protocol;
 
+/// Binary value encoder and decoder registry (C++ value layout)
+pub mod value_codec;
+
+/// Binary container format (C++ value layout with a header block)
+pub mod binary_protocol;
+
+/// Resumable decoders for containers received in pieces
+pub mod incremental;
+
+/// Zero-copy read-only view over a binary container
+pub mod container_view;
+
+/// Serde serializer and deserializer backed by container values
+pub mod serde_bridge;
+
+/// Serde `Serialize` / `Deserialize` for values and containers
+pub mod value_serde;
+
+/// Owned value payloads (closed enum over every value type)
+pub mod value_data;
+
+/// Path expressions into nested containers and arrays (`order.items[3].price`)
+pub mod value_path;
+
+/// JSONPath-style queries over container trees (`$..items[?(@.qty > 5)].price`)
+pub mod query;
+
+/// Structural diff between two containers
+pub mod diff;
+
+/// Typed patches computed from two containers and applied atomically
+pub mod patch;
+
+/// Deep merge of containers with conflict policies
+pub mod merge;
+
+/// Schemas and validation for message types
+pub mod schema;
+
+/// Schema compatibility rules and upgrades between versions
+pub mod schema_evolution;
+
+/// JSON Schema export and import of message type schemas
+pub mod json_schema;
+
+/// Length-prefixed framing for streams of serialized containers
+pub mod framing;
+
 /// JSON v2.0 adapter for cross-language compatibility
 pub mod json_v2_adapter;
 
 /// Domain-agnostic value storage (separate from messaging-specific container)
 pub mod value_store;
 
+/// Helpers shared by the unit tests of the core modules
+#[cfg(test)]
+mod test_support;
+
 /// Re-export error types
 ///
 /// ```rust
@@ -108,7 +160,7 @@ pub use value::{BaseValue, Value};
 /// // to_str() returns the numeric string representation (matches C++ string_value = 12)
 /// assert_eq!(vtype.to_str(), "12");
 /// ```
-pub use value_types::ValueType;
+pub use value_types::{TypeSupport, ValueType};
 
 /// Re-export ValueContainer, Builder and constants
 ///
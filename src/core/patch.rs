@@ -0,0 +1,855 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Container Patches
+//!
+//! A [`Patch`] is a list of [`PatchOp`]s, modeled on JSON Patch (RFC 6902) but
+//! carrying typed values: `replace` and `remove` can require the exact old value
+//! (type and payload), and `test_type` checks a `ValueType`.
+//!
+//! - [`Patch::diff`] computes the operations that turn one container into another,
+//!   with the old values as preconditions.
+//! - [`Patch::apply`] runs every operation under the container's write lock. If
+//!   any operation or precondition fails, the container is left untouched.
+//! - A patch is itself a container ([`Patch::to_container`]), so it travels in
+//!   the wire protocol ([`Patch::to_wire`]) or JSON v2.0 ([`Patch::to_json_v2`]).
+//!
+//! Paths use [`value_path`] syntax and are resolved when the operation runs,
+//! after the operations before it. `add` appends a value to the
+//! container (empty path), `ContainerValue` or `ArrayValue` at its path.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::patch::Patch;
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let mut state = ValueContainer::new();
+//! state.add_value(Arc::new(IntValue::new("count", 1))).unwrap();
+//!
+//! let mut next = state.copy(true);
+//! next.set_path("count", Arc::new(IntValue::new("count", 2))).unwrap();
+//! next.add_value(Arc::new(StringValue::new("note", "hi"))).unwrap();
+//!
+//! // Send only the change
+//! let wire = Patch::diff(&state, &next).to_wire().unwrap();
+//!
+//! let mut replica = state.copy(true);
+//! Patch::from_wire(&wire).unwrap().apply(&mut replica).unwrap();
+//! assert!(replica.diff(&next).is_empty());
+//!
+//! // The old value no longer matches, so nothing is applied
+//! assert!(Patch::from_wire(&wire).unwrap().apply(&mut replica).is_err());
+//! assert!(replica.diff(&next).is_empty());
+//! ```
+//!
+//! [`Patch`]: crate::core::patch::Patch
+//! [`PatchOp`]: crate::core::patch::PatchOp
+//! [`Patch::diff`]: crate::core::patch::Patch::diff
+//! [`Patch::apply`]: crate::core::patch::Patch::apply
+//! [`Patch::to_container`]: crate::core::patch::Patch::to_container
+//! [`Patch::to_wire`]: crate::core::patch::Patch::to_wire
+//! [`Patch::to_json_v2`]: crate::core::patch::Patch::to_json_v2
+//! [`value_path`]: crate::core::value_path
+
+use super::container::{ContainerInner, ValueContainer};
+use super::diff::{diff_headers, same_payload, Change};
+use super::json_v2_adapter::JsonV2Adapter;
+use super::value_path::{child_path, element_path, PathLevel, ValuePath};
+use super::{ContainerError, Result, Value, ValueType};
+use crate::values::{ArrayValue, ContainerValue, StringValue};
+use indexmap::IndexMap;
+use std::sync::Arc;
+
+/// `message_type` of a container holding a patch
+pub const PATCH_MESSAGE_TYPE: &str = "container_patch";
+
+/// One patch operation
+#[derive(Debug, Clone)]
+pub enum PatchOp {
+    /// Append `value` to the container (empty `parent`), or to the
+    /// `ContainerValue` or `ArrayValue` at `parent`
+    Add {
+        parent: String,
+        value: Arc<dyn Value>,
+    },
+    /// Remove the value at `path`, which must equal `old` if given
+    Remove {
+        path: String,
+        old: Option<Arc<dyn Value>>,
+    },
+    /// Replace the value at `path`, which must exist and equal `old` if given
+    Replace {
+        path: String,
+        old: Option<Arc<dyn Value>>,
+        value: Arc<dyn Value>,
+    },
+    /// Require the value at `path` to equal `value` (type and payload)
+    Test { path: String, value: Arc<dyn Value> },
+    /// Require the value at `path` to have type `value_type`
+    TestType { path: String, value_type: ValueType },
+    /// Set a header field (`source_id`, ..., `version`), which must equal `old` if given
+    SetHeader {
+        field: String,
+        old: Option<String>,
+        value: String,
+    },
+}
+
+impl PatchOp {
+    /// Operation name used when serialized (`add`, `remove`, ...)
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatchOp::Add { .. } => "add",
+            PatchOp::Remove { .. } => "remove",
+            PatchOp::Replace { .. } => "replace",
+            PatchOp::Test { .. } => "test",
+            PatchOp::TestType { .. } => "test_type",
+            PatchOp::SetHeader { .. } => "header",
+        }
+    }
+
+    fn apply(&self, inner: &mut ContainerInner) -> Result<()> {
+        match self {
+            PatchOp::Add { parent, value } => {
+                if parent.is_empty() {
+                    return inner.push(Arc::clone(value));
+                }
+                let path = ValuePath::parse(parent)?;
+                let target = path.get_at(inner)?;
+                let any = target.as_any();
+                let updated: Arc<dyn Value> =
+                    if let Some(container) = any.downcast_ref::<ContainerValue>() {
+                        let mut container = container.clone();
+                        container.add_child(Arc::clone(value));
+                        Arc::new(container)
+                    } else if let Some(array) = any.downcast_ref::<ArrayValue>() {
+                        let mut array = array.clone();
+                        array.push(Arc::clone(value));
+                        Arc::new(array)
+                    } else {
+                        return Err(ContainerError::Other(format!(
+                            "'{}' is a {}, not a container or array",
+                            parent,
+                            target.value_type()
+                        )));
+                    };
+                path.set_at(inner, updated)?;
+            }
+            PatchOp::Remove { path, old } => {
+                let value_path = ValuePath::parse(path)?;
+                if let Some(old) = old {
+                    expect_value(path, &value_path.get_at(inner)?, old)?;
+                }
+                value_path.remove_at(inner)?;
+            }
+            PatchOp::Replace { path, old, value } => {
+                let value_path = ValuePath::parse(path)?;
+                let current = value_path.get_at(inner)?;
+                if let Some(old) = old {
+                    expect_value(path, &current, old)?;
+                }
+                value_path.set_at(inner, Arc::clone(value))?;
+            }
+            PatchOp::Test { path, value } => {
+                let current = ValuePath::parse(path)?.get_at(inner)?;
+                expect_value(path, &current, value)?;
+            }
+            PatchOp::TestType { path, value_type } => {
+                let current = ValuePath::parse(path)?.get_at(inner)?;
+                if current.value_type() != *value_type {
+                    return Err(ContainerError::Other(format!(
+                        "'{}' is a {}, expected a {}",
+                        path,
+                        current.value_type(),
+                        value_type
+                    )));
+                }
+            }
+            PatchOp::SetHeader { field, old, value } => {
+                let header = inner.header_mut(field).ok_or_else(|| {
+                    ContainerError::Other(format!("unknown header field '{}'", field))
+                })?;
+                if let Some(old) = old {
+                    if header != old {
+                        return Err(ContainerError::Other(format!(
+                            "header {} is {:?}, expected {:?}",
+                            field, header, old
+                        )));
+                    }
+                }
+                *header = value.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn expect_value(path: &str, current: &Arc<dyn Value>, expected: &Arc<dyn Value>) -> Result<()> {
+    if current.value_type() != expected.value_type() {
+        return Err(ContainerError::Other(format!(
+            "'{}' is a {}, expected a {}",
+            path,
+            current.value_type(),
+            expected.value_type()
+        )));
+    }
+    if !same_payload(current.as_ref(), expected.as_ref()) {
+        return Err(ContainerError::Other(format!(
+            "'{}' does not hold the expected value",
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// An ordered list of patch operations
+#[derive(Debug, Clone, Default)]
+pub struct Patch {
+    ops: Vec<PatchOp>,
+}
+
+impl Patch {
+    /// Create an empty patch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an operation
+    pub fn push(&mut self, op: PatchOp) {
+        self.ops.push(op);
+    }
+
+    /// The operations, in the order they are applied
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    /// Number of operations
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Whether the patch has no operations
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Compute the patch that turns `old` into `new`
+    ///
+    /// Values are paired as in [`diff`](super::diff). Replaced and removed values
+    /// carry their old value as a precondition, so the patch only applies to a
+    /// container that still matches `old` in those places.
+    pub fn diff(old: &ValueContainer, new: &ValueContainer) -> Self {
+        let mut ops: Vec<PatchOp> = diff_headers(old, new)
+            .into_iter()
+            .filter_map(|change| match change {
+                Change::Header { field, old, new } => Some(PatchOp::SetHeader {
+                    field: field.to_string(),
+                    old: Some(old),
+                    value: new,
+                }),
+                _ => None,
+            })
+            .collect();
+        diff_named(&old.values(), &new.values(), "", &mut ops);
+        Self { ops }
+    }
+
+    /// Apply every operation, or none
+    ///
+    /// Runs under the container's write lock, so other handles to the same
+    /// container see either none or all of the changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PatchError` with the index of the first operation that failed;
+    /// the container is left unchanged.
+    pub fn apply(&self, container: &mut ValueContainer) -> Result<()> {
+        container.transact(|inner| {
+            for (index, op) in self.ops.iter().enumerate() {
+                op.apply(inner).map_err(|err| ContainerError::PatchError {
+                    index,
+                    op: op.name(),
+                    message: err.to_string(),
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Represent the patch as a container with one `operation` value per op
+    ///
+    /// Each `operation` is a `ContainerValue` with `op` and `path` strings, and
+    /// `value` / `old` containers wrapping the value so its name and type are
+    /// kept (`type` holds the type code for `test_type`; header ops use strings).
+    ///
+    /// # Errors
+    ///
+    /// Returns the container's value limit error for a patch with more than
+    /// `ABSOLUTE_MAX_VALUES` operations.
+    pub fn to_container(&self) -> Result<ValueContainer> {
+        let mut container = ValueContainer::builder()
+            .message_type(PATCH_MESSAGE_TYPE)
+            .max_values(self.ops.len().max(1))
+            .build();
+        for op in &self.ops {
+            let mut fields: Vec<Arc<dyn Value>> = vec![Arc::new(StringValue::new("op", op.name()))];
+            let mut field = |name: &str, value: Arc<dyn Value>| {
+                fields.push(Arc::new(ContainerValue::new(name, vec![value])));
+            };
+            match op {
+                PatchOp::Add { parent, value } => {
+                    field("value", Arc::clone(value));
+                    fields.push(Arc::new(StringValue::new("path", parent.as_str())));
+                }
+                PatchOp::Remove { path, old } => {
+                    if let Some(old) = old {
+                        field("old", Arc::clone(old));
+                    }
+                    fields.push(Arc::new(StringValue::new("path", path.as_str())));
+                }
+                PatchOp::Replace { path, old, value } => {
+                    field("value", Arc::clone(value));
+                    if let Some(old) = old {
+                        field("old", Arc::clone(old));
+                    }
+                    fields.push(Arc::new(StringValue::new("path", path.as_str())));
+                }
+                PatchOp::Test { path, value } => {
+                    field("value", Arc::clone(value));
+                    fields.push(Arc::new(StringValue::new("path", path.as_str())));
+                }
+                PatchOp::TestType { path, value_type } => {
+                    fields.push(Arc::new(StringValue::new("type", value_type.to_str())));
+                    fields.push(Arc::new(StringValue::new("path", path.as_str())));
+                }
+                PatchOp::SetHeader { field, old, value } => {
+                    fields.push(Arc::new(StringValue::new("value", value.as_str())));
+                    if let Some(old) = old {
+                        fields.push(Arc::new(StringValue::new("old", old.as_str())));
+                    }
+                    fields.push(Arc::new(StringValue::new("path", field.as_str())));
+                }
+            }
+            container.add_value(Arc::new(ContainerValue::new("operation", fields)))?;
+        }
+        Ok(container)
+    }
+
+    /// Read a patch written by [`to_container`](Self::to_container)
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the container is not a patch or an
+    /// operation is malformed.
+    pub fn from_container(container: &ValueContainer) -> Result<Self> {
+        if container.message_type() != PATCH_MESSAGE_TYPE {
+            return Err(invalid(format!(
+                "message type is '{}', not '{}'",
+                container.message_type(),
+                PATCH_MESSAGE_TYPE
+            )));
+        }
+
+        let mut ops = Vec::with_capacity(container.value_count());
+        for (index, value) in container.values().iter().enumerate() {
+            let op = value
+                .as_any()
+                .downcast_ref::<ContainerValue>()
+                .filter(|_| value.name() == "operation")
+                .ok_or_else(|| invalid(format!("value {} is not an operation", index)))
+                .and_then(read_op)
+                .map_err(|err| invalid(format!("operation {}: {}", index, err)))?;
+            ops.push(op);
+        }
+        Ok(Self { ops })
+    }
+
+    /// Serialize in the C++ wire protocol
+    pub fn to_wire(&self) -> Result<String> {
+        self.to_container()?.serialize_cpp_wire()
+    }
+
+    /// Deserialize from the C++ wire protocol
+    pub fn from_wire(data: &str) -> Result<Self> {
+        Self::from_container(&ValueContainer::deserialize_cpp_wire(data)?)
+    }
+
+    /// Serialize as JSON v2.0
+    pub fn to_json_v2(&self) -> Result<String> {
+        JsonV2Adapter::to_v2_json(&self.to_container()?, false)
+    }
+
+    /// Deserialize from JSON v2.0
+    pub fn from_json_v2(json: &str) -> Result<Self> {
+        Self::from_container(&JsonV2Adapter::from_v2_json(json)?)
+    }
+}
+
+impl FromIterator<PatchOp> for Patch {
+    fn from_iter<I: IntoIterator<Item = PatchOp>>(iter: I) -> Self {
+        Self {
+            ops: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Patch {
+    type Item = PatchOp;
+    type IntoIter = std::vec::IntoIter<PatchOp>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.into_iter()
+    }
+}
+
+fn invalid(message: String) -> ContainerError {
+    ContainerError::InvalidDataFormat(format!("invalid patch: {}", message))
+}
+
+fn read_op(op: &ContainerValue) -> Result<PatchOp> {
+    let text = |name: &str| -> Result<String> {
+        let value = op
+            .get_child(name, 0)
+            .ok_or_else(|| ContainerError::ValueNotFound(name.to_string()))?;
+        if value.value_type() != ValueType::String {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "'{}' is a {}, not a string_value",
+                name,
+                value.value_type()
+            )));
+        }
+        Ok(value.to_string())
+    };
+    let wrapped = |name: &str| -> Result<Option<Arc<dyn Value>>> {
+        let Some(wrapper) = op.get_child(name, 0) else {
+            return Ok(None);
+        };
+        match wrapper.as_any().downcast_ref::<ContainerValue>() {
+            Some(wrapper) if wrapper.child_count() == 1 => {
+                Ok(Some(Arc::clone(&wrapper.children()[0])))
+            }
+            _ => Err(ContainerError::InvalidDataFormat(format!(
+                "'{}' must be a container_value holding one value",
+                name
+            ))),
+        }
+    };
+    let required = |name: &str| -> Result<Arc<dyn Value>> {
+        wrapped(name)?.ok_or_else(|| ContainerError::ValueNotFound(name.to_string()))
+    };
+
+    let path = text("path")?;
+    Ok(match text("op")?.as_str() {
+        "add" => PatchOp::Add {
+            parent: path,
+            value: required("value")?,
+        },
+        "remove" => PatchOp::Remove {
+            path,
+            old: wrapped("old")?,
+        },
+        "replace" => PatchOp::Replace {
+            path,
+            old: wrapped("old")?,
+            value: required("value")?,
+        },
+        "test" => PatchOp::Test {
+            path,
+            value: required("value")?,
+        },
+        "test_type" => {
+            let code = text("type")?;
+            PatchOp::TestType {
+                path,
+                value_type: ValueType::from_type_code(&code).ok_or_else(|| {
+                    ContainerError::InvalidDataFormat(format!("unknown type code '{}'", code))
+                })?,
+            }
+        }
+        "header" => PatchOp::SetHeader {
+            field: path,
+            old: op.get_child("old", 0).map(|_| text("old")).transpose()?,
+            value: text("value")?,
+        },
+        other => {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "unknown op '{}'",
+                other
+            )))
+        }
+    })
+}
+
+// ============================================================================
+// Computing patches
+// ============================================================================
+//
+// Paths are resolved when each operation runs, so operations for one level come
+// in an order that keeps them valid: changes inside paired values first (while
+// every value is still in place), then removals from the last position down,
+// then additions, which append and need no index.
+
+/// Values with one name, from the old and the new side
+type Pairs<'a> = (Vec<&'a Arc<dyn Value>>, Vec<&'a Arc<dyn Value>>);
+
+fn diff_named(
+    old: &[Arc<dyn Value>],
+    new: &[Arc<dyn Value>],
+    parent: &str,
+    ops: &mut Vec<PatchOp>,
+) {
+    let mut names: IndexMap<&str, Pairs<'_>> = IndexMap::new();
+    for value in old {
+        names.entry(value.name()).or_default().0.push(value);
+    }
+    for value in new {
+        names.entry(value.name()).or_default().1.push(value);
+    }
+
+    for (name, (old, new)) in names {
+        let paired = old.len().min(new.len());
+        for nth in 0..paired {
+            let path = child_path(parent, name, (old.len() > 1).then_some(nth));
+            diff_value(old[nth], new[nth], path, ops);
+        }
+        // While removing position `nth` there are `nth + 1` values left
+        for nth in (paired..old.len()).rev() {
+            ops.push(PatchOp::Remove {
+                path: child_path(parent, name, (nth > 0).then_some(nth)),
+                old: Some(Arc::clone(old[nth])),
+            });
+        }
+        for value in &new[paired..] {
+            ops.push(PatchOp::Add {
+                parent: parent.to_string(),
+                value: Arc::clone(value),
+            });
+        }
+    }
+}
+
+fn diff_elements(
+    old: &[Arc<dyn Value>],
+    new: &[Arc<dyn Value>],
+    parent: &str,
+    ops: &mut Vec<PatchOp>,
+) {
+    let paired = old.len().min(new.len());
+    for index in 0..paired {
+        diff_value(&old[index], &new[index], element_path(parent, index), ops);
+    }
+    for index in (paired..old.len()).rev() {
+        ops.push(PatchOp::Remove {
+            path: element_path(parent, index),
+            old: Some(Arc::clone(&old[index])),
+        });
+    }
+    for value in &new[paired..] {
+        ops.push(PatchOp::Add {
+            parent: parent.to_string(),
+            value: Arc::clone(value),
+        });
+    }
+}
+
+fn diff_value(old: &Arc<dyn Value>, new: &Arc<dyn Value>, path: String, ops: &mut Vec<PatchOp>) {
+    if old.value_type() == new.value_type() {
+        let (old_any, new_any) = (old.as_any(), new.as_any());
+        if let (Some(old), Some(new)) = (
+            old_any.downcast_ref::<ContainerValue>(),
+            new_any.downcast_ref::<ContainerValue>(),
+        ) {
+            return diff_named(old.children(), new.children(), &path, ops);
+        }
+        if let (Some(old), Some(new)) = (
+            old_any.downcast_ref::<ArrayValue>(),
+            new_any.downcast_ref::<ArrayValue>(),
+        ) {
+            return diff_elements(old.elements(), new.elements(), &path, ops);
+        }
+        if same_payload(old.as_ref(), new.as_ref()) {
+            return;
+        }
+    }
+    ops.push(PatchOp::Replace {
+        path,
+        old: Some(Arc::clone(old)),
+        value: Arc::clone(new),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::container::ABSOLUTE_MAX_VALUES;
+    use crate::values::{DoubleValue, IntValue, LLongValue};
+
+    fn items(qty: &[i32]) -> Arc<dyn Value> {
+        let items = qty
+            .iter()
+            .map(|&q| -> Arc<dyn Value> {
+                Arc::new(ContainerValue::new(
+                    "",
+                    vec![Arc::new(IntValue::new("qty", q))],
+                ))
+            })
+            .collect();
+        Arc::new(ArrayValue::new("items", items))
+    }
+
+    fn container(values: Vec<Arc<dyn Value>>) -> ValueContainer {
+        let mut container = ValueContainer::new();
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+        container
+    }
+
+    fn int(name: &str, n: i32) -> Arc<dyn Value> {
+        Arc::new(IntValue::new(name, n))
+    }
+
+    /// Diff, apply to a copy of `old` and check the result equals `new`
+    fn round_trip(old: &ValueContainer, new: &ValueContainer) -> Patch {
+        let patch = Patch::diff(old, new);
+        let mut target = old.copy(true);
+        target.apply_patch(&patch).unwrap();
+        let left = target.diff(new);
+        assert!(left.is_empty(), "patch {:?} left:\n{}", patch.ops(), left);
+        patch
+    }
+
+    #[test]
+    fn test_diff_and_apply() {
+        let order = |qty: &[i32], total: Arc<dyn Value>| -> Arc<dyn Value> {
+            Arc::new(ContainerValue::new("order", vec![items(qty), total]))
+        };
+        let old = container(vec![
+            order(&[1, 2, 3], Arc::new(DoubleValue::new("total", 6.0))),
+            int("tag", 1),
+            int("tag", 2),
+            int("tag", 3),
+        ]);
+        let mut new = container(vec![
+            order(&[1, 5], Arc::new(LLongValue::new("total", 6))),
+            int("tag", 1),
+            Arc::new(ContainerValue::new("meta", vec![int("v", 1)])),
+        ]);
+        new.set_message_type("order_update");
+
+        let patch = round_trip(&old, &new);
+        let names: Vec<&str> = patch.ops().iter().map(PatchOp::name).collect();
+        assert_eq!(
+            names,
+            ["header", "replace", "remove", "replace", "remove", "remove", "add"]
+        );
+
+        // And back again: additions to arrays and duplicates
+        round_trip(&new, &old);
+        assert!(Patch::diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_arrays() {
+        // A single array called `items` becomes two: the second must be added as a
+        // value, not as an element of the first
+        let old = container(vec![items(&[1])]);
+        let new = container(vec![items(&[1, 2]), items(&[3])]);
+        round_trip(&old, &new);
+        round_trip(&new, &old);
+
+        let swapped = container(vec![items(&[3]), items(&[1, 2])]);
+        round_trip(&new, &swapped);
+    }
+
+    #[test]
+    fn test_failed_precondition_leaves_container_untouched() {
+        let old = container(vec![int("a", 1), int("b", 2), items(&[1, 2])]);
+        let mut new = old.copy(true);
+        new.set_path("a", int("a", 10)).unwrap();
+        new.remove_path("items[1]").unwrap();
+        new.set_path("b", int("b", 20)).unwrap();
+        let patch = Patch::diff(&old, &new);
+
+        // `b` changed since the patch was made: no operation is applied, not even `a`
+        let mut target = old.copy(true);
+        target.set_path("b", int("b", 3)).unwrap();
+        let before = target.copy(true);
+        let shared = target.clone();
+
+        let err = target.apply_patch(&patch).unwrap_err();
+        match err {
+            ContainerError::PatchError { index, op, message } => {
+                assert_eq!((index, op), (1, "replace"));
+                assert!(message.contains("expected value"), "{message}");
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+        assert!(target.diff(&before).is_empty());
+        assert!(shared.diff(&before).is_empty());
+
+        // A type mismatch is reported as such
+        let mut retyped = old.copy(true);
+        retyped
+            .set_path("a", Arc::new(LLongValue::new("a", 1)))
+            .unwrap();
+        let err = retyped.apply_patch(&patch).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is a llong_value, expected a int_value"));
+    }
+
+    #[test]
+    fn test_explicit_ops() {
+        let mut target = container(vec![int("a", 1), items(&[])]);
+        let shared = target.clone();
+
+        let patch: Patch = [
+            PatchOp::Test {
+                path: "a".to_string(),
+                value: int("a", 1),
+            },
+            PatchOp::TestType {
+                path: "items".to_string(),
+                value_type: ValueType::Array,
+            },
+            PatchOp::Add {
+                parent: "items".to_string(),
+                value: int("", 7),
+            },
+            PatchOp::Add {
+                parent: String::new(),
+                value: int("b", 2),
+            },
+            PatchOp::SetHeader {
+                field: "target_id".to_string(),
+                old: Some(String::new()),
+                value: "server".to_string(),
+            },
+        ]
+        .into_iter()
+        .collect();
+        target.apply_patch(&patch).unwrap();
+
+        // Applied in place for every handle to the container
+        assert_eq!(shared.get_path("items[0]").unwrap().to_int().unwrap(), 7);
+        assert_eq!(shared.get_path("b").unwrap().to_int().unwrap(), 2);
+        assert_eq!(shared.target_id(), "server");
+
+        for (op, message) in [
+            (
+                PatchOp::Add {
+                    parent: "a".to_string(),
+                    value: int("x", 1),
+                },
+                "not a container or array",
+            ),
+            (
+                PatchOp::Replace {
+                    path: "missing".to_string(),
+                    old: None,
+                    value: int("missing", 1),
+                },
+                "no value named 'missing'",
+            ),
+            (
+                PatchOp::SetHeader {
+                    field: "bogus".to_string(),
+                    old: None,
+                    value: String::new(),
+                },
+                "unknown header field",
+            ),
+        ] {
+            let err = Patch::from_iter([op]).apply(&mut target).unwrap_err();
+            assert!(err.to_string().contains(message), "{err}");
+        }
+
+        // The container's value limit applies to added values
+        let mut full = ValueContainer::with_max_values(1);
+        let add = Patch::from_iter([PatchOp::Add {
+            parent: String::new(),
+            value: int("a", 1),
+        }]);
+        full.apply_patch(&add).unwrap();
+        assert!(full.apply_patch(&add).is_err());
+        assert_eq!(full.value_count(), 1);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let old = container(vec![int("a", 1), items(&[1, 2]), int("gone", 0)]);
+        let mut new = container(vec![
+            Arc::new(LLongValue::new("a", 1)),
+            items(&[1, 4, 5]),
+            Arc::new(StringValue::new("note", "x;y]")),
+        ]);
+        new.set_version("2.0");
+        let mut patch = Patch::diff(&old, &new);
+        patch.push(PatchOp::TestType {
+            path: "a".to_string(),
+            value_type: ValueType::LLong,
+        });
+
+        let via_wire = Patch::from_wire(&patch.to_wire().unwrap()).unwrap();
+        let via_json = Patch::from_json_v2(&patch.to_json_v2().unwrap()).unwrap();
+        for decoded in [via_wire, via_json] {
+            assert_eq!(decoded.len(), patch.len());
+            let mut target = old.copy(true);
+            target.apply_patch(&decoded).unwrap();
+            assert!(target.diff(&new).is_empty(), "{}", target.diff(&new));
+        }
+
+        let err = Patch::from_container(&old).unwrap_err();
+        assert!(err.to_string().contains("not 'container_patch'"));
+
+        // More operations than a container can hold is an error, not a panic
+        let huge: Patch = (0..=ABSOLUTE_MAX_VALUES)
+            .map(|n| PatchOp::Remove {
+                path: format!("v{n}"),
+                old: None,
+            })
+            .collect();
+        assert!(huge.to_container().is_err());
+        assert!(huge.to_wire().is_err());
+        assert!(huge.to_json_v2().is_err());
+        let mut bad = ValueContainer::builder()
+            .message_type(PATCH_MESSAGE_TYPE)
+            .build();
+        bad.add_value(Arc::new(ContainerValue::new(
+            "operation",
+            vec![Arc::new(StringValue::new("op", "move"))],
+        )))
+        .unwrap();
+        assert!(Patch::from_container(&bad).is_err());
+    }
+}
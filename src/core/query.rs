@@ -0,0 +1,1149 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Query Expressions
+//!
+//! A JSONPath-style [`Query`] selects every value in a container tree that matches
+//! an expression. Compile it once with [`Query::compile`] and run it against a
+//! [`ValueContainer`] ([`Query::select`]) or a `ContainerValue` / `ArrayValue`
+//! ([`Query::select_value`]).
+//!
+//! | Syntax | Selects |
+//! |--------|---------|
+//! | `$` | the container (optional at the start) |
+//! | `.name`, `['name']` | values called `name` (all of them when names repeat) |
+//! | `.*`, `[*]` | every child of a container or element of an array |
+//! | `..name`, `..*`, `..[0]` | the same, at any depth below |
+//! | `[2]`, `[-1]` | array element by index (negative counts from the end) |
+//! | `[1:3]`, `[:2]`, `[-2:]` | array elements in a range |
+//! | `[0,2]`, `['a','b']` | several selectors at once |
+//! | `[?(@.qty > 5)]` | children or elements for which the filter holds |
+//!
+//! Filters compare `@` (the candidate), a relative path such as `@.item.qty` or
+//! `@[0]`, and literals (`5`, `-1.5e3`, `'text'`, `"text"`, `true`, `false`,
+//! `null`) with `==`, `!=`, `<`, `<=`, `>`, `>=`, and combine conditions with
+//! `&&`, `||`, `!` and parentheses. A path on its own tests that it exists.
+//!
+//! Numeric values are compared as numbers: integers of every width (including
+//! `ullong_value` above `i64::MAX`) exactly, and floats by their exact value.
+//! Strings compare with strings, booleans and nulls with their own kind; any other
+//! comparison, including one with a missing path, bytes, a container or an array,
+//! is false (`!=` is true).
+//!
+//! Each match carries a path in [`value_path`] syntax that leads back to it,
+//! e.g. `order.items[3].price`.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::query::Query;
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let item = |qty: i32, price: f64| -> Arc<dyn Value> {
+//!     Arc::new(ContainerValue::new(
+//!         "",
+//!         vec![
+//!             Arc::new(IntValue::new("qty", qty)),
+//!             Arc::new(DoubleValue::new("price", price)),
+//!         ],
+//!     ))
+//! };
+//! let items = ArrayValue::new("items", vec![item(2, 1.5), item(8, 4.0), item(6, 2.5)]);
+//! let mut container = ValueContainer::new();
+//! container
+//!     .add_value(Arc::new(ContainerValue::new("order", vec![Arc::new(items)])))
+//!     .unwrap();
+//!
+//! let query = Query::compile("$..items[?(@.qty > 5)].price").unwrap();
+//! let matches = query.select(&container);
+//! let paths: Vec<&str> = matches.iter().map(|m| m.path.as_str()).collect();
+//! assert_eq!(paths, ["order.items[1].price", "order.items[2].price"]);
+//! assert_eq!(matches[0].value.to_double().unwrap(), 4.0);
+//! ```
+//!
+//! [`ValueContainer`]: crate::core::ValueContainer
+//! [`Query`]: crate::core::query::Query
+//! [`Query::compile`]: crate::core::query::Query::compile
+//! [`Query::select`]: crate::core::query::Query::select
+//! [`Query::select_value`]: crate::core::query::Query::select_value
+//! [`value_path`]: crate::core::value_path
+
+use super::container::ValueContainer;
+use super::value_path::{child_path, element_path};
+use super::wire_protocol::MAX_NESTING_DEPTH;
+use super::{ContainerError, Result, Value, ValueType};
+use crate::values::{ArrayValue, ContainerValue};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A value selected by a [`Query`]
+#[derive(Debug, Clone)]
+pub struct QueryMatch {
+    /// Path to the value (see [`value_path`](super::value_path)), relative to
+    /// what the query ran against
+    pub path: String,
+    /// The matching value
+    pub value: Arc<dyn Value>,
+}
+
+/// A compiled query expression such as `$..items[?(@.qty > 5)].price`
+#[derive(Debug, Clone)]
+pub struct Query {
+    query: String,
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    /// Compile a query expression
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` with the byte offset of the first invalid token.
+    pub fn compile(query: &str) -> Result<Self> {
+        let segments = Parser::new(query).parse_query()?;
+        Ok(Self {
+            query: query.to_string(),
+            segments,
+        })
+    }
+
+    /// The query as written
+    pub fn as_str(&self) -> &str {
+        &self.query
+    }
+
+    /// Select matching values from a container, in document order per step
+    pub fn select(&self, container: &ValueContainer) -> Vec<QueryMatch> {
+        let values = container.values();
+        self.run(Node::root(Kind::Named(&values)))
+    }
+
+    /// Select matching values below a `ContainerValue` or `ArrayValue`
+    ///
+    /// Paths are relative to `value`; below an array they start with an index
+    /// (`[2].price`). Other value types have nothing to select.
+    pub fn select_value(&self, value: &dyn Value) -> Vec<QueryMatch> {
+        let any = value.as_any();
+        if let Some(container) = any.downcast_ref::<ContainerValue>() {
+            self.run(Node::root(Kind::Named(container.children())))
+        } else if let Some(array) = any.downcast_ref::<ArrayValue>() {
+            self.run(Node::root(Kind::Elements(array.elements())))
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn run(&self, root: Node<'_>) -> Vec<QueryMatch> {
+        let mut nodes = vec![root];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for node in &nodes {
+                match segment {
+                    Segment::Child(selectors) => select_all(node, selectors, &mut next),
+                    Segment::Descendant(selectors) => {
+                        for descendant in descendants(node) {
+                            select_all(&descendant, selectors, &mut next);
+                        }
+                    }
+                }
+            }
+            nodes = next;
+        }
+
+        nodes
+            .into_iter()
+            .filter_map(|node| match node.kind {
+                Kind::Value(value) => Some(QueryMatch {
+                    path: node.path,
+                    value,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Query {
+    type Err = ContainerError;
+
+    fn from_str(query: &str) -> Result<Self> {
+        Self::compile(query)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.query)
+    }
+}
+
+// ============================================================================
+// Compiled form
+// ============================================================================
+
+#[derive(Debug, Clone)]
+enum Segment {
+    /// `.x` or `[x]`: children of each node
+    Child(Vec<Selector>),
+    /// `..x`: children of each node and of all its descendants
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Filter(Expr),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Exists(RelPath),
+    Compare(Operand, CmpOp, Operand),
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Path(RelPath),
+    Literal(Scalar),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Singular path from `@`
+#[derive(Debug, Clone)]
+struct RelPath(Vec<RelStep>);
+
+#[derive(Debug, Clone)]
+enum RelStep {
+    Name(String),
+    Index(i64),
+}
+
+/// Comparable form of a value or literal
+#[derive(Debug, Clone, PartialEq)]
+enum Scalar {
+    Null,
+    Bool(bool),
+    /// Every integer width, up to `u64::MAX`, without rounding
+    Int(i128),
+    Float(f64),
+    Str(String),
+}
+
+impl Expr {
+    fn eval(&self, value: &Arc<dyn Value>) -> bool {
+        match self {
+            Expr::Or(a, b) => a.eval(value) || b.eval(value),
+            Expr::And(a, b) => a.eval(value) && b.eval(value),
+            Expr::Not(a) => !a.eval(value),
+            Expr::Exists(path) => path.resolve(value).is_some(),
+            Expr::Compare(left, op, right) => compare(left.eval(value), *op, right.eval(value)),
+        }
+    }
+}
+
+impl Operand {
+    /// `None` if the path does not match or the value has no comparable form
+    fn eval(&self, value: &Arc<dyn Value>) -> Option<Scalar> {
+        match self {
+            Operand::Literal(scalar) => Some(scalar.clone()),
+            Operand::Path(path) => Scalar::from_value(path.resolve(value)?.as_ref()),
+        }
+    }
+}
+
+impl RelPath {
+    fn resolve(&self, value: &Arc<dyn Value>) -> Option<Arc<dyn Value>> {
+        let mut current = Arc::clone(value);
+        for step in &self.0 {
+            current = match step {
+                RelStep::Name(name) => current
+                    .as_any()
+                    .downcast_ref::<ContainerValue>()?
+                    .get_child(name, 0)?,
+                RelStep::Index(index) => {
+                    let array = current.as_any().downcast_ref::<ArrayValue>()?;
+                    array.at(resolve_index(*index, array.count())?)?
+                }
+            };
+        }
+        Some(current)
+    }
+}
+
+impl Scalar {
+    fn from_value(value: &dyn Value) -> Option<Self> {
+        let value_type = value.value_type();
+        match value_type {
+            ValueType::Null => Some(Scalar::Null),
+            ValueType::Bool => value.to_bool().ok().map(Scalar::Bool),
+            ValueType::String => Some(Scalar::Str(value.to_string())),
+            _ if value_type.is_integer() => match value.to_long() {
+                Ok(n) => Some(Scalar::Int(i128::from(n))),
+                // Only ullong values above i64::MAX get here
+                Err(_) => value.to_ulong().ok().map(|n| Scalar::Int(i128::from(n))),
+            },
+            _ if value_type.is_float() => value.to_double().ok().map(Scalar::Float),
+            _ => None,
+        }
+    }
+}
+
+/// Compare an integer with a float by exact value
+fn cmp_int_float(int: i128, float: f64) -> Option<Ordering> {
+    // 2^127; every finite float below it in magnitude converts to i128 exactly
+    const LIMIT: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+    if float.is_nan() {
+        return None;
+    }
+    if float >= LIMIT {
+        return Some(Ordering::Less);
+    }
+    if float < -LIMIT {
+        return Some(Ordering::Greater);
+    }
+    let whole = float.trunc();
+    // Equal whole parts: the fraction decides
+    let fraction = 0.0_f64.partial_cmp(&(float - whole))?;
+    Some(int.cmp(&(whole as i128)).then(fraction))
+}
+
+fn compare(left: Option<Scalar>, op: CmpOp, right: Option<Scalar>) -> bool {
+    let ordering = match (&left, &right) {
+        (Some(Scalar::Int(a)), Some(Scalar::Int(b))) => Some(a.cmp(b)),
+        (Some(Scalar::Float(a)), Some(Scalar::Float(b))) => a.partial_cmp(b),
+        (Some(Scalar::Int(a)), Some(Scalar::Float(b))) => cmp_int_float(*a, *b),
+        (Some(Scalar::Float(a)), Some(Scalar::Int(b))) => {
+            cmp_int_float(*b, *a).map(Ordering::reverse)
+        }
+        (Some(Scalar::Str(a)), Some(Scalar::Str(b))) => Some(a.cmp(b)),
+        (Some(Scalar::Bool(a)), Some(Scalar::Bool(b))) if a == b => Some(Ordering::Equal),
+        (Some(Scalar::Null), Some(Scalar::Null)) => Some(Ordering::Equal),
+        _ => None,
+    };
+    match op {
+        CmpOp::Eq => ordering == Some(Ordering::Equal),
+        CmpOp::Ne => ordering != Some(Ordering::Equal),
+        CmpOp::Lt => ordering == Some(Ordering::Less),
+        CmpOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CmpOp::Gt => ordering == Some(Ordering::Greater),
+        CmpOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+/// Index into a sequence of `len`, counting from the end if negative
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let len = i64::try_from(len).ok()?;
+    let index = if index < 0 { len + index } else { index };
+    (0..len).contains(&index).then_some(index as usize)
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+#[derive(Clone)]
+enum Kind<'a> {
+    /// Named values: the top level of what the query runs against
+    Named(&'a [Arc<dyn Value>]),
+    /// Array elements: the top level of a query run against an array
+    Elements(&'a [Arc<dyn Value>]),
+    /// A value in the tree
+    Value(Arc<dyn Value>),
+}
+
+#[derive(Clone)]
+struct Node<'a> {
+    kind: Kind<'a>,
+    path: String,
+}
+
+/// Children of a node, with their paths
+enum Members<'a> {
+    Named(Vec<Node<'a>>),
+    Elements(Vec<Node<'a>>),
+    None,
+}
+
+impl<'a> Node<'a> {
+    fn root(kind: Kind<'a>) -> Self {
+        Self {
+            kind,
+            path: String::new(),
+        }
+    }
+
+    fn members(&self) -> Members<'a> {
+        match &self.kind {
+            Kind::Named(values) => Members::Named(self.named_children(values)),
+            Kind::Elements(values) => Members::Elements(self.elements(values)),
+            Kind::Value(value) => {
+                let any = value.as_any();
+                if let Some(container) = any.downcast_ref::<ContainerValue>() {
+                    Members::Named(self.named_children(container.children()))
+                } else if let Some(array) = any.downcast_ref::<ArrayValue>() {
+                    Members::Elements(self.elements(array.elements()))
+                } else {
+                    Members::None
+                }
+            }
+        }
+    }
+
+    fn named_children(&self, values: &[Arc<dyn Value>]) -> Vec<Node<'a>> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for value in values {
+            *counts.entry(value.name()).or_default() += 1;
+        }
+
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        values
+            .iter()
+            .map(|value| {
+                let name = value.name();
+                let nth = seen.entry(name).or_default();
+                // Repeated names are told apart by index, as in `get_value_array`
+                let path = child_path(&self.path, name, (counts[name] > 1).then_some(*nth));
+                *nth += 1;
+                Node {
+                    kind: Kind::Value(Arc::clone(value)),
+                    path,
+                }
+            })
+            .collect()
+    }
+
+    fn elements(&self, values: &[Arc<dyn Value>]) -> Vec<Node<'a>> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| Node {
+                kind: Kind::Value(Arc::clone(value)),
+                path: element_path(&self.path, index),
+            })
+            .collect()
+    }
+}
+
+fn select_all<'a>(node: &Node<'a>, selectors: &[Selector], out: &mut Vec<Node<'a>>) {
+    let members = node.members();
+    for selector in selectors {
+        match (selector, &members) {
+            (Selector::Name(name), Members::Named(children)) => out.extend(
+                children
+                    .iter()
+                    .filter(|child| value_of(child).name() == name)
+                    .cloned(),
+            ),
+            (Selector::Wildcard, Members::Named(nodes) | Members::Elements(nodes)) => {
+                out.extend(nodes.iter().cloned())
+            }
+            (Selector::Index(index), Members::Elements(elements)) => {
+                if let Some(index) = resolve_index(*index, elements.len()) {
+                    out.push(elements[index].clone());
+                }
+            }
+            (Selector::Slice(start, end), Members::Elements(elements)) => {
+                let len = elements.len() as i64;
+                let clamp = |bound: i64| {
+                    if bound < 0 {
+                        (len + bound).max(0)
+                    } else {
+                        bound.min(len)
+                    }
+                };
+                let start = start.map_or(0, clamp) as usize;
+                let end = end.map_or(len, clamp) as usize;
+                if start < end {
+                    out.extend(elements[start..end].iter().cloned());
+                }
+            }
+            (Selector::Filter(expr), Members::Named(nodes) | Members::Elements(nodes)) => out
+                .extend(
+                    nodes
+                        .iter()
+                        .filter(|child| expr.eval(value_of(child)))
+                        .cloned(),
+                ),
+            _ => {}
+        }
+    }
+}
+
+/// The value of a child node (children are always values)
+fn value_of<'n>(node: &'n Node<'_>) -> &'n Arc<dyn Value> {
+    match &node.kind {
+        Kind::Value(value) => value,
+        _ => unreachable!("children are values"),
+    }
+}
+
+/// `node` and everything below it, in document order
+fn descendants<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    let mut out = Vec::new();
+    let mut stack = vec![node.clone()];
+    while let Some(node) = stack.pop() {
+        match node.members() {
+            Members::Named(children) | Members::Elements(children) => {
+                stack.extend(children.into_iter().rev())
+            }
+            Members::None => {}
+        }
+        out.push(node);
+    }
+    out
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+struct Parser<'q> {
+    query: &'q str,
+    pos: usize,
+    /// Open parentheses in the current filter
+    depth: usize,
+}
+
+impl<'q> Parser<'q> {
+    fn new(query: &'q str) -> Self {
+        Self {
+            query,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, message: impl fmt::Display) -> ContainerError {
+        ContainerError::ParseError(format!(
+            "invalid query '{}' at offset {}: {}",
+            self.query, self.pos, message
+        ))
+    }
+
+    fn rest(&self) -> &'q str {
+        &self.query[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", token)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.query.len() - trimmed.len();
+    }
+
+    fn parse_query(&mut self) -> Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+        let explicit_root = self.eat("$");
+        if !explicit_root && self.peek().is_some_and(is_name_char) {
+            // `order.items` reads as `$.order.items`
+            segments.push(Segment::Child(vec![Selector::Name(self.parse_name()?)]));
+        }
+
+        while self.pos < self.query.len() {
+            let segment = if self.eat("..") {
+                Segment::Descendant(match self.peek() {
+                    Some('[') => self.parse_brackets()?,
+                    _ => vec![self.parse_dot_selector()?],
+                })
+            } else if self.eat(".") {
+                Segment::Child(vec![self.parse_dot_selector()?])
+            } else if self.peek() == Some('[') {
+                Segment::Child(self.parse_brackets()?)
+            } else {
+                return Err(self.error("expected '.', '..' or '['"));
+            };
+            segments.push(segment);
+        }
+
+        if segments.is_empty() && !explicit_root {
+            return Err(self.error("empty query"));
+        }
+        Ok(segments)
+    }
+
+    fn parse_dot_selector(&mut self) -> Result<Selector> {
+        if self.eat("*") {
+            Ok(Selector::Wildcard)
+        } else {
+            Ok(Selector::Name(self.parse_name()?))
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String> {
+        let rest = self.rest();
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn parse_brackets(&mut self) -> Result<Vec<Selector>> {
+        self.expect("[")?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.parse_bracket_selector()?);
+            self.skip_whitespace();
+            if self.eat("]") {
+                return Ok(selectors);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn parse_bracket_selector(&mut self) -> Result<Selector> {
+        match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('\'' | '"') => Ok(Selector::Name(self.parse_string()?)),
+            Some('?') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                Ok(Selector::Filter(self.parse_or()?))
+            }
+            _ => {
+                let start = self.parse_optional_int()?;
+                self.skip_whitespace();
+                if !self.eat(":") {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| self.error("expected a selector"));
+                }
+                self.skip_whitespace();
+                let end = self.parse_optional_int()?;
+                Ok(Selector::Slice(start, end))
+            }
+        }
+    }
+
+    fn parse_optional_int(&mut self) -> Result<Option<i64>> {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return Ok(None);
+        }
+        let text = &rest[..sign + digits];
+        let value = text
+            .parse()
+            .map_err(|_| self.error(format!("index {} is out of range", text)))?;
+        self.pos += text.len();
+        Ok(Some(value))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        let quote = self.peek().expect("caller checked the quote");
+        self.pos += 1;
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c if c == quote => {
+                    self.pos += offset + 1;
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut expr = self.parse_and()?;
+        while self.eat("||") {
+            self.skip_whitespace();
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut expr = self.parse_unary()?;
+        while self.eat("&&") {
+            self.skip_whitespace();
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    /// Count one more level of filter nesting, failing past `MAX_NESTING_DEPTH`
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err(self.error("filter is nested too deeply"));
+        }
+        Ok(())
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let expr = if self.rest().starts_with('!') && !self.rest().starts_with("!=") {
+            self.pos += 1;
+            // `!` recurses like `(`, so it counts towards the same limit
+            self.enter()?;
+            self.skip_whitespace();
+            let expr = Expr::Not(Box::new(self.parse_unary()?));
+            self.depth -= 1;
+            expr
+        } else if self.eat("(") {
+            self.enter()?;
+            self.skip_whitespace();
+            let expr = self.parse_or()?;
+            self.expect(")")?;
+            self.depth -= 1;
+            expr
+        } else {
+            self.parse_comparison()?
+        };
+        self.skip_whitespace();
+        Ok(expr)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr> {
+        let start = self.pos;
+        let left = self.parse_operand()?;
+        self.skip_whitespace();
+
+        let op = [
+            ("==", CmpOp::Eq),
+            ("!=", CmpOp::Ne),
+            ("<=", CmpOp::Le),
+            (">=", CmpOp::Ge),
+            ("<", CmpOp::Lt),
+            (">", CmpOp::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token))
+        .map(|(_, op)| op);
+
+        match (left, op) {
+            (left, Some(op)) => {
+                self.skip_whitespace();
+                Ok(Expr::Compare(left, op, self.parse_operand()?))
+            }
+            (Operand::Path(path), None) => Ok(Expr::Exists(path)),
+            (Operand::Literal(_), None) => {
+                self.pos = start;
+                Err(self.error("expected a condition"))
+            }
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand> {
+        if self.eat("@") {
+            return Ok(Operand::Path(self.parse_rel_path()?));
+        }
+        if matches!(self.peek(), Some('\'' | '"')) {
+            return Ok(Operand::Literal(Scalar::Str(self.parse_string()?)));
+        }
+        for (keyword, scalar) in [
+            ("true", Scalar::Bool(true)),
+            ("false", Scalar::Bool(false)),
+            ("null", Scalar::Null),
+        ] {
+            if self.eat(keyword) {
+                return Ok(Operand::Literal(scalar));
+            }
+        }
+        self.parse_number().map(Operand::Literal)
+    }
+
+    fn parse_rel_path(&mut self) -> Result<RelPath> {
+        let mut steps = Vec::new();
+        loop {
+            if self.eat(".") {
+                steps.push(RelStep::Name(self.parse_name()?));
+            } else if self.eat("[") {
+                self.skip_whitespace();
+                let step = if matches!(self.peek(), Some('\'' | '"')) {
+                    RelStep::Name(self.parse_string()?)
+                } else {
+                    RelStep::Index(
+                        self.parse_optional_int()?
+                            .ok_or_else(|| self.error("expected an index or a quoted name"))?,
+                    )
+                };
+                self.skip_whitespace();
+                self.expect("]")?;
+                steps.push(step);
+            } else {
+                return Ok(RelPath(steps));
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Scalar> {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| {
+                !(c.is_ascii_digit()
+                    || c == '.'
+                    || c == 'e'
+                    || c == 'E'
+                    || (c == '-' || c == '+') && (i == 0 || rest[..i].ends_with(['e', 'E'])))
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let text = &rest[..len];
+        let scalar = if let Ok(n) = text.parse::<i128>() {
+            Scalar::Int(n)
+        } else if let Ok(f) = text.parse::<f64>() {
+            Scalar::Float(f)
+        } else {
+            return Err(self.error("expected '@', a string, a number, true, false or null"));
+        };
+        self.pos += len;
+        Ok(scalar)
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    !matches!(
+        c,
+        '.' | '['
+            | ']'
+            | '('
+            | ')'
+            | '*'
+            | '$'
+            | '@'
+            | ','
+            | '\''
+            | '"'
+            | '='
+            | '!'
+            | '<'
+            | '>'
+            | '&'
+            | '|'
+    ) && !c.is_whitespace()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::{BoolValue, BytesValue, DoubleValue, IntValue, StringValue, ULLongValue};
+
+    fn item(sku: &str, qty: i32, price: f64) -> Arc<dyn Value> {
+        Arc::new(ContainerValue::new(
+            "",
+            vec![
+                Arc::new(StringValue::new("sku", sku)),
+                Arc::new(IntValue::new("qty", qty)),
+                Arc::new(DoubleValue::new("price", price)),
+            ],
+        ))
+    }
+
+    /// Two orders with `items` arrays, plus repeated top-level `tag`s
+    fn sample() -> ValueContainer {
+        let order = |name: &str, items: Vec<Arc<dyn Value>>| -> Arc<dyn Value> {
+            Arc::new(ContainerValue::new(
+                name,
+                vec![
+                    Arc::new(ArrayValue::new("items", items)),
+                    Arc::new(BoolValue::new("paid", name == "first")),
+                ],
+            ))
+        };
+
+        let mut container = ValueContainer::new();
+        container
+            .add_value(order(
+                "first",
+                vec![item("a", 2, 1.5), item("b", 8, 4.0), item("c", 6, 2.5)],
+            ))
+            .unwrap();
+        container
+            .add_value(order("second", vec![item("d", 10, 9.0)]))
+            .unwrap();
+        container
+            .add_value(Arc::new(StringValue::new("tag", "x")))
+            .unwrap();
+        container
+            .add_value(Arc::new(StringValue::new("tag", "y")))
+            .unwrap();
+        container
+    }
+
+    fn paths(container: &ValueContainer, query: &str) -> Vec<String> {
+        Query::compile(query)
+            .unwrap()
+            .select(container)
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_selectors() {
+        let container = sample();
+        assert_eq!(paths(&container, "$.first.paid"), ["first.paid"]);
+        assert_eq!(paths(&container, "first['paid']"), ["first.paid"]);
+        assert_eq!(paths(&container, "$.tag"), ["tag{0}", "tag{1}"]);
+        assert_eq!(paths(&container, "$.*.paid"), ["first.paid", "second.paid"]);
+        assert_eq!(
+            paths(&container, "$.first.items[-1].sku"),
+            ["first.items[2].sku"]
+        );
+        assert_eq!(
+            paths(&container, "$.first.items[0,2].qty"),
+            ["first.items[0].qty", "first.items[2].qty"]
+        );
+        assert_eq!(
+            paths(&container, "$.first.items[1:].qty"),
+            ["first.items[1].qty", "first.items[2].qty"]
+        );
+        assert_eq!(
+            paths(&container, "$.first.items[:-2].qty"),
+            ["first.items[0].qty"]
+        );
+        assert!(paths(&container, "$.first.items[5]").is_empty());
+        assert!(paths(&container, "$.first[0]").is_empty());
+        assert!(paths(&container, "$").is_empty());
+    }
+
+    #[test]
+    fn test_recursive_descent() {
+        let container = sample();
+        assert_eq!(
+            paths(&container, "$..price"),
+            [
+                "first.items[0].price",
+                "first.items[1].price",
+                "first.items[2].price",
+                "second.items[0].price"
+            ]
+        );
+        assert_eq!(
+            paths(&container, "$..items[0].sku"),
+            ["first.items[0].sku", "second.items[0].sku"]
+        );
+        // Every value in the tree: 2 orders, 2 arrays, 4 items, 12 fields, 2 flags, 2 tags
+        assert_eq!(paths(&container, "$..*").len(), 24);
+    }
+
+    #[test]
+    fn test_filters() {
+        let container = sample();
+        assert_eq!(
+            paths(&container, "$..items[?(@.qty > 5)].sku"),
+            [
+                "first.items[1].sku",
+                "first.items[2].sku",
+                "second.items[0].sku"
+            ]
+        );
+        assert_eq!(
+            paths(&container, "$..items[?(@.qty >= 6 && @.price < 3)]"),
+            ["first.items[2]"]
+        );
+        assert_eq!(
+            paths(
+                &container,
+                "$..items[?(@.sku == 'a' || !(@.qty != 10))].sku"
+            ),
+            ["first.items[0].sku", "second.items[0].sku"]
+        );
+        assert_eq!(paths(&container, "$[?(@.paid == true)]"), ["first"]);
+        assert_eq!(paths(&container, "$[?(@.paid)]"), ["first", "second"]);
+        assert_eq!(
+            paths(&container, "$.tag[?(@ == \"y\")]"),
+            Vec::<String>::new()
+        );
+        assert_eq!(paths(&container, "$[?(@ == 'y')]"), ["tag{1}"]);
+
+        // Integers compare exactly, also against floats
+        let mut numbers = ValueContainer::new();
+        numbers
+            .add_value(Arc::new(ULLongValue::new("big", u64::MAX)))
+            .unwrap();
+        numbers
+            .add_value(Arc::new(IntValue::new("small", 3)))
+            .unwrap();
+        numbers
+            .add_value(Arc::new(ULLongValue::new("near", u64::MAX - 1)))
+            .unwrap();
+        assert_eq!(
+            paths(&numbers, "$[?(@ > 9007199254740993)]"),
+            ["big", "near"]
+        );
+        assert_eq!(paths(&numbers, "$[?(@ == 3.0)]"), ["small"]);
+        assert_eq!(paths(&numbers, "$[?(@ > 2.5 && @ < 3.5)]"), ["small"]);
+        assert_eq!(paths(&numbers, "$[?(@ == 18446744073709551615)]"), ["big"]);
+        assert_eq!(
+            paths(&numbers, "$[?(@ < 18446744073709551615)]"),
+            ["small", "near"]
+        );
+        // u64::MAX rounds to 2^64 as a double, but compares exactly
+        assert_eq!(
+            paths(&numbers, "$[?(@ < 18446744073709551616.0)]"),
+            ["big", "small", "near"]
+        );
+        assert_eq!(paths(&numbers, "$[?(@ < 'z')]"), Vec::<String>::new());
+        assert_eq!(paths(&numbers, "$[?(@ != 'z')]"), ["big", "small", "near"]);
+
+        // Missing paths and values without a scalar form are never equal
+        let mut blobs = ValueContainer::new();
+        for (name, data) in [("x", vec![1u8]), ("y", vec![2u8])] {
+            let children: Vec<Arc<dyn Value>> = vec![
+                Arc::new(BytesValue::new("a", data)),
+                Arc::new(BytesValue::new("b", vec![3])),
+            ];
+            blobs
+                .add_value(Arc::new(ContainerValue::new(name, children)))
+                .unwrap();
+        }
+        for filter in [
+            "$[?(@.missing == @.other)]",
+            "$[?(@.missing <= @.other)]",
+            "$[?(@.missing >= @.other)]",
+            "$[?(@.a == @.b)]",
+            "$[?(@.a == @.a)]",
+            "$[?(@.a >= @.b)]",
+            "$[?(@ == @)]",
+        ] {
+            assert_eq!(paths(&blobs, filter), Vec::<String>::new(), "{filter}");
+        }
+        assert_eq!(paths(&blobs, "$[?(@.missing != @.other)]"), ["x", "y"]);
+        assert_eq!(paths(&blobs, "$[?(@.a != @.b)]"), ["x", "y"]);
+    }
+
+    #[test]
+    fn test_select_value_and_paths() {
+        let container = sample();
+        let first = container.get_value("first").unwrap();
+        let query = Query::compile("items[?(@.qty < 5)].price").unwrap();
+        let matches = query.select_value(first.as_ref());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "items[0].price");
+
+        let items = container.get_path("first.items").unwrap();
+        let matches = Query::compile("$[1].sku")
+            .unwrap()
+            .select_value(items.as_ref());
+        assert_eq!(matches[0].path, "[1].sku");
+        assert!(Query::compile("$.*")
+            .unwrap()
+            .select_value(&IntValue::new("n", 1))
+            .is_empty());
+
+        // Every match path leads back to the value through get_path
+        for found in container.query("$..*").unwrap() {
+            let value = container.get_path(&found.path).unwrap();
+            assert!(Arc::ptr_eq(&value, &found.value), "path {}", found.path);
+        }
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        for query in [
+            "",
+            "$.",
+            "$..",
+            "$[",
+            "$[1",
+            "$['a",
+            "$[?(@.a > )]",
+            "$[?(5)]",
+            "$[?(@.a == 1]",
+            "$[x]",
+            "$ .a",
+            "$.a[99999999999999999999]",
+        ] {
+            let err = Query::compile(query).unwrap_err();
+            assert!(
+                matches!(err, ContainerError::ParseError(_)),
+                "query {query:?}: {err:?}"
+            );
+        }
+
+        let deep = format!("$[?({}@.a{})]", "(".repeat(1000), ")".repeat(1000));
+        assert!(Query::compile(&deep).is_err());
+        let negated = format!("$[?({}@.a)]", "!".repeat(20_000));
+        assert!(Query::compile(&negated).is_err());
+        let mixed = format!("$[?({}@.a{})]", "!(".repeat(200), ")".repeat(200));
+        assert!(Query::compile(&mixed).is_err());
+
+        // Within the limit, `!` still works
+        let container = sample();
+        let even = format!("$[?({}@.paid)]", "!".repeat(MAX_NESTING_DEPTH - 2));
+        assert_eq!(paths(&container, &even), ["first", "second"]);
+    }
+}
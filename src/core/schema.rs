@@ -0,0 +1,1084 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Schemas for Message Types
+//!
+//! A [`Schema`] lists the fields a container of one message type may hold:
+//! whether each is required, which [`ValueType`]s it accepts, and constraints
+//! on its content ([`ValueSchema`]). [`Schema::validate`] walks a container
+//! and returns every [`Violation`] with its path, descending into arrays and
+//! nested `ContainerValue`s that have schemas of their own.
+//!
+//! [`SchemaRegistry`] keeps the versions of each `message_type`, checks new
+//! versions for compatibility and validates or upgrades containers according
+//! to their `message_type` and `version` headers.
+//!
+//! Constraints apply to the kinds of value they make sense for and are
+//! ignored otherwise:
+//!
+//! - `min` / `max`: numeric values (integers are compared exactly)
+//! - `min_length` / `max_length`: characters of a string, bytes of a byte
+//!   array, elements of an array
+//! - `pattern`: strings (unanchored, as in JSON Schema; use `^...$` to match
+//!   the whole string)
+//!
+//! A field that appears several times is validated at every occurrence.
+//! Values that the schema does not mention are allowed unless the schema is
+//! closed with [`Schema::deny_unknown`].
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::schema::{Schema, SchemaRegistry, ValueSchema};
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let login = Schema::new()
+//!     .required("user_id", ValueSchema::new(ValueType::LLong).min(1))
+//!     .optional("device", ValueSchema::new(ValueType::String).max_length(64));
+//!
+//! let mut registry = SchemaRegistry::new();
+//! registry.register("user_login", "1.0.0.0", login).unwrap();
+//!
+//! let mut message = ValueContainer::new();
+//! message.set_message_type("user_login");
+//! message.set_version("1.0.0.0");
+//! message.add_value(Arc::new(IntValue::new("device", 7))).unwrap();
+//!
+//! let report = registry.validate(&message).unwrap();
+//! assert_eq!(report.len(), 2);
+//! println!("{}", report);
+//! // user_id: missing required field
+//! // device: expected string_value, found int_value
+//! ```
+//!
+//! [`Schema`]: crate::core::schema::Schema
+//! [`ValueSchema`]: crate::core::schema::ValueSchema
+//! [`Schema::validate`]: crate::core::schema::Schema::validate
+//! [`Violation`]: crate::core::schema::Violation
+//! [`SchemaRegistry`]: crate::core::schema::SchemaRegistry
+//! [`Schema::deny_unknown`]: crate::core::schema::Schema::deny_unknown
+
+use super::container::ValueContainer;
+use super::schema_evolution::{
+    check_compatibility, compare_versions, upgrade_values, Compatibility, Incompatibility,
+};
+use super::value_data::ValueData;
+use super::value_path::{child_path, element_path};
+use super::{ContainerError, Result, Value, ValueType};
+use crate::values::{ArrayValue, ContainerValue};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Numeric bound of a [`ValueSchema`]
+///
+/// Integer bounds are compared exactly with integer values; anything involving
+/// a float or a decimal is compared as `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    /// Integer bound (wide enough for every integer value type)
+    Int(i128),
+    /// Floating point bound
+    Float(f64),
+}
+
+impl Limit {
+    fn as_f64(self) -> f64 {
+        match self {
+            Limit::Int(n) => n as f64,
+            Limit::Float(x) => x,
+        }
+    }
+
+    /// Whether `value` is below this bound
+    fn above(self, value: Limit) -> bool {
+        match (value, self) {
+            (Limit::Int(v), Limit::Int(b)) => v < b,
+            _ => value.as_f64() < self.as_f64(),
+        }
+    }
+
+    /// Whether `value` is above this bound
+    fn below(self, value: Limit) -> bool {
+        match (value, self) {
+            (Limit::Int(v), Limit::Int(b)) => v > b,
+            _ => value.as_f64() > self.as_f64(),
+        }
+    }
+}
+
+impl From<i32> for Limit {
+    fn from(n: i32) -> Self {
+        Limit::Int(n.into())
+    }
+}
+
+impl From<i64> for Limit {
+    fn from(n: i64) -> Self {
+        Limit::Int(n.into())
+    }
+}
+
+impl From<u64> for Limit {
+    fn from(n: u64) -> Self {
+        Limit::Int(n.into())
+    }
+}
+
+impl From<f64> for Limit {
+    fn from(x: f64) -> Self {
+        Limit::Float(x)
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Int(n) => write!(f, "{}", n),
+            Limit::Float(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+/// Accepted types and constraints for one value
+#[derive(Debug, Clone, Default)]
+pub struct ValueSchema {
+    types: Vec<ValueType>,
+    min: Option<Limit>,
+    max: Option<Limit>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<Regex>,
+    elements: Option<Box<ValueSchema>>,
+    fields: Option<Box<Schema>>,
+    default: Option<ValueData>,
+}
+
+impl ValueSchema {
+    /// Schema accepting values of one type
+    pub fn new(value_type: ValueType) -> Self {
+        Self::any_of([value_type])
+    }
+
+    /// Schema accepting values of any of the given types (any type if empty)
+    pub fn any_of(types: impl IntoIterator<Item = ValueType>) -> Self {
+        Self {
+            types: types.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Schema accepting values of any type
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Smallest allowed number (inclusive)
+    #[must_use = "builder methods return a new value"]
+    pub fn min(mut self, min: impl Into<Limit>) -> Self {
+        self.min = Some(min.into());
+        self
+    }
+
+    /// Largest allowed number (inclusive)
+    #[must_use = "builder methods return a new value"]
+    pub fn max(mut self, max: impl Into<Limit>) -> Self {
+        self.max = Some(max.into());
+        self
+    }
+
+    /// Smallest allowed length
+    #[must_use = "builder methods return a new value"]
+    pub fn min_length(mut self, min: usize) -> Self {
+        self.min_length = Some(min);
+        self
+    }
+
+    /// Largest allowed length
+    #[must_use = "builder methods return a new value"]
+    pub fn max_length(mut self, max: usize) -> Self {
+        self.max_length = Some(max);
+        self
+    }
+
+    /// Regular expression that strings must match
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaError` if the expression does not compile.
+    pub fn pattern(mut self, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            ContainerError::SchemaError(format!("invalid pattern '{}': {}", pattern, e))
+        })?;
+        self.pattern = Some(regex);
+        Ok(self)
+    }
+
+    /// Schema for every element of an array
+    #[must_use = "builder methods return a new value"]
+    pub fn elements(mut self, elements: ValueSchema) -> Self {
+        self.elements = Some(Box::new(elements));
+        self
+    }
+
+    /// Schema for the children of a nested container
+    #[must_use = "builder methods return a new value"]
+    pub fn fields(mut self, fields: Schema) -> Self {
+        self.fields = Some(Box::new(fields));
+        self
+    }
+
+    /// Value filled in when the field is absent from a container being
+    /// upgraded (see [`SchemaRegistry::upgrade`])
+    ///
+    /// A required field with a default is compatible with older versions that
+    /// lack it.
+    #[must_use = "builder methods return a new value"]
+    pub fn with_default(mut self, default: ValueData) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Accepted types (empty means any)
+    pub fn types(&self) -> &[ValueType] {
+        &self.types
+    }
+
+    /// Numeric bounds
+    pub fn range(&self) -> (Option<Limit>, Option<Limit>) {
+        (self.min, self.max)
+    }
+
+    /// Length bounds
+    pub fn length(&self) -> (Option<usize>, Option<usize>) {
+        (self.min_length, self.max_length)
+    }
+
+    /// Pattern for strings, as written
+    pub fn pattern_str(&self) -> Option<&str> {
+        self.pattern.as_ref().map(Regex::as_str)
+    }
+
+    /// Schema for array elements
+    pub fn element_schema(&self) -> Option<&ValueSchema> {
+        self.elements.as_deref()
+    }
+
+    /// Schema for nested container children
+    pub fn field_schema(&self) -> Option<&Schema> {
+        self.fields.as_deref()
+    }
+
+    /// Default for absent fields
+    pub fn default_value(&self) -> Option<&ValueData> {
+        self.default.as_ref()
+    }
+
+    fn check(&self, value: &dyn Value, path: &str, out: &mut Vec<Violation>) {
+        let found = value.value_type();
+        if !self.types.is_empty() && !self.types.contains(&found) {
+            out.push(Violation::new(
+                path,
+                ViolationKind::WrongType {
+                    expected: self.types.clone(),
+                    found,
+                },
+            ));
+            return;
+        }
+
+        if let Some(number) = number(value) {
+            let low = self.min.is_some_and(|min| min.above(number));
+            let high = self.max.is_some_and(|max| max.below(number));
+            if low || high {
+                out.push(Violation::new(
+                    path,
+                    ViolationKind::OutOfRange {
+                        value: number,
+                        min: self.min,
+                        max: self.max,
+                    },
+                ));
+            }
+        }
+
+        let any = value.as_any();
+        let length = match found {
+            ValueType::String => Some(value.to_string().chars().count()),
+            ValueType::Bytes => Some(value.size()),
+            _ => any.downcast_ref::<ArrayValue>().map(ArrayValue::count),
+        };
+        if let Some(length) = length {
+            let short = self.min_length.is_some_and(|min| length < min);
+            let long = self.max_length.is_some_and(|max| length > max);
+            if short || long {
+                out.push(Violation::new(
+                    path,
+                    ViolationKind::Length {
+                        length,
+                        min: self.min_length,
+                        max: self.max_length,
+                    },
+                ));
+            }
+        }
+
+        if let (Some(pattern), ValueType::String) = (&self.pattern, found) {
+            if !pattern.is_match(&value.to_string()) {
+                out.push(Violation::new(
+                    path,
+                    ViolationKind::Pattern {
+                        pattern: pattern.as_str().to_string(),
+                    },
+                ));
+            }
+        }
+
+        if let (Some(elements), Some(array)) = (&self.elements, any.downcast_ref::<ArrayValue>()) {
+            for (index, element) in array.elements().iter().enumerate() {
+                elements.check(element.as_ref(), &element_path(path, index), out);
+            }
+        }
+        if let (Some(fields), Some(container)) =
+            (&self.fields, any.downcast_ref::<ContainerValue>())
+        {
+            fields.check(container.children(), path, out);
+        }
+    }
+}
+
+/// The numeric payload of `value`, if it is a number
+fn number(value: &dyn Value) -> Option<Limit> {
+    let value_type = value.value_type();
+    if value_type.is_float() || value_type == ValueType::Decimal {
+        value.to_double().ok().map(Limit::Float)
+    } else if matches!(
+        value_type,
+        ValueType::UShort | ValueType::UInt | ValueType::ULong | ValueType::ULLong
+    ) {
+        value.to_ulong().ok().map(Limit::from)
+    } else if value_type.is_integer() {
+        value.to_long().ok().map(Limit::from)
+    } else {
+        None
+    }
+}
+
+/// A named field of a [`Schema`]
+#[derive(Debug, Clone)]
+pub struct FieldSchema {
+    name: String,
+    aliases: Vec<String>,
+    required: bool,
+    schema: ValueSchema,
+}
+
+impl FieldSchema {
+    /// Field name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Former names of the field
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// Whether the field must be present
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Accepted types and constraints
+    pub fn schema(&self) -> &ValueSchema {
+        &self.schema
+    }
+}
+
+/// The fields of one message type (or of a nested container)
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    fields: Vec<FieldSchema>,
+    deny_unknown: bool,
+}
+
+impl Schema {
+    /// Schema without fields that accepts any value
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field that must be present
+    ///
+    /// Adding a field with the name of an existing one replaces it.
+    #[must_use = "builder methods return a new value"]
+    pub fn required(self, name: impl Into<String>, schema: ValueSchema) -> Self {
+        self.field(name.into(), true, schema)
+    }
+
+    /// Add a field that may be absent
+    ///
+    /// Adding a field with the name of an existing one replaces it.
+    #[must_use = "builder methods return a new value"]
+    pub fn optional(self, name: impl Into<String>, schema: ValueSchema) -> Self {
+        self.field(name.into(), false, schema)
+    }
+
+    /// Record a former name of a field
+    ///
+    /// Older versions that call the field `alias` stay compatible, and
+    /// upgraded containers get their values renamed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema has no field named `field`.
+    #[track_caller]
+    #[must_use = "builder methods return a new value"]
+    pub fn alias(mut self, field: &str, alias: impl Into<String>) -> Self {
+        let Some(existing) = self.fields.iter_mut().find(|f| f.name == field) else {
+            panic!("schema has no field named '{}'", field);
+        };
+        existing.aliases.push(alias.into());
+        self
+    }
+
+    /// Report values that no field describes
+    #[must_use = "builder methods return a new value"]
+    pub fn deny_unknown(mut self) -> Self {
+        self.deny_unknown = true;
+        self
+    }
+
+    fn field(mut self, name: String, required: bool, schema: ValueSchema) -> Self {
+        let field = FieldSchema {
+            name,
+            aliases: Vec::new(),
+            required,
+            schema,
+        };
+        match self.fields.iter_mut().find(|f| f.name == field.name) {
+            Some(existing) => *existing = field,
+            None => self.fields.push(field),
+        }
+        self
+    }
+
+    /// Fields in the order they were added
+    pub fn fields(&self) -> &[FieldSchema] {
+        &self.fields
+    }
+
+    /// Field by name
+    pub fn get(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Whether values that no field describes are reported
+    pub fn denies_unknown(&self) -> bool {
+        self.deny_unknown
+    }
+
+    /// Validate the values of a container
+    pub fn validate(&self, container: &ValueContainer) -> ValidationReport {
+        self.validate_values(&container.values())
+    }
+
+    /// Validate a list of values, as held by a container or a `ContainerValue`
+    pub fn validate_values(&self, values: &[Arc<dyn Value>]) -> ValidationReport {
+        let mut violations = Vec::new();
+        self.check(values, "", &mut violations);
+        ValidationReport { violations }
+    }
+
+    /// Validate a container and fail on the first report with violations
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaViolation` listing every violation.
+    pub fn check_container(&self, container: &ValueContainer) -> Result<()> {
+        self.validate(container)
+            .into_result(&container.message_type())
+    }
+
+    fn check(&self, values: &[Arc<dyn Value>], parent: &str, out: &mut Vec<Violation>) {
+        for field in &self.fields {
+            let matching: Vec<_> = values.iter().filter(|v| v.name() == field.name).collect();
+            if matching.is_empty() && field.required {
+                out.push(Violation::new(
+                    &child_path(parent, &field.name, None),
+                    ViolationKind::Missing,
+                ));
+            }
+            let repeated = matching.len() > 1;
+            for (nth, value) in matching.into_iter().enumerate() {
+                let path = child_path(parent, &field.name, repeated.then_some(nth));
+                field.schema.check(value.as_ref(), &path, out);
+            }
+        }
+
+        if self.deny_unknown {
+            for value in values {
+                if self.get(value.name()).is_none() {
+                    out.push(Violation::new(
+                        &child_path(parent, value.name(), None),
+                        ViolationKind::Unexpected,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// What is wrong with a value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// A required field is absent
+    Missing,
+    /// A value that the schema does not describe (closed schemas only)
+    Unexpected,
+    /// The value has a type the field does not accept
+    WrongType {
+        expected: Vec<ValueType>,
+        found: ValueType,
+    },
+    /// A number outside `min..=max`
+    OutOfRange {
+        value: Limit,
+        min: Option<Limit>,
+        max: Option<Limit>,
+    },
+    /// A string, byte array or array whose length is outside `min..=max`
+    Length {
+        length: usize,
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    /// A string that does not match the pattern
+    Pattern { pattern: String },
+}
+
+/// Format an inclusive range with optional ends (`1..=10`, `>= 1`, `<= 10`)
+fn bounds<T: fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{}..={}", min, max),
+        (Some(min), None) => format!(">= {}", min),
+        (None, Some(max)) => format!("<= {}", max),
+        (None, None) => "unbounded".to_string(),
+    }
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::Missing => write!(f, "missing required field"),
+            ViolationKind::Unexpected => write!(f, "unexpected field"),
+            ViolationKind::WrongType { expected, found } => {
+                let expected: Vec<_> = expected.iter().map(ToString::to_string).collect();
+                write!(f, "expected {}, found {}", expected.join(" or "), found)
+            }
+            ViolationKind::OutOfRange { value, min, max } => {
+                write!(f, "{} is out of range {}", value, bounds(*min, *max))
+            }
+            ViolationKind::Length { length, min, max } => {
+                write!(
+                    f,
+                    "length {} is out of range {}",
+                    length,
+                    bounds(*min, *max)
+                )
+            }
+            ViolationKind::Pattern { pattern } => {
+                write!(f, "does not match pattern '{}'", pattern)
+            }
+        }
+    }
+}
+
+/// One place where a container does not match its schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Path to the value (see [`value_path`](super::value_path))
+    pub path: String,
+    /// What is wrong
+    pub kind: ViolationKind,
+}
+
+impl Violation {
+    fn new(path: &str, kind: ViolationKind) -> Self {
+        Self {
+            path: path.to_string(),
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+/// Every violation found by a validation, in tree order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Whether the container matches its schema
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Number of violations
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    /// Whether there are no violations (same as [`is_valid`](Self::is_valid))
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// The violations
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Iterate over the violations
+    pub fn iter(&self) -> std::slice::Iter<'_, Violation> {
+        self.violations.iter()
+    }
+
+    /// `Ok` if valid, otherwise `SchemaViolation` for `message_type`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaViolation` listing every violation.
+    pub fn into_result(self, message_type: &str) -> Result<()> {
+        if self.is_valid() {
+            return Ok(());
+        }
+        Err(ContainerError::SchemaViolation {
+            message_type: message_type.to_string(),
+            violations: self.violations.iter().map(ToString::to_string).collect(),
+        })
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for ValidationReport {
+    type Item = Violation;
+    type IntoIter = std::vec::IntoIter<Violation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.violations.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationReport {
+    type Item = &'a Violation;
+    type IntoIter = std::slice::Iter<'a, Violation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.violations.iter()
+    }
+}
+
+/// Registered versions of one message type, ordered by version
+type Versions = Vec<(String, Schema)>;
+
+/// Schema versions by `message_type`
+///
+/// Versions are matched against the container's `version` header and ordered
+/// by their dot-separated parts (`1.10` after `1.9`). Registering a version
+/// checks it against its neighbours with the registry's [`Compatibility`]
+/// mode (backward by default), see [`schema_evolution`](super::schema_evolution).
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: HashMap<String, Versions>,
+    compatibility: Compatibility,
+}
+
+impl SchemaRegistry {
+    /// Empty registry checking backward compatibility
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty registry checking the given compatibility
+    pub fn with_compatibility(compatibility: Compatibility) -> Self {
+        Self {
+            schemas: HashMap::new(),
+            compatibility,
+        }
+    }
+
+    /// The compatibility checked when registering
+    pub fn compatibility(&self) -> Compatibility {
+        self.compatibility
+    }
+
+    /// Register a version of a message type's schema, returning the one it
+    /// replaces
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaError` if the schema is incompatible with the version
+    /// before or after it; the registry is unchanged.
+    pub fn register(
+        &mut self,
+        message_type: impl Into<String>,
+        version: impl Into<String>,
+        schema: Schema,
+    ) -> Result<Option<Schema>> {
+        let (message_type, version) = (message_type.into(), version.into());
+        let versions = self.schemas.entry(message_type.clone()).or_default();
+        let (position, existing) =
+            match versions.binary_search_by(|(v, _)| compare_versions(v, &version)) {
+                Ok(position) => (position, true),
+                Err(position) => (position, false),
+            };
+
+        let mode = self.compatibility;
+        let previous = position.checked_sub(1).map(|i| &versions[i]);
+        let next = versions.get(position + usize::from(existing));
+        let mut problems = Vec::new();
+        if let Some((old, old_schema)) = previous {
+            problems.extend(
+                check_compatibility(old_schema, &schema, mode)
+                    .into_iter()
+                    .map(|problem| format!("{} ({})", problem, old)),
+            );
+        }
+        if let Some((new, new_schema)) = next {
+            problems.extend(
+                check_compatibility(&schema, new_schema, mode)
+                    .into_iter()
+                    .map(|problem| format!("{} ({})", problem, new)),
+            );
+        }
+        if !problems.is_empty() {
+            if versions.is_empty() {
+                self.schemas.remove(&message_type);
+            }
+            return Err(ContainerError::SchemaError(format!(
+                "version {} of '{}' is incompatible: {}",
+                version,
+                message_type,
+                problems.join("; ")
+            )));
+        }
+
+        if existing {
+            Ok(Some(std::mem::replace(&mut versions[position].1, schema)))
+        } else {
+            versions.insert(position, (version, schema));
+            Ok(None)
+        }
+    }
+
+    /// Remove a version of a message type's schema
+    pub fn unregister(&mut self, message_type: &str, version: &str) -> Option<Schema> {
+        let versions = self.schemas.get_mut(message_type)?;
+        let position = versions.iter().position(|(v, _)| v == version)?;
+        let (_, schema) = versions.remove(position);
+        if versions.is_empty() {
+            self.schemas.remove(message_type);
+        }
+        Some(schema)
+    }
+
+    /// Schema for a version of a message type
+    pub fn get(&self, message_type: &str, version: &str) -> Option<&Schema> {
+        self.schemas
+            .get(message_type)?
+            .iter()
+            .find(|(v, _)| v == version)
+            .map(|(_, schema)| schema)
+    }
+
+    /// Newest version of a message type and its schema
+    pub fn latest(&self, message_type: &str) -> Option<(&str, &Schema)> {
+        self.schemas
+            .get(message_type)?
+            .last()
+            .map(|(version, schema)| (version.as_str(), schema))
+    }
+
+    /// Registered versions of a message type, oldest first
+    pub fn versions(&self, message_type: &str) -> Vec<&str> {
+        self.schemas
+            .get(message_type)
+            .map(|versions| versions.iter().map(|(v, _)| v.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether any version is registered for a message type
+    pub fn contains(&self, message_type: &str) -> bool {
+        self.schemas.contains_key(message_type)
+    }
+
+    /// Check a proposed schema against the latest version of a message type
+    ///
+    /// Returns nothing if no version is registered yet.
+    pub fn check_compatibility(&self, message_type: &str, schema: &Schema) -> Vec<Incompatibility> {
+        self.latest(message_type)
+            .map(|(_, latest)| check_compatibility(latest, schema, self.compatibility))
+            .unwrap_or_default()
+    }
+
+    /// JSON Schema for the latest version of a message type, pinning its
+    /// `protocol_version` (see [`json_schema`](super::json_schema))
+    pub fn to_json_schema(&self, message_type: &str) -> Option<serde_json::Value> {
+        let (version, schema) = self.latest(message_type)?;
+        Some(super::json_schema::export(
+            message_type,
+            Some(version),
+            schema,
+        ))
+    }
+
+    /// Schema for the `message_type` and `version` headers of a container
+    fn schema_for(&self, container: &ValueContainer) -> Result<&Schema> {
+        let (message_type, version) = (container.message_type(), container.version());
+        self.get(&message_type, &version).ok_or_else(|| {
+            ContainerError::SchemaError(format!(
+                "no schema registered for message type '{}' version {}",
+                message_type, version
+            ))
+        })
+    }
+
+    /// Validate a container against the schema for its `message_type` and
+    /// `version` headers
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaError` if no schema is registered for that version.
+    pub fn validate(&self, container: &ValueContainer) -> Result<ValidationReport> {
+        Ok(self.schema_for(container)?.validate(container))
+    }
+
+    /// Validate a container and turn violations into an error
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaError` if no schema is registered for that version, or
+    /// `SchemaViolation` listing every violation.
+    pub fn check(&self, container: &ValueContainer) -> Result<()> {
+        self.validate(container)?
+            .into_result(&container.message_type())
+    }
+
+    /// Copy of a container reshaped to the latest version of its message type
+    ///
+    /// The container is upgraded one registered version at a time (see
+    /// [`schema_evolution`](super::schema_evolution)), then its `version`
+    /// header is set to the latest version. A container already at the latest
+    /// version is copied unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SchemaError` if the container's version is not registered, or
+    /// `SchemaViolation` if the upgraded container does not match the latest
+    /// schema.
+    pub fn upgrade(&self, container: &ValueContainer) -> Result<ValueContainer> {
+        self.schema_for(container)?;
+        let message_type = container.message_type();
+        let version = container.version();
+        let versions = &self.schemas[&message_type];
+
+        let mut values = container.values();
+        for (_, schema) in versions
+            .iter()
+            .filter(|(v, _)| compare_versions(v, &version).is_gt())
+        {
+            values = upgrade_values(schema, &values)?;
+        }
+
+        let (latest, schema) = versions
+            .last()
+            .expect("the container's version is registered");
+        let mut upgraded = container.copy(false);
+        upgraded.set_version(latest.as_str());
+        for value in values {
+            upgraded.add_value(value)?;
+        }
+        schema.check_container(&upgraded)?;
+        Ok(upgraded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::{BytesValue, IntValue, LLongValue, StringValue, ULLongValue};
+
+    fn message(values: Vec<Arc<dyn Value>>) -> ValueContainer {
+        let mut container = ValueContainer::new();
+        container.set_message_type("user_login");
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+        container
+    }
+
+    fn login() -> Schema {
+        Schema::new()
+            .required("user_id", ValueSchema::new(ValueType::LLong).min(1))
+            .optional(
+                "device",
+                ValueSchema::new(ValueType::String)
+                    .max_length(8)
+                    .pattern("^[a-z]+$")
+                    .unwrap(),
+            )
+    }
+
+    #[test]
+    fn test_valid_and_missing() {
+        let ok = message(vec![Arc::new(LLongValue::new("user_id", 42))]);
+        assert!(login().validate(&ok).is_valid());
+
+        let report = login().validate(&message(vec![]));
+        assert_eq!(
+            report.violations(),
+            [Violation::new("user_id", ViolationKind::Missing)]
+        );
+    }
+
+    #[test]
+    fn test_every_violation_is_reported() {
+        let bad = message(vec![
+            Arc::new(IntValue::new("user_id", 42)),
+            Arc::new(StringValue::new("device", "Phone-12345")),
+        ]);
+        let report = bad.validate(&login());
+        let kinds: Vec<_> = report.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "user_id: expected llong_value, found int_value",
+                "device: length 11 is out of range <= 8",
+                "device: does not match pattern '^[a-z]+$'",
+            ]
+        );
+
+        let err = login().check_container(&bad).unwrap_err();
+        assert!(
+            matches!(err, ContainerError::SchemaViolation { ref violations, .. } if violations.len() == 3)
+        );
+    }
+
+    #[test]
+    fn test_integer_ranges_are_exact() {
+        let schema =
+            Schema::new().required("id", ValueSchema::new(ValueType::ULLong).max(u64::MAX - 1));
+        let at_max = message(vec![Arc::new(ULLongValue::new("id", u64::MAX - 1))]);
+        let over = message(vec![Arc::new(ULLongValue::new("id", u64::MAX))]);
+        assert!(schema.validate(&at_max).is_valid());
+        assert!(matches!(
+            schema.validate(&over).violations()[0].kind,
+            ViolationKind::OutOfRange { .. }
+        ));
+    }
+
+    #[test]
+    fn test_arrays_and_nested_containers() {
+        let address = Schema::new()
+            .required("city", ValueSchema::new(ValueType::String).min_length(1))
+            .deny_unknown();
+        let schema = Schema::new()
+            .required(
+                "tags",
+                ValueSchema::new(ValueType::Array)
+                    .max_length(2)
+                    .elements(ValueSchema::any_of([ValueType::Int, ValueType::Bytes])),
+            )
+            .optional(
+                "address",
+                ValueSchema::new(ValueType::Container).fields(address),
+            );
+
+        let tags: Vec<Arc<dyn Value>> = vec![
+            Arc::new(IntValue::new("", 1)),
+            Arc::new(StringValue::new("", "x")),
+            Arc::new(BytesValue::new("", vec![1])),
+        ];
+        let address = ContainerValue::new(
+            "address",
+            vec![
+                Arc::new(StringValue::new("city", "")),
+                Arc::new(IntValue::new("zip", 1)),
+            ],
+        );
+        let container = message(vec![
+            Arc::new(ArrayValue::new("tags", tags)),
+            Arc::new(address),
+        ]);
+
+        let paths: Vec<_> = container
+            .validate(&schema)
+            .into_iter()
+            .map(|v| v.path)
+            .collect();
+        assert_eq!(paths, ["tags", "tags[1]", "address.city", "address.zip"]);
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = SchemaRegistry::new();
+        assert!(registry
+            .register("user_login", "1.0.0.0", login())
+            .unwrap()
+            .is_none());
+
+        let ok = message(vec![Arc::new(LLongValue::new("user_id", 7))]);
+        assert!(registry.check(&ok).is_ok());
+
+        let mut other = ok.copy(true);
+        other.set_message_type("logout");
+        assert!(matches!(
+            registry.validate(&other),
+            Err(ContainerError::SchemaError(_))
+        ));
+        assert!(ValueSchema::any().pattern("(").is_err());
+    }
+}
@@ -0,0 +1,563 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Schema Evolution
+//!
+//! Rules for changing the [`Schema`] of a message type without breaking peers
+//! that still use an older version, and for upgrading old containers.
+//!
+//! [`check_compatibility`] tells whether a reader using one schema can read
+//! containers written with another:
+//!
+//! - every required reader field must be present in the writer (under its
+//!   name or one of its [aliases][Schema::alias]) and required there, or have
+//!   a [default][ValueSchema::with_default]; optional fields may be added
+//!   freely;
+//! - every type the writer allows must be accepted by the reader, directly or
+//!   by lossless [widening][widens] (`int_value` to `llong_value`,
+//!   `float_value` to `double_value`, ...);
+//! - a closed reader ([`Schema::deny_unknown`]) must describe every writer
+//!   field.
+//!
+//! Only the shape is compared; numeric ranges, lengths and patterns are not.
+//!
+//! A new version is *backward* compatible if it can read containers of the
+//! previous version and *forward* compatible if the previous version can read
+//! its containers. [`SchemaRegistry`] enforces
+//! the chosen [`Compatibility`] when versions are registered and upgrades old
+//! containers one version at a time: renaming aliased values, widening
+//! numbers, filling in defaults and dropping values a closed schema no longer
+//! has.
+//!
+//! [`Schema`]: crate::core::schema::Schema
+//! [`check_compatibility`]: crate::core::schema_evolution::check_compatibility
+//! [`Schema::deny_unknown`]: crate::core::schema::Schema::deny_unknown
+//! [`Compatibility`]: crate::core::schema_evolution::Compatibility
+//! [`SchemaRegistry`]: crate::core::schema::SchemaRegistry
+//! [Schema::alias]: crate::core::schema::Schema::alias
+//! [ValueSchema::with_default]: crate::core::schema::ValueSchema::with_default
+//! [widens]: crate::core::schema_evolution::widens
+
+use super::schema::{Schema, ValueSchema};
+use super::value_data::ValueData;
+use super::value_path::child_path;
+use super::{Result, Value, ValueType};
+use crate::values::{ArrayValue, ContainerValue};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+/// Which versions of a message type must be able to read each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compatibility {
+    /// No checks
+    None,
+    /// A new version can read containers of the previous version
+    #[default]
+    Backward,
+    /// The previous version can read containers of a new version
+    Forward,
+    /// Both backward and forward
+    Full,
+}
+
+impl Compatibility {
+    /// The directions this mode checks
+    pub fn directions(self) -> &'static [Direction] {
+        match self {
+            Compatibility::None => &[],
+            Compatibility::Backward => &[Direction::Backward],
+            Compatibility::Forward => &[Direction::Forward],
+            Compatibility::Full => &[Direction::Backward, Direction::Forward],
+        }
+    }
+}
+
+/// Which way a compatibility check failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The new version cannot read containers of the old one
+    Backward,
+    /// The old version cannot read containers of the new one
+    Forward,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Backward => write!(f, "backward"),
+            Direction::Forward => write!(f, "forward"),
+        }
+    }
+}
+
+/// A reason why a reader cannot read a writer's containers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    /// Path to the field (`[*]` stands for every element of an array)
+    pub path: String,
+    /// Direction of the failed check
+    pub direction: Direction,
+    /// What is incompatible
+    pub reason: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.direction, self.path, self.reason)
+    }
+}
+
+/// Check that `new` and `old` are compatible in the given mode
+pub fn check_compatibility(
+    old: &Schema,
+    new: &Schema,
+    mode: Compatibility,
+) -> Vec<Incompatibility> {
+    let mut out = Vec::new();
+    for &direction in mode.directions() {
+        let (reader, writer) = match direction {
+            Direction::Backward => (new, old),
+            Direction::Forward => (old, new),
+        };
+        Checker {
+            direction,
+            out: &mut out,
+        }
+        .fields(reader, writer, "");
+    }
+    out
+}
+
+/// Whether every value of type `from` converts to `to` without loss
+///
+/// Integers widen to integers of at least the same width and signedness (or
+/// unsigned to a wider signed type), and to floats whose mantissa holds them;
+/// `float_value` widens to `double_value`. A type does not widen to itself.
+///
+/// ```
+/// use rust_container_system::core::schema_evolution::widens;
+/// use rust_container_system::ValueType;
+///
+/// assert!(widens(ValueType::Int, ValueType::LLong));
+/// assert!(widens(ValueType::UInt, ValueType::Double));
+/// assert!(!widens(ValueType::LLong, ValueType::Int));
+/// assert!(!widens(ValueType::Int, ValueType::Float));
+/// ```
+pub fn widens(from: ValueType, to: ValueType) -> bool {
+    if from == to {
+        return false;
+    }
+    match (int_width(from), int_width(to)) {
+        (Some((from_bits, from_signed)), Some((to_bits, to_signed))) => {
+            if from_signed == to_signed {
+                from_bits <= to_bits
+            } else {
+                !from_signed && from_bits < to_bits
+            }
+        }
+        (Some((bits, signed)), None) => {
+            mantissa(to).is_some_and(|digits| bits - u32::from(signed) <= digits)
+        }
+        _ => from == ValueType::Float && to == ValueType::Double,
+    }
+}
+
+/// Width in bits and signedness of an integer type (as stored in [`ValueData`])
+fn int_width(value_type: ValueType) -> Option<(u32, bool)> {
+    match value_type {
+        ValueType::Short => Some((16, true)),
+        ValueType::UShort => Some((16, false)),
+        ValueType::Int | ValueType::Long => Some((32, true)),
+        ValueType::UInt | ValueType::ULong => Some((32, false)),
+        ValueType::LLong => Some((64, true)),
+        ValueType::ULLong => Some((64, false)),
+        _ => None,
+    }
+}
+
+/// Mantissa bits of a float type
+fn mantissa(value_type: ValueType) -> Option<u32> {
+    match value_type {
+        ValueType::Float => Some(24),
+        ValueType::Double => Some(53),
+        _ => None,
+    }
+}
+
+/// Whether a value of type `found` is accepted as is or by widening
+fn readable(schema: &ValueSchema, found: ValueType) -> bool {
+    let types = schema.types();
+    types.is_empty() || types.iter().any(|&t| t == found || widens(found, t))
+}
+
+struct Checker<'a> {
+    direction: Direction,
+    out: &'a mut Vec<Incompatibility>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, path: String, reason: String) {
+        self.out.push(Incompatibility {
+            path,
+            direction: self.direction,
+            reason,
+        });
+    }
+
+    fn fields(&mut self, reader: &Schema, writer: &Schema, parent: &str) {
+        for field in reader.fields() {
+            let path = child_path(parent, field.name(), None);
+            let has_default = field.schema().default_value().is_some();
+            let Some(source) = counterpart(writer, field.name(), field.aliases()) else {
+                if field.is_required() && !has_default {
+                    self.report(path, "required field is missing and has no default".into());
+                }
+                continue;
+            };
+            if field.is_required() && !source.is_required() && !has_default {
+                self.report(path.clone(), "required field may be absent".into());
+            }
+            self.value(field.schema(), source.schema(), path);
+        }
+
+        if reader.denies_unknown() {
+            for field in writer.fields() {
+                let known = reader.fields().iter().any(|f| {
+                    f.name() == field.name() || f.aliases().iter().any(|a| a == field.name())
+                });
+                if !known {
+                    let path = child_path(parent, field.name(), None);
+                    self.report(path, "field is not accepted".into());
+                }
+            }
+        }
+    }
+
+    fn value(&mut self, reader: &ValueSchema, writer: &ValueSchema, path: String) {
+        if !reader.types().is_empty() {
+            if writer.types().is_empty() {
+                self.report(path.clone(), "any type may be written".into());
+            }
+            for &found in writer.types() {
+                if !readable(reader, found) {
+                    self.report(path.clone(), format!("{} cannot be read", found));
+                }
+            }
+        }
+
+        if let Some(fields) = reader.field_schema() {
+            let any = Schema::new();
+            self.fields(fields, writer.field_schema().unwrap_or(&any), &path);
+        }
+        if let Some(elements) = reader.element_schema() {
+            let any = ValueSchema::any();
+            let writer = writer.element_schema().unwrap_or(&any);
+            self.value(elements, writer, format!("{}[*]", path));
+        }
+    }
+}
+
+/// The writer field a reader field reads: same name first, then its aliases
+fn counterpart<'a>(
+    writer: &'a Schema,
+    name: &str,
+    aliases: &[String],
+) -> Option<&'a super::schema::FieldSchema> {
+    writer
+        .get(name)
+        .or_else(|| aliases.iter().find_map(|alias| writer.get(alias)))
+}
+
+/// Order two version strings by their dot-separated parts
+///
+/// Numeric parts compare as numbers and other parts as text, so `1.10` comes
+/// after `1.9`. Missing trailing parts count as `0`.
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        let (l, r) = match (left.next(), right.next()) {
+            (None, None) => return a.cmp(b),
+            (l, r) => (l.unwrap_or("0"), r.unwrap_or("0")),
+        };
+        let order = match (l.parse::<u64>(), r.parse::<u64>()) {
+            (Ok(l), Ok(r)) => l.cmp(&r),
+            _ => l.cmp(r),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+/// Reshape values written for an older schema to `schema`
+///
+/// Values are renamed from aliases, widened and recursed into; missing fields
+/// with defaults are appended and unknown values dropped if `schema` is closed.
+pub(crate) fn upgrade_values(
+    schema: &Schema,
+    values: &[Arc<dyn Value>],
+) -> Result<Vec<Arc<dyn Value>>> {
+    let mut upgraded: Vec<Arc<dyn Value>> = Vec::with_capacity(values.len());
+    for value in values {
+        let name = value.name();
+        let field = schema.get(name).or_else(|| {
+            schema.fields().iter().find(|f| {
+                f.aliases().iter().any(|a| a == name)
+                    && !values.iter().any(|v| v.name() == f.name())
+            })
+        });
+        match field {
+            Some(field) => upgraded.push(upgrade_value(field.schema(), value, field.name())?),
+            None if schema.denies_unknown() => {}
+            None => upgraded.push(Arc::clone(value)),
+        }
+    }
+
+    for field in schema.fields() {
+        if let Some(default) = field.schema().default_value() {
+            if !upgraded.iter().any(|v| v.name() == field.name()) {
+                upgraded.push(default.clone().into_value(field.name()));
+            }
+        }
+    }
+    Ok(upgraded)
+}
+
+fn upgrade_value(
+    schema: &ValueSchema,
+    value: &Arc<dyn Value>,
+    name: &str,
+) -> Result<Arc<dyn Value>> {
+    let found = value.value_type();
+    let types = schema.types();
+    if !types.is_empty() && !types.contains(&found) {
+        if let Some(&to) = types.iter().find(|&&t| widens(found, t)) {
+            return Ok(widen(value.as_ref(), to)?.into_value(name));
+        }
+    }
+
+    let any = value.as_any();
+    if let (Some(fields), Some(container)) =
+        (schema.field_schema(), any.downcast_ref::<ContainerValue>())
+    {
+        let children = upgrade_values(fields, container.children())?;
+        return Ok(Arc::new(ContainerValue::new(name, children)));
+    }
+    if let (Some(elements), Some(array)) =
+        (schema.element_schema(), any.downcast_ref::<ArrayValue>())
+    {
+        let elements = array
+            .elements()
+            .iter()
+            .map(|element| upgrade_value(elements, element, element.name()))
+            .collect::<Result<_>>()?;
+        return Ok(Arc::new(ArrayValue::new(name, elements)));
+    }
+
+    if value.name() == name {
+        Ok(Arc::clone(value))
+    } else {
+        Ok(ValueData::from_value(value.as_ref())?.into_value(name))
+    }
+}
+
+/// Convert a number to a type it [`widens`] to
+fn widen(value: &dyn Value, to: ValueType) -> Result<ValueData> {
+    Ok(match to {
+        ValueType::Short => ValueData::Short(value.to_short()?),
+        ValueType::UShort => ValueData::UShort(value.to_ushort()?),
+        ValueType::Int => ValueData::Int(value.to_int()?),
+        ValueType::UInt => ValueData::UInt(value.to_uint()?),
+        ValueType::Long => ValueData::Long(value.to_int()?),
+        ValueType::ULong => ValueData::ULong(value.to_uint()?),
+        ValueType::LLong => ValueData::LLong(value.to_long()?),
+        ValueType::ULLong => ValueData::ULLong(value.to_ulong()?),
+        ValueType::Float => ValueData::Float(value.to_float()?),
+        _ => ValueData::Double(value.to_double()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::schema::SchemaRegistry;
+    use crate::core::{ContainerError, ValueContainer};
+    use crate::values::{IntValue, StringValue};
+
+    fn v1() -> Schema {
+        Schema::new()
+            .required("uid", ValueSchema::new(ValueType::Int))
+            .optional("note", ValueSchema::new(ValueType::String))
+    }
+
+    fn v2() -> Schema {
+        Schema::new()
+            .required("user_id", ValueSchema::new(ValueType::LLong))
+            .alias("user_id", "uid")
+            .optional("note", ValueSchema::new(ValueType::String))
+            .optional("device", ValueSchema::new(ValueType::String))
+            .required(
+                "retries",
+                ValueSchema::new(ValueType::Int).with_default(ValueData::Int(3)),
+            )
+    }
+
+    #[test]
+    fn test_widening() {
+        assert!(widens(ValueType::Short, ValueType::Int));
+        assert!(widens(ValueType::Int, ValueType::Long));
+        assert!(widens(ValueType::UShort, ValueType::Int));
+        assert!(widens(ValueType::Short, ValueType::Float));
+        assert!(widens(ValueType::Float, ValueType::Double));
+        assert!(!widens(ValueType::UInt, ValueType::Int));
+        assert!(!widens(ValueType::Int, ValueType::UInt));
+        assert!(!widens(ValueType::LLong, ValueType::Double));
+        assert!(!widens(ValueType::Double, ValueType::Float));
+        assert!(!widens(ValueType::String, ValueType::Bytes));
+    }
+
+    #[test]
+    fn test_compatibility_directions() {
+        assert!(check_compatibility(&v1(), &v2(), Compatibility::Backward).is_empty());
+
+        // The old reader cannot read llong ids
+        let forward = check_compatibility(&v1(), &v2(), Compatibility::Forward);
+        let reasons: Vec<_> = forward.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            reasons,
+            ["forward uid: required field is missing and has no default",]
+        );
+
+        let strict = v1().required("email", ValueSchema::new(ValueType::String));
+        let problems = check_compatibility(&v1(), &strict, Compatibility::Full);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].direction, Direction::Backward);
+        assert_eq!(problems[0].path, "email");
+
+        let closed = Schema::new()
+            .required("uid", ValueSchema::new(ValueType::Short))
+            .deny_unknown();
+        let problems = check_compatibility(&v1(), &closed, Compatibility::Backward);
+        let paths: Vec<_> = problems.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, ["uid", "note"]);
+    }
+
+    #[test]
+    fn test_nested_compatibility() {
+        let item = |qty: ValueType| {
+            Schema::new().required(
+                "items",
+                ValueSchema::new(ValueType::Array).elements(
+                    ValueSchema::new(ValueType::Container)
+                        .fields(Schema::new().required("qty", ValueSchema::new(qty))),
+                ),
+            )
+        };
+        let old = item(ValueType::Int);
+        let new = item(ValueType::LLong);
+        assert!(check_compatibility(&old, &new, Compatibility::Backward).is_empty());
+        let problems = check_compatibility(&old, &new, Compatibility::Forward);
+        assert_eq!(problems[0].path, "items[*].qty");
+        assert_eq!(problems[0].reason, "llong_value cannot be read");
+    }
+
+    #[test]
+    fn test_version_order() {
+        assert_eq!(compare_versions("1.9", "1.10"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0.0", "1.10"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0.0", "1.0.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0-beta", "1.0-alpha"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_registry_versions() {
+        let mut registry = SchemaRegistry::with_compatibility(Compatibility::Full);
+        registry.register("login", "1.0", v1()).unwrap();
+        let err = registry.register("login", "2.0", v2()).unwrap_err();
+        assert!(matches!(err, ContainerError::SchemaError(ref m) if m.contains("forward uid")));
+        assert_eq!(registry.versions("login"), ["1.0"]);
+
+        let mut registry = SchemaRegistry::new();
+        registry.register("login", "2.0", v2()).unwrap();
+        registry.register("login", "1.0", v1()).unwrap();
+        assert_eq!(registry.versions("login"), ["1.0", "2.0"]);
+        assert_eq!(registry.latest("login").unwrap().0, "2.0");
+        assert!(registry
+            .check_compatibility("login", &v2().deny_unknown())
+            .is_empty());
+        assert!(registry.unregister("login", "1.0").is_some());
+        assert!(registry.get("login", "1.0").is_none());
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let v3 = Schema::new()
+            .required("account_id", ValueSchema::new(ValueType::LLong))
+            .alias("account_id", "user_id")
+            .optional("note", ValueSchema::new(ValueType::String))
+            .optional("device", ValueSchema::new(ValueType::String))
+            .optional("retries", ValueSchema::new(ValueType::Int))
+            .deny_unknown();
+        let mut registry = SchemaRegistry::new();
+        registry.register("login", "1.0", v1()).unwrap();
+        registry.register("login", "2.0", v2()).unwrap();
+        registry.register("login", "3.0", v3).unwrap();
+
+        let mut old = ValueContainer::new();
+        old.set_message_type("login");
+        old.set_version("1.0");
+        old.add_value(Arc::new(IntValue::new("uid", 7))).unwrap();
+        old.add_value(Arc::new(StringValue::new("note", "hi")))
+            .unwrap();
+        old.add_value(Arc::new(StringValue::new("legacy", "x")))
+            .unwrap();
+
+        let upgraded = registry.upgrade(&old).unwrap();
+        assert_eq!(upgraded.version(), "3.0");
+        let id = upgraded.get_value("account_id").unwrap();
+        assert_eq!(id.value_type(), ValueType::LLong);
+        assert_eq!(id.to_long().unwrap(), 7);
+        assert_eq!(upgraded.get_value("retries").unwrap().to_int().unwrap(), 3);
+        assert_eq!(upgraded.get_value("note").unwrap().to_string(), "hi");
+        assert!(upgraded.get_value("uid").is_none());
+        assert!(upgraded.get_value("legacy").is_none());
+        assert_eq!(old.version(), "1.0");
+
+        let again = registry.upgrade(&upgraded).unwrap();
+        assert!(again.diff(&upgraded).is_empty());
+
+        old.set_version("0.9");
+        assert!(matches!(
+            registry.upgrade(&old),
+            Err(ContainerError::SchemaError(_))
+        ));
+    }
+}
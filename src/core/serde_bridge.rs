@@ -0,0 +1,1335 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Serde Bridge
+//!
+//! A `serde::Serializer` that turns any `Serialize` type into values, and a
+//! `serde::Deserializer` that reads any `Deserialize` type back out of them. This
+//! replaces building containers field by field with `add_value`.
+//!
+//! # Type Mapping
+//!
+//! | Rust | Value |
+//! |------|-------|
+//! | `bool` | `bool_value` |
+//! | `i8`, `i16` / `u8`, `u16` | `short_value` / `ushort_value` |
+//! | `i32` / `u32` | `int_value` / `uint_value` |
+//! | `i64` / `u64` | `llong_value` / `ullong_value` |
+//! | `f32` / `f64` | `float_value` / `double_value` |
+//! | `char`, `str`, `String` | `string_value` |
+//! | byte slices serialized with `serialize_bytes` | `bytes_value` |
+//! | `None`, `()`, unit structs | `null_value` |
+//! | sequences, tuples, tuple structs | `array_value` (elements have empty names) |
+//! | structs, maps | `container_value` (one child per field or entry) |
+//!
+//! `Some(x)` and newtype structs serialize as `x`. Enums are externally tagged: a unit
+//! variant becomes a `string_value` holding the variant name, and any other variant a
+//! `container_value` with a single child named after the variant. Map keys must be
+//! strings, chars, integers or unit variants; they become child names.
+//!
+//! When deserializing, integers of any width are accepted for any integer field as
+//! long as the value fits, and `long_value` / `ulong_value` are read like the other
+//! integer types. Errors name the field that failed, as in
+//! `at 'user.tags[1]': invalid type: integer 5, expected a string`.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::serde_bridge::{from_container, to_container};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct User {
+//!     id: u32,
+//!     name: String,
+//!     tags: Vec<String>,
+//!     manager: Option<Box<User>>,
+//! }
+//!
+//! let user = User {
+//!     id: 7,
+//!     name: "Alice".to_string(),
+//!     tags: vec!["admin".to_string()],
+//!     manager: None,
+//! };
+//!
+//! let container = to_container(&user).unwrap();
+//! assert_eq!(container.get_value("id").unwrap().to_int().unwrap(), 7);
+//! assert_eq!(container.get_value("name").unwrap().to_string(), "Alice");
+//!
+//! let restored: User = from_container(&container).unwrap();
+//! assert_eq!(restored, user);
+//! ```
+
+use crate::core::{ContainerError, Result, Value, ValueContainer, ValueType};
+use crate::values::{
+    ArrayValue, BoolValue, BytesValue, ContainerValue, DoubleValue, FloatValue, IntValue,
+    LLongValue, NullValue, ShortValue, StringValue, UIntValue, ULLongValue, UShortValue,
+};
+use serde::de::value::{StrDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// Serialize `value` as a single value named `name`
+///
+/// Structs and maps become a `ContainerValue` tree; see the module documentation for
+/// the full type mapping.
+///
+/// # Errors
+///
+/// Returns `ContainerError::SerializationError` if a map key is not a string, char,
+/// integer or unit variant, or if the `Serialize` implementation fails.
+pub fn to_value<T: Serialize + ?Sized>(name: &str, value: &T) -> Result<Arc<dyn Value>> {
+    value.serialize(ValueSerializer { name })
+}
+
+/// Serialize a struct or map into a `ValueContainer`, one top-level value per field
+///
+/// The header keeps the `ValueContainer::new` defaults.
+///
+/// # Errors
+///
+/// Same as [`to_value`], plus `ContainerError::SerializationError` if `value` does
+/// not serialize as a struct or map, and any error from `ValueContainer::add_value`.
+pub fn to_container<T: Serialize + ?Sized>(value: &T) -> Result<ValueContainer> {
+    let value = to_value("", value)?;
+    let fields = match value.as_any().downcast_ref::<ContainerValue>() {
+        Some(fields) if value.value_type() == ValueType::Container => fields,
+        _ => {
+            return Err(ContainerError::SerializationError(format!(
+                "only structs and maps can be serialized into a container (got {})",
+                value.value_type()
+            )))
+        }
+    };
+
+    let mut container = ValueContainer::new();
+    for child in fields.children() {
+        container.add_value(Arc::clone(child))?;
+    }
+    Ok(container)
+}
+
+/// Deserialize a `T` from a single value
+///
+/// # Errors
+///
+/// Returns `ContainerError::DeserializationError` naming the failing field if the
+/// value's shape or types do not match `T`.
+pub fn from_value<T: DeserializeOwned>(value: &dyn Value) -> Result<T> {
+    T::deserialize(ValueDeserializer {
+        value,
+        path: &Path::Root,
+    })
+}
+
+/// Deserialize a `T` from the top-level values of `container`
+///
+/// The values are read like the fields of a struct or the entries of a map; the
+/// header is ignored.
+///
+/// # Errors
+///
+/// Same as [`from_value`].
+pub fn from_container<T: DeserializeOwned>(container: &ValueContainer) -> Result<T> {
+    from_value(&ContainerValue::new("", container.values()))
+}
+
+// ============================================================================
+// Serializer
+// ============================================================================
+
+/// Serializer producing one value with the given name
+struct ValueSerializer<'n> {
+    name: &'n str,
+}
+
+impl ser::Serializer for ValueSerializer<'_> {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+    type SerializeSeq = SeqBuilder;
+    type SerializeTuple = SeqBuilder;
+    type SerializeTupleStruct = SeqBuilder;
+    type SerializeTupleVariant = SeqBuilder;
+    type SerializeMap = FieldsBuilder;
+    type SerializeStruct = FieldsBuilder;
+    type SerializeStructVariant = FieldsBuilder;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        Ok(Arc::new(BoolValue::new(self.name, v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        self.serialize_i16(v as i16)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        Ok(Arc::new(ShortValue::new(self.name, v)))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        Ok(Arc::new(IntValue::new(self.name, v)))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        Ok(Arc::new(LLongValue::new(self.name, v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        self.serialize_u16(v as u16)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        Ok(Arc::new(UShortValue::new(self.name, v)))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        Ok(Arc::new(UIntValue::new(self.name, v)))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        Ok(Arc::new(ULLongValue::new(self.name, v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        Ok(Arc::new(FloatValue::new(self.name, v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        Ok(Arc::new(DoubleValue::new(self.name, v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        Ok(Arc::new(StringValue::new(self.name, v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(Arc::new(StringValue::new(self.name, v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        Ok(Arc::new(BytesValue::from_slice(self.name, v)))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Ok(Arc::new(NullValue::new(self.name)))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        let inner = value.serialize(ValueSerializer { name: variant })?;
+        Ok(Arc::new(ContainerValue::new(self.name, vec![inner])))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SeqBuilder::new(self.name, None, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        Ok(SeqBuilder::new(self.name, None, Some(len)))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Ok(SeqBuilder::new(self.name, None, Some(len)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(SeqBuilder::new(self.name, Some(variant), Some(len)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(FieldsBuilder::new(self.name, None, len))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(FieldsBuilder::new(self.name, None, Some(len)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(FieldsBuilder::new(self.name, Some(variant), Some(len)))
+    }
+}
+
+/// Wrap the value built by `build` in a single-child container named `name` if it
+/// belongs to an enum variant
+fn wrap_variant(
+    name: String,
+    variant: Option<&'static str>,
+    build: impl FnOnce(String) -> Arc<dyn Value>,
+) -> Arc<dyn Value> {
+    match variant {
+        None => build(name),
+        Some(variant) => Arc::new(ContainerValue::new(name, vec![build(variant.to_string())])),
+    }
+}
+
+/// Collects the elements of a sequence, tuple or tuple variant into an `ArrayValue`
+struct SeqBuilder {
+    name: String,
+    variant: Option<&'static str>,
+    elements: Vec<Arc<dyn Value>>,
+}
+
+impl SeqBuilder {
+    fn new(name: &str, variant: Option<&'static str>, len: Option<usize>) -> Self {
+        Self {
+            name: name.to_string(),
+            variant,
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.elements
+            .push(value.serialize(ValueSerializer { name: "" })?);
+        Ok(())
+    }
+
+    fn finish(self) -> Arc<dyn Value> {
+        let elements = self.elements;
+        wrap_variant(self.name, self.variant, |name| {
+            Arc::new(ArrayValue::new(name, elements))
+        })
+    }
+}
+
+impl ser::SerializeSeq for SeqBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SeqBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+/// Collects the fields of a struct, struct variant or map into a `ContainerValue`
+struct FieldsBuilder {
+    name: String,
+    variant: Option<&'static str>,
+    children: Vec<Arc<dyn Value>>,
+    /// Map key waiting for its value
+    key: Option<String>,
+}
+
+impl FieldsBuilder {
+    fn new(name: &str, variant: Option<&'static str>, len: Option<usize>) -> Self {
+        Self {
+            name: name.to_string(),
+            variant,
+            children: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<()> {
+        self.children
+            .push(value.serialize(ValueSerializer { name })?);
+        Ok(())
+    }
+
+    fn finish(self) -> Arc<dyn Value> {
+        let children = self.children;
+        wrap_variant(self.name, self.variant, |name| {
+            Arc::new(ContainerValue::new(name, children))
+        })
+    }
+}
+
+impl ser::SerializeMap for FieldsBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().ok_or_else(|| {
+            ContainerError::SerializationError("map value serialized before its key".to_string())
+        })?;
+        self.push(&key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStruct for FieldsBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for FieldsBuilder {
+    type Ok = Arc<dyn Value>;
+    type Error = ContainerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.finish())
+    }
+}
+
+/// Serializer turning a map key into a value name
+struct KeySerializer;
+
+fn key_error(kind: &str) -> ContainerError {
+    ContainerError::SerializationError(format!(
+        "map keys must be strings, chars, integers or unit variants (got {})",
+        kind
+    ))
+}
+
+macro_rules! serialize_key_display {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<String> {
+                Ok(v.to_string())
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = ContainerError;
+    type SerializeSeq = Impossible<String, ContainerError>;
+    type SerializeTuple = Impossible<String, ContainerError>;
+    type SerializeTupleStruct = Impossible<String, ContainerError>;
+    type SerializeTupleVariant = Impossible<String, ContainerError>;
+    type SerializeMap = Impossible<String, ContainerError>;
+    type SerializeStruct = Impossible<String, ContainerError>;
+    type SerializeStructVariant = Impossible<String, ContainerError>;
+
+    serialize_key_display! {
+        serialize_i8(i8), serialize_i16(i16), serialize_i32(i32), serialize_i64(i64),
+        serialize_u8(u8), serialize_u16(u16), serialize_u32(u32), serialize_u64(u64),
+        serialize_char(char), serialize_str(&str),
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String> {
+        Err(key_error("bool"))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        Err(key_error("f32"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        Err(key_error("f64"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(key_error("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(key_error("None"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_error("unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<String> {
+        Err(key_error(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String> {
+        Err(key_error(name))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_error("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_error("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_error(name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_error(name))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_error("map"))
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_error(name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_error(name))
+    }
+}
+
+// ============================================================================
+// Deserializer
+// ============================================================================
+
+/// Location of a value below the root, for error messages
+enum Path<'a> {
+    Root,
+    Field(&'a Path<'a>, &'a str),
+    Index(&'a Path<'a>, usize),
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Path::Root => Ok(()),
+            Path::Field(Path::Root, name) => f.write_str(name),
+            Path::Field(parent, name) => write!(f, "{}.{}", parent, name),
+            Path::Index(parent, index) => write!(f, "{}[{}]", parent, index),
+        }
+    }
+}
+
+/// Prefix of error messages that already name the failing field
+const PATH_PREFIX: &str = "at '";
+
+/// Deserializer reading from one value
+#[derive(Clone, Copy)]
+struct ValueDeserializer<'a> {
+    value: &'a dyn Value,
+    path: &'a Path<'a>,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    /// Name the failing field, unless a nested value already did
+    fn annotate(&self, error: ContainerError) -> ContainerError {
+        match error {
+            ContainerError::DeserializationError(message)
+                if !matches!(self.path, Path::Root) && !message.starts_with(PATH_PREFIX) =>
+            {
+                ContainerError::DeserializationError(format!(
+                    "{}{}': {}",
+                    PATH_PREFIX, self.path, message
+                ))
+            }
+            other => other,
+        }
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let value = self.value;
+        match value.value_type() {
+            ValueType::Null => visitor.visit_unit(),
+            ValueType::Bool => visitor.visit_bool(value.to_bool()?),
+            ValueType::Short | ValueType::Int | ValueType::Long | ValueType::LLong => {
+                visitor.visit_i64(value.to_long()?)
+            }
+            ValueType::UShort | ValueType::UInt | ValueType::ULong | ValueType::ULLong => {
+                visitor.visit_u64(value.to_ulong()?)
+            }
+            ValueType::Float => visitor.visit_f32(value.to_float()?),
+            ValueType::Double => visitor.visit_f64(value.to_double()?),
+            ValueType::String => match value.as_any().downcast_ref::<StringValue>() {
+                Some(text) => visitor.visit_str(text.value()),
+                None => visitor.visit_string(value.to_string()),
+            },
+            // Decimals, times and UUIDs travel as their text so `Decimal`,
+            // `Timestamp` and `Uuid` fields round-trip
+            ValueType::Decimal | ValueType::Timestamp | ValueType::Duration | ValueType::Uuid => {
+                visitor.visit_string(value.to_string())
+            }
+            ValueType::Bytes => visitor.visit_bytes(bytes_of(value)?),
+            ValueType::Container => visitor.visit_map(FieldAccess {
+                fields: children_of(value)?.iter(),
+                current: None,
+                path: self.path,
+            }),
+            ValueType::Array => visitor.visit_seq(ElementAccess {
+                elements: elements_of(value)?.iter().enumerate(),
+                path: self.path,
+            }),
+        }
+    }
+}
+
+fn children_of(value: &dyn Value) -> Result<&[Arc<dyn Value>]> {
+    value
+        .as_any()
+        .downcast_ref::<ContainerValue>()
+        .map(ContainerValue::children)
+        .ok_or_else(|| unsupported_value(value))
+}
+
+fn elements_of(value: &dyn Value) -> Result<&[Arc<dyn Value>]> {
+    value
+        .as_any()
+        .downcast_ref::<ArrayValue>()
+        .map(ArrayValue::elements)
+        .ok_or_else(|| unsupported_value(value))
+}
+
+fn bytes_of(value: &dyn Value) -> Result<&[u8]> {
+    value
+        .as_any()
+        .downcast_ref::<BytesValue>()
+        .map(BytesValue::data)
+        .ok_or_else(|| unsupported_value(value))
+}
+
+fn unsupported_value(value: &dyn Value) -> ContainerError {
+    ContainerError::DeserializationError(format!(
+        "{} '{}' is not a built-in value type",
+        value.value_type(),
+        value.name()
+    ))
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = ContainerError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.visit(visitor).map_err(|e| self.annotate(e))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value.value_type() {
+            ValueType::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // Lets Vec<u8> fields read bytes values as well as arrays
+        match self.value.value_type() {
+            ValueType::Bytes => bytes_of(self.value)
+                .and_then(|bytes| {
+                    visitor.visit_seq(de::value::SeqDeserializer::new(bytes.iter().copied()))
+                })
+                .map_err(|e| self.annotate(e)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let result = match self.value.value_type() {
+            ValueType::String => {
+                visitor.visit_enum(StringDeserializer::<ContainerError>::new(self.value.to_string()))
+            }
+            ValueType::Container => match children_of(self.value) {
+                Ok([variant]) => visitor.visit_enum(VariantDeserializer {
+                    value: variant.as_ref(),
+                    path: self.path,
+                }),
+                Ok(children) => Err(ContainerError::DeserializationError(format!(
+                    "expected a container with exactly one child naming the enum variant, found {} children",
+                    children.len()
+                ))),
+                Err(e) => Err(e),
+            },
+            other => Err(ContainerError::DeserializationError(format!(
+                "expected an enum variant name or a single-child container, found {}",
+                other
+            ))),
+        };
+        result.map_err(|e| self.annotate(e))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf unit unit_struct tuple tuple_struct map struct identifier
+    }
+}
+
+/// Map access over the children of a container, keyed by child name
+struct FieldAccess<'a> {
+    fields: std::slice::Iter<'a, Arc<dyn Value>>,
+    current: Option<&'a dyn Value>,
+    path: &'a Path<'a>,
+}
+
+impl<'de> de::MapAccess<'de> for FieldAccess<'_> {
+    type Error = ContainerError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.fields.next() {
+            Some(field) => {
+                self.current = Some(field.as_ref());
+                seed.deserialize(KeyDeserializer(field.name())).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self.current.take().ok_or_else(|| {
+            ContainerError::DeserializationError("map value requested before its key".to_string())
+        })?;
+        let path = Path::Field(self.path, value.name());
+        seed.deserialize(ValueDeserializer { value, path: &path })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+/// Sequence access over the elements of an array
+struct ElementAccess<'a> {
+    elements: std::iter::Enumerate<std::slice::Iter<'a, Arc<dyn Value>>>,
+    path: &'a Path<'a>,
+}
+
+impl<'de> de::SeqAccess<'de> for ElementAccess<'_> {
+    type Error = ContainerError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.elements.next() {
+            Some((index, element)) => {
+                let path = Path::Index(self.path, index);
+                seed.deserialize(ValueDeserializer {
+                    value: element.as_ref(),
+                    path: &path,
+                })
+                .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+/// Enum access for a variant stored as the single child of a container
+struct VariantDeserializer<'a> {
+    value: &'a dyn Value,
+    path: &'a Path<'a>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for VariantDeserializer<'a> {
+    type Error = ContainerError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant =
+            seed.deserialize(StrDeserializer::<ContainerError>::new(self.value.name()))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer<'_> {
+    type Error = ContainerError;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value.value_type() {
+            ValueType::Null => Ok(()),
+            other => Err(ContainerError::DeserializationError(format!(
+                "unit variant '{}' must hold a null_value, found {}",
+                self.value.name(),
+                other
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        let path = Path::Field(self.path, self.value.name());
+        seed.deserialize(ValueDeserializer {
+            value: self.value,
+            path: &path,
+        })
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        let path = Path::Field(self.path, self.value.name());
+        de::Deserializer::deserialize_seq(
+            ValueDeserializer {
+                value: self.value,
+                path: &path,
+            },
+            visitor,
+        )
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let path = Path::Field(self.path, self.value.name());
+        de::Deserializer::deserialize_map(
+            ValueDeserializer {
+                value: self.value,
+                path: &path,
+            },
+            visitor,
+        )
+    }
+}
+
+/// Deserializer for a child name used as a map key
+///
+/// Integer keys are parsed from the name, so maps keyed by integers round-trip.
+struct KeyDeserializer<'a>(&'a str);
+
+macro_rules! deserialize_key_parsed {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                match self.0.parse() {
+                    Ok(number) => visitor.$visit(number),
+                    Err(_) => visitor.visit_str(self.0),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'_> {
+    type Error = ContainerError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_str(self.0)
+    }
+
+    deserialize_key_parsed! {
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32, deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8, deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32, deserialize_u64 => visit_u64,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(StrDeserializer::<ContainerError>::new(self.0))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::{LongValue, ULongValue};
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Primitives {
+        flag: bool,
+        tiny: i8,
+        small: i16,
+        int: i32,
+        big: i64,
+        byte: u8,
+        word: u16,
+        uint: u32,
+        ubig: u64,
+        single: f32,
+        double: f64,
+        letter: char,
+        text: String,
+        nothing: Option<i32>,
+        unit: (),
+    }
+
+    fn primitives() -> Primitives {
+        Primitives {
+            flag: true,
+            tiny: -8,
+            small: -1600,
+            int: -42,
+            big: i64::MIN,
+            byte: 200,
+            word: 65000,
+            uint: 4_000_000_000,
+            ubig: u64::MAX,
+            single: 1.5,
+            double: std::f64::consts::E,
+            letter: 'é',
+            text: "안녕 ];,".to_string(),
+            nothing: None,
+            unit: (),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Point(i32, i32),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Address {
+        city: String,
+        zip: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        id: u32,
+        name: String,
+        tags: Vec<String>,
+        address: Address,
+        shapes: Vec<Shape>,
+        scores: BTreeMap<u32, f64>,
+        attributes: HashMap<String, String>,
+        pair: (i32, String),
+    }
+
+    fn user() -> User {
+        User {
+            id: 7,
+            name: "Alice".to_string(),
+            tags: vec!["admin".to_string(), "ops".to_string()],
+            address: Address {
+                city: "Seoul".to_string(),
+                zip: Some("04524".to_string()),
+            },
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(2.5),
+                Shape::Point(1, -1),
+                Shape::Rect {
+                    width: 3,
+                    height: 4,
+                },
+            ],
+            scores: BTreeMap::from([(1, 0.5), (20, 1.0)]),
+            attributes: HashMap::from([("team".to_string(), "core".to_string())]),
+            pair: (3, "three".to_string()),
+        }
+    }
+
+    fn deserialization_message(error: ContainerError) -> String {
+        match error {
+            ContainerError::DeserializationError(message) => message,
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_primitive_type_mapping() {
+        let container = to_container(&primitives()).unwrap();
+        let types: Vec<ValueType> = container
+            .values()
+            .iter()
+            .map(|value| value.value_type())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ValueType::Bool,
+                ValueType::Short,
+                ValueType::Short,
+                ValueType::Int,
+                ValueType::LLong,
+                ValueType::UShort,
+                ValueType::UShort,
+                ValueType::UInt,
+                ValueType::ULLong,
+                ValueType::Float,
+                ValueType::Double,
+                ValueType::String,
+                ValueType::String,
+                ValueType::Null,
+                ValueType::Null,
+            ]
+        );
+        assert_eq!(container.get_value("text").unwrap().to_string(), "안녕 ];,");
+
+        let restored: Primitives = from_container(&container).unwrap();
+        assert_eq!(restored, primitives());
+    }
+
+    #[test]
+    fn test_nested_roundtrip() {
+        let container = to_container(&user()).unwrap();
+
+        let address = container.get_value("address").unwrap();
+        assert_eq!(address.value_type(), ValueType::Container);
+        let tags = container.get_value("tags").unwrap();
+        let tags = tags.as_any().downcast_ref::<ArrayValue>().unwrap();
+        assert_eq!(tags.elements()[1].to_string(), "ops");
+        assert_eq!(tags.elements()[1].name(), "");
+
+        let scores = container.get_value("scores").unwrap();
+        let scores = scores.as_any().downcast_ref::<ContainerValue>().unwrap();
+        assert_eq!(scores.children()[1].name(), "20");
+
+        let restored: User = from_container(&container).unwrap();
+        assert_eq!(restored, user());
+    }
+
+    #[test]
+    fn test_enum_representation() {
+        let unit = to_value("shape", &Shape::Empty).unwrap();
+        assert_eq!(unit.value_type(), ValueType::String);
+        assert_eq!(unit.to_string(), "Empty");
+
+        let rect = to_value(
+            "shape",
+            &Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+        )
+        .unwrap();
+        let rect = rect.as_any().downcast_ref::<ContainerValue>().unwrap();
+        assert_eq!(rect.children().len(), 1);
+        assert_eq!(rect.children()[0].name(), "Rect");
+        assert_eq!(rect.children()[0].value_type(), ValueType::Container);
+
+        for shape in user().shapes {
+            let value = to_value("shape", &shape).unwrap();
+            assert_eq!(from_value::<Shape>(value.as_ref()).unwrap(), shape);
+        }
+    }
+
+    #[test]
+    fn test_to_container_requires_struct_or_map() {
+        assert!(matches!(
+            to_container(&42),
+            Err(ContainerError::SerializationError(_))
+        ));
+        assert!(matches!(
+            to_container(&vec![1, 2]),
+            Err(ContainerError::SerializationError(_))
+        ));
+        let map = to_container(&BTreeMap::from([("a", 1)])).unwrap();
+        assert_eq!(map.get_value("a").unwrap().to_int().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_unsupported_map_keys() {
+        let map = HashMap::from([((1, 2), "pair")]);
+        assert!(matches!(
+            to_value("map", &map),
+            Err(ContainerError::SerializationError(message)) if message.contains("map keys")
+        ));
+    }
+
+    #[test]
+    fn test_type_mismatch_names_the_field() {
+        let mut container = to_container(&user()).unwrap();
+        container.remove_value("id");
+        container
+            .add_value(Arc::new(StringValue::new("id", "seven")))
+            .unwrap();
+        let message = deserialization_message(from_container::<User>(&container).unwrap_err());
+        assert!(
+            message.starts_with("at 'id': invalid type: string"),
+            "{}",
+            message
+        );
+
+        let bad_tag = ContainerValue::new(
+            "",
+            vec![Arc::new(ArrayValue::new(
+                "tags",
+                vec![
+                    Arc::new(StringValue::new("", "ok")) as Arc<dyn Value>,
+                    Arc::new(IntValue::new("", 5)),
+                ],
+            )) as Arc<dyn Value>],
+        );
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Tags {
+            tags: Vec<String>,
+        }
+        let message = deserialization_message(from_value::<Tags>(&bad_tag).unwrap_err());
+        assert!(
+            message.starts_with("at 'tags[1]': invalid type: integer `5`"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_missing_field_and_range_errors() {
+        let mut container = to_container(&user()).unwrap();
+        container.remove_value("address");
+        let message = deserialization_message(from_container::<User>(&container).unwrap_err());
+        assert!(message.contains("missing field `address`"), "{}", message);
+
+        let nested = ContainerValue::new(
+            "",
+            vec![Arc::new(ContainerValue::new(
+                "address",
+                vec![Arc::new(IntValue::new("zip", 1)) as Arc<dyn Value>],
+            )) as Arc<dyn Value>],
+        );
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Wrapper {
+            address: Address,
+        }
+        let message = deserialization_message(from_value::<Wrapper>(&nested).unwrap_err());
+        assert!(message.starts_with("at 'address.zip':"), "{}", message);
+
+        let big = ULLongValue::new("n", u64::MAX);
+        let message = deserialization_message(from_value::<i32>(&big).unwrap_err());
+        assert!(message.contains("invalid value"), "{}", message);
+    }
+
+    #[test]
+    fn test_reads_hand_built_values() {
+        let mut container = ValueContainer::new();
+        let values: Vec<Arc<dyn Value>> = vec![
+            Arc::new(LongValue::new("long", -5).unwrap()),
+            Arc::new(ULongValue::new("ulong", 5).unwrap()),
+            Arc::new(BytesValue::new("raw", vec![1, 2, 3])),
+            Arc::new(IntValue::new("as_float", 3)),
+        ];
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Record {
+            long: i64,
+            ulong: u8,
+            raw: Vec<u8>,
+            as_float: f64,
+        }
+        let record: Record = from_container(&container).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                long: -5,
+                ulong: 5,
+                raw: vec![1, 2, 3],
+                as_float: 3.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_self_describing_targets() {
+        let json: serde_json::Value = from_container(&to_container(&user()).unwrap()).unwrap();
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["address"]["city"], "Seoul");
+        assert_eq!(json["tags"][1], "ops");
+        assert_eq!(json["shapes"][0], "Empty");
+        assert_eq!(json["shapes"][3]["Rect"]["height"], 4);
+    }
+}
@@ -0,0 +1,1226 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Binary Value Codec
+//!
+//! Encodes and decodes single values in the C++ container_system binary value layout:
+//!
+//! ```text
+//! [type:1][name_len:4 LE][name:UTF-8][value_size:4 LE][value:value_size]
+//! ```
+//!
+//! | Type | Value payload |
+//! |------|---------------|
+//! | null_value | empty |
+//! | bool_value | 1 byte (0 or 1) |
+//! | short_value, ushort_value | 2 bytes |
+//! | int_value, uint_value, long_value, ulong_value, float_value | 4 bytes |
+//! | llong_value, ullong_value, double_value | 8 bytes |
+//! | string_value | UTF-8 bytes |
+//! | bytes_value | raw bytes |
+//! | container_value, array_value | `[count:4 LE]` followed by `count` encoded child values |
+//! | decimal_value | 17 bytes: `[scale:1][mantissa:16 LE i128]` |
+//! | timestamp_value | 14 bytes: `[seconds:8 LE i64][nanos:4 LE][offset minutes:2 LE i16]` |
+//! | duration_value | 12 bytes: `[seconds:8 LE u64][nanos:4 LE]` |
+//! | uuid_value | the 16 bytes, most significant first |
+//!
+//! `long_value` / `ulong_value` are 32-bit, matching `LongValue` / `ULongValue` and the
+//! C++ cross-platform `long` encoding.
+//!
+//! `ValueDecoder` rebuilds all built-in types. Applications can register their own
+//! decoder for any `ValueType` to reconstruct custom `Value` implementations; nested
+//! containers and arrays are decoded through the same registry.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::value_codec::{decode_value, encode_value};
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let value: Arc<dyn Value> = Arc::new(ULLongValue::new("big", u64::MAX));
+//! let bytes = encode_value(&value).unwrap();
+//!
+//! let (decoded, consumed) = decode_value(&bytes).unwrap();
+//! assert_eq!(consumed, bytes.len());
+//! assert_eq!(decoded.value_type(), ValueType::ULLong);
+//! assert_eq!(decoded.to_ulong().unwrap(), u64::MAX);
+//! ```
+
+use crate::core::value_types::ValueType;
+use crate::core::wire_protocol::MAX_NESTING_DEPTH;
+use crate::core::{ContainerError, Result, Value};
+use crate::values::decimal_value::DECIMAL_PAYLOAD_SIZE;
+use crate::values::time_values::{
+    duration_from_payload, duration_of, duration_payload, timestamp_of,
+};
+use crate::values::uuid_value::uuid_of;
+use crate::values::{
+    ArrayValue, BoolValue, BytesValue, ContainerValue, Decimal, DecimalValue, DoubleValue,
+    DurationValue, FloatValue, IntValue, LLongValue, LongValue, NullValue, ShortValue, StringValue,
+    Timestamp, TimestampValue, UIntValue, ULLongValue, ULongValue, UShortValue, Uuid, UuidValue,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+/// Smallest possible encoded value: type(1) + name_len(4) + value_size(4)
+pub const MIN_ENCODED_VALUE_SIZE: usize = 9;
+
+/// Encode a value (and, for containers and arrays, all of its descendants)
+///
+/// # Errors
+///
+/// Returns [`ContainerError::SerializationError`] naming the value if it reports a type
+/// it cannot be encoded as (e.g. a custom `Value` claiming to be a container), or if a
+/// name or payload exceeds 4 GiB.
+pub fn encode_value(value: &Arc<dyn Value>) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(MIN_ENCODED_VALUE_SIZE + value.name().len() + value.size());
+    encode_value_into(value, &mut out)?;
+    Ok(out)
+}
+
+/// Append the encoding of a value to `out`
+///
+/// On error `out` may contain a partially written value.
+pub fn encode_value_into(value: &Arc<dyn Value>, out: &mut Vec<u8>) -> Result<()> {
+    let name = value.name();
+    let payload = payload_of(value)?;
+
+    out.push(value.value_type() as u8);
+    write_len(out, name.len(), "name")?;
+    out.extend_from_slice(name.as_bytes());
+
+    // value_size is patched once the payload is written
+    let size_pos = out.len();
+    out.extend_from_slice(&[0; 4]);
+    let payload_start = out.len();
+
+    match payload {
+        Payload::Fixed(bytes, len) => out.extend_from_slice(&bytes[..len]),
+        Payload::Text(text) => out.extend_from_slice(text.as_bytes()),
+        Payload::Bytes(bytes) => out.extend_from_slice(bytes),
+        Payload::Children(children) => encode_children(children, out)?,
+    }
+
+    let value_size = out.len() - payload_start;
+    let value_size = payload_size_u32(name, value_size)?;
+    out[size_pos..payload_start].copy_from_slice(&value_size.to_le_bytes());
+    Ok(())
+}
+
+/// Write the encoding of a value to `writer`
+///
+/// Produces the same bytes as [`encode_value`] without building them in memory first:
+/// container and array sizes are computed up front, and string and byte payloads are
+/// copied straight from the value into the writer.
+///
+/// On error the writer may have received a partially written value.
+///
+/// # Example
+///
+/// ```
+/// use rust_container_system::core::value_codec::{encode_value, write_value};
+/// use rust_container_system::prelude::*;
+/// use std::sync::Arc;
+///
+/// let value: Arc<dyn Value> = Arc::new(BytesValue::new("blob", vec![7; 1024]));
+///
+/// let mut out = Vec::new();
+/// write_value(&value, &mut out).unwrap();
+/// assert_eq!(out, encode_value(&value).unwrap());
+/// ```
+pub fn write_value<W: Write + ?Sized>(value: &Arc<dyn Value>, writer: &mut W) -> Result<()> {
+    let name = value.name();
+    let payload = payload_of(value)?;
+    let value_size = payload_size_u32(name, payload_len(&payload)?)?;
+
+    let mut header = Vec::with_capacity(MIN_ENCODED_VALUE_SIZE + name.len());
+    header.push(value.value_type() as u8);
+    write_len(&mut header, name.len(), "name")?;
+    header.extend_from_slice(name.as_bytes());
+    header.extend_from_slice(&value_size.to_le_bytes());
+    writer.write_all(&header)?;
+
+    match payload {
+        Payload::Fixed(bytes, len) => writer.write_all(&bytes[..len])?,
+        Payload::Text(text) => writer.write_all(text.as_bytes())?,
+        Payload::Bytes(bytes) => writer.write_all(bytes)?,
+        Payload::Children(children) => {
+            write_len(writer, children.len(), "child count")?;
+            for child in children {
+                write_value(child, writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Number of bytes [`encode_value`] produces for a value, without encoding it
+pub fn encoded_len(value: &Arc<dyn Value>) -> Result<usize> {
+    Ok(MIN_ENCODED_VALUE_SIZE + value.name().len() + payload_len(&payload_of(value)?)?)
+}
+
+/// Largest fixed-width payload (`decimal_value`)
+const MAX_FIXED_PAYLOAD: usize = DECIMAL_PAYLOAD_SIZE;
+
+/// Payload of a value, borrowed from the value where possible
+enum Payload<'v> {
+    /// Fixed-width scalar: the first `len` bytes are used
+    Fixed([u8; MAX_FIXED_PAYLOAD], usize),
+    Text(Cow<'v, str>),
+    Bytes(&'v [u8]),
+    Children(&'v [Arc<dyn Value>]),
+}
+
+fn payload_of(value: &Arc<dyn Value>) -> Result<Payload<'_>> {
+    fn fixed<const N: usize>(bytes: [u8; N]) -> Payload<'static> {
+        let mut buf = [0; MAX_FIXED_PAYLOAD];
+        buf[..N].copy_from_slice(&bytes);
+        Payload::Fixed(buf, N)
+    }
+
+    let name = value.name();
+    let value_type = value.value_type();
+    Ok(match value_type {
+        ValueType::Null => Payload::Fixed([0; MAX_FIXED_PAYLOAD], 0),
+        ValueType::Bool => fixed([value.to_bool()? as u8]),
+        ValueType::Short => fixed(value.to_short()?.to_le_bytes()),
+        ValueType::UShort => fixed(value.to_ushort()?.to_le_bytes()),
+        ValueType::Int => fixed(value.to_int()?.to_le_bytes()),
+        ValueType::UInt => fixed(value.to_uint()?.to_le_bytes()),
+        ValueType::Long => fixed((value.to_long()? as i32).to_le_bytes()),
+        ValueType::ULong => fixed((value.to_ulong()? as u32).to_le_bytes()),
+        ValueType::LLong => fixed(value.to_long()?.to_le_bytes()),
+        ValueType::ULLong => fixed(value.to_ulong()?.to_le_bytes()),
+        ValueType::Float => fixed(value.to_float()?.to_le_bytes()),
+        ValueType::Double => fixed(value.to_double()?.to_le_bytes()),
+        ValueType::Decimal => {
+            let decimal = match value.as_any().downcast_ref::<DecimalValue>() {
+                Some(decimal) => decimal.value(),
+                None => value
+                    .to_string()
+                    .parse::<Decimal>()
+                    .map_err(|_| mismatched_type(name, value_type))?,
+            };
+            fixed(decimal.to_payload())
+        }
+        ValueType::Timestamp => match timestamp_of(value.as_ref()) {
+            Ok(timestamp) => fixed(timestamp.to_payload()),
+            Err(_) => return Err(mismatched_type(name, value_type)),
+        },
+        ValueType::Duration => match duration_of(value.as_ref()) {
+            Ok(duration) => fixed(duration_payload(duration)),
+            Err(_) => return Err(mismatched_type(name, value_type)),
+        },
+        ValueType::Uuid => match uuid_of(value.as_ref()) {
+            Ok(uuid) => fixed(*uuid.as_bytes()),
+            Err(_) => return Err(mismatched_type(name, value_type)),
+        },
+        ValueType::String => match value.as_any().downcast_ref::<StringValue>() {
+            Some(string) => Payload::Text(Cow::Borrowed(string.value())),
+            None => Payload::Text(Cow::Owned(value.to_string())),
+        },
+        ValueType::Bytes => Payload::Bytes(
+            value
+                .as_any()
+                .downcast_ref::<BytesValue>()
+                .ok_or_else(|| mismatched_type(name, value_type))?
+                .data(),
+        ),
+        ValueType::Container => Payload::Children(
+            value
+                .as_any()
+                .downcast_ref::<ContainerValue>()
+                .ok_or_else(|| mismatched_type(name, value_type))?
+                .children(),
+        ),
+        ValueType::Array => Payload::Children(
+            value
+                .as_any()
+                .downcast_ref::<ArrayValue>()
+                .ok_or_else(|| mismatched_type(name, value_type))?
+                .elements(),
+        ),
+    })
+}
+
+fn payload_len(payload: &Payload<'_>) -> Result<usize> {
+    Ok(match payload {
+        Payload::Fixed(_, len) => *len,
+        Payload::Text(text) => text.len(),
+        Payload::Bytes(bytes) => bytes.len(),
+        Payload::Children(children) => {
+            let mut len = 4;
+            for child in children.iter() {
+                len += encoded_len(child)?;
+            }
+            len
+        }
+    })
+}
+
+fn payload_size_u32(name: &str, value_size: usize) -> Result<u32> {
+    u32::try_from(value_size).map_err(|_| {
+        ContainerError::SerializationError(format!(
+            "Value '{}' payload of {} bytes exceeds 4 GiB",
+            name, value_size
+        ))
+    })
+}
+
+/// Append a child count followed by each encoded child
+///
+/// This is the payload layout of `container_value` and `array_value`.
+pub fn encode_children(children: &[Arc<dyn Value>], out: &mut Vec<u8>) -> Result<()> {
+    write_len(out, children.len(), "child count")?;
+    for child in children {
+        encode_value_into(child, out)?;
+    }
+    Ok(())
+}
+
+/// Decode one value from the start of `data` using the built-in decoders
+///
+/// Returns the value and the number of bytes consumed.
+pub fn decode_value(data: &[u8]) -> Result<(Arc<dyn Value>, usize)> {
+    ValueDecoder::new().decode(data)
+}
+
+/// Signature of a registered decoder
+///
+/// Receives the value name, its payload (the `value_size` bytes after the header) and a
+/// [`DecodeContext`] for decoding nested values.
+pub type DecodeFn = dyn Fn(&str, &[u8], &DecodeContext<'_>) -> Result<Arc<dyn Value>> + Send + Sync;
+
+/// Binary value decoder with a registry of per-type overrides
+///
+/// Without registrations, every `ValueType` is rebuilt as its built-in value type
+/// (`null_value` as [`NullValue`], `container_value` as [`ContainerValue`], ...).
+/// A decoder registered for a type replaces the built-in one, including for values
+/// nested inside containers and arrays.
+///
+/// # Example
+///
+/// ```
+/// use rust_container_system::core::value_codec::{encode_value, ValueDecoder};
+/// use rust_container_system::prelude::*;
+/// use std::sync::Arc;
+///
+/// // Decode every string as upper case
+/// let mut decoder = ValueDecoder::new();
+/// decoder.register(ValueType::String, |name, payload, _ctx| {
+///     let text = String::from_utf8_lossy(payload).to_uppercase();
+///     Ok(Arc::new(StringValue::new(name, text)) as Arc<dyn Value>)
+/// });
+///
+/// let value: Arc<dyn Value> = Arc::new(StringValue::new("greeting", "hello"));
+/// let (decoded, _) = decoder.decode(&encode_value(&value).unwrap()).unwrap();
+/// assert_eq!(decoded.to_string(), "HELLO");
+/// ```
+#[derive(Clone, Default)]
+pub struct ValueDecoder {
+    custom: HashMap<ValueType, Arc<DecodeFn>>,
+}
+
+impl ValueDecoder {
+    /// Create a decoder using only the built-in decoders
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a decoder for `value_type`, replacing any previous one
+    pub fn register<F>(&mut self, value_type: ValueType, decoder: F)
+    where
+        F: Fn(&str, &[u8], &DecodeContext<'_>) -> Result<Arc<dyn Value>> + Send + Sync + 'static,
+    {
+        self.custom.insert(value_type, Arc::new(decoder));
+    }
+
+    /// Remove the decoder registered for `value_type`, restoring the built-in one
+    ///
+    /// Returns true if a decoder was registered.
+    pub fn unregister(&mut self, value_type: ValueType) -> bool {
+        self.custom.remove(&value_type).is_some()
+    }
+
+    /// Check whether a custom decoder is registered for `value_type`
+    pub fn is_registered(&self, value_type: ValueType) -> bool {
+        self.custom.contains_key(&value_type)
+    }
+
+    /// Decode one value from the start of `data`
+    ///
+    /// Returns the value and the number of bytes consumed; `data` may continue past
+    /// the value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainerError::WireFormat`] with the byte offset (relative to `data`)
+    /// if the input is truncated, a type byte is unknown, a fixed-size payload has the
+    /// wrong size, a string is not valid UTF-8, or nesting exceeds
+    /// [`MAX_NESTING_DEPTH`]. Errors from registered decoders are returned unchanged.
+    pub fn decode(&self, data: &[u8]) -> Result<(Arc<dyn Value>, usize)> {
+        let mut reader = BinaryReader::new(data);
+        let value = self.decode_from(&mut reader, 0)?;
+        Ok((value, reader.pos))
+    }
+
+    /// Decode a value payload whose type and name are already known
+    ///
+    /// This is the entry point for formats that store the type and name outside the
+    /// value layout.
+    pub fn decode_payload(
+        &self,
+        value_type: ValueType,
+        name: &str,
+        payload: &[u8],
+    ) -> Result<Arc<dyn Value>> {
+        let ctx = DecodeContext {
+            decoder: self,
+            data: payload,
+            offset: 0,
+            depth: 0,
+        };
+        self.dispatch(value_type, name, payload, &ctx)
+    }
+
+    /// Decode the value at the reader's position (used by container-level formats)
+    pub(crate) fn decode_from(
+        &self,
+        reader: &mut BinaryReader<'_>,
+        depth: usize,
+    ) -> Result<Arc<dyn Value>> {
+        let start = reader.pos;
+        let type_byte = reader.read_u8()?;
+        let value_type = ValueType::from_u8(type_byte).ok_or_else(|| {
+            BinaryReader::error(start, format!("unknown type byte {}", type_byte))
+        })?;
+        let name_len = reader.read_len()?;
+        let name = reader.read_str(name_len)?;
+        let value_size = reader.read_len()?;
+        let payload_offset = reader.pos;
+        let payload = reader.read_bytes(value_size)?;
+
+        let ctx = DecodeContext {
+            decoder: self,
+            data: reader.data,
+            offset: payload_offset,
+            depth,
+        };
+        self.dispatch(value_type, name, payload, &ctx)
+    }
+
+    /// Read one value from `reader`
+    ///
+    /// Consumes exactly the bytes of one encoded value and leaves the reader positioned
+    /// after it. Nested containers and arrays are rebuilt child by child, so at most one
+    /// scalar payload is buffered at a time. Length prefixes are not trusted for
+    /// allocation: payloads are read in bounded chunks, so a corrupt length fails at the
+    /// end of the input instead of reserving memory up front.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`decode`](Self::decode), with offsets counted from where reading started.
+    /// I/O errors other than a premature end of input are returned as
+    /// [`ContainerError::IoError`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_container_system::core::value_codec::{encode_value, ValueDecoder};
+    /// use rust_container_system::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// let value: Arc<dyn Value> = Arc::new(StringValue::new("greeting", "hello"));
+    /// let bytes = encode_value(&value).unwrap();
+    ///
+    /// let mut reader = bytes.as_slice();
+    /// let decoded = ValueDecoder::new().read_value(&mut reader).unwrap();
+    /// assert_eq!(decoded.to_string(), "hello");
+    /// assert!(reader.is_empty());
+    /// ```
+    pub fn read_value<R: Read + ?Sized>(&self, reader: &mut R) -> Result<Arc<dyn Value>> {
+        self.read_from(&mut StreamReader::new(reader), 0)
+    }
+
+    /// Read the value at the stream reader's position (used by container-level formats)
+    pub(crate) fn read_from<R: Read + ?Sized>(
+        &self,
+        reader: &mut StreamReader<'_, R>,
+        depth: usize,
+    ) -> Result<Arc<dyn Value>> {
+        let start = reader.pos;
+        let type_byte = reader.read_u8()?;
+        let value_type = ValueType::from_u8(type_byte).ok_or_else(|| {
+            BinaryReader::error(start, format!("unknown type byte {}", type_byte))
+        })?;
+        let name_len = reader.read_len()?;
+        let name = reader.read_string(name_len)?;
+        let value_size = reader.read_len()?;
+        let payload_offset = reader.pos;
+
+        let nested = matches!(value_type, ValueType::Container | ValueType::Array);
+        if !nested || self.is_registered(value_type) {
+            let payload = reader.read_bytes(value_size)?;
+            let ctx = DecodeContext {
+                decoder: self,
+                data: &payload,
+                offset: 0,
+                depth,
+            };
+            return self
+                .dispatch(value_type, &name, &payload, &ctx)
+                .map_err(|e| offset_by(e, payload_offset));
+        }
+
+        // Rebuild nested values child by child instead of buffering the whole payload
+        if depth >= MAX_NESTING_DEPTH {
+            return Err(BinaryReader::error(
+                payload_offset,
+                format!("nesting depth exceeds maximum of {}", MAX_NESTING_DEPTH),
+            ));
+        }
+        let end = payload_offset + value_size;
+        let outer_end = reader.limit(end)?;
+
+        let count = reader.read_len()?;
+        if count > (end - reader.pos) / MIN_ENCODED_VALUE_SIZE {
+            return Err(BinaryReader::error(
+                payload_offset,
+                format!(
+                    "count {} cannot fit in the remaining {} bytes",
+                    count,
+                    end - reader.pos
+                ),
+            ));
+        }
+        let mut children = Vec::with_capacity(count);
+        for _ in 0..count {
+            children.push(self.read_from(reader, depth + 1)?);
+        }
+        if reader.pos != end {
+            return Err(BinaryReader::error(
+                reader.pos,
+                format!("{} bytes after the last child", end - reader.pos),
+            ));
+        }
+        reader.end = outer_end;
+
+        Ok(match value_type {
+            ValueType::Array => Arc::new(ArrayValue::new(name, children)),
+            _ => Arc::new(ContainerValue::new(name, children)),
+        })
+    }
+
+    fn dispatch(
+        &self,
+        value_type: ValueType,
+        name: &str,
+        payload: &[u8],
+        ctx: &DecodeContext<'_>,
+    ) -> Result<Arc<dyn Value>> {
+        match self.custom.get(&value_type) {
+            Some(decoder) => decoder(name, payload, ctx),
+            None => decode_builtin(value_type, name, payload, ctx),
+        }
+    }
+}
+
+impl std::fmt::Debug for ValueDecoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut registered: Vec<_> = self.custom.keys().collect();
+        registered.sort_by_key(|vt| **vt as u8);
+        f.debug_struct("ValueDecoder")
+            .field("registered", &registered)
+            .finish()
+    }
+}
+
+/// State handed to decoders while decoding a value
+pub struct DecodeContext<'a> {
+    decoder: &'a ValueDecoder,
+    /// Input the payload was taken from
+    data: &'a [u8],
+    /// Offset of the payload within `data`
+    offset: usize,
+    depth: usize,
+}
+
+impl DecodeContext<'_> {
+    /// The decoder in use, including its registrations
+    pub fn decoder(&self) -> &ValueDecoder {
+        self.decoder
+    }
+
+    /// Nesting depth of the value being decoded (0 for top-level values)
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Decode a `[count:4][child]...` payload through the same registry
+    ///
+    /// The children must fill the payload exactly. Errors report offsets relative to the
+    /// start of the original input.
+    pub fn decode_children(&self, payload: &[u8]) -> Result<Vec<Arc<dyn Value>>> {
+        // Payloads handed out by the decoder are sub-slices of `data`; anything else
+        // is decoded on its own
+        let (data, start) = match subslice_offset(self.data, payload) {
+            Some(start) => (self.data, start),
+            None => (payload, 0),
+        };
+
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(BinaryReader::error(
+                start,
+                format!("nesting depth exceeds maximum of {}", MAX_NESTING_DEPTH),
+            ));
+        }
+
+        let mut reader = BinaryReader {
+            data,
+            pos: start,
+            end: start + payload.len(),
+        };
+        let count_offset = reader.pos;
+        let count = reader.read_len()?;
+        reader.check_count(count, count_offset)?;
+
+        let mut children = Vec::with_capacity(count);
+        for _ in 0..count {
+            children.push(self.decoder.decode_from(&mut reader, self.depth + 1)?);
+        }
+        if reader.pos != reader.end {
+            return Err(BinaryReader::error(
+                reader.pos,
+                format!("{} bytes after the last child", reader.end - reader.pos),
+            ));
+        }
+        Ok(children)
+    }
+}
+
+/// Offset of `part` within `whole`, if `part` is a sub-slice of it
+fn subslice_offset(whole: &[u8], part: &[u8]) -> Option<usize> {
+    let whole_start = whole.as_ptr() as usize;
+    let part_start = part.as_ptr() as usize;
+    (part_start >= whole_start && part_start + part.len() <= whole_start + whole.len())
+        .then(|| part_start - whole_start)
+}
+
+/// Rebuild a value as its built-in type
+fn decode_builtin(
+    value_type: ValueType,
+    name: &str,
+    payload: &[u8],
+    ctx: &DecodeContext<'_>,
+) -> Result<Arc<dyn Value>> {
+    if let Some(expected) = fixed_payload_size(value_type) {
+        if payload.len() != expected {
+            return Err(BinaryReader::error(
+                ctx.offset,
+                format!(
+                    "{} '{}' has value_size {} (expected {})",
+                    value_type,
+                    name,
+                    payload.len(),
+                    expected
+                ),
+            ));
+        }
+    }
+
+    Ok(match value_type {
+        ValueType::Null => Arc::new(NullValue::new(name)),
+        ValueType::Bool => Arc::new(BoolValue::new(name, payload[0] != 0)),
+        ValueType::Short => Arc::new(ShortValue::new(name, i16::from_le_bytes(array(payload)))),
+        ValueType::UShort => Arc::new(UShortValue::new(name, u16::from_le_bytes(array(payload)))),
+        ValueType::Int => Arc::new(IntValue::new(name, i32::from_le_bytes(array(payload)))),
+        ValueType::UInt => Arc::new(UIntValue::new(name, u32::from_le_bytes(array(payload)))),
+        ValueType::Long => Arc::new(LongValue::new(
+            name,
+            i32::from_le_bytes(array(payload)) as i64,
+        )?),
+        ValueType::ULong => Arc::new(ULongValue::new(
+            name,
+            u32::from_le_bytes(array(payload)) as u64,
+        )?),
+        ValueType::LLong => Arc::new(LLongValue::new(name, i64::from_le_bytes(array(payload)))),
+        ValueType::ULLong => Arc::new(ULLongValue::new(name, u64::from_le_bytes(array(payload)))),
+        ValueType::Float => Arc::new(FloatValue::new(name, f32::from_le_bytes(array(payload)))),
+        ValueType::Double => Arc::new(DoubleValue::new(name, f64::from_le_bytes(array(payload)))),
+        ValueType::Decimal => {
+            let decimal = Decimal::from_payload(payload).map_err(|e| {
+                BinaryReader::error(ctx.offset, format!("invalid decimal '{}': {}", name, e))
+            })?;
+            Arc::new(DecimalValue::new(name, decimal))
+        }
+        ValueType::Timestamp => {
+            let timestamp = Timestamp::from_payload(payload).map_err(|e| {
+                BinaryReader::error(ctx.offset, format!("invalid timestamp '{}': {}", name, e))
+            })?;
+            Arc::new(TimestampValue::new(name, timestamp))
+        }
+        ValueType::Duration => {
+            let duration = duration_from_payload(payload).map_err(|e| {
+                BinaryReader::error(ctx.offset, format!("invalid duration '{}': {}", name, e))
+            })?;
+            Arc::new(DurationValue::new(name, duration))
+        }
+        ValueType::Uuid => Arc::new(UuidValue::new(name, Uuid::from_bytes(array(payload)))),
+        ValueType::String => {
+            let text = std::str::from_utf8(payload).map_err(|e| {
+                BinaryReader::error(ctx.offset, format!("invalid UTF-8 in '{}': {}", name, e))
+            })?;
+            Arc::new(StringValue::new(name, text))
+        }
+        ValueType::Bytes => Arc::new(BytesValue::from_slice(name, payload)),
+        ValueType::Container => Arc::new(ContainerValue::new(name, ctx.decode_children(payload)?)),
+        ValueType::Array => Arc::new(ArrayValue::new(name, ctx.decode_children(payload)?)),
+    })
+}
+
+/// Payload size for fixed-width types
+pub(crate) fn fixed_payload_size(value_type: ValueType) -> Option<usize> {
+    match value_type {
+        ValueType::Long | ValueType::ULong => Some(4),
+        ValueType::Container | ValueType::Array => None,
+        other => other.size_bytes(),
+    }
+}
+
+/// Write a 4-byte little-endian length
+pub(crate) fn write_len<W: Write + ?Sized>(out: &mut W, len: usize, what: &str) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| {
+        ContainerError::SerializationError(format!("{} of {} bytes exceeds 4 GiB", what, len))
+    })?;
+    out.write_all(&len.to_le_bytes())?;
+    Ok(())
+}
+
+fn mismatched_type(name: &str, value_type: ValueType) -> ContainerError {
+    ContainerError::SerializationError(format!(
+        "Value '{}' reports {} but has no matching concrete type",
+        name, value_type
+    ))
+}
+
+/// Copy a payload whose length has already been checked into a fixed-size array
+pub(crate) fn array<const N: usize>(payload: &[u8]) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(payload);
+    bytes
+}
+
+/// Bounds-checked cursor over binary input
+///
+/// Every error carries the byte offset from the start of `data`.
+pub(crate) struct BinaryReader<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) pos: usize,
+    pub(crate) end: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            end: data.len(),
+        }
+    }
+
+    pub(crate) fn error(offset: usize, message: impl Into<String>) -> ContainerError {
+        ContainerError::WireFormat {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.end - self.pos {
+            return Err(Self::error(
+                self.pos,
+                format!("need {} bytes but only {} remain", len, self.end - self.pos),
+            ));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub(crate) fn read_len(&mut self) -> Result<usize> {
+        Ok(u32::from_le_bytes(array(self.read_bytes(4)?)) as usize)
+    }
+
+    pub(crate) fn read_str(&mut self, len: usize) -> Result<&'a str> {
+        let offset = self.pos;
+        let bytes = self.read_bytes(len)?;
+        std::str::from_utf8(bytes).map_err(|e| Self::error(offset, format!("invalid UTF-8: {}", e)))
+    }
+
+    /// Reject counts that cannot possibly fit in the remaining input
+    pub(crate) fn check_count(&self, count: usize, offset: usize) -> Result<()> {
+        let remaining = self.end - self.pos;
+        if count > remaining / MIN_ENCODED_VALUE_SIZE {
+            return Err(Self::error(
+                offset,
+                format!(
+                    "count {} cannot fit in the remaining {} bytes",
+                    count, remaining
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Shift the offset of a format error reported relative to a buffered payload
+pub(crate) fn offset_by(error: ContainerError, base: usize) -> ContainerError {
+    match error {
+        ContainerError::WireFormat { offset, message } => ContainerError::WireFormat {
+            offset: base + offset,
+            message,
+        },
+        other => other,
+    }
+}
+
+/// Largest buffer reserved ahead of data actually received from a stream
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Offset-tracking cursor over a byte stream
+///
+/// Every error carries the byte offset from where reading started. While `end` is set,
+/// reads past it are rejected (used to keep children inside their parent's payload).
+pub(crate) struct StreamReader<'r, R: ?Sized> {
+    inner: &'r mut R,
+    pub(crate) pos: usize,
+    pub(crate) end: Option<usize>,
+}
+
+impl<'r, R: Read + ?Sized> StreamReader<'r, R> {
+    pub(crate) fn new(inner: &'r mut R) -> Self {
+        Self {
+            inner,
+            pos: 0,
+            end: None,
+        }
+    }
+
+    /// Restrict reads to end at `end`, returning the previous limit
+    pub(crate) fn limit(&mut self, end: usize) -> Result<Option<usize>> {
+        if let Some(outer) = self.end {
+            if end > outer {
+                return Err(BinaryReader::error(
+                    self.pos,
+                    format!(
+                        "need {} bytes but only {} remain",
+                        end - self.pos,
+                        outer - self.pos
+                    ),
+                ));
+            }
+        }
+        Ok(self.end.replace(end))
+    }
+
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>> {
+        if let Some(end) = self.end {
+            if len > end - self.pos {
+                return Err(BinaryReader::error(
+                    self.pos,
+                    format!("need {} bytes but only {} remain", len, end - self.pos),
+                ));
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(len.min(READ_CHUNK_SIZE));
+        let read = (&mut *self.inner)
+            .take(len as u64)
+            .read_to_end(&mut bytes)?;
+        if read < len {
+            return Err(BinaryReader::error(
+                self.pos + read,
+                format!("need {} bytes but the input ended after {}", len, read),
+            ));
+        }
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub(crate) fn read_len(&mut self) -> Result<usize> {
+        Ok(u32::from_le_bytes(array(&self.read_bytes(4)?)) as usize)
+    }
+
+    pub(crate) fn read_string(&mut self, len: usize) -> Result<String> {
+        let offset = self.pos;
+        String::from_utf8(self.read_bytes(len)?)
+            .map_err(|e| BinaryReader::error(offset, format!("invalid UTF-8: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::Trickle;
+
+    fn all_builtin_values() -> Vec<Arc<dyn Value>> {
+        vec![
+            Arc::new(NullValue::new("null")),
+            Arc::new(BoolValue::new("bool", true)),
+            Arc::new(ShortValue::new("short", i16::MIN)),
+            Arc::new(UShortValue::new("ushort", u16::MAX)),
+            Arc::new(IntValue::new("int", -42)),
+            Arc::new(UIntValue::new("uint", u32::MAX)),
+            Arc::new(LongValue::new("long", i32::MIN as i64).unwrap()),
+            Arc::new(ULongValue::new("ulong", u32::MAX as u64).unwrap()),
+            Arc::new(LLongValue::new("llong", i64::MIN)),
+            Arc::new(ULLongValue::new("ullong", u64::MAX)),
+            Arc::new(FloatValue::new("float", -0.25)),
+            Arc::new(DoubleValue::new("double", std::f64::consts::PI)),
+            Arc::new(StringValue::new("string", "héllo")),
+            Arc::new(BytesValue::new("bytes", vec![0, 0xFF])),
+            Arc::new(ContainerValue::new(
+                "container",
+                vec![Arc::new(IntValue::new("inner", 1)) as Arc<dyn Value>],
+            )),
+            Arc::new(ArrayValue::new(
+                "array",
+                vec![
+                    Arc::new(StringValue::new("", "a")) as Arc<dyn Value>,
+                    Arc::new(ArrayValue::new("", vec![])),
+                ],
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_roundtrip_all_16_types() {
+        let values = all_builtin_values();
+        assert_eq!(values.len(), 16);
+
+        for value in values {
+            let bytes = encode_value(&value).unwrap();
+            let (decoded, consumed) = decode_value(&bytes).unwrap();
+            assert_eq!(consumed, bytes.len(), "{}", value.name());
+            assert_eq!(decoded.name(), value.name());
+            assert_eq!(decoded.value_type(), value.value_type());
+            assert_eq!(decoded.to_string(), value.to_string(), "{}", value.name());
+            assert_eq!(encode_value(&decoded).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_encoding_matches_full_layout_to_bytes() {
+        // Types whose to_bytes() already emits the C++ layout must encode identically
+        let values: Vec<Arc<dyn Value>> = vec![
+            Arc::new(NullValue::new("n")),
+            Arc::new(BoolValue::new("b", true)),
+            Arc::new(IntValue::new("i", 7)),
+            Arc::new(LongValue::new("l", -7).unwrap()),
+            Arc::new(FloatValue::new("f", 1.5)),
+            Arc::new(DoubleValue::new("d", 2.5)),
+            Arc::new(StringValue::new("s", "text")),
+            Arc::new(BytesValue::new("by", vec![1, 2, 3])),
+        ];
+        for value in values {
+            assert_eq!(
+                encode_value(&value).unwrap(),
+                value.to_bytes(),
+                "{}",
+                value.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_reports_consumed_bytes() {
+        let mut data = encode_value(&(Arc::new(IntValue::new("a", 1)) as Arc<dyn Value>)).unwrap();
+        let first_len = data.len();
+        data.extend(encode_value(&(Arc::new(IntValue::new("b", 2)) as Arc<dyn Value>)).unwrap());
+
+        let (first, consumed) = decode_value(&data).unwrap();
+        assert_eq!(first.name(), "a");
+        assert_eq!(consumed, first_len);
+        let (second, _) = decode_value(&data[consumed..]).unwrap();
+        assert_eq!(second.to_int().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_registered_decoder_applies_to_nested_values() {
+        let mut decoder = ValueDecoder::new();
+        decoder.register(ValueType::Int, |name, payload, ctx| {
+            let n = i32::from_le_bytes(payload.try_into().unwrap());
+            // Tag the depth into the value so the test can see it
+            Ok(Arc::new(IntValue::new(name, n * 100 + ctx.depth() as i32)) as Arc<dyn Value>)
+        });
+        assert!(decoder.is_registered(ValueType::Int));
+
+        let nested: Arc<dyn Value> = Arc::new(ContainerValue::new(
+            "outer",
+            vec![Arc::new(ArrayValue::new(
+                "list",
+                vec![Arc::new(IntValue::new("", 3)) as Arc<dyn Value>],
+            )) as Arc<dyn Value>],
+        ));
+        let (decoded, _) = decoder.decode(&encode_value(&nested).unwrap()).unwrap();
+
+        let outer = decoded.as_any().downcast_ref::<ContainerValue>().unwrap();
+        let list = outer.children()[0]
+            .as_any()
+            .downcast_ref::<ArrayValue>()
+            .unwrap();
+        assert_eq!(list.elements()[0].to_int().unwrap(), 302);
+
+        assert!(decoder.unregister(ValueType::Int));
+        let (decoded, _) = decoder
+            .decode(&encode_value(&(Arc::new(IntValue::new("x", 3)) as Arc<dyn Value>)).unwrap())
+            .unwrap();
+        assert_eq!(decoded.to_int().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_custom_container_decoder_uses_context() {
+        // Flatten containers into arrays while still decoding their children
+        let mut decoder = ValueDecoder::new();
+        decoder.register(ValueType::Container, |name, payload, ctx| {
+            Ok(Arc::new(ArrayValue::new(name, ctx.decode_children(payload)?)) as Arc<dyn Value>)
+        });
+
+        let value: Arc<dyn Value> = Arc::new(ContainerValue::new(
+            "c",
+            vec![Arc::new(BoolValue::new("flag", true)) as Arc<dyn Value>],
+        ));
+        let (decoded, _) = decoder.decode(&encode_value(&value).unwrap()).unwrap();
+        assert_eq!(decoded.value_type(), ValueType::Array);
+        let array = decoded.as_any().downcast_ref::<ArrayValue>().unwrap();
+        assert!(array.elements()[0].to_bool().unwrap());
+    }
+
+    #[test]
+    fn test_decode_payload() {
+        let decoder = ValueDecoder::new();
+        let value = decoder
+            .decode_payload(ValueType::UShort, "port", &8080u16.to_le_bytes())
+            .unwrap();
+        assert_eq!(value.name(), "port");
+        assert_eq!(value.to_ushort().unwrap(), 8080);
+
+        let children = encode_value(
+            &(Arc::new(ArrayValue::new(
+                "",
+                vec![Arc::new(IntValue::new("", 5)) as Arc<dyn Value>],
+            )) as Arc<dyn Value>),
+        )
+        .unwrap();
+        // Skip type(1) + name_len(4) + name(0) + value_size(4) to get the array payload
+        let value = decoder
+            .decode_payload(ValueType::Array, "list", &children[9..])
+            .unwrap();
+        let array = value.as_any().downcast_ref::<ArrayValue>().unwrap();
+        assert_eq!(array.elements()[0].to_int().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_decode_errors_have_offsets() {
+        assert!(matches!(
+            decode_value(&[42, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(ContainerError::WireFormat { offset: 0, .. })
+        ));
+
+        // Wrong payload size for a fixed-width type points at the payload
+        let mut bytes =
+            encode_value(&(Arc::new(ShortValue::new("s", 1)) as Arc<dyn Value>)).unwrap();
+        bytes[6] = 1; // value_size = 1
+        bytes.pop();
+        match decode_value(&bytes) {
+            Err(ContainerError::WireFormat { offset, .. }) => assert_eq!(offset, 10),
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+
+        // Errors inside nested children are reported relative to the whole input
+        let nested: Arc<dyn Value> = Arc::new(ArrayValue::new(
+            "a",
+            vec![Arc::new(StringValue::new("s", "ok")) as Arc<dyn Value>],
+        ));
+        let mut bytes = encode_value(&nested).unwrap();
+        let string_type_offset = 1 + 4 + 1 + 4 + 4;
+        bytes[string_type_offset] = 99;
+        match decode_value(&bytes) {
+            Err(ContainerError::WireFormat { offset, .. }) => {
+                assert_eq!(offset, string_type_offset)
+            }
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_truncated_values_fail() {
+        for value in all_builtin_values() {
+            let bytes = encode_value(&value).unwrap();
+            for len in 0..bytes.len() {
+                assert!(
+                    decode_value(&bytes[..len]).is_err(),
+                    "{} truncated at {}",
+                    value.name(),
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_value_matches_encode_value() {
+        for value in all_builtin_values() {
+            let encoded = encode_value(&value).unwrap();
+            let mut written = Vec::new();
+            write_value(&value, &mut written).unwrap();
+            assert_eq!(written, encoded, "{}", value.name());
+            assert_eq!(
+                encoded_len(&value).unwrap(),
+                encoded.len(),
+                "{}",
+                value.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_value_all_16_types() {
+        for value in all_builtin_values() {
+            let mut bytes = encode_value(&value).unwrap();
+            let value_len = bytes.len();
+            bytes.extend_from_slice(b"next");
+
+            let mut reader = Trickle(&bytes);
+            let decoded = ValueDecoder::new().read_value(&mut reader).unwrap();
+            assert_eq!(decoded.name(), value.name());
+            assert_eq!(decoded.value_type(), value.value_type());
+            assert_eq!(encode_value(&decoded).unwrap(), bytes[..value_len]);
+            // The reader is left just past the value
+            assert_eq!(reader.0, b"next");
+        }
+    }
+
+    #[test]
+    fn test_read_value_uses_registered_decoders() {
+        let mut decoder = ValueDecoder::new();
+        decoder.register(ValueType::Int, |name, payload, ctx| {
+            let n = i32::from_le_bytes(payload.try_into().unwrap());
+            Ok(Arc::new(IntValue::new(name, n * 100 + ctx.depth() as i32)) as Arc<dyn Value>)
+        });
+
+        let nested: Arc<dyn Value> = Arc::new(ContainerValue::new(
+            "outer",
+            vec![Arc::new(ArrayValue::new(
+                "list",
+                vec![Arc::new(IntValue::new("", 3)) as Arc<dyn Value>],
+            )) as Arc<dyn Value>],
+        ));
+        let bytes = encode_value(&nested).unwrap();
+        let decoded = decoder.read_value(&mut bytes.as_slice()).unwrap();
+
+        let outer = decoded.as_any().downcast_ref::<ContainerValue>().unwrap();
+        let list = outer.children()[0]
+            .as_any()
+            .downcast_ref::<ArrayValue>()
+            .unwrap();
+        assert_eq!(list.elements()[0].to_int().unwrap(), 302);
+    }
+
+    #[test]
+    fn test_read_value_errors_match_decode() {
+        // Corrupt type byte of a nested child and wrong size of a nested fixed-width value
+        let nested: Arc<dyn Value> = Arc::new(ArrayValue::new(
+            "a",
+            vec![
+                Arc::new(StringValue::new("s", "ok")) as Arc<dyn Value>,
+                Arc::new(ShortValue::new("n", 1)),
+            ],
+        ));
+        let bytes = encode_value(&nested).unwrap();
+
+        let mut bad_type = bytes.clone();
+        bad_type[1 + 4 + 1 + 4 + 4] = 99;
+        let mut bad_size = bytes.clone();
+        let size_offset = bytes.len() - 2 - 4;
+        bad_size[size_offset] = 1;
+        bad_size.remove(bad_size.len() - 1);
+        // Keep the enclosing sizes consistent with the shorter child
+        let outer_size = u32::from_le_bytes(array(&bad_size[6..10])) - 1;
+        bad_size[6..10].copy_from_slice(&outer_size.to_le_bytes());
+
+        for bytes in [bad_type, bad_size] {
+            let expected = match decode_value(&bytes) {
+                Err(ContainerError::WireFormat { offset, .. }) => offset,
+                other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+            };
+            match ValueDecoder::new().read_value(&mut bytes.as_slice()) {
+                Err(ContainerError::WireFormat { offset, .. }) => assert_eq!(offset, expected),
+                other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_value_truncated_and_oversized() {
+        for value in all_builtin_values() {
+            let bytes = encode_value(&value).unwrap();
+            for len in 0..bytes.len() {
+                assert!(
+                    ValueDecoder::new()
+                        .read_value(&mut Trickle(&bytes[..len]))
+                        .is_err(),
+                    "{} truncated at {}",
+                    value.name(),
+                    len
+                );
+            }
+        }
+
+        // A huge declared size fails at the end of the input instead of allocating it
+        let mut bytes =
+            encode_value(&(Arc::new(BytesValue::new("b", vec![1, 2])) as Arc<dyn Value>)).unwrap();
+        bytes[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        match ValueDecoder::new().read_value(&mut bytes.as_slice()) {
+            Err(ContainerError::WireFormat { offset, .. }) => assert_eq!(offset, bytes.len()),
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,437 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Owned Value Data
+//!
+//! [`ValueData`] is a closed enum with one variant per [`ValueType`], and [`NamedValue`]
+//! pairs it with a name. Unlike `Arc<dyn Value>` it needs no heap allocation for
+//! numbers, can be matched exhaustively, and holds nested containers and arrays
+//! directly.
+//!
+//! Conversions to and from `Arc<dyn Value>` are lossless: [`NamedValue::from_value`]
+//! keeps the exact `ValueType` (e.g. `long_value` stays `Long`, not `LLong`), and
+//! [`NamedValue::into_value`] rebuilds the built-in value type.
+//!
+//! [`ValueContainer::new_inline`] creates a container that stores `NamedValue`s in place
+//! instead of one `Arc` per value; see [`ValueContainer::add_data`] and
+//! [`ValueContainer::with_data`].
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::core::value_data::{NamedValue, ValueData};
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let value: Arc<dyn Value> = Arc::new(LongValue::new("offset", -5).unwrap());
+//! let named = NamedValue::from_value(value.as_ref()).unwrap();
+//! assert_eq!(named.data, ValueData::Long(-5));
+//!
+//! let total = match &named.data {
+//!     ValueData::Long(n) | ValueData::Int(n) => i64::from(*n),
+//!     ValueData::LLong(n) => *n,
+//!     _ => 0,
+//! };
+//! assert_eq!(total, -5);
+//!
+//! let back = named.into_value();
+//! assert_eq!(back.value_type(), ValueType::Long);
+//! ```
+//!
+//! [`ValueContainer::new_inline`]: crate::core::ValueContainer::new_inline
+//! [`ValueContainer::add_data`]: crate::core::ValueContainer::add_data
+//! [`ValueContainer::with_data`]: crate::core::ValueContainer::with_data
+//! [`ValueData`]: crate::core::value_data::ValueData
+//! [`NamedValue`]: crate::core::value_data::NamedValue
+//! [`NamedValue::from_value`]: crate::core::value_data::NamedValue::from_value
+//! [`NamedValue::into_value`]: crate::core::value_data::NamedValue::into_value
+
+use crate::core::{ContainerError, Result, Value, ValueType};
+use crate::values::time_values::{duration_of, timestamp_of};
+use crate::values::uuid_value::uuid_of;
+use crate::values::{
+    ArrayValue, BoolValue, BytesValue, ContainerValue, Decimal, DecimalValue, DoubleValue,
+    DurationValue, FloatValue, IntValue, LLongValue, LongValue, NullValue, ShortValue, StringValue,
+    Timestamp, TimestampValue, UIntValue, ULLongValue, ULongValue, UShortValue, Uuid, UuidValue,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The payload of a value, one variant per [`ValueType`]
+///
+/// `Long` and `ULong` are 32 bits wide, matching `LongValue` / `ULongValue` and the
+/// C++ cross-platform `long` encoding.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ValueData {
+    /// `null_value`
+    #[default]
+    Null,
+    /// `bool_value`
+    Bool(bool),
+    /// `short_value`
+    Short(i16),
+    /// `ushort_value`
+    UShort(u16),
+    /// `int_value`
+    Int(i32),
+    /// `uint_value`
+    UInt(u32),
+    /// `long_value` (32-bit)
+    Long(i32),
+    /// `ulong_value` (32-bit)
+    ULong(u32),
+    /// `llong_value`
+    LLong(i64),
+    /// `ullong_value`
+    ULLong(u64),
+    /// `float_value`
+    Float(f32),
+    /// `double_value`
+    Double(f64),
+    /// `string_value`
+    String(String),
+    /// `bytes_value`
+    Bytes(Vec<u8>),
+    /// `container_value` with its children
+    Container(Vec<NamedValue>),
+    /// `array_value` with its elements
+    Array(Vec<NamedValue>),
+    /// `decimal_value`
+    Decimal(Decimal),
+    /// `timestamp_value`
+    Timestamp(Timestamp),
+    /// `duration_value`
+    Duration(Duration),
+    /// `uuid_value`
+    Uuid(Uuid),
+}
+
+impl ValueData {
+    /// The value type this payload is encoded as
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            ValueData::Null => ValueType::Null,
+            ValueData::Bool(_) => ValueType::Bool,
+            ValueData::Short(_) => ValueType::Short,
+            ValueData::UShort(_) => ValueType::UShort,
+            ValueData::Int(_) => ValueType::Int,
+            ValueData::UInt(_) => ValueType::UInt,
+            ValueData::Long(_) => ValueType::Long,
+            ValueData::ULong(_) => ValueType::ULong,
+            ValueData::LLong(_) => ValueType::LLong,
+            ValueData::ULLong(_) => ValueType::ULLong,
+            ValueData::Float(_) => ValueType::Float,
+            ValueData::Double(_) => ValueType::Double,
+            ValueData::String(_) => ValueType::String,
+            ValueData::Bytes(_) => ValueType::Bytes,
+            ValueData::Decimal(_) => ValueType::Decimal,
+            ValueData::Timestamp(_) => ValueType::Timestamp,
+            ValueData::Duration(_) => ValueType::Duration,
+            ValueData::Uuid(_) => ValueType::Uuid,
+            ValueData::Container(_) => ValueType::Container,
+            ValueData::Array(_) => ValueType::Array,
+        }
+    }
+
+    /// Read the payload of any value
+    ///
+    /// Bytes, containers and arrays must be the built-in types; scalars are read
+    /// through the `Value` conversions, so custom implementations work too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTypeConversion` if the value does not convert to its own type,
+    /// including a `long_value` / `ulong_value` outside the 32-bit range.
+    pub fn from_value(value: &dyn Value) -> Result<Self> {
+        let value_type = value.value_type();
+        let mismatched = || ContainerError::InvalidTypeConversion {
+            from: format!("{} '{}'", value_type, value.name()),
+            to: format!("built-in {}", value_type),
+        };
+
+        Ok(match value_type {
+            ValueType::Null => ValueData::Null,
+            ValueType::Bool => ValueData::Bool(value.to_bool()?),
+            ValueType::Short => ValueData::Short(value.to_short()?),
+            ValueType::UShort => ValueData::UShort(value.to_ushort()?),
+            ValueType::Int => ValueData::Int(value.to_int()?),
+            ValueType::UInt => ValueData::UInt(value.to_uint()?),
+            ValueType::Long => match value.as_any().downcast_ref::<LongValue>() {
+                Some(long) => ValueData::Long(long.value()),
+                None => ValueData::Long(LongValue::new("", value.to_long()?)?.value()),
+            },
+            ValueType::ULong => match value.as_any().downcast_ref::<ULongValue>() {
+                Some(ulong) => ValueData::ULong(ulong.value()),
+                None => ValueData::ULong(ULongValue::new("", value.to_ulong()?)?.value()),
+            },
+            ValueType::LLong => ValueData::LLong(value.to_long()?),
+            ValueType::ULLong => ValueData::ULLong(value.to_ulong()?),
+            ValueType::Float => ValueData::Float(value.to_float()?),
+            ValueType::Double => ValueData::Double(value.to_double()?),
+            ValueType::String => match value.as_any().downcast_ref::<StringValue>() {
+                Some(string) => ValueData::String(string.value().to_string()),
+                None => ValueData::String(value.to_string()),
+            },
+            ValueType::Decimal => {
+                ValueData::Decimal(match value.as_any().downcast_ref::<DecimalValue>() {
+                    Some(decimal) => decimal.value(),
+                    None => value.to_string().parse().map_err(|_| mismatched())?,
+                })
+            }
+            ValueType::Timestamp => {
+                ValueData::Timestamp(timestamp_of(value).map_err(|_| mismatched())?)
+            }
+            ValueType::Duration => {
+                ValueData::Duration(duration_of(value).map_err(|_| mismatched())?)
+            }
+            ValueType::Uuid => ValueData::Uuid(uuid_of(value).map_err(|_| mismatched())?),
+            ValueType::Bytes => ValueData::Bytes(
+                value
+                    .as_any()
+                    .downcast_ref::<BytesValue>()
+                    .ok_or_else(mismatched)?
+                    .data()
+                    .to_vec(),
+            ),
+            ValueType::Container => ValueData::Container(NamedValue::from_values(
+                value
+                    .as_any()
+                    .downcast_ref::<ContainerValue>()
+                    .ok_or_else(mismatched)?
+                    .children(),
+            )?),
+            ValueType::Array => ValueData::Array(NamedValue::from_values(
+                value
+                    .as_any()
+                    .downcast_ref::<ArrayValue>()
+                    .ok_or_else(mismatched)?
+                    .elements(),
+            )?),
+        })
+    }
+
+    /// Build the built-in value type named `name`
+    pub fn into_value(self, name: impl Into<String>) -> Arc<dyn Value> {
+        let name = name.into();
+        match self {
+            ValueData::Null => Arc::new(NullValue::new(name)),
+            ValueData::Bool(v) => Arc::new(BoolValue::new(name, v)),
+            ValueData::Short(v) => Arc::new(ShortValue::new(name, v)),
+            ValueData::UShort(v) => Arc::new(UShortValue::new(name, v)),
+            ValueData::Int(v) => Arc::new(IntValue::new(name, v)),
+            ValueData::UInt(v) => Arc::new(UIntValue::new(name, v)),
+            ValueData::Long(v) => Arc::new(LongValue::from_i32(name, v)),
+            ValueData::ULong(v) => Arc::new(ULongValue::from_u32(name, v)),
+            ValueData::LLong(v) => Arc::new(LLongValue::new(name, v)),
+            ValueData::ULLong(v) => Arc::new(ULLongValue::new(name, v)),
+            ValueData::Float(v) => Arc::new(FloatValue::new(name, v)),
+            ValueData::Double(v) => Arc::new(DoubleValue::new(name, v)),
+            ValueData::String(v) => Arc::new(StringValue::new(name, v)),
+            ValueData::Bytes(v) => Arc::new(BytesValue::new(name, v)),
+            ValueData::Decimal(v) => Arc::new(DecimalValue::new(name, v)),
+            ValueData::Timestamp(v) => Arc::new(TimestampValue::new(name, v)),
+            ValueData::Duration(v) => Arc::new(DurationValue::new(name, v)),
+            ValueData::Uuid(v) => Arc::new(UuidValue::new(name, v)),
+            ValueData::Container(children) => {
+                Arc::new(ContainerValue::new(name, NamedValue::into_values(children)))
+            }
+            ValueData::Array(elements) => {
+                Arc::new(ArrayValue::new(name, NamedValue::into_values(elements)))
+            }
+        }
+    }
+}
+
+/// A name plus its [`ValueData`]: the owned counterpart of `Arc<dyn Value>`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NamedValue {
+    /// Value name (empty for array elements)
+    pub name: String,
+    /// Value payload
+    pub data: ValueData,
+}
+
+impl NamedValue {
+    /// Create a named value
+    pub fn new(name: impl Into<String>, data: ValueData) -> Self {
+        Self {
+            name: name.into(),
+            data,
+        }
+    }
+
+    /// The value type of the payload
+    pub fn value_type(&self) -> ValueType {
+        self.data.value_type()
+    }
+
+    /// Copy a value (and all of its descendants) out of an `Arc<dyn Value>`
+    ///
+    /// See [`ValueData::from_value`] for the errors.
+    pub fn from_value(value: &dyn Value) -> Result<Self> {
+        Ok(Self::new(value.name(), ValueData::from_value(value)?))
+    }
+
+    /// Copy a list of values, e.g. the children of a `ContainerValue`
+    pub fn from_values(values: &[Arc<dyn Value>]) -> Result<Vec<Self>> {
+        values
+            .iter()
+            .map(|value| Self::from_value(value.as_ref()))
+            .collect()
+    }
+
+    /// Build the built-in value, moving strings and bytes instead of copying them
+    pub fn into_value(self) -> Arc<dyn Value> {
+        self.data.into_value(self.name)
+    }
+
+    /// Build the built-in value from a borrowed `NamedValue`
+    pub fn to_value(&self) -> Arc<dyn Value> {
+        self.clone().into_value()
+    }
+
+    /// Build the built-in values for a list
+    pub fn into_values(values: Vec<Self>) -> Vec<Arc<dyn Value>> {
+        values.into_iter().map(Self::into_value).collect()
+    }
+}
+
+impl From<NamedValue> for Arc<dyn Value> {
+    fn from(value: NamedValue) -> Self {
+        value.into_value()
+    }
+}
+
+impl TryFrom<&dyn Value> for NamedValue {
+    type Error = ContainerError;
+
+    fn try_from(value: &dyn Value) -> Result<Self> {
+        Self::from_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_types() -> Vec<Arc<dyn Value>> {
+        vec![
+            Arc::new(NullValue::new("null")),
+            Arc::new(BoolValue::new("bool", true)),
+            Arc::new(ShortValue::new("short", i16::MIN)),
+            Arc::new(UShortValue::new("ushort", u16::MAX)),
+            Arc::new(IntValue::new("int", -7)),
+            Arc::new(UIntValue::new("uint", 7)),
+            Arc::new(LongValue::new("long", i64::from(i32::MIN)).unwrap()),
+            Arc::new(ULongValue::new("ulong", u64::from(u32::MAX)).unwrap()),
+            Arc::new(LLongValue::new("llong", i64::MAX)),
+            Arc::new(ULLongValue::new("ullong", u64::MAX)),
+            Arc::new(FloatValue::new("float", 0.5)),
+            Arc::new(DoubleValue::new("double", 1e300)),
+            Arc::new(StringValue::new("string", "text")),
+            Arc::new(BytesValue::new("bytes", vec![1, 2, 3])),
+            Arc::new(ContainerValue::new(
+                "container",
+                vec![Arc::new(ArrayValue::new(
+                    "array",
+                    vec![Arc::new(StringValue::new("", "x"))],
+                ))],
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        for value in all_types() {
+            let named = NamedValue::from_value(value.as_ref()).unwrap();
+            assert_eq!(named.name, value.name());
+            assert_eq!(named.value_type(), value.value_type());
+
+            let back = named.clone().into_value();
+            assert_eq!(back.value_type(), value.value_type());
+            assert_eq!(back.to_bytes(), value.to_bytes());
+            assert_eq!(NamedValue::from_value(back.as_ref()).unwrap(), named);
+        }
+    }
+
+    #[test]
+    fn test_nested_data() {
+        let container = all_types().pop().unwrap();
+        let named = NamedValue::try_from(container.as_ref()).unwrap();
+        let ValueData::Container(children) = &named.data else {
+            panic!("expected a container");
+        };
+        assert_eq!(
+            children[0].data,
+            ValueData::Array(vec![NamedValue::new("", ValueData::String("x".into()))])
+        );
+    }
+
+    /// A value that claims to be a container but is not a `ContainerValue`
+    #[derive(Debug)]
+    struct FakeContainer;
+
+    impl Value for FakeContainer {
+        fn name(&self) -> &str {
+            "fake"
+        }
+        fn value_type(&self) -> ValueType {
+            ValueType::Container
+        }
+        fn size(&self) -> usize {
+            0
+        }
+        fn to_string(&self) -> String {
+            String::new()
+        }
+        fn to_bytes(&self) -> Vec<u8> {
+            Vec::new()
+        }
+        fn to_json(&self) -> Result<String> {
+            Ok("null".to_string())
+        }
+        fn to_xml(&self) -> Result<String> {
+            Ok(String::new())
+        }
+        fn clone_value(&self) -> Arc<dyn Value> {
+            Arc::new(FakeContainer)
+        }
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_builtin_compound_values() {
+        assert!(matches!(
+            NamedValue::from_value(&FakeContainer),
+            Err(ContainerError::InvalidTypeConversion { .. })
+        ));
+    }
+}
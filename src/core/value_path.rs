@@ -0,0 +1,911 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Path Expressions
+//!
+//! A [`ValuePath`] addresses one value inside a [`ValueContainer`], walking
+//! `ContainerValue` children and `ArrayValue` elements:
+//!
+//! ```text
+//! order.items[3].price
+//! ```
+//!
+//! Segments are separated by `.`; each is a value name, optionally followed by a
+//! `{n}` selector, and then any number of `[index]` selectors. A `\` makes the next
+//! character part of the name, so `a\.b` names a single value called `a.b`.
+//!
+//! - `{n}` picks the nth value with that name (as returned by
+//!   `ValueContainer::get_value_array` / `ContainerValue::get_children`); without
+//!   it a segment means the first value with that name.
+//! - `[index]` always selects an element of an `ArrayValue`.
+//!
+//! So `tags{1}` is the second value called `tags`, `items[3]` is the fourth element
+//! of the array `items`, and `tags{1}[0]` is the first element of the second `tags`
+//! array. Adding another value called `items` does not change what `items[3]` means.
+//!
+//! Errors are [`ContainerError::PathError`] and name the segment that failed.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let item: Arc<dyn Value> = Arc::new(ContainerValue::new(
+//!     "",
+//!     vec![Arc::new(DoubleValue::new("price", 9.5))],
+//! ));
+//! let items: Arc<dyn Value> = Arc::new(ArrayValue::new("items", vec![item]));
+//! let mut container = ValueContainer::new();
+//! container
+//!     .add_value(Arc::new(ContainerValue::new("order", vec![items])))
+//!     .unwrap();
+//!
+//! let price = container.get_path("order.items[0].price").unwrap();
+//! assert_eq!(price.to_double().unwrap(), 9.5);
+//!
+//! container
+//!     .set_path("order.items[0].price", Arc::new(DoubleValue::new("price", 12.0)))
+//!     .unwrap();
+//! assert_eq!(container.get_path("order.items[0].price").unwrap().to_double().unwrap(), 12.0);
+//!
+//! let err = container.get_path("order.items[4].price").unwrap_err();
+//! assert!(err.to_string().contains("at 'items[4]'"));
+//! ```
+//!
+//! [`ValueContainer`]: crate::core::ValueContainer
+//! [`ValuePath`]: crate::core::value_path::ValuePath
+
+use super::container::ValueContainer;
+use super::value_data::NamedValue;
+use super::wire_protocol::MAX_NESTING_DEPTH;
+use super::{ContainerError, Result, Value};
+use crate::values::{ArrayValue, ContainerValue};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// One `name{n}[index]...` part of a [`ValuePath`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSegment {
+    name: String,
+    nth: Option<usize>,
+    indices: Vec<usize>,
+    /// The segment as written in the path
+    text: String,
+}
+
+impl PathSegment {
+    /// Value name (with escapes resolved)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `{n}` selector: which of the values with this name, if given
+    pub fn nth(&self) -> Option<usize> {
+        self.nth
+    }
+
+    /// `[index]` selectors for array elements
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// The segment as written in the path
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A parsed path expression such as `order.items[3].price`
+///
+/// Parse once with [`ValuePath::parse`] to reuse a path; the `ValueContainer`
+/// methods `get_path`, `set_path` and `remove_path` parse on every call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValuePath {
+    path: String,
+    segments: Vec<PathSegment>,
+}
+
+impl ValuePath {
+    /// Parse a path expression
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` for an empty path or segment name, an unclosed or
+    /// non-numeric selector, or more than `MAX_NESTING_DEPTH` segments.
+    pub fn parse(path: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut rest = path;
+
+        loop {
+            let (segment, remaining) = parse_segment(path, rest)?;
+            segments.push(segment);
+            if segments.len() > MAX_NESTING_DEPTH {
+                return Err(path_error(
+                    path,
+                    rest,
+                    format!("path is deeper than {} segments", MAX_NESTING_DEPTH),
+                ));
+            }
+            match remaining.strip_prefix('.') {
+                Some(next) => rest = next,
+                None => break,
+            }
+        }
+
+        Ok(Self {
+            path: path.to_string(),
+            segments,
+        })
+    }
+
+    /// The path as written
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// The segments of the path, outermost first
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Get the value this path points to
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` naming the first segment that does not match.
+    pub fn get(&self, container: &ValueContainer) -> Result<Arc<dyn Value>> {
+        container.with_path_level(|level| self.get_at(level))
+    }
+
+    /// Store `value` at this path, returning the value it replaced
+    ///
+    /// Missing containers along the path are created as `ContainerValue`s. A
+    /// segment may also append one past the last value with that name (`{n}`
+    /// equal to their count), and the last index one past the last element of
+    /// an array. A value stored under a name is
+    /// renamed to match the path if needed; array elements are stored as given.
+    ///
+    /// Nested containers and arrays are copied along the path rather than
+    /// modified in place, so other holders of those values are not affected. On
+    /// error the container is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` naming the segment that failed, or the container's
+    /// value limit error.
+    pub fn set(
+        &self,
+        container: &mut ValueContainer,
+        value: Arc<dyn Value>,
+    ) -> Result<Option<Arc<dyn Value>>> {
+        container.with_path_level_mut(|level| self.set_at(level, value))
+    }
+
+    /// Remove the value this path points to and return it
+    ///
+    /// Nested containers and arrays are copied along the path as in
+    /// [`set`](Self::set).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError` naming the first segment that does not match.
+    pub fn remove(&self, container: &mut ValueContainer) -> Result<Arc<dyn Value>> {
+        container.with_path_level_mut(|level| self.remove_at(level))
+    }
+
+    /// [`get`](Self::get) starting from `level`
+    pub(crate) fn get_at(&self, level: &dyn PathLevel) -> Result<Arc<dyn Value>> {
+        self.get_in(level, 0)
+    }
+
+    /// [`set`](Self::set) starting from `level`
+    pub(crate) fn set_at(
+        &self,
+        level: &mut dyn PathLevel,
+        value: Arc<dyn Value>,
+    ) -> Result<Option<Arc<dyn Value>>> {
+        self.edit_in(level, 0, &mut Edit::Set(Some(value)))
+    }
+
+    /// [`remove`](Self::remove) starting from `level`
+    pub(crate) fn remove_at(&self, level: &mut dyn PathLevel) -> Result<Arc<dyn Value>> {
+        self.edit_in(level, 0, &mut Edit::Remove)
+            .map(|removed| removed.expect("remove always returns the removed value"))
+    }
+
+    fn error(&self, segment: usize, message: impl Into<String>) -> ContainerError {
+        ContainerError::PathError {
+            path: self.path.clone(),
+            segment: self.segments[segment].text.clone(),
+            message: message.into(),
+        }
+    }
+
+    /// Pick which of the values named by segment `i` at `level` it refers to
+    fn select(&self, level: &dyn PathLevel, i: usize) -> Result<usize> {
+        let segment = &self.segments[i];
+        let count = level.count(&segment.name);
+        if count == 0 {
+            return Err(self.error(i, format!("no value named '{}'", segment.name)));
+        }
+        match segment.nth.unwrap_or(0) {
+            nth if nth >= count => Err(self.error(
+                i,
+                format!(
+                    "'{{{}}}' is out of range ({} values named '{}')",
+                    nth, count, segment.name
+                ),
+            )),
+            nth => Ok(nth),
+        }
+    }
+
+    fn get_in(&self, level: &dyn PathLevel, i: usize) -> Result<Arc<dyn Value>> {
+        let nth = self.select(level, i)?;
+        let indices = &self.segments[i].indices;
+        let mut value = level
+            .nth(&self.segments[i].name, nth)
+            .expect("select checked the index");
+        for &index in indices {
+            value = self.element(&value, i, index)?;
+        }
+
+        if i + 1 == self.segments.len() {
+            return Ok(value);
+        }
+        let child = self.as_container(&value, i + 1)?;
+        self.get_in(child, i + 1)
+    }
+
+    fn edit_in(
+        &self,
+        level: &mut dyn PathLevel,
+        i: usize,
+        edit: &mut Edit,
+    ) -> Result<Option<Arc<dyn Value>>> {
+        let segment = &self.segments[i];
+        let last = i + 1 == self.segments.len();
+
+        if let Edit::Set(value) = edit {
+            // Appending a value with this name, or creating a missing container
+            let append = segment.indices.is_empty()
+                && segment.nth.unwrap_or(0) == level.count(&segment.name);
+            if append {
+                let new = if last {
+                    self.named(value.take().expect("set value is used once"), i)?
+                } else {
+                    let mut child = ContainerValue::new(segment.name.clone(), Vec::new());
+                    self.edit_in(&mut child, i + 1, edit)?;
+                    Arc::new(child)
+                };
+                level.push(new)?;
+                return Ok(None);
+            }
+        }
+
+        let nth = self.select(level, i)?;
+        let indices = segment.indices.as_slice();
+        let current = level
+            .nth(&segment.name, nth)
+            .expect("select checked the index");
+
+        if indices.is_empty() {
+            if last {
+                return match edit {
+                    Edit::Set(value) => {
+                        let new = self.named(value.take().expect("set value is used once"), i)?;
+                        level.replace_nth(&segment.name, nth, new)?;
+                        Ok(Some(current))
+                    }
+                    Edit::Remove => Ok(level.remove_nth(&segment.name, nth)),
+                };
+            }
+            let (new, result) = self.edit_child(&current, i, edit)?;
+            level.replace_nth(&segment.name, nth, new)?;
+            return Ok(result);
+        }
+
+        let (new, result) = self.edit_element(&current, i, indices, edit)?;
+        level.replace_nth(&segment.name, nth, new)?;
+        Ok(result)
+    }
+
+    /// Copy the array `value` with the element at `indices` edited
+    fn edit_element(
+        &self,
+        value: &Arc<dyn Value>,
+        i: usize,
+        indices: &[usize],
+        edit: &mut Edit,
+    ) -> Result<Edited> {
+        let (&index, rest) = indices.split_first().expect("indices are not empty");
+        let mut array = self.as_array(value, i)?.clone();
+        let last = i + 1 == self.segments.len() && rest.is_empty();
+
+        if last {
+            let result = match edit {
+                Edit::Set(value) => {
+                    let value = value.take().expect("set value is used once");
+                    if index == array.count() {
+                        array.push(value);
+                        None
+                    } else {
+                        Some(
+                            array
+                                .set(index, value)
+                                .ok_or_else(|| self.out_of_range(i, index, &array))?,
+                        )
+                    }
+                }
+                Edit::Remove => Some(
+                    array
+                        .remove(index)
+                        .ok_or_else(|| self.out_of_range(i, index, &array))?,
+                ),
+            };
+            return Ok((Arc::new(array), result));
+        }
+
+        let element = self.element(value, i, index)?;
+        let (new, result) = if rest.is_empty() {
+            self.edit_child(&element, i, edit)?
+        } else {
+            self.edit_element(&element, i, rest, edit)?
+        };
+        array.set(index, new);
+        Ok((Arc::new(array), result))
+    }
+
+    /// Copy the container `value` with segment `i + 1` edited inside it
+    fn edit_child(&self, value: &Arc<dyn Value>, i: usize, edit: &mut Edit) -> Result<Edited> {
+        let mut child = self.as_container(value, i + 1)?.clone();
+        let result = self.edit_in(&mut child, i + 1, edit)?;
+        Ok((Arc::new(child), result))
+    }
+
+    fn element(&self, value: &Arc<dyn Value>, i: usize, index: usize) -> Result<Arc<dyn Value>> {
+        let array = self.as_array(value, i)?;
+        array
+            .at(index)
+            .ok_or_else(|| self.out_of_range(i, index, array))
+    }
+
+    fn as_array<'v>(&self, value: &'v Arc<dyn Value>, i: usize) -> Result<&'v ArrayValue> {
+        value.as_any().downcast_ref::<ArrayValue>().ok_or_else(|| {
+            self.error(
+                i,
+                format!(
+                    "'{}' is a {}, not an array",
+                    value.name(),
+                    value.value_type()
+                ),
+            )
+        })
+    }
+
+    /// `value` as the container that segment `i` is looked up in
+    fn as_container<'v>(&self, value: &'v Arc<dyn Value>, i: usize) -> Result<&'v ContainerValue> {
+        value
+            .as_any()
+            .downcast_ref::<ContainerValue>()
+            .ok_or_else(|| {
+                self.error(
+                    i,
+                    format!("parent is a {}, not a container", value.value_type()),
+                )
+            })
+    }
+
+    fn out_of_range(&self, i: usize, index: usize, array: &ArrayValue) -> ContainerError {
+        self.error(
+            i,
+            format!(
+                "index {} is out of range (array has {} elements)",
+                index,
+                array.count()
+            ),
+        )
+    }
+
+    /// `value` with the name of segment `i`
+    fn named(&self, value: Arc<dyn Value>, i: usize) -> Result<Arc<dyn Value>> {
+        let name = &self.segments[i].name;
+        if value.name() == name {
+            return Ok(value);
+        }
+        let mut named = NamedValue::from_value(value.as_ref()).map_err(|_| {
+            self.error(i, format!("cannot rename '{}' to '{}'", value.name(), name))
+        })?;
+        named.name = name.clone();
+        Ok(named.into_value())
+    }
+}
+
+impl FromStr for ValuePath {
+    type Err = ContainerError;
+
+    fn from_str(path: &str) -> Result<Self> {
+        Self::parse(path)
+    }
+}
+
+impl fmt::Display for ValuePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// A copied container or array after an edit, and what the edit returned
+type Edited = (Arc<dyn Value>, Option<Arc<dyn Value>>);
+
+/// What `edit_in` does at the end of the path
+enum Edit {
+    /// Store the value (taken once it is placed)
+    Set(Option<Arc<dyn Value>>),
+    Remove,
+}
+
+/// A level of named values: the container itself or a `ContainerValue`
+pub(crate) trait PathLevel {
+    /// Number of values with this name
+    fn count(&self, name: &str) -> usize;
+
+    /// The nth value with this name
+    fn nth(&self, name: &str, nth: usize) -> Option<Arc<dyn Value>>;
+
+    /// Replace the nth value with this name (`value` has the same name)
+    fn replace_nth(&mut self, name: &str, nth: usize, value: Arc<dyn Value>) -> Result<()>;
+
+    /// Append a value
+    fn push(&mut self, value: Arc<dyn Value>) -> Result<()>;
+
+    /// Remove and return the nth value with this name
+    fn remove_nth(&mut self, name: &str, nth: usize) -> Option<Arc<dyn Value>>;
+}
+
+impl PathLevel for ContainerValue {
+    fn count(&self, name: &str) -> usize {
+        self.children()
+            .iter()
+            .filter(|child| child.name() == name)
+            .count()
+    }
+
+    fn nth(&self, name: &str, nth: usize) -> Option<Arc<dyn Value>> {
+        self.get_child(name, nth)
+    }
+
+    fn replace_nth(&mut self, name: &str, nth: usize, value: Arc<dyn Value>) -> Result<()> {
+        self.set_child(name, nth, value)
+            .map(|_| ())
+            .ok_or_else(|| ContainerError::ValueNotFound(name.to_string()))
+    }
+
+    fn push(&mut self, value: Arc<dyn Value>) -> Result<()> {
+        self.add_child(value);
+        Ok(())
+    }
+
+    fn remove_nth(&mut self, name: &str, nth: usize) -> Option<Arc<dyn Value>> {
+        self.remove_child_at(name, nth)
+    }
+}
+
+/// Path of the value called `name` below `parent` (`nth` tells repeated names apart)
+pub(crate) fn child_path(parent: &str, name: &str, nth: Option<usize>) -> String {
+    let mut path = String::with_capacity(parent.len() + name.len() + 1);
+    path.push_str(parent);
+    if !path.is_empty() {
+        path.push('.');
+    }
+    for c in name.chars() {
+        if matches!(c, '.' | '[' | ']' | '{' | '}' | '\\') {
+            path.push('\\');
+        }
+        path.push(c);
+    }
+    if let Some(nth) = nth {
+        path.push_str(&format!("{{{}}}", nth));
+    }
+    path
+}
+
+/// Path of element `index` of the array at `parent`
+pub(crate) fn element_path(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
+
+fn path_error(path: &str, segment: &str, message: impl Into<String>) -> ContainerError {
+    ContainerError::PathError {
+        path: path.to_string(),
+        segment: segment.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Parse one segment from the start of `rest`, returning it and what follows
+fn parse_segment<'a>(path: &str, rest: &'a str) -> Result<(PathSegment, &'a str)> {
+    let mut name = String::new();
+    let mut chars = rest.char_indices();
+    let mut end = rest.len();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped)) => name.push(escaped),
+                None => return Err(path_error(path, rest, "path ends with '\\'")),
+            },
+            '.' | '[' | ']' | '{' | '}' => {
+                end = offset;
+                break;
+            }
+            _ => name.push(c),
+        }
+    }
+
+    // The number between `open` and `close` at the start of `remaining`, if any
+    let selector =
+        |remaining: &'a str, open: char, close: char| -> Result<Option<(usize, &'a str)>> {
+            let Some(after) = remaining.strip_prefix(open) else {
+                return Ok(None);
+            };
+            let end = after
+                .find(close)
+                .ok_or_else(|| path_error(path, rest, format!("missing '{}'", close)))?;
+            let digits = &after[..end];
+            let number = if digits.bytes().all(|b| b.is_ascii_digit()) {
+                digits.parse().ok()
+            } else {
+                None
+            }
+            .ok_or_else(|| {
+                path_error(
+                    path,
+                    &rest[..rest.len() - after.len() + end + 1],
+                    format!("'{}' is not an index", digits),
+                )
+            })?;
+            Ok(Some((number, &after[end + 1..])))
+        };
+
+    let mut remaining = &rest[end..];
+    let mut nth = None;
+    if let Some((number, after)) = selector(remaining, '{', '}')? {
+        nth = Some(number);
+        remaining = after;
+    }
+    let mut indices = Vec::new();
+    while let Some((index, after)) = selector(remaining, '[', ']')? {
+        indices.push(index);
+        remaining = after;
+    }
+
+    let text = &rest[..rest.len() - remaining.len()];
+    if name.is_empty() {
+        return Err(path_error(path, text, "empty value name"));
+    }
+    if let Some(c) = remaining.chars().next().filter(|&c| c != '.') {
+        return Err(path_error(
+            path,
+            &rest[..rest.len() - remaining.len() + c.len_utf8()],
+            format!("unexpected '{}'", c),
+        ));
+    }
+
+    Ok((
+        PathSegment {
+            name,
+            nth,
+            indices,
+            text: text.to_string(),
+        },
+        remaining,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ValueType;
+    use crate::values::{DoubleValue, IntValue, StringValue};
+
+    /// `order { id, items: [ { price }, { price } ] }`, plus two top-level `tag`s
+    fn order() -> ValueContainer {
+        let item = |price: f64| -> Arc<dyn Value> {
+            Arc::new(ContainerValue::new(
+                "",
+                vec![Arc::new(DoubleValue::new("price", price))],
+            ))
+        };
+        let items: Arc<dyn Value> = Arc::new(ArrayValue::new("items", vec![item(1.5), item(2.5)]));
+        let order: Arc<dyn Value> = Arc::new(ContainerValue::new(
+            "order",
+            vec![Arc::new(IntValue::new("id", 7)), items],
+        ));
+
+        let mut container = ValueContainer::new();
+        container.add_value(order).unwrap();
+        container
+            .add_value(Arc::new(IntValue::new("tag", 1)))
+            .unwrap();
+        container
+            .add_value(Arc::new(IntValue::new("tag", 2)))
+            .unwrap();
+        container
+    }
+
+    fn segment_of(err: ContainerError) -> String {
+        match err {
+            ContainerError::PathError { segment, .. } => segment,
+            other => panic!("expected a path error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let path = ValuePath::parse("order{1}.items[3][1].a\\.b\\[c\\]\\{d\\}").unwrap();
+        let parts: Vec<(&str, Option<usize>, &[usize])> = path
+            .segments()
+            .iter()
+            .map(|s| (s.name(), s.nth(), s.indices()))
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                ("order", Some(1), &[][..]),
+                ("items", None, &[3, 1][..]),
+                ("a.b[c]{d}", None, &[][..])
+            ]
+        );
+        assert_eq!(path.segments()[1].as_str(), "items[3][1]");
+        assert_eq!(path.to_string(), "order{1}.items[3][1].a\\.b\\[c\\]\\{d\\}");
+        assert_eq!(child_path("", "a{b}", Some(2)), "a\\{b\\}{2}");
+
+        for (bad, segment) in [
+            ("", ""),
+            ("a..b", ""),
+            ("a.[0]", "[0]"),
+            ("a[x]", "a[x]"),
+            ("a[1", "a[1"),
+            ("a[-1]", "a[-1]"),
+            ("a]b", "a]"),
+            ("a[0]é", "a[0]é"),
+            ("a{x}", "a{x}"),
+            ("a{1", "a{1"),
+            ("a[0]{1}", "a[0]{"),
+            ("a{0}{1}", "a{0}{"),
+            ("a}", "a}"),
+            ("a\\", "a\\"),
+        ] {
+            let err = ValuePath::parse(bad).unwrap_err();
+            assert_eq!(segment_of(err), segment, "path {bad:?}");
+        }
+
+        let deep = vec!["a"; MAX_NESTING_DEPTH + 1].join(".");
+        assert!(ValuePath::parse(&deep).is_err());
+    }
+
+    #[test]
+    fn test_get_path() {
+        let container = order();
+        assert_eq!(container.get_path("order.id").unwrap().to_int().unwrap(), 7);
+        let price = container.get_path("order.items[1].price").unwrap();
+        assert_eq!(price.to_double().unwrap(), 2.5);
+        assert_eq!(
+            container.get_path("order.items").unwrap().value_type(),
+            ValueType::Array
+        );
+
+        // Duplicate names are indexed like get_value_array
+        assert_eq!(container.get_path("tag").unwrap().to_int().unwrap(), 1);
+        assert_eq!(container.get_path("tag{1}").unwrap().to_int().unwrap(), 2);
+
+        for (path, segment) in [
+            ("missing", "missing"),
+            ("order.missing", "missing"),
+            ("order.items[2].price", "items[2]"),
+            ("order.items[0].cost", "cost"),
+            ("order.id.value", "value"),
+            ("order.id[0][0]", "id[0][0]"),
+            ("tag{2}", "tag{2}"),
+        ] {
+            let err = container.get_path(path).unwrap_err();
+            assert_eq!(segment_of(err), segment, "path {path:?}");
+        }
+    }
+
+    #[test]
+    fn test_set_path() {
+        let mut container = order();
+        let before = container.get_path("order").unwrap();
+
+        let old = container
+            .set_path("order.items[0].price", Arc::new(DoubleValue::new("x", 9.0)))
+            .unwrap();
+        assert_eq!(old.unwrap().to_double().unwrap(), 1.5);
+        let price = container.get_path("order.items[0].price").unwrap();
+        assert_eq!(price.name(), "price");
+        assert_eq!(price.to_double().unwrap(), 9.0);
+
+        // Values shared with other holders are copied, not modified
+        let items = before
+            .as_any()
+            .downcast_ref::<ContainerValue>()
+            .unwrap()
+            .get_child("items", 0)
+            .unwrap();
+        let first = items
+            .as_any()
+            .downcast_ref::<ArrayValue>()
+            .unwrap()
+            .at(0)
+            .unwrap();
+        let old_price = first.as_any().downcast_ref::<ContainerValue>().unwrap();
+        assert_eq!(
+            old_price
+                .get_child("price", 0)
+                .unwrap()
+                .to_double()
+                .unwrap(),
+            1.5
+        );
+
+        // Appending to an array, to duplicates and creating missing containers
+        let element: Arc<dyn Value> = Arc::new(ContainerValue::new("", vec![]));
+        assert!(container
+            .set_path("order.items[2]", element)
+            .unwrap()
+            .is_none());
+        assert!(container
+            .set_path("tag{2}", Arc::new(IntValue::new("tag", 3)))
+            .unwrap()
+            .is_none());
+        assert!(container
+            .set_path(
+                "meta.source.host",
+                Arc::new(StringValue::new("", "localhost"))
+            )
+            .unwrap()
+            .is_none());
+        assert_eq!(container.get_value_array("tag").len(), 3);
+        assert_eq!(
+            container.get_path("meta.source.host").unwrap().to_string(),
+            "localhost"
+        );
+        assert_eq!(
+            container.get_path("order.items[2]").unwrap().value_type(),
+            ValueType::Container
+        );
+
+        // Failures leave the container unchanged
+        let wire = container.serialize_cpp_wire().unwrap();
+        for (path, segment) in [
+            ("order.items[9].price", "items[9]"),
+            ("order.id.value", "value"),
+            ("tag{5}", "tag{5}"),
+        ] {
+            let err = container
+                .set_path(path, Arc::new(IntValue::new("", 0)))
+                .unwrap_err();
+            assert_eq!(segment_of(err), segment, "path {path:?}");
+        }
+        assert_eq!(container.serialize_cpp_wire().unwrap(), wire);
+    }
+
+    #[test]
+    fn test_remove_path() {
+        let mut container = order();
+
+        let removed = container.remove_path("tag{0}").unwrap();
+        assert_eq!(removed.to_int().unwrap(), 1);
+        assert_eq!(container.get_path("tag").unwrap().to_int().unwrap(), 2);
+
+        let removed = container.remove_path("order.items[0]").unwrap();
+        assert_eq!(removed.value_type(), ValueType::Container);
+        let price = container.get_path("order.items[0].price").unwrap();
+        assert_eq!(price.to_double().unwrap(), 2.5);
+
+        container.remove_path("order.items[0].price").unwrap();
+        assert!(container.get_path("order.items[0].price").is_err());
+
+        let err = container.remove_path("order.items[1]").unwrap_err();
+        assert_eq!(segment_of(err), "items[1]");
+    }
+
+    #[test]
+    fn test_duplicate_arrays() {
+        let mut container = order();
+        let err = container.get_path("tag[0]").unwrap_err();
+        assert_eq!(segment_of(err), "tag[0]");
+
+        // A second `items` array does not change what `items[0]` refers to
+        let more: Arc<dyn Value> = Arc::new(ArrayValue::new(
+            "items",
+            vec![Arc::new(IntValue::new("", 9)) as Arc<dyn Value>],
+        ));
+        container.set_path("order.items{1}", more).unwrap();
+        let old = container
+            .set_path("order.items[0]", Arc::new(IntValue::new("", 0)))
+            .unwrap();
+        assert_eq!(old.unwrap().value_type(), ValueType::Container);
+        assert_eq!(
+            container
+                .get_path("order.items{1}[0]")
+                .unwrap()
+                .to_int()
+                .unwrap(),
+            9
+        );
+        assert_eq!(
+            container
+                .remove_path("order.items{1}[0]")
+                .unwrap()
+                .to_int()
+                .unwrap(),
+            9
+        );
+        assert_eq!(
+            container
+                .get_path("order.items[1].price")
+                .unwrap()
+                .to_double()
+                .unwrap(),
+            2.5
+        );
+    }
+
+    #[test]
+    fn test_inline_container() {
+        use crate::core::value_data::ValueData;
+
+        let mut container = ValueContainer::new_inline();
+        container.add_data("a", ValueData::Int(1)).unwrap();
+        container.add_data("b", ValueData::Int(2)).unwrap();
+        container.add_data("a", ValueData::Int(3)).unwrap();
+
+        container
+            .set_path("a{1}", Arc::new(IntValue::new("a", 4)))
+            .unwrap();
+        container
+            .set_path("c.d", Arc::new(IntValue::new("d", 5)))
+            .unwrap();
+        assert_eq!(container.remove_path("a{0}").unwrap().to_int().unwrap(), 1);
+
+        assert_eq!(container.get_path("a").unwrap().to_int().unwrap(), 4);
+        assert_eq!(container.get_path("c.d").unwrap().to_int().unwrap(), 5);
+        let names: Vec<String> = container
+            .values()
+            .iter()
+            .map(|v| v.name().to_string())
+            .collect();
+        assert_eq!(names, ["b", "a", "c"]);
+    }
+}
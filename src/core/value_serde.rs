@@ -0,0 +1,834 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Serde Support for Values
+//!
+//! `Serialize` / `Deserialize` for `dyn Value`, so that `ValueContainer`, `ArrayValue`,
+//! `ContainerValue` and any struct holding values can be written with any serde format
+//! (JSON, bincode, postcard, RON, ...) without going through the bespoke adapters.
+//!
+//! A `dyn Value` is written as an externally tagged enum named `Value`. The variant is
+//! the value type name (`"int"`, `"llong"`, ...) and its index is the `ValueType` code,
+//! so compact formats store a single integer tag. The variant content has the same
+//! shape as the concrete type, e.g. `IntValue` is `{ name, value }`:
+//!
+//! ```json
+//! {"llong": {"name": "user_id", "value": 42}}
+//! ```
+//!
+//! | Type | `value` field |
+//! |------|---------------|
+//! | null_value | absent |
+//! | bool_value | `bool` |
+//! | short_value ... ullong_value | the integer of the stored width (`long_value` is `i32`) |
+//! | float_value, double_value | `f32`, `f64` |
+//! | string_value | string |
+//! | bytes_value | bytes |
+//! | container_value, array_value | sequence of tagged values |
+//! | decimal_value | exact decimal string, e.g. `"12.50"` |
+//! | timestamp_value | RFC 3339 string, e.g. `"2024-03-01T09:30:00Z"` |
+//! | duration_value | ISO 8601 string, e.g. `"PT1H30M"` |
+//! | uuid_value | canonical UUID string |
+//!
+//! Deserializing rebuilds the built-in type for the tag, so the exact `ValueType`
+//! survives a round trip. The format never relies on `deserialize_any`, which keeps it
+//! usable with non-self-describing formats. Nesting is limited to
+//! [`MAX_NESTING_DEPTH`].
+//!
+//! Use [`value`] and [`values`] with `#[serde(with = "...")]` for `Arc<dyn Value>` and
+//! `Vec<Arc<dyn Value>>` fields.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_container_system::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let mut container = ValueContainer::new();
+//! container.set_message_type("login");
+//! container.add_value(Arc::new(LLongValue::new("user_id", 42))).unwrap();
+//!
+//! let json = serde_json::to_string(&container).unwrap();
+//! let restored: ValueContainer = serde_json::from_str(&json).unwrap();
+//!
+//! assert_eq!(restored.message_type(), "login");
+//! assert_eq!(restored.get_value("user_id").unwrap().value_type(), ValueType::LLong);
+//! ```
+//!
+//! [`MAX_NESTING_DEPTH`]: crate::core::wire_protocol::MAX_NESTING_DEPTH
+//! [`value`]: crate::core::value_serde::value
+//! [`values`]: crate::core::value_serde::values
+
+use crate::core::wire_protocol::MAX_NESTING_DEPTH;
+use crate::core::{Value, ValueType};
+use crate::values::time_values::parse_duration;
+use crate::values::{
+    ArrayValue, BoolValue, BytesValue, ContainerValue, Decimal, DecimalValue, DoubleValue,
+    DurationValue, FloatValue, IntValue, LLongValue, LongValue, NullValue, ShortValue, StringValue,
+    Timestamp, TimestampValue, UIntValue, ULLongValue, ULongValue, UShortValue, Uuid, UuidValue,
+};
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use serde::ser::{self, SerializeStruct, Serializer};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Variant names, indexed by `ValueType` code
+const VARIANTS: &[&str] = &[
+    "null",
+    "bool",
+    "short",
+    "ushort",
+    "int",
+    "uint",
+    "long",
+    "ulong",
+    "llong",
+    "ullong",
+    "float",
+    "double",
+    "string",
+    "bytes",
+    "container",
+    "array",
+    "decimal",
+    "timestamp",
+    "duration",
+    "uuid",
+];
+
+const NULL_FIELDS: &[&str] = &["name"];
+const FIELDS: &[&str] = &["name", "value"];
+
+/// `#[serde(with = "...")]` module for `Arc<dyn Value>` fields
+///
+/// ```
+/// use rust_container_system::core::Value;
+/// use serde::{Deserialize, Serialize};
+/// use std::sync::Arc;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Envelope {
+///     #[serde(with = "rust_container_system::core::value_serde::value")]
+///     payload: Arc<dyn Value>,
+/// }
+/// ```
+pub mod value {
+    use super::ValueSeed;
+    use crate::core::Value;
+    use serde::de::DeserializeSeed;
+    use serde::{Deserializer, Serialize, Serializer};
+    use std::sync::Arc;
+
+    /// Serialize a value as its tagged representation
+    pub fn serialize<S: Serializer>(
+        value: &Arc<dyn Value>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.as_ref().serialize(serializer)
+    }
+
+    /// Rebuild a value from its tagged representation
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<dyn Value>, D::Error> {
+        ValueSeed { depth: 0 }.deserialize(deserializer)
+    }
+}
+
+/// `#[serde(with = "...")]` module for `Vec<Arc<dyn Value>>` fields
+///
+/// `ArrayValue`, `ContainerValue` and `ValueContainer` store their values with it.
+pub mod values {
+    use super::ValuesSeed;
+    use crate::core::Value;
+    use serde::de::DeserializeSeed;
+    use serde::{Deserializer, Serializer};
+    use std::sync::Arc;
+
+    /// Serialize values as a sequence of tagged values
+    pub fn serialize<S: Serializer>(
+        values: &[Arc<dyn Value>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| value.as_ref()))
+    }
+
+    /// Rebuild values from a sequence of tagged values
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Arc<dyn Value>>, D::Error> {
+        ValuesSeed { depth: 0 }.deserialize(deserializer)
+    }
+}
+
+impl Serialize for dyn Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value_type = self.value_type();
+        let index = value_type as u32;
+        let content = Content {
+            name: self.name(),
+            payload: payload_of(self).map_err(ser::Error::custom)?,
+        };
+        serializer.serialize_newtype_variant("Value", index, VARIANTS[index as usize], &content)
+    }
+}
+
+/// Variant content: the value name plus its payload
+struct Content<'v> {
+    name: &'v str,
+    payload: Payload<'v>,
+}
+
+impl Serialize for Content<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = match self.payload {
+            Payload::Null => NULL_FIELDS,
+            _ => FIELDS,
+        };
+        let mut state = serializer.serialize_struct("Value", fields.len())?;
+        state.serialize_field("name", self.name)?;
+        if fields.len() > 1 {
+            state.serialize_field("value", &self.payload)?;
+        }
+        state.end()
+    }
+}
+
+/// The `value` field of a variant, borrowed from the value where possible
+enum Payload<'v> {
+    Null,
+    Bool(bool),
+    Short(i16),
+    UShort(u16),
+    Int(i32),
+    UInt(u32),
+    LLong(i64),
+    ULLong(u64),
+    Float(f32),
+    Double(f64),
+    Text(Cow<'v, str>),
+    Bytes(&'v [u8]),
+    Children(&'v [Arc<dyn Value>]),
+}
+
+impl Serialize for Payload<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Payload::Null => serializer.serialize_unit(),
+            Payload::Bool(v) => serializer.serialize_bool(*v),
+            Payload::Short(v) => serializer.serialize_i16(*v),
+            Payload::UShort(v) => serializer.serialize_u16(*v),
+            Payload::Int(v) => serializer.serialize_i32(*v),
+            Payload::UInt(v) => serializer.serialize_u32(*v),
+            Payload::LLong(v) => serializer.serialize_i64(*v),
+            Payload::ULLong(v) => serializer.serialize_u64(*v),
+            Payload::Float(v) => serializer.serialize_f32(*v),
+            Payload::Double(v) => serializer.serialize_f64(*v),
+            Payload::Text(text) => serializer.serialize_str(text),
+            Payload::Bytes(bytes) => bytes.serialize(serializer),
+            Payload::Children(children) => values::serialize(children, serializer),
+        }
+    }
+}
+
+/// Read the payload of a value through the `Value` conversions
+///
+/// Bytes, containers and arrays must be the built-in types; any other type can be a
+/// custom `Value` implementation.
+fn payload_of(value: &dyn Value) -> crate::core::Result<Payload<'_>> {
+    let mismatched = || {
+        crate::core::ContainerError::SerializationError(format!(
+            "Value '{}' reports type {} but is not the built-in implementation",
+            value.name(),
+            value.value_type()
+        ))
+    };
+    let out_of_range = |number: i128| {
+        crate::core::ContainerError::SerializationError(format!(
+            "Value '{}' ({}) does not fit in a 32-bit {}",
+            value.name(),
+            number,
+            value.value_type()
+        ))
+    };
+
+    Ok(match value.value_type() {
+        ValueType::Null => Payload::Null,
+        ValueType::Bool => Payload::Bool(value.to_bool()?),
+        ValueType::Short => Payload::Short(value.to_short()?),
+        ValueType::UShort => Payload::UShort(value.to_ushort()?),
+        ValueType::Int => Payload::Int(value.to_int()?),
+        ValueType::UInt => Payload::UInt(value.to_uint()?),
+        ValueType::Long => {
+            let number = value.to_long()?;
+            Payload::Int(i32::try_from(number).map_err(|_| out_of_range(number.into()))?)
+        }
+        ValueType::ULong => {
+            let number = value.to_ulong()?;
+            Payload::UInt(u32::try_from(number).map_err(|_| out_of_range(number.into()))?)
+        }
+        ValueType::LLong => Payload::LLong(value.to_long()?),
+        ValueType::ULLong => Payload::ULLong(value.to_ulong()?),
+        ValueType::Float => Payload::Float(value.to_float()?),
+        ValueType::Double => Payload::Double(value.to_double()?),
+        ValueType::String => match value.as_any().downcast_ref::<StringValue>() {
+            Some(string) => Payload::Text(Cow::Borrowed(string.value())),
+            None => Payload::Text(Cow::Owned(value.to_string())),
+        },
+        ValueType::Decimal | ValueType::Timestamp | ValueType::Duration | ValueType::Uuid => {
+            Payload::Text(Cow::Owned(value.to_string()))
+        }
+        ValueType::Bytes => Payload::Bytes(
+            value
+                .as_any()
+                .downcast_ref::<BytesValue>()
+                .ok_or_else(mismatched)?
+                .data(),
+        ),
+        ValueType::Container => Payload::Children(
+            value
+                .as_any()
+                .downcast_ref::<ContainerValue>()
+                .ok_or_else(mismatched)?
+                .children(),
+        ),
+        ValueType::Array => Payload::Children(
+            value
+                .as_any()
+                .downcast_ref::<ArrayValue>()
+                .ok_or_else(mismatched)?
+                .elements(),
+        ),
+    })
+}
+
+/// Deserializes one tagged value; `depth` counts the enclosing containers and arrays
+#[derive(Clone, Copy)]
+struct ValueSeed {
+    depth: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed {
+    type Value = Arc<dyn Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_enum("Value", VARIANTS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed {
+    type Value = Arc<dyn Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a tagged container value")
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        use de::VariantAccess;
+
+        let (Tag(value_type), variant) = data.variant()?;
+        let content = ContentSeed {
+            value_type,
+            depth: self.depth,
+        };
+        variant.newtype_variant_seed(content)
+    }
+}
+
+/// Variant tag, accepted as a type name or as a `ValueType` code
+struct Tag(ValueType);
+
+impl<'de> de::Deserialize<'de> for Tag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TagVisitor;
+
+        impl Visitor<'_> for TagVisitor {
+            type Value = Tag;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a value type name or code")
+            }
+
+            fn visit_u64<E: de::Error>(self, code: u64) -> Result<Tag, E> {
+                u8::try_from(code)
+                    .ok()
+                    .and_then(ValueType::from_u8)
+                    .map(Tag)
+                    .ok_or_else(|| {
+                        E::invalid_value(de::Unexpected::Unsigned(code), &"a known value type code")
+                    })
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Tag, E> {
+                VARIANTS
+                    .iter()
+                    .position(|variant| *variant == name)
+                    .and_then(|code| ValueType::from_u8(code as u8))
+                    .or_else(|| ValueType::from_type_code(name))
+                    .map(Tag)
+                    .ok_or_else(|| E::unknown_variant(name, VARIANTS))
+            }
+
+            fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<Tag, E> {
+                match std::str::from_utf8(name) {
+                    Ok(name) => self.visit_str(name),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(name), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(TagVisitor)
+    }
+}
+
+/// Deserializes the `{ name, value }` content of a variant
+struct ContentSeed {
+    value_type: ValueType,
+    depth: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for ContentSeed {
+    type Value = Arc<dyn Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let fields = match self.value_type {
+            ValueType::Null => NULL_FIELDS,
+            _ => FIELDS,
+        };
+        deserializer.deserialize_struct("Value", fields, self)
+    }
+}
+
+impl<'de> Visitor<'de> for ContentSeed {
+    type Value = Arc<dyn Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} value", self.value_type)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let name: String = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let payload = if self.value_type == ValueType::Null {
+            OwnedPayload::Null
+        } else {
+            seq.next_element_seed(self.payload_seed())?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?
+        };
+        build(name, payload).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut name: Option<String> = None;
+        let mut payload = None;
+        while let Some(field) = map.next_key::<Field>()? {
+            match field {
+                Field::Name if name.is_some() => return Err(de::Error::duplicate_field("name")),
+                Field::Name => name = Some(map.next_value()?),
+                Field::Value if self.value_type == ValueType::Null => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+                Field::Value if payload.is_some() => {
+                    return Err(de::Error::duplicate_field("value"))
+                }
+                Field::Value => payload = Some(map.next_value_seed(self.payload_seed())?),
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+
+        let name = name.ok_or_else(|| de::Error::missing_field("name"))?;
+        let payload = match (self.value_type, payload) {
+            (ValueType::Null, _) => OwnedPayload::Null,
+            (_, Some(payload)) => payload,
+            (_, None) => return Err(de::Error::missing_field("value")),
+        };
+        build(name, payload).map_err(de::Error::custom)
+    }
+}
+
+impl ContentSeed {
+    fn payload_seed(&self) -> PayloadSeed {
+        PayloadSeed {
+            value_type: self.value_type,
+            depth: self.depth,
+        }
+    }
+}
+
+/// Field names of the variant content
+enum Field {
+    Name,
+    Value,
+    Other,
+}
+
+impl<'de> de::Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("`name` or `value`")
+            }
+
+            fn visit_u64<E: de::Error>(self, index: u64) -> Result<Field, E> {
+                Ok(match index {
+                    0 => Field::Name,
+                    1 => Field::Value,
+                    _ => Field::Other,
+                })
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Field, E> {
+                Ok(match name {
+                    "name" => Field::Name,
+                    "value" => Field::Value,
+                    _ => Field::Other,
+                })
+            }
+
+            fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<Field, E> {
+                Ok(match name {
+                    b"name" => Field::Name,
+                    b"value" => Field::Value,
+                    _ => Field::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+/// An owned `value` field, waiting for the name to be known
+enum OwnedPayload {
+    Null,
+    Bool(bool),
+    Short(i16),
+    UShort(u16),
+    Int(i32),
+    UInt(u32),
+    Long(i32),
+    ULong(u32),
+    LLong(i64),
+    ULLong(u64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Decimal(Decimal),
+    Timestamp(Timestamp),
+    Duration(Duration),
+    Uuid(Uuid),
+    Bytes(Vec<u8>),
+    Container(Vec<Arc<dyn Value>>),
+    Array(Vec<Arc<dyn Value>>),
+}
+
+/// Deserializes the `value` field for a known type
+struct PayloadSeed {
+    value_type: ValueType,
+    depth: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for PayloadSeed {
+    type Value = OwnedPayload;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        use serde::Deserialize;
+
+        let children = || ValuesSeed {
+            depth: self.depth + 1,
+        };
+        Ok(match self.value_type {
+            ValueType::Null => {
+                de::IgnoredAny::deserialize(deserializer)?;
+                OwnedPayload::Null
+            }
+            ValueType::Bool => OwnedPayload::Bool(bool::deserialize(deserializer)?),
+            ValueType::Short => OwnedPayload::Short(i16::deserialize(deserializer)?),
+            ValueType::UShort => OwnedPayload::UShort(u16::deserialize(deserializer)?),
+            ValueType::Int => OwnedPayload::Int(i32::deserialize(deserializer)?),
+            ValueType::UInt => OwnedPayload::UInt(u32::deserialize(deserializer)?),
+            ValueType::Long => OwnedPayload::Long(i32::deserialize(deserializer)?),
+            ValueType::ULong => OwnedPayload::ULong(u32::deserialize(deserializer)?),
+            ValueType::LLong => OwnedPayload::LLong(i64::deserialize(deserializer)?),
+            ValueType::ULLong => OwnedPayload::ULLong(u64::deserialize(deserializer)?),
+            ValueType::Float => OwnedPayload::Float(f32::deserialize(deserializer)?),
+            ValueType::Double => OwnedPayload::Double(f64::deserialize(deserializer)?),
+            ValueType::String => OwnedPayload::String(String::deserialize(deserializer)?),
+            ValueType::Decimal => OwnedPayload::Decimal(Decimal::deserialize(deserializer)?),
+            ValueType::Timestamp => OwnedPayload::Timestamp(Timestamp::deserialize(deserializer)?),
+            ValueType::Uuid => OwnedPayload::Uuid(Uuid::deserialize(deserializer)?),
+            ValueType::Duration => {
+                let text = String::deserialize(deserializer)?;
+                OwnedPayload::Duration(parse_duration(&text).map_err(de::Error::custom)?)
+            }
+            ValueType::Bytes => {
+                OwnedPayload::Bytes(deserializer.deserialize_byte_buf(BytesVisitor)?)
+            }
+            ValueType::Container => OwnedPayload::Container(children().deserialize(deserializer)?),
+            ValueType::Array => OwnedPayload::Array(children().deserialize(deserializer)?),
+        })
+    }
+}
+
+/// Accepts bytes from formats that have a bytes type and from those that write a
+/// sequence of integers
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Deserializes a sequence of tagged values one level below `depth`
+#[derive(Clone, Copy)]
+struct ValuesSeed {
+    depth: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for ValuesSeed {
+    type Value = Vec<Arc<dyn Value>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(de::Error::custom(format!(
+                "nesting depth exceeds maximum of {}",
+                MAX_NESTING_DEPTH
+            )));
+        }
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValuesSeed {
+    type Value = Vec<Arc<dyn Value>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of tagged container values")
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // Size hints come from the input, so they only bound the initial allocation
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(1024));
+        let element = ValueSeed { depth: self.depth };
+        while let Some(value) = seq.next_element_seed(element)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+/// Build the built-in value for a payload
+fn build(name: String, payload: OwnedPayload) -> crate::core::Result<Arc<dyn Value>> {
+    Ok(match payload {
+        OwnedPayload::Null => Arc::new(NullValue::new(name)),
+        OwnedPayload::Bool(v) => Arc::new(BoolValue::new(name, v)),
+        OwnedPayload::Short(v) => Arc::new(ShortValue::new(name, v)),
+        OwnedPayload::UShort(v) => Arc::new(UShortValue::new(name, v)),
+        OwnedPayload::Int(v) => Arc::new(IntValue::new(name, v)),
+        OwnedPayload::UInt(v) => Arc::new(UIntValue::new(name, v)),
+        OwnedPayload::Long(v) => Arc::new(LongValue::new(name, i64::from(v))?),
+        OwnedPayload::ULong(v) => Arc::new(ULongValue::new(name, u64::from(v))?),
+        OwnedPayload::LLong(v) => Arc::new(LLongValue::new(name, v)),
+        OwnedPayload::ULLong(v) => Arc::new(ULLongValue::new(name, v)),
+        OwnedPayload::Float(v) => Arc::new(FloatValue::new(name, v)),
+        OwnedPayload::Double(v) => Arc::new(DoubleValue::new(name, v)),
+        OwnedPayload::String(v) => Arc::new(StringValue::new(name, v)),
+        OwnedPayload::Decimal(v) => Arc::new(DecimalValue::new(name, v)),
+        OwnedPayload::Timestamp(v) => Arc::new(TimestampValue::new(name, v)),
+        OwnedPayload::Duration(v) => Arc::new(DurationValue::new(name, v)),
+        OwnedPayload::Uuid(v) => Arc::new(UuidValue::new(name, v)),
+        OwnedPayload::Bytes(v) => Arc::new(BytesValue::new(name, v)),
+        OwnedPayload::Container(children) => Arc::new(ContainerValue::new(name, children)),
+        OwnedPayload::Array(elements) => Arc::new(ArrayValue::new(name, elements)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ValueContainer;
+
+    fn sample() -> Vec<Arc<dyn Value>> {
+        vec![
+            Arc::new(NullValue::new("nothing")),
+            Arc::new(BoolValue::new("flag", true)),
+            Arc::new(ShortValue::new("s", -2)),
+            Arc::new(UShortValue::new("us", 2)),
+            Arc::new(IntValue::new("i", -4)),
+            Arc::new(UIntValue::new("ui", 4)),
+            Arc::new(LongValue::new("l", -6).unwrap()),
+            Arc::new(ULongValue::new("ul", 6).unwrap()),
+            Arc::new(LLongValue::new("ll", i64::MIN)),
+            Arc::new(ULLongValue::new("ull", u64::MAX)),
+            Arc::new(FloatValue::new("f", 1.5)),
+            Arc::new(DoubleValue::new("d", -2.25)),
+            Arc::new(StringValue::new("text", "a \"quoted\" ];")),
+            Arc::new(BytesValue::new("raw", vec![0, 1, 255])),
+            Arc::new(ContainerValue::new(
+                "nested",
+                vec![Arc::new(ArrayValue::new(
+                    "list",
+                    vec![Arc::new(IntValue::new("", 1)), Arc::new(NullValue::new(""))],
+                ))],
+            )),
+        ]
+    }
+
+    fn assert_same(left: &[Arc<dyn Value>], right: &[Arc<dyn Value>]) {
+        assert_eq!(left.len(), right.len());
+        for (a, b) in left.iter().zip(right) {
+            assert_eq!(a.name(), b.name());
+            assert_eq!(a.value_type(), b.value_type());
+            assert_eq!(a.to_bytes(), b.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let value: Arc<dyn Value> = Arc::new(LLongValue::new("user_id", 42));
+        let json = serde_json::to_string(value.as_ref()).unwrap();
+        assert_eq!(json, r#"{"llong":{"name":"user_id","value":42}}"#);
+
+        let null: Arc<dyn Value> = Arc::new(NullValue::new("gone"));
+        let json = serde_json::to_string(null.as_ref()).unwrap();
+        assert_eq!(json, r#"{"null":{"name":"gone"}}"#);
+    }
+
+    #[test]
+    fn test_round_trip_preserves_types() {
+        let values = sample();
+        let json = serde_json::to_string(&ArrayValue::new("all", values.clone())).unwrap();
+        let back: ArrayValue = serde_json::from_str(&json).unwrap();
+        assert_same(back.elements(), &values);
+
+        let bytes = bincode::serialize(&ContainerValue::new("all", values.clone())).unwrap();
+        let back: ContainerValue = bincode::deserialize(&bytes).unwrap();
+        assert_same(back.children(), &values);
+    }
+
+    #[test]
+    fn test_container_round_trip() {
+        let mut container = ValueContainer::new();
+        container.set_source("client", "1");
+        container.set_target("server", "2");
+        container.set_message_type("everything");
+        container.set_version("3.0");
+        for value in sample() {
+            container.add_value(value).unwrap();
+        }
+
+        for back in [
+            serde_json::from_str::<ValueContainer>(&serde_json::to_string(&container).unwrap())
+                .unwrap(),
+            bincode::deserialize::<ValueContainer>(&bincode::serialize(&container).unwrap())
+                .unwrap(),
+        ] {
+            assert_eq!(back.source_id(), "client");
+            assert_eq!(back.target_sub_id(), "2");
+            assert_eq!(back.message_type(), "everything");
+            assert_eq!(back.version(), "3.0");
+            assert_same(&back.values(), &container.values());
+            assert_eq!(back.get_value("ull").unwrap().to_ulong().unwrap(), u64::MAX);
+        }
+    }
+
+    #[test]
+    fn test_accepts_codes_and_any_field_order() {
+        let json = r#"{"8":{"value":7,"name":"n","extra":true}}"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let value = value::deserialize(&mut de).unwrap();
+        assert_eq!(value.value_type(), ValueType::LLong);
+        assert_eq!(value.to_long().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let parse = |json: &str| {
+            let mut de = serde_json::Deserializer::from_str(json);
+            value::deserialize(&mut de)
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(parse(r#"{"int64":{"name":"n","value":1}}"#).contains("unknown variant"));
+        assert!(parse(r#"{"short":{"name":"n","value":70000}}"#).contains("invalid value"));
+        assert!(parse(r#"{"int":{"name":"n"}}"#).contains("missing field `value`"));
+        assert!(parse(r#"{"string":{"value":"x"}}"#).contains("missing field `name`"));
+
+        let mut deep: Arc<dyn Value> = Arc::new(NullValue::new(""));
+        for _ in 0..=MAX_NESTING_DEPTH {
+            deep = Arc::new(ArrayValue::new("", vec![deep]));
+        }
+        let bytes = bincode::serialize(&ArrayValue::new("", vec![deep])).unwrap();
+        let err = bincode::deserialize::<ArrayValue>(&bytes).unwrap_err();
+        assert!(err.to_string().contains("nesting depth"));
+    }
+
+    #[test]
+    fn test_container_limit_applies() {
+        let mut container = ValueContainer::with_max_values(crate::core::DEFAULT_MAX_VALUES + 1);
+        for i in 0..=crate::core::DEFAULT_MAX_VALUES {
+            container
+                .add_value(Arc::new(IntValue::new("", i as i32)))
+                .unwrap();
+        }
+        let json = serde_json::to_string(&container).unwrap();
+        let err = serde_json::from_str::<ValueContainer>(&json).unwrap_err();
+        assert!(err.to_string().contains("value limit"));
+    }
+}
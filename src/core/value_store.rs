@@ -57,37 +57,15 @@
 
 use crate::core::error::ContainerError;
 use crate::core::value::Value;
+use crate::core::value_codec::{encode_value, ValueDecoder};
 use crate::core::value_types::ValueType;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
-/// Helper function to convert u8 to ValueType
-fn value_type_from_u8(byte: u8) -> ValueType {
-    match byte {
-        0 => ValueType::Null,
-        1 => ValueType::Bool,
-        2 => ValueType::Short,
-        3 => ValueType::UShort,
-        4 => ValueType::Int,
-        5 => ValueType::UInt,
-        6 => ValueType::Long,
-        7 => ValueType::ULong,
-        8 => ValueType::LLong,
-        9 => ValueType::ULLong,
-        10 => ValueType::Float,
-        11 => ValueType::Double,
-        12 => ValueType::String,
-        13 => ValueType::Bytes,
-        14 => ValueType::Container,
-        15 => ValueType::Array,
-        _ => ValueType::Null,
-    }
-}
-
 /// Binary format version for compatibility
-pub const BINARY_VERSION: u8 = 1;
+pub const BINARY_VERSION: u8 = 2;
 
 /// Statistics for ValueStore operations
 #[derive(Debug, Clone, Default)]
@@ -247,14 +225,14 @@ impl ValueStore {
     /// Serialize to binary format.
     ///
     /// Binary format:
-    /// - Version byte (1)
+    /// - Version byte (2)
     /// - Number of entries (4 bytes, uint32, little-endian)
     /// - For each entry:
     ///   - Key length (4 bytes, uint32, little-endian)
     ///   - Key data (UTF-8)
     ///   - Value type (1 byte)
     ///   - Value length (4 bytes, uint32, little-endian)
-    ///   - Value data
+    ///   - Value data in the C++ binary value layout (see [`value_codec`](crate::core::value_codec))
     pub fn serialize_binary(&self) -> Result<Vec<u8>, ContainerError> {
         self.serialization_count.fetch_add(1, Ordering::Relaxed);
 
@@ -279,8 +257,8 @@ impl ValueStore {
             // Value type
             result.push(value.value_type() as u8);
 
-            // Value data - use to_bytes() for binary representation
-            let value_data = value.to_bytes();
+            // Value data
+            let value_data = encode_value(value)?;
             let value_len = value_data.len() as u32;
             result.extend_from_slice(&value_len.to_le_bytes());
             result.extend_from_slice(&value_data);
@@ -291,10 +269,86 @@ impl ValueStore {
 
     /// Deserialize from binary format.
     ///
-    /// Note: Requires a factory function to create values from type and data.
-    pub fn deserialize_binary<F>(data: &[u8], factory: F) -> Result<Self, ContainerError>
+    /// Values of all built-in types, including nested containers and arrays, are
+    /// rebuilt without a factory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rust_container_system::core::ValueStore;
+    /// use rust_container_system::values::{IntValue, StringValue};
+    /// use std::sync::Arc;
+    ///
+    /// let store = ValueStore::new();
+    /// store.add("count".to_string(), Arc::new(IntValue::new("count", 42)));
+    /// store.add("name".to_string(), Arc::new(StringValue::new("name", "Alice")));
+    ///
+    /// let data = store.serialize_binary().unwrap();
+    /// let restored = ValueStore::deserialize_binary(&data).unwrap();
+    /// assert_eq!(restored.get("count").unwrap().to_int().unwrap(), 42);
+    /// ```
+    pub fn deserialize_binary(data: &[u8]) -> Result<Self, ContainerError> {
+        Self::deserialize_binary_with_decoder(data, &ValueDecoder::new())
+    }
+
+    /// Deserialize from binary format, rebuilding values through `decoder`.
+    ///
+    /// Use this to reconstruct custom `Value` implementations registered with a
+    /// [`ValueDecoder`].
+    pub fn deserialize_binary_with_decoder(
+        data: &[u8],
+        decoder: &ValueDecoder,
+    ) -> Result<Self, ContainerError> {
+        let store = Self::new();
+
+        Self::read_binary_entries(data, &[BINARY_VERSION], |key, _value_type, value_data| {
+            let (value, consumed) = decoder.decode(value_data)?;
+            if consumed != value_data.len() {
+                return Err(ContainerError::DeserializationError(format!(
+                    "Value for key '{}' has {} trailing bytes",
+                    key,
+                    value_data.len() - consumed
+                )));
+            }
+            store.values.write().insert(key, value);
+            Ok(())
+        })?;
+
+        Ok(store)
+    }
+
+    /// Deserialize from binary format using a factory function.
+    ///
+    /// The factory receives the key, the value type and the raw value data, and may
+    /// return `None` to skip an entry. Also accepts data written by version 1 of the
+    /// format, where value data was each value's `to_bytes()` output.
+    pub fn deserialize_binary_with_factory<F>(
+        data: &[u8],
+        factory: F,
+    ) -> Result<Self, ContainerError>
     where
         F: Fn(&str, ValueType, &[u8]) -> Option<Arc<dyn Value>>,
+    {
+        let store = Self::new();
+
+        Self::read_binary_entries(data, &[1, BINARY_VERSION], |key, value_type, value_data| {
+            if let Some(value) = factory(&key, value_type, value_data) {
+                store.values.write().insert(key, value);
+            }
+            Ok(())
+        })?;
+
+        Ok(store)
+    }
+
+    /// Walk the entries of a binary store, checking the version and bounds.
+    fn read_binary_entries<F>(
+        data: &[u8],
+        accepted_versions: &[u8],
+        mut on_entry: F,
+    ) -> Result<(), ContainerError>
+    where
+        F: FnMut(String, ValueType, &[u8]) -> Result<(), ContainerError>,
     {
         if data.len() < 5 {
             return Err(ContainerError::DeserializationError(
@@ -308,7 +362,7 @@ impl ValueStore {
         let version = data[offset];
         offset += 1;
 
-        if version != BINARY_VERSION {
+        if !accepted_versions.contains(&version) {
             return Err(ContainerError::DeserializationError(format!(
                 "Unsupported binary version: {}",
                 version
@@ -319,8 +373,6 @@ impl ValueStore {
         let count = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
         offset += 4;
 
-        let store = Self::new();
-
         // Read each key-value pair
         for i in 0..count {
             if offset + 4 > data.len() {
@@ -334,7 +386,7 @@ impl ValueStore {
             let key_len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
             offset += 4;
 
-            if offset + key_len + 5 > data.len() {
+            if key_len > data.len() - offset || data.len() - offset - key_len < 5 {
                 return Err(ContainerError::DeserializationError(
                     "Truncated key data".to_string(),
                 ));
@@ -349,7 +401,7 @@ impl ValueStore {
             offset += key_len;
 
             // Read value type
-            let value_type = value_type_from_u8(data[offset]);
+            let value_type = ValueType::from_u8(data[offset]).unwrap_or(ValueType::Null);
             offset += 1;
 
             // Read value length
@@ -357,7 +409,7 @@ impl ValueStore {
                 u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
             offset += 4;
 
-            if offset + value_len > data.len() {
+            if value_len > data.len() - offset {
                 return Err(ContainerError::DeserializationError(
                     "Truncated value data".to_string(),
                 ));
@@ -367,13 +419,10 @@ impl ValueStore {
             let value_data = &data[offset..offset + value_len];
             offset += value_len;
 
-            // Create value using factory
-            if let Some(value) = factory(&key, value_type, value_data) {
-                store.values.write().insert(key, value);
-            }
+            on_entry(key, value_type, value_data)?;
         }
 
-        Ok(store)
+        Ok(())
     }
 
     /// Convert to JSON format (alias for serialize).
@@ -600,4 +649,99 @@ mod tests {
         assert!(!data.is_empty());
         assert_eq!(data[0], BINARY_VERSION);
     }
+
+    #[test]
+    fn test_binary_roundtrip_without_factory() {
+        let nested = ContainerValue::new(
+            "nested",
+            vec![Arc::new(StringValue::new("city", "Seoul")) as Arc<dyn Value>],
+        );
+        let store = ValueStore::new();
+        store.add("bool".to_string(), Arc::new(BoolValue::new("bool", true)));
+        store.add("short".to_string(), Arc::new(ShortValue::new("short", -7)));
+        store.add(
+            "long".to_string(),
+            Arc::new(LongValue::new("long", 1 << 30).unwrap()),
+        );
+        store.add(
+            "llong".to_string(),
+            Arc::new(LLongValue::new("llong", i64::MIN)),
+        );
+        store.add(
+            "double".to_string(),
+            Arc::new(DoubleValue::new("double", 2.5)),
+        );
+        store.add(
+            "bytes".to_string(),
+            Arc::new(BytesValue::new("bytes", vec![0, 1, 255])),
+        );
+        store.add("nested".to_string(), Arc::new(nested));
+
+        let data = store.serialize_binary().unwrap();
+        let restored = ValueStore::deserialize_binary(&data).unwrap();
+
+        assert_eq!(restored.size(), store.size());
+        assert!(restored.get("bool").unwrap().to_bool().unwrap());
+        assert_eq!(restored.get("short").unwrap().to_short().unwrap(), -7);
+        assert_eq!(restored.get("long").unwrap().to_long().unwrap(), 1 << 30);
+        assert_eq!(restored.get("llong").unwrap().to_long().unwrap(), i64::MIN);
+        assert_eq!(restored.get("double").unwrap().to_double().unwrap(), 2.5);
+        let bytes = restored.get("bytes").unwrap();
+        let bytes = bytes.as_any().downcast_ref::<BytesValue>().unwrap();
+        assert_eq!(bytes.data(), &[0, 1, 255]);
+
+        let nested = restored.get("nested").unwrap();
+        let nested = nested.as_any().downcast_ref::<ContainerValue>().unwrap();
+        assert_eq!(nested.get_child("city", 0).unwrap().to_string(), "Seoul");
+    }
+
+    #[test]
+    fn test_binary_deserialize_with_decoder() {
+        let store = ValueStore::new();
+        store.add("count".to_string(), Arc::new(IntValue::new("count", 42)));
+        let data = store.serialize_binary().unwrap();
+
+        let mut decoder = ValueDecoder::new();
+        decoder.register(ValueType::Int, |name, payload, _ctx| {
+            let raw = i32::from_le_bytes(payload.try_into().unwrap());
+            Ok(Arc::new(IntValue::new(name, raw * 2)) as Arc<dyn Value>)
+        });
+
+        let restored = ValueStore::deserialize_binary_with_decoder(&data, &decoder).unwrap();
+        assert_eq!(restored.get("count").unwrap().to_int().unwrap(), 84);
+    }
+
+    #[test]
+    fn test_binary_deserialize_with_factory() {
+        let store = ValueStore::new();
+        store.add("count".to_string(), Arc::new(IntValue::new("count", 42)));
+        store.add("name".to_string(), Arc::new(StringValue::new("name", "x")));
+        let data = store.serialize_binary().unwrap();
+
+        let restored =
+            ValueStore::deserialize_binary_with_factory(&data, |key, value_type, _data| {
+                (value_type == ValueType::Int)
+                    .then(|| Arc::new(IntValue::new(key, 1)) as Arc<dyn Value>)
+            })
+            .unwrap();
+
+        assert_eq!(restored.size(), 1);
+        assert_eq!(restored.get("count").unwrap().to_int().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_binary_deserialize_rejects_bad_input() {
+        assert!(ValueStore::deserialize_binary(&[BINARY_VERSION, 1, 0]).is_err());
+        assert!(ValueStore::deserialize_binary(&[99, 0, 0, 0, 0]).is_err());
+        // Version 1 data is only readable through the factory variant
+        assert!(ValueStore::deserialize_binary(&[1, 0, 0, 0, 0]).is_err());
+        assert!(
+            ValueStore::deserialize_binary_with_factory(&[1, 0, 0, 0, 0], |_, _, _| None).is_ok()
+        );
+
+        let store = ValueStore::new();
+        store.add("count".to_string(), Arc::new(IntValue::new("count", 42)));
+        let data = store.serialize_binary().unwrap();
+        assert!(ValueStore::deserialize_binary(&data[..data.len() - 1]).is_err());
+    }
 }
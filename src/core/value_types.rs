@@ -34,7 +34,9 @@ use std::fmt;
 
 /// Value types supported by the container system
 ///
-/// Each variant corresponds to a specific data type (0-14).
+/// Codes 0-15 are the core types shared with every C++ container_system peer.
+/// Codes from 16 are extended types that are only sent to peers that support
+/// them (see [`TypeSupport`]).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[repr(u8)]
 pub enum ValueType {
@@ -71,6 +73,14 @@ pub enum ValueType {
     Container = 14,
     /// Array/list of values
     Array = 15,
+    /// Exact decimal number (128-bit mantissa with a scale, extended type)
+    Decimal = 16,
+    /// Instant with nanosecond precision and optional UTC offset (extended type)
+    Timestamp = 17,
+    /// Non-negative span of time with nanosecond precision (extended type)
+    Duration = 18,
+    /// 128-bit universally unique identifier (extended type)
+    Uuid = 19,
 }
 
 impl ValueType {
@@ -101,6 +111,46 @@ impl ValueType {
             "13" => Some(ValueType::Bytes),
             "14" => Some(ValueType::Container),
             "15" => Some(ValueType::Array),
+            "16" => Some(ValueType::Decimal),
+            "17" => Some(ValueType::Timestamp),
+            "18" => Some(ValueType::Duration),
+            "19" => Some(ValueType::Uuid),
+            _ => None,
+        }
+    }
+
+    /// Convert a binary type byte to ValueType
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::ValueType;
+    ///
+    /// assert_eq!(ValueType::from_u8(12), Some(ValueType::String));
+    /// assert_eq!(ValueType::from_u8(16), Some(ValueType::Decimal));
+    /// assert_eq!(ValueType::from_u8(200), None);
+    /// ```
+    pub fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(ValueType::Null),
+            1 => Some(ValueType::Bool),
+            2 => Some(ValueType::Short),
+            3 => Some(ValueType::UShort),
+            4 => Some(ValueType::Int),
+            5 => Some(ValueType::UInt),
+            6 => Some(ValueType::Long),
+            7 => Some(ValueType::ULong),
+            8 => Some(ValueType::LLong),
+            9 => Some(ValueType::ULLong),
+            10 => Some(ValueType::Float),
+            11 => Some(ValueType::Double),
+            12 => Some(ValueType::String),
+            13 => Some(ValueType::Bytes),
+            14 => Some(ValueType::Container),
+            15 => Some(ValueType::Array),
+            16 => Some(ValueType::Decimal),
+            17 => Some(ValueType::Timestamp),
+            18 => Some(ValueType::Duration),
+            19 => Some(ValueType::Uuid),
             _ => None,
         }
     }
@@ -132,10 +182,14 @@ impl ValueType {
             ValueType::Bytes => "13",
             ValueType::Container => "14",
             ValueType::Array => "15",
+            ValueType::Decimal => "16",
+            ValueType::Timestamp => "17",
+            ValueType::Duration => "18",
+            ValueType::Uuid => "19",
         }
     }
 
-    /// Check if this is a numeric type (integer or float)
+    /// Check if this is a numeric type (integer, float or decimal)
     ///
     /// # Example
     /// ```
@@ -158,6 +212,7 @@ impl ValueType {
                 | ValueType::ULLong
                 | ValueType::Float
                 | ValueType::Double
+                | ValueType::Decimal
         )
     }
 
@@ -198,6 +253,22 @@ impl ValueType {
         matches!(self, ValueType::Float | ValueType::Double)
     }
 
+    /// Check if this is an extended type (code 16 and up)
+    ///
+    /// Extended types are unknown to C++ peers that predate them; see
+    /// [`TypeSupport`].
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::ValueType;
+    ///
+    /// assert!(ValueType::Decimal.is_extended());
+    /// assert!(!ValueType::Array.is_extended());
+    /// ```
+    pub fn is_extended(&self) -> bool {
+        *self as u8 >= 16
+    }
+
     /// Get size in bytes for fixed-size types
     ///
     /// Returns None for variable-size types (String, Bytes, Container).
@@ -221,6 +292,10 @@ impl ValueType {
             | ValueType::LLong
             | ValueType::ULLong
             | ValueType::Double => Some(8),
+            ValueType::Decimal => Some(17),
+            ValueType::Timestamp => Some(14),
+            ValueType::Duration => Some(12),
+            ValueType::Uuid => Some(16),
             ValueType::Bytes | ValueType::String | ValueType::Container | ValueType::Array => None,
         }
     }
@@ -248,11 +323,108 @@ impl fmt::Display for ValueType {
                 ValueType::Bytes => "bytes_value",
                 ValueType::Container => "container_value",
                 ValueType::Array => "array_value",
+                ValueType::Decimal => "decimal_value",
+                ValueType::Timestamp => "timestamp_value",
+                ValueType::Duration => "duration_value",
+                ValueType::Uuid => "uuid_value",
             }
         )
     }
 }
 
+/// Extended types, in code order
+const EXTENDED_TYPES: [ValueType; 4] = [
+    ValueType::Decimal,
+    ValueType::Timestamp,
+    ValueType::Duration,
+    ValueType::Uuid,
+];
+
+/// The value types a peer can decode
+///
+/// Every peer understands the core types (codes 0-15). Extended types are
+/// negotiated: a peer advertises the names of the extended types it supports
+/// ([`type_names`](Self::type_names)), and values of any other extended type
+/// are sent to it as `string_value` holding their canonical text (see
+/// [`ValueContainer::for_peer`](crate::core::ValueContainer::for_peer)).
+///
+/// # Example
+/// ```
+/// use rust_container_system::core::value_types::TypeSupport;
+/// use rust_container_system::ValueType;
+///
+/// let legacy = TypeSupport::CORE;
+/// assert!(!legacy.supports(ValueType::Decimal));
+///
+/// let advertised = TypeSupport::ALL.type_names();
+/// assert_eq!(
+///     advertised,
+///     ["decimal_value", "timestamp_value", "duration_value", "uuid_value"]
+/// );
+/// assert_eq!(TypeSupport::from_type_names(advertised), TypeSupport::ALL);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeSupport {
+    /// Bit `code - 16` is set for each supported extended type
+    extended: u32,
+}
+
+impl TypeSupport {
+    /// Core types only (C++ peers without extended types)
+    pub const CORE: Self = Self { extended: 0 };
+
+    /// Every type this crate implements
+    pub const ALL: Self = Self {
+        extended: (1 << EXTENDED_TYPES.len()) - 1,
+    };
+
+    fn bit(value_type: ValueType) -> u32 {
+        1 << (value_type as u8 - 16)
+    }
+
+    /// Add support for a type
+    #[must_use = "builder methods return a new value"]
+    pub fn with(mut self, value_type: ValueType) -> Self {
+        if value_type.is_extended() {
+            self.extended |= Self::bit(value_type);
+        }
+        self
+    }
+
+    /// Whether values of this type can be sent as they are
+    pub fn supports(&self, value_type: ValueType) -> bool {
+        !value_type.is_extended() || self.extended & Self::bit(value_type) != 0
+    }
+
+    /// Support parsed from advertised type names (`decimal_value`, ...)
+    ///
+    /// Core and unknown names are ignored.
+    pub fn from_type_names<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> Self {
+        names.into_iter().fold(Self::CORE, |support, name| {
+            EXTENDED_TYPES
+                .iter()
+                .find(|t| t.to_string() == name.as_ref())
+                .map_or(support, |&t| support.with(t))
+        })
+    }
+
+    /// Names of the supported extended types, to advertise to a peer
+    pub fn type_names(&self) -> Vec<String> {
+        EXTENDED_TYPES
+            .iter()
+            .filter(|t| self.supports(**t))
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+impl Default for TypeSupport {
+    /// Core types only, which every peer understands
+    fn default() -> Self {
+        Self::CORE
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +438,15 @@ mod tests {
         assert_eq!(ValueType::from_type_code("abc"), None);
     }
 
+    #[test]
+    fn test_value_type_from_u8() {
+        for byte in 0..=19u8 {
+            assert_eq!(ValueType::from_u8(byte).unwrap() as u8, byte);
+        }
+        assert_eq!(ValueType::from_u8(20), None);
+        assert_eq!(ValueType::from_u8(255), None);
+    }
+
     #[test]
     fn test_value_type_to_str() {
         assert_eq!(ValueType::Null.to_str(), "0");
@@ -282,6 +463,7 @@ mod tests {
         assert!(!ValueType::String.is_numeric());
         assert!(!ValueType::Bool.is_numeric());
         assert!(!ValueType::Bytes.is_numeric());
+        assert!(ValueType::Decimal.is_numeric());
     }
 
     #[test]
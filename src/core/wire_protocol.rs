@@ -50,6 +50,53 @@
 //! - long_value, ulong_value, llong_value, ullong_value
 //! - float_value, double_value, string_value, bytes_value
 //! - container_value (for nested containers)
+//! - array_value (for arrays)
+//!
+//! Extended types (see [`TypeSupport`](crate::core::value_types::TypeSupport)):
+//! - decimal_value (canonical decimal text, e.g. `-12.50`)
+//! - timestamp_value (RFC 3339, e.g. `2024-03-01T09:30:00.25+09:00`)
+//! - duration_value (ISO 8601, e.g. `PT1H30M`)
+//! - uuid_value (canonical text, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`)
+//!
+//! ## Escaping
+//!
+//! Header values, value names and string data are escaped with a backslash so that
+//! free text cannot be mistaken for protocol delimiters:
+//!
+//! | Character | Escaped |
+//! |-----------|---------|
+//! | `\` | `\\` |
+//! | `[` `]` | `\[` `\]` |
+//! | `,` `;` | `\,` `\;` |
+//! | line feed / carriage return | `\n` / `\r` |
+//!
+//! Text without these characters is emitted unchanged, so ordinary payloads stay
+//! byte-identical to C++ output. Unescaped input produced by C++ peers is still
+//! accepted: a name ends at the first unescaped `,`, data ends at the first
+//! unescaped `];`, and raw line breaks inside a field are kept.
+//!
+//! ## Error Handling
+//!
+//! `serialize_cpp_wire` and `deserialize_cpp_wire` skip values that cannot be
+//! serialized and ignore unknown header IDs. The `_with_mode` variants make this
+//! explicit: `WireMode::Strict` fails on the first such problem, while
+//! `WireMode::Lenient` records each one in a `WireReport`.
+//!
+//! ## Streaming
+//!
+//! `write_cpp_wire` writes to any `std::io::Write` and `read_cpp_wire` reads from any
+//! `std::io::Read`. Neither holds the whole message in memory: the reader rebuilds
+//! values item by item and buffers at most the item it is parsing.
+//!
+//! ## Nested Values
+//!
+//! `container_value` and `array_value` items carry their child count as data and
+//! are immediately followed by their children, serialized depth-first as regular
+//! items (matching the C++ container_system layout):
+//!
+//! ```text
+//! [user,container_value,2];[id,int_value,7];[tags,array_value,1];[,string_value,admin];
+//! ```
 //!
 //! # Example
 //!
@@ -74,7 +121,9 @@
 
 use crate::core::value_types::ValueType;
 use crate::core::{ContainerError, Result, Value, ValueContainer};
-use crate::values::ArrayValue;
+use crate::values::{ArrayValue, BytesValue, ContainerValue, StringValue};
+use std::borrow::Cow;
+use std::io::{Read, Write};
 use std::sync::Arc;
 
 // C++ header field IDs (matching container.cpp constants)
@@ -85,6 +134,77 @@ const SOURCE_SUB_ID: u8 = 4;
 const MESSAGE_TYPE: u8 = 5;
 const MESSAGE_VERSION: u8 = 6;
 
+/// Maximum nesting depth accepted when decoding container/array items
+///
+/// Guards against stack exhaustion from maliciously deep payloads.
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// How the wire codec handles values and header fields it cannot process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireMode {
+    /// Fail on the first problem, naming the offending value or header ID
+    Strict,
+    /// Skip problematic values and unknown header IDs, recording each one in a [`WireReport`]
+    #[default]
+    Lenient,
+}
+
+/// A problem that was tolerated in [`WireMode::Lenient`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireWarning {
+    /// A value could not be serialized and was left out of the output
+    SkippedValue {
+        /// Name of the skipped value
+        name: String,
+        /// Why serialization failed
+        reason: String,
+    },
+    /// A header field with an unrecognised ID was ignored
+    UnknownHeaderId {
+        /// The unrecognised header ID, which may be outside the range of known IDs
+        id: u64,
+        /// Byte offset of the header item in the input
+        offset: usize,
+    },
+}
+
+impl std::fmt::Display for WireWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WireWarning::SkippedValue { name, reason } => {
+                write!(f, "skipped value '{}': {}", name, reason)
+            }
+            WireWarning::UnknownHeaderId { id, offset } => {
+                write!(f, "ignored unknown header ID {} at byte {}", id, offset)
+            }
+        }
+    }
+}
+
+/// Warnings collected while encoding or decoding in [`WireMode::Lenient`]
+///
+/// Always empty in [`WireMode::Strict`], where the first problem is returned as an error.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WireReport {
+    warnings: Vec<WireWarning>,
+}
+
+impl WireReport {
+    /// All warnings, in the order they were encountered
+    pub fn warnings(&self) -> &[WireWarning] {
+        &self.warnings
+    }
+
+    /// True if nothing was skipped or ignored
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    fn push(&mut self, warning: WireWarning) {
+        self.warnings.push(warning);
+    }
+}
+
 /// Serialize a ValueContainer to C++ wire protocol format
 ///
 /// This produces byte-for-byte compatible output with C++ container_system.
@@ -94,16 +214,100 @@ const MESSAGE_VERSION: u8 = 6;
 /// @header={{[id,value];...}};@data={{[name,type,data];...}};
 /// ```
 ///
+/// Values that cannot be serialized are skipped; use [`serialize_cpp_wire_with_mode`]
+/// to fail on them or to find out which ones were dropped.
+///
 /// # Errors
 ///
 /// Returns error if:
 /// - Value serialization fails
 /// - Invalid UTF-8 in strings
 pub fn serialize_cpp_wire(container: &ValueContainer) -> Result<String> {
-    let mut result = String::with_capacity(512);
+    serialize_cpp_wire_with_mode(container, WireMode::Lenient).map(|(wire_data, _)| wire_data)
+}
+
+/// Serialize a ValueContainer to C++ wire protocol format with explicit error handling
+///
+/// # Example
+/// ```
+/// use rust_container_system::prelude::*;
+/// use rust_container_system::core::wire_protocol::{serialize_cpp_wire_with_mode, WireMode};
+/// use std::sync::Arc;
+///
+/// let mut container = ValueContainer::new();
+/// container.add_value(Arc::new(IntValue::new("count", 42))).unwrap();
+///
+/// let (wire_data, report) = serialize_cpp_wire_with_mode(&container, WireMode::Strict).unwrap();
+/// assert!(wire_data.contains("[count,int_value,42];"));
+/// assert!(report.is_clean());
+/// ```
+///
+/// # Errors
+///
+/// In [`WireMode::Strict`], returns [`ContainerError::SerializationError`] naming the first
+/// value that cannot be serialized. In [`WireMode::Lenient`] such values are skipped and
+/// listed in the returned [`WireReport`].
+pub fn serialize_cpp_wire_with_mode(
+    container: &ValueContainer,
+    mode: WireMode,
+) -> Result<(String, WireReport)> {
+    let mut out = Vec::with_capacity(512);
+    let report = write_cpp_wire_with_mode(container, &mut out, mode)?;
+    let wire_data = String::from_utf8(out).map_err(|e| {
+        ContainerError::SerializationError(format!("Wire output is not UTF-8: {}", e))
+    })?;
+    Ok((wire_data, report))
+}
+
+/// Write a ValueContainer in C++ wire protocol format to `writer`
+///
+/// Produces the same output as [`serialize_cpp_wire`] without building it in memory
+/// first: string data is escaped and bytes are hex-encoded straight into the writer.
+/// Output is written in many small pieces, so wrap unbuffered writers such as files
+/// or sockets in a [`std::io::BufWriter`].
+///
+/// # Example
+/// ```
+/// use rust_container_system::prelude::*;
+/// use rust_container_system::core::wire_protocol::{serialize_cpp_wire, write_cpp_wire};
+/// use std::sync::Arc;
+///
+/// let mut container = ValueContainer::new();
+/// container.add_value(Arc::new(BytesValue::new("blob", vec![0xAB; 4096]))).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_cpp_wire(&container, &mut out).unwrap();
+/// assert_eq!(out, serialize_cpp_wire(&container).unwrap().into_bytes());
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ContainerError::IoError`] if the writer fails; the writer may then hold a
+/// partial message.
+pub fn write_cpp_wire<W: Write + ?Sized>(container: &ValueContainer, writer: &mut W) -> Result<()> {
+    write_cpp_wire_with_mode(container, writer, WireMode::Lenient).map(|_| ())
+}
+
+/// Write a ValueContainer in C++ wire protocol format to `writer` with explicit error handling
+///
+/// Each value is checked before any of it is written, so in [`WireMode::Lenient`] a
+/// value that cannot be serialized is skipped cleanly even though earlier output has
+/// already reached the writer.
+///
+/// # Errors
+///
+/// Same as [`serialize_cpp_wire_with_mode`], plus [`ContainerError::IoError`] if the
+/// writer fails. After an error the writer may hold a partial message.
+pub fn write_cpp_wire_with_mode<W: Write + ?Sized>(
+    container: &ValueContainer,
+    writer: &mut W,
+    mode: WireMode,
+) -> Result<WireReport> {
+    let mut report = WireReport::default();
+    let mut header = String::with_capacity(256);
 
     // Serialize header
-    result.push_str("@header={{");
+    header.push_str("@header={{");
 
     // Only include routing fields if message_type is not "data_container"
     let msg_type = container.message_type();
@@ -114,57 +318,105 @@ pub fn serialize_cpp_wire(container: &ValueContainer) -> Result<String> {
         let source_sub_id = container.source_sub_id();
 
         if !target_id.is_empty() || !target_sub_id.is_empty() {
-            result.push_str(&format!("[{},{}];", TARGET_ID, target_id));
-            result.push_str(&format!("[{},{}];", TARGET_SUB_ID, target_sub_id));
+            header.push_str(&format!("[{},{}];", TARGET_ID, escape_wire(&target_id)));
+            header.push_str(&format!(
+                "[{},{}];",
+                TARGET_SUB_ID,
+                escape_wire(&target_sub_id)
+            ));
         }
         if !source_id.is_empty() || !source_sub_id.is_empty() {
-            result.push_str(&format!("[{},{}];", SOURCE_ID, source_id));
-            result.push_str(&format!("[{},{}];", SOURCE_SUB_ID, source_sub_id));
+            header.push_str(&format!("[{},{}];", SOURCE_ID, escape_wire(&source_id)));
+            header.push_str(&format!(
+                "[{},{}];",
+                SOURCE_SUB_ID,
+                escape_wire(&source_sub_id)
+            ));
         }
     }
 
     // Always include message_type and version
-    result.push_str(&format!("[{},{}];", MESSAGE_TYPE, msg_type));
-    result.push_str(&format!("[{},{}];", MESSAGE_VERSION, container.version()));
-    result.push_str("}};");
+    header.push_str(&format!("[{},{}];", MESSAGE_TYPE, escape_wire(&msg_type)));
+    header.push_str(&format!(
+        "[{},{}];",
+        MESSAGE_VERSION,
+        escape_wire(&container.version())
+    ));
+    header.push_str("}};");
 
     // Serialize data
-    result.push_str("@data={{");
+    header.push_str("@data={{");
+    writer.write_all(header.as_bytes())?;
 
-    // Serialize all values
-    container.with_values(|values| {
-        for value in values {
-            // Skip values that fail to serialize (e.g., types not yet supported)
-            // Future work: Add full support for all numeric types
-            if let Ok(serialized) = serialize_value_cpp(value) {
-                result.push_str(&serialized);
+    // Snapshot the values so the container is not locked while writing
+    for value in container.values() {
+        if let Err(e) = check_value_cpp(&value) {
+            match mode {
+                WireMode::Strict => {
+                    return Err(ContainerError::SerializationError(format!(
+                        "Value '{}' ({}) cannot be serialized: {}",
+                        value.name(),
+                        value_type_to_cpp_name(value.value_type()),
+                        e
+                    )));
+                }
+                WireMode::Lenient => {
+                    report.push(WireWarning::SkippedValue {
+                        name: value.name().to_string(),
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
             }
         }
-    });
+        write_value_cpp(&value, writer)?;
+    }
 
-    result.push_str("}};");
+    writer.write_all(b"}};")?;
 
-    Ok(result)
+    Ok(report)
 }
 
-/// Serialize a single value to C++ wire protocol format
-///
-/// # Format
-/// ```text
-/// [name,type_name,data];
-/// ```
-///
-/// Examples:
-/// - `[count,int_value,42];`
-/// - `[name,string_value,Alice];`
-/// - `[data,bytes_value,48656c6c6f];` (hex-encoded bytes)
-fn serialize_value_cpp(value: &Arc<dyn Value>) -> Result<String> {
+/// Data field of a value, borrowed from the value where possible
+enum WireData<'v> {
+    /// Formatted number or boolean, escaped string, or child count
+    Text(Cow<'v, str>),
+    /// Raw bytes, written as hex
+    Hex(Cow<'v, [u8]>),
+}
+
+/// Compute the data field of a value and, for containers and arrays, its children
+fn wire_data_cpp(value: &Arc<dyn Value>) -> Result<(WireData<'_>, &[Arc<dyn Value>])> {
     let name = value.name();
     let value_type = value.value_type();
-    let type_name = value_type_to_cpp_name(value_type);
+
+    // Nested values are written as "[name,type,count];" followed by their children
+    let children: &[Arc<dyn Value>] = match value_type {
+        ValueType::Container => value
+            .as_any()
+            .downcast_ref::<ContainerValue>()
+            .ok_or_else(|| {
+                ContainerError::SerializationError(format!(
+                    "Value '{}' reports container_value but is not a ContainerValue",
+                    name
+                ))
+            })?
+            .children(),
+        ValueType::Array => value
+            .as_any()
+            .downcast_ref::<ArrayValue>()
+            .ok_or_else(|| {
+                ContainerError::SerializationError(format!(
+                    "Value '{}' reports array_value but is not an ArrayValue",
+                    name
+                ))
+            })?
+            .elements(),
+        _ => &[],
+    };
 
     // Serialize data based on type
-    let data_str = match value_type {
+    let text = match value_type {
         ValueType::Bool => if value.to_bool()? { "true" } else { "false" }.to_string(),
         ValueType::Short => value.to_short()?.to_string(),
         ValueType::UShort => value.to_ushort()?.to_string(),
@@ -174,35 +426,73 @@ fn serialize_value_cpp(value: &Arc<dyn Value>) -> Result<String> {
         ValueType::ULong | ValueType::ULLong => value.to_ulong()?.to_string(),
         ValueType::Float => value.to_float()?.to_string(),
         ValueType::Double => value.to_double()?.to_string(),
-        ValueType::String => value.to_string(),
-        ValueType::Bytes => {
-            // Convert bytes to hex string (matching C++ hex encoding)
-            use crate::values::BytesValue;
-            if let Some(bytes_val) = value.as_any().downcast_ref::<BytesValue>() {
-                bytes_to_hex(bytes_val.data())
-            } else {
-                // Fallback to generic to_bytes() if downcast fails
-                let bytes = value.to_bytes();
-                bytes_to_hex(&bytes)
-            }
+        // Canonical decimal, time and UUID text never needs escaping
+        ValueType::Decimal | ValueType::Timestamp | ValueType::Duration | ValueType::Uuid => {
+            value.to_string()
         }
-        ValueType::Container => {
-            // For containers, store child count (matching C++ behavior)
-            // Note: child_count() support requires ContainerValue implementation
-            "0".to_string() // Placeholder - full support requires ContainerValue
+        ValueType::String => {
+            return Ok(match value.as_any().downcast_ref::<StringValue>() {
+                Some(string) => (WireData::Text(escape_wire(string.value())), children),
+                None => {
+                    let text = value.to_string();
+                    let text = match escape_wire(&text) {
+                        Cow::Owned(escaped) => escaped,
+                        Cow::Borrowed(_) => text,
+                    };
+                    (WireData::Text(Cow::Owned(text)), children)
+                }
+            });
         }
-        ValueType::Array => {
-            // For arrays, store element count (matching ArrayValue behavior)
-            if let Some(array_val) = value.as_any().downcast_ref::<ArrayValue>() {
-                array_val.count().to_string()
-            } else {
-                "0".to_string()
-            }
+        ValueType::Bytes => {
+            // Bytes are hex-encoded (matching C++ hex encoding)
+            let bytes = match value.as_any().downcast_ref::<BytesValue>() {
+                Some(bytes_val) => Cow::Borrowed(bytes_val.data()),
+                // Fallback to generic to_bytes() if downcast fails
+                None => Cow::Owned(value.to_bytes()),
+            };
+            return Ok((WireData::Hex(bytes), children));
         }
+        ValueType::Container | ValueType::Array => children.len().to_string(),
         ValueType::Null => String::new(),
     };
 
-    Ok(format!("[{},{},{}];", name, type_name, data_str))
+    Ok((WireData::Text(Cow::Owned(text)), children))
+}
+
+/// Check that a value and all of its descendants can be serialized, without writing anything
+fn check_value_cpp(value: &Arc<dyn Value>) -> Result<()> {
+    let (_, children) = wire_data_cpp(value)?;
+    children.iter().try_for_each(check_value_cpp)
+}
+
+/// Write a value (and, for containers and arrays, all of its descendants) to `out`
+///
+/// # Format
+/// ```text
+/// [name,type_name,data];
+/// ```
+///
+/// Examples:
+/// - `[count,int_value,42];`
+/// - `[name,string_value,Alice];`
+/// - `[data,bytes_value,48656c6c6f];` (hex-encoded bytes)
+/// - `[user,container_value,1];[id,int_value,7];` (child count, then children)
+fn write_value_cpp<W: Write + ?Sized>(value: &Arc<dyn Value>, out: &mut W) -> Result<()> {
+    let (data, children) = wire_data_cpp(value)?;
+    let type_name = value_type_to_cpp_name(value.value_type());
+
+    write!(out, "[{},{},", escape_wire(value.name()), type_name)?;
+    match data {
+        WireData::Text(text) => out.write_all(text.as_bytes())?,
+        WireData::Hex(bytes) => write_hex(&bytes, out)?,
+    }
+    out.write_all(b"];")?;
+
+    for child in children {
+        write_value_cpp(child, out)?;
+    }
+
+    Ok(())
 }
 
 /// Convert ValueType to C++ type name string
@@ -224,6 +514,10 @@ fn value_type_to_cpp_name(vt: ValueType) -> &'static str {
         ValueType::Container => "container_value",
         ValueType::Array => "array_value",
         ValueType::Null => "null_value",
+        ValueType::Decimal => "decimal_value",
+        ValueType::Timestamp => "timestamp_value",
+        ValueType::Duration => "duration_value",
+        ValueType::Uuid => "uuid_value",
     }
 }
 
@@ -246,28 +540,51 @@ fn cpp_name_to_value_type(name: &str) -> Option<ValueType> {
         "container_value" => Some(ValueType::Container),
         "array_value" => Some(ValueType::Array),
         "null_value" => Some(ValueType::Null),
+        "decimal_value" => Some(ValueType::Decimal),
+        "timestamp_value" => Some(ValueType::Timestamp),
+        "duration_value" => Some(ValueType::Duration),
+        "uuid_value" => Some(ValueType::Uuid),
         _ => None,
     }
 }
 
-/// Convert bytes to hex string (uppercase, matching C++ format)
-fn bytes_to_hex(bytes: &[u8]) -> String {
-    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+/// Write bytes as a lowercase hex string (matching C++ format) in bounded chunks
+fn write_hex<W: Write + ?Sized>(bytes: &[u8], out: &mut W) -> std::io::Result<()> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut buf = [0u8; 1024];
+    for chunk in bytes.chunks(buf.len() / 2) {
+        for (i, b) in chunk.iter().enumerate() {
+            buf[2 * i] = DIGITS[(b >> 4) as usize];
+            buf[2 * i + 1] = DIGITS[(b & 0x0f) as usize];
+        }
+        out.write_all(&buf[..chunk.len() * 2])?;
+    }
+    Ok(())
 }
 
 /// Convert hex string to bytes
+///
+/// Works on the raw bytes so that non-ASCII input is an error, not a panic.
 fn hex_to_bytes(hex: &str) -> Result<Vec<u8>> {
+    fn digit(c: u8) -> Option<u8> {
+        (c as char).to_digit(16).map(|d| d as u8)
+    }
+
+    let hex = hex.as_bytes();
     if !hex.len().is_multiple_of(2) {
         return Err(ContainerError::InvalidDataFormat(
             "Hex string must have even length".to_string(),
         ));
     }
 
-    (0..hex.len())
-        .step_by(2)
-        .map(|i| {
-            u8::from_str_radix(&hex[i..i + 2], 16)
-                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid hex byte: {}", e)))
+    hex.chunks_exact(2)
+        .enumerate()
+        .map(|(i, pair)| match (digit(pair[0]), digit(pair[1])) {
+            (Some(high), Some(low)) => Ok((high << 4) | low),
+            _ => Err(ContainerError::InvalidDataFormat(format!(
+                "Invalid hex digit at position {}",
+                i * 2
+            ))),
         })
         .collect()
 }
@@ -282,182 +599,868 @@ fn hex_to_bytes(hex: &str) -> Result<Vec<u8>> {
 /// @header={{[id,value];...}};@data={{[name,type,data];...}};
 /// ```
 ///
+/// Unknown header IDs are ignored; use [`deserialize_cpp_wire_with_mode`] to reject them
+/// or to find out which ones were seen.
+///
 /// # Errors
 ///
-/// Returns error if:
-/// - Protocol format is invalid
-/// - Required fields are missing
+/// Returns [`ContainerError::WireFormat`] with the byte offset of the problem if:
+/// - Protocol format is invalid (unterminated items, bad escapes, stray characters)
+/// - A header ID is not a number or a type name is not recognised
 /// - Value parsing fails
 pub fn deserialize_cpp_wire(wire_data: &str) -> Result<ValueContainer> {
-    use crate::values::*;
+    deserialize_cpp_wire_with_mode(wire_data, WireMode::Lenient).map(|(container, _)| container)
+}
 
-    // Remove newlines for easier parsing
-    let clean_data = wire_data.replace("\r\n", "").replace('\n', "");
+/// Deserialize a ValueContainer from C++ wire protocol format with explicit error handling
+///
+/// # Example
+/// ```
+/// use rust_container_system::core::wire_protocol::{
+///     deserialize_cpp_wire_with_mode, WireMode, WireWarning,
+/// };
+///
+/// let wire_data = "@header={{[5,event];[9,extra];}};@data={{[count,int_value,42];}};";
+///
+/// let (container, report) = deserialize_cpp_wire_with_mode(wire_data, WireMode::Lenient).unwrap();
+/// assert_eq!(container.value_count(), 1);
+/// assert!(matches!(report.warnings()[0], WireWarning::UnknownHeaderId { id: 9, .. }));
+///
+/// assert!(deserialize_cpp_wire_with_mode(wire_data, WireMode::Strict).is_err());
+/// ```
+///
+/// # Errors
+///
+/// Same as [`deserialize_cpp_wire`]. In addition, [`WireMode::Strict`] returns
+/// [`ContainerError::WireFormat`] naming the first unknown header ID.
+pub fn deserialize_cpp_wire_with_mode(
+    wire_data: &str,
+    mode: WireMode,
+) -> Result<(ValueContainer, WireReport)> {
+    let mut tokenizer = WireTokenizer::new();
+    let mut builder = WireMessageBuilder::new(mode);
+    tokenizer.drive(wire_data.as_bytes(), true, &mut builder)?;
+    builder.finish()
+}
 
-    // Parse header section
-    let header_regex = regex::Regex::new(r"@header=\s*\{\{?\s*(.*?)\s*\}\}?;")
-        .map_err(|e| ContainerError::InvalidDataFormat(format!("Regex error: {}", e)))?;
+/// Read a ValueContainer in C++ wire protocol format from `reader`
+///
+/// Reads until the end of the input. Values are rebuilt item by item as the input
+/// arrives, so only the item being parsed is buffered rather than the whole message.
+///
+/// # Example
+/// ```
+/// use rust_container_system::core::wire_protocol::read_cpp_wire;
+///
+/// let wire_data = "@header={{[5,event];}};@data={{[count,int_value,42];}};";
+/// let container = read_cpp_wire(&mut wire_data.as_bytes()).unwrap();
+/// assert_eq!(container.get_value("count").unwrap().to_int().unwrap(), 42);
+/// ```
+///
+/// # Errors
+///
+/// Same as [`deserialize_cpp_wire`], plus [`ContainerError::IoError`] if the reader
+/// fails. Input that is not valid UTF-8 is rejected with [`ContainerError::WireFormat`].
+pub fn read_cpp_wire<R: Read + ?Sized>(reader: &mut R) -> Result<ValueContainer> {
+    read_cpp_wire_with_mode(reader, WireMode::Lenient).map(|(container, _)| container)
+}
 
-    let mut source_id = String::new();
-    let mut source_sub_id = String::new();
-    let mut target_id = String::new();
-    let mut target_sub_id = String::new();
-    let mut message_type = String::from("data_container");
-    let mut _version = String::from("1.0.0.0"); // Stored but not used (API limitation)
+/// Read a ValueContainer in C++ wire protocol format from `reader` with explicit error handling
+///
+/// # Errors
+///
+/// Same as [`deserialize_cpp_wire_with_mode`], plus [`ContainerError::IoError`] if the
+/// reader fails.
+pub fn read_cpp_wire_with_mode<R: Read + ?Sized>(
+    reader: &mut R,
+    mode: WireMode,
+) -> Result<(ValueContainer, WireReport)> {
+    let mut tokenizer = WireTokenizer::new();
+    let mut builder = WireMessageBuilder::new(mode);
+    let mut buf = Vec::new();
+    let mut eof = false;
 
-    if let Some(header_match) = header_regex.captures(&clean_data) {
-        let header_content = header_match.get(1).map(|m| m.as_str()).unwrap_or("");
+    while !tokenizer.drive(&buf, eof, &mut builder)? {
+        tokenizer.compact(&mut buf);
+        // Read at least as much as is still buffered so re-scanning a large item stays linear
+        let want = READ_CHUNK_SIZE.max(buf.len());
+        let read = (&mut *reader).take(want as u64).read_to_end(&mut buf)?;
+        eof = read < want;
+    }
+    builder.finish()
+}
 
-        // Parse header pairs: [id,value];
-        let pair_regex = regex::Regex::new(r"\[(\d+),(.*?)\];")
-            .map_err(|e| ContainerError::InvalidDataFormat(format!("Regex error: {}", e)))?;
+/// Escape protocol delimiters in free text (see the module-level "Escaping" section)
+fn escape_wire(text: &str) -> Cow<'_, str> {
+    if !text
+        .bytes()
+        .any(|b| matches!(b, b'\\' | b'[' | b']' | b',' | b';' | b'\n' | b'\r'))
+    {
+        return Cow::Borrowed(text);
+    }
 
-        for cap in pair_regex.captures_iter(header_content) {
-            let id: u8 = cap[1].parse().map_err(|e| {
-                ContainerError::InvalidDataFormat(format!("Invalid header ID: {}", e))
-            })?;
-            let value = cap[2].trim();
-
-            match id {
-                TARGET_ID => target_id = value.to_string(),
-                TARGET_SUB_ID => target_sub_id = value.to_string(),
-                SOURCE_ID => source_id = value.to_string(),
-                SOURCE_SUB_ID => source_sub_id = value.to_string(),
-                MESSAGE_TYPE => message_type = value.to_string(),
-                MESSAGE_VERSION => _version = value.to_string(),
-                _ => {} // Ignore unknown IDs
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\\' | '[' | ']' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
             }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
         }
     }
+    Cow::Owned(escaped)
+}
 
-    // Create container with header
-    let mut container = ValueContainer::new();
-    container.set_source(source_id, source_sub_id);
-    container.set_target(target_id, target_sub_id);
-    container.set_message_type(message_type);
-    // Note: Version field is readonly, using default "1.0.0.0"
-    // Full version setting would require adding set_version() to ValueContainer API
+/// Bytes requested from a reader at a time when streaming
+const READ_CHUNK_SIZE: usize = 64 * 1024;
 
-    // Parse data section
-    let data_regex = regex::Regex::new(r"@data=\s*\{\{?\s*(.*?)\s*\}\}?;")
-        .map_err(|e| ContainerError::InvalidDataFormat(format!("Regex error: {}", e)))?;
+/// A single `[id,value];` pair from the header section
+struct HeaderField<'a> {
+    id: Cow<'a, str>,
+    value: Cow<'a, str>,
+    /// Byte offset of the opening `[`
+    offset: usize,
+}
 
-    if let Some(data_match) = data_regex.captures(&clean_data) {
-        let data_content = data_match.get(1).map(|m| m.as_str()).unwrap_or("");
+/// A single `[name,type,data];` item from the data section
+struct WireItem<'a> {
+    name: Cow<'a, str>,
+    type_name: Cow<'a, str>,
+    data: Cow<'a, str>,
+    /// Byte offset of the opening `[`
+    offset: usize,
+    /// Byte offset of the first byte of the type field
+    type_offset: usize,
+    /// Byte offset of the first byte of the data field
+    data_offset: usize,
+}
 
-        // Parse value items: [name,type,data];
-        let item_regex = regex::Regex::new(r"\[(\w+),\s*(\w+),\s*(.*?)\];")
-            .map_err(|e| ContainerError::InvalidDataFormat(format!("Regex error: {}", e)))?;
+#[derive(Clone, Copy)]
+enum SectionKind {
+    Header,
+    Data,
+}
 
-        for cap in item_regex.captures_iter(data_content) {
-            let name = &cap[1];
-            let type_name = &cap[2];
-            let data_str = &cap[3];
+/// The section the tokenizer is inside of, with its terminator (`}};` or `};`)
+#[derive(Clone, Copy)]
+struct OpenSection {
+    kind: SectionKind,
+    close: &'static str,
+}
 
-            let value_type = cpp_name_to_value_type(type_name).ok_or_else(|| {
-                ContainerError::InvalidDataFormat(format!("Unknown C++ type name: {}", type_name))
-            })?;
+/// One complete token of a wire message
+enum WireToken<'a> {
+    Open(OpenSection),
+    Close,
+    Header(HeaderField<'a>),
+    Item(WireItem<'a>),
+    /// The input ended between sections
+    End,
+}
 
-            // Parse value based on type
-            let parsed_value: Arc<dyn Value> = match value_type {
-                ValueType::Bool => {
-                    let val = data_str == "true";
-                    Arc::new(BoolValue::new(name, val))
-                }
-                ValueType::Short => {
-                    let val: i16 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid short: {}", e))
-                    })?;
-                    Arc::new(ShortValue::new(name, val))
-                }
-                ValueType::UShort => {
-                    let val: u16 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid ushort: {}", e))
-                    })?;
-                    Arc::new(UShortValue::new(name, val))
-                }
-                ValueType::Int => {
-                    let val: i32 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid int: {}", e))
-                    })?;
-                    Arc::new(IntValue::new(name, val))
-                }
-                ValueType::UInt => {
-                    let val: u32 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid uint: {}", e))
-                    })?;
-                    Arc::new(UIntValue::new(name, val))
-                }
-                ValueType::Long => {
-                    let val: i64 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid long: {}", e))
-                    })?;
-                    Arc::new(LongValue::new(name, val)?)
-                }
-                ValueType::LLong => {
-                    let val: i64 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid llong: {}", e))
-                    })?;
-                    Arc::new(LLongValue::new(name, val))
+/// Resumable single-pass tokenizer for the wire protocol
+///
+/// Walks the input once, unescaping fields as it goes. Fields without escapes
+/// borrow from the input; only escaped fields allocate. The input may arrive in
+/// pieces: a token that runs past the end of the buffer is left unconsumed until
+/// more input is available. All reported offsets are byte positions in the whole
+/// input.
+pub(crate) struct WireTokenizer {
+    /// Offset of the start of the current buffer within the whole input
+    base: usize,
+    /// Position of the first unconsumed byte within the current buffer
+    pos: usize,
+    section: Option<OpenSection>,
+    /// Whether a section has been opened since the last `@data` section closed
+    started: bool,
+    /// Where the previous scan of a pending last field stopped (absolute field start
+    /// and scan position), so a large field is not re-scanned from its start
+    resume: Option<(usize, usize)>,
+}
+
+/// Why [`WireTokenizer::advance`] stopped
+pub(crate) enum WireProgress {
+    /// The buffer ends inside a token
+    NeedMoreData,
+    /// A `@data` section was closed
+    DataClosed,
+    /// The input ended between sections
+    End,
+}
+
+impl WireTokenizer {
+    pub(crate) fn new() -> Self {
+        Self {
+            base: 0,
+            pos: 0,
+            section: None,
+            started: false,
+            resume: None,
+        }
+    }
+
+    /// Feed every complete token in `buf` to `builder`
+    ///
+    /// `buf` holds the unconsumed input from the previous call followed by any new
+    /// input; `eof` marks that no more input will follow. Returns true once the input
+    /// has ended, which only happens when `eof` is set.
+    fn drive(&mut self, buf: &[u8], eof: bool, builder: &mut WireMessageBuilder) -> Result<bool> {
+        loop {
+            match self.advance(buf, eof, builder)? {
+                WireProgress::NeedMoreData => return Ok(false),
+                WireProgress::DataClosed => {}
+                WireProgress::End => return Ok(true),
+            }
+        }
+    }
+
+    /// Feed complete tokens in `buf` to `builder` until the buffer runs out, a `@data`
+    /// section closes, or the input ends
+    pub(crate) fn advance(
+        &mut self,
+        buf: &[u8],
+        eof: bool,
+        builder: &mut WireMessageBuilder,
+    ) -> Result<WireProgress> {
+        loop {
+            let mut cursor = WireCursor {
+                buf,
+                pos: self.pos,
+                base: self.base,
+                eof,
+                resume: self.resume,
+            };
+            let token = cursor.token(self.section)?;
+            self.resume = cursor.resume;
+            let Some(token) = token else {
+                return Ok(WireProgress::NeedMoreData);
+            };
+            self.pos = cursor.pos;
+
+            match token {
+                WireToken::Open(section) => {
+                    self.section = Some(section);
+                    self.started = true;
                 }
-                ValueType::ULong => {
-                    let val: u64 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid ulong: {}", e))
-                    })?;
-                    Arc::new(ULongValue::new(name, val)?)
+                WireToken::Close => {
+                    let kind = self.section.take().map(|section| section.kind);
+                    if matches!(kind, Some(SectionKind::Data)) {
+                        self.started = false;
+                        return Ok(WireProgress::DataClosed);
+                    }
                 }
-                ValueType::ULLong => {
-                    let val: u64 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid ullong: {}", e))
-                    })?;
-                    Arc::new(ULLongValue::new(name, val))
+                WireToken::Header(field) => builder.header(field)?,
+                WireToken::Item(item) => builder.item(item)?,
+                WireToken::End => return Ok(WireProgress::End),
+            }
+        }
+    }
+
+    /// Drop the consumed prefix of `buf`
+    pub(crate) fn compact(&mut self, buf: &mut Vec<u8>) {
+        buf.drain(..self.pos);
+        self.base += self.pos;
+        self.pos = 0;
+    }
+
+    /// Check whether part of a message has been consumed or is still buffered in `buf`
+    pub(crate) fn in_message(&self, buf: &[u8]) -> bool {
+        self.started || buf[self.pos..].iter().any(|b| !b.is_ascii_whitespace())
+    }
+}
+
+/// Cursor over the buffered input for one token
+///
+/// Scanning methods return `Ok(None)` when the token runs past the end of the buffer
+/// and more input may follow; the caller then leaves the token unconsumed.
+struct WireCursor<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    base: usize,
+    eof: bool,
+    resume: Option<(usize, usize)>,
+}
+
+impl<'a> WireCursor<'a> {
+    fn error(&self, pos: usize, message: impl Into<String>) -> ContainerError {
+        ContainerError::WireFormat {
+            offset: self.base + pos,
+            message: message.into(),
+        }
+    }
+
+    /// Describe the character at `pos` for error messages
+    fn describe_at(&self, pos: usize) -> String {
+        let rest = self.buf.get(pos..).unwrap_or_default();
+        match String::from_utf8_lossy(&rest[..rest.len().min(4)])
+            .chars()
+            .next()
+        {
+            Some(c) => format!("'{}'", c.escape_default()),
+            None => "end of input".to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .buf
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume `token` if the input starts with it
+    fn eat(&mut self, token: &str) -> Option<bool> {
+        let rest = &self.buf[self.pos..];
+        if rest.starts_with(token.as_bytes()) {
+            self.pos += token.len();
+            Some(true)
+        } else if !self.eof && token.as_bytes().starts_with(rest) {
+            None
+        } else {
+            Some(false)
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<Option<()>> {
+        match self.eat(token) {
+            Some(true) => Ok(Some(())),
+            Some(false) => Err(self.error(
+                self.pos,
+                format!("expected '{}', found {}", token, self.describe_at(self.pos)),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Scan the next token; `@header=` and `@data=` sections may come in either order
+    fn token(&mut self, section: Option<OpenSection>) -> Result<Option<WireToken<'a>>> {
+        self.skip_whitespace();
+        let Some(section) = section else {
+            return self.section_start();
+        };
+
+        let Some(more) = self.next_item(section.close)? else {
+            return Ok(None);
+        };
+        if !more {
+            return Ok(Some(WireToken::Close));
+        }
+
+        let offset = self.pos - 1;
+        let token = match section.kind {
+            SectionKind::Header => {
+                let Some((id, _)) = self.read_field(false, offset)? else {
+                    return Ok(None);
+                };
+                let Some((value, _)) = self.read_field(true, offset)? else {
+                    return Ok(None);
+                };
+                WireToken::Header(HeaderField {
+                    id,
+                    value,
+                    offset: self.base + offset,
+                })
+            }
+            SectionKind::Data => {
+                let Some((name, _)) = self.read_field(false, offset)? else {
+                    return Ok(None);
+                };
+                let Some((type_name, type_offset)) = self.read_field(false, offset)? else {
+                    return Ok(None);
+                };
+                let Some((data, data_offset)) = self.read_field(true, offset)? else {
+                    return Ok(None);
+                };
+                WireToken::Item(WireItem {
+                    name,
+                    type_name,
+                    data,
+                    offset: self.base + offset,
+                    type_offset: self.base + type_offset,
+                    data_offset: self.base + data_offset,
+                })
+            }
+        };
+        Ok(Some(token))
+    }
+
+    /// Scan `@header={{` or `@data={{` (or the end of the input)
+    fn section_start(&mut self) -> Result<Option<WireToken<'a>>> {
+        if self.pos >= self.buf.len() {
+            return Ok(self.eof.then_some(WireToken::End));
+        }
+
+        let section_start = self.pos;
+        let Some(is_header) = self.eat("@header=") else {
+            return Ok(None);
+        };
+        let kind = if is_header {
+            SectionKind::Header
+        } else {
+            match self.eat("@data=") {
+                Some(true) => SectionKind::Data,
+                Some(false) => {
+                    return Err(self.error(
+                        section_start,
+                        format!(
+                            "expected '@header=' or '@data=', found {}",
+                            self.describe_at(section_start)
+                        ),
+                    ));
                 }
-                ValueType::Float => {
-                    let val: f32 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid float: {}", e))
-                    })?;
-                    Arc::new(FloatValue::new(name, val))
+                None => return Ok(None),
+            }
+        };
+
+        // Consume the opening `{` or `{{` and pick the matching terminator
+        self.skip_whitespace();
+        if self.expect("{")?.is_none() {
+            return Ok(None);
+        }
+        let Some(double) = self.eat("{") else {
+            return Ok(None);
+        };
+        let close = if double { "}};" } else { "};" };
+        Ok(Some(WireToken::Open(OpenSection { kind, close })))
+    }
+
+    /// Advance to the next item, returning `false` once the section terminator is consumed
+    fn next_item(&mut self, close: &str) -> Result<Option<bool>> {
+        self.skip_whitespace();
+        match self.buf.get(self.pos) {
+            Some(b'[') => {
+                self.pos += 1;
+                return Ok(Some(true));
+            }
+            Some(b'}') => return Ok(self.expect(close)?.map(|_| false)),
+            None if !self.eof => return Ok(None),
+            _ => {}
+        }
+        Err(self.error(
+            self.pos,
+            format!(
+                "expected '[' or '{}', found {}",
+                close,
+                self.describe_at(self.pos)
+            ),
+        ))
+    }
+
+    /// Read one field of an item, returning its unescaped text and starting position
+    ///
+    /// Inner fields end at an unescaped `,`. The last field ends at an unescaped `]`
+    /// immediately followed by `;`; any other unescaped `[`, `]`, `,` or `;` in it is
+    /// taken literally so that unescaped C++ output still parses. Raw line breaks are
+    /// kept as data, like any other unescaped character.
+    fn read_field(
+        &mut self,
+        last: bool,
+        item_start: usize,
+    ) -> Result<Option<(Cow<'a, str>, usize)>> {
+        let start = self.pos;
+        let mut owned: Option<Vec<u8>> = None;
+        let mut segment_start = start;
+
+        // Skip the part of an unescaped last field that an earlier call already scanned
+        if last {
+            if let Some((field_start, scanned)) = self.resume.take() {
+                if field_start == self.base + start {
+                    self.pos = scanned - self.base;
                 }
-                ValueType::Double => {
-                    let val: f64 = data_str.parse().map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid double: {}", e))
-                    })?;
-                    Arc::new(DoubleValue::new(name, val))
+            }
+        }
+
+        loop {
+            let Some(&b) = self.buf.get(self.pos) else {
+                if !self.eof {
+                    self.pend(last, &owned, start);
+                    return Ok(None);
                 }
-                ValueType::String => Arc::new(StringValue::new(name, data_str)),
-                ValueType::Bytes => {
-                    let bytes = hex_to_bytes(data_str)?;
-                    Arc::new(BytesValue::new(name, bytes))
+                return Err(self.error(item_start, "unterminated item: reached end of input"));
+            };
+
+            let end = match b {
+                b',' if !last => Some(1),
+                b']' if last => match self.buf.get(self.pos + 1) {
+                    Some(b';') => Some(2),
+                    None if !self.eof => {
+                        self.pend(last, &owned, start);
+                        return Ok(None);
+                    }
+                    _ => None,
+                },
+                b'[' | b']' | b';' if !last => {
+                    return Err(
+                        self.error(self.pos, format!("unexpected '{}' before ','", b as char))
+                    );
                 }
-                ValueType::Array => {
-                    // Array format in wire protocol: just stores count
-                    // Full nested array support would require format change
-                    let _count: usize = data_str.parse().unwrap_or(0);
-                    Arc::new(ArrayValue::new(name, vec![]))
+                _ => None,
+            };
+
+            if let Some(terminator_len) = end {
+                let field_end = self.pos;
+                self.pos += terminator_len;
+                let text = match owned {
+                    Some(mut text) => {
+                        text.extend_from_slice(&self.buf[segment_start..field_end]);
+                        String::from_utf8(text)
+                            .map(Cow::Owned)
+                            .map_err(|e| e.utf8_error())
+                    }
+                    None => std::str::from_utf8(&self.buf[start..field_end]).map(Cow::Borrowed),
+                };
+                let text =
+                    text.map_err(|e| self.error(start, format!("invalid UTF-8 in field: {}", e)))?;
+                return Ok(Some((text, start)));
+            }
+
+            match b {
+                b'\\' => {
+                    let unescaped = match self.buf.get(self.pos + 1) {
+                        Some(&c @ (b'\\' | b'[' | b']' | b',' | b';')) => c,
+                        Some(b'n') => b'\n',
+                        Some(b'r') => b'\r',
+                        Some(_) => {
+                            return Err(self.error(
+                                self.pos,
+                                format!(
+                                    "invalid escape sequence '\\{}'",
+                                    self.describe_at(self.pos + 1).trim_matches('\'')
+                                ),
+                            ));
+                        }
+                        None if !self.eof => return Ok(None),
+                        None => {
+                            return Err(
+                                self.error(self.pos, "incomplete escape sequence at end of input")
+                            );
+                        }
+                    };
+                    let text = owned.get_or_insert_with(Vec::new);
+                    text.extend_from_slice(&self.buf[segment_start..self.pos]);
+                    text.push(unescaped);
+                    self.pos += 2;
+                    segment_start = self.pos;
                 }
-                ValueType::Container => {
-                    // Container nesting not fully supported in current wire protocol
-                    // Create empty nested container as placeholder
-                    use crate::values::ContainerValue;
-                    Arc::new(ContainerValue::new(name, vec![]))
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Remember how far an unescaped last field was scanned before the buffer ran out
+    fn pend(&mut self, last: bool, owned: &Option<Vec<u8>>, start: usize) {
+        if last && owned.is_none() {
+            self.resume = Some((self.base + start, self.base + self.pos));
+        }
+    }
+}
+
+/// A container or array item still waiting for its children
+struct OpenNested {
+    name: String,
+    value_type: ValueType,
+    expected: usize,
+    children: Vec<Arc<dyn Value>>,
+    /// Byte offset of the item's opening `[`
+    offset: usize,
+}
+
+impl OpenNested {
+    fn build(self) -> Arc<dyn Value> {
+        match self.value_type {
+            ValueType::Array => Arc::new(ArrayValue::new(self.name, self.children)),
+            _ => Arc::new(ContainerValue::new(self.name, self.children)),
+        }
+    }
+}
+
+/// Builds a container from header fields and data items as they are tokenized
+///
+/// Containers and arrays consume the items that follow them as children, one item at
+/// a time, so a message never has to be held in memory as a whole.
+pub(crate) struct WireMessageBuilder {
+    mode: WireMode,
+    report: WireReport,
+    /// target_id, target_sub_id, source_id, source_sub_id (header IDs 1-4)
+    routing: [String; 4],
+    message_type: String,
+    version: String,
+    values: Vec<Arc<dyn Value>>,
+    /// Enclosing containers and arrays of the next item, innermost last
+    open: Vec<OpenNested>,
+}
+
+impl WireMessageBuilder {
+    pub(crate) fn new(mode: WireMode) -> Self {
+        Self {
+            mode,
+            report: WireReport::default(),
+            routing: Default::default(),
+            message_type: String::from("data_container"),
+            version: String::from("1.0.0.0"),
+            values: Vec::new(),
+            open: Vec::new(),
+        }
+    }
+
+    fn header(&mut self, field: HeaderField<'_>) -> Result<()> {
+        let id: u64 = field
+            .id
+            .trim()
+            .parse()
+            .map_err(|e| ContainerError::WireFormat {
+                offset: field.offset,
+                message: format!("Invalid header ID '{}': {}", field.id, e),
+            })?;
+        let value = field.value.trim().to_string();
+
+        // IDs beyond u8 are simply unknown, like any other unrecognised ID
+        match u8::try_from(id) {
+            Ok(known @ (TARGET_ID | TARGET_SUB_ID | SOURCE_ID | SOURCE_SUB_ID)) => {
+                self.routing[(known - TARGET_ID) as usize] = value
+            }
+            Ok(MESSAGE_TYPE) => self.message_type = value,
+            Ok(MESSAGE_VERSION) => self.version = value,
+            _ => match self.mode {
+                WireMode::Strict => {
+                    return Err(ContainerError::WireFormat {
+                        offset: field.offset,
+                        message: format!("Unknown header ID {}", id),
+                    });
                 }
-                ValueType::Null => {
-                    // Null values are represented as empty containers
-                    use crate::values::ContainerValue;
-                    Arc::new(ContainerValue::new(name, vec![]))
+                WireMode::Lenient => self.report.push(WireWarning::UnknownHeaderId {
+                    id,
+                    offset: field.offset,
+                }),
+            },
+        }
+        Ok(())
+    }
+
+    fn item(&mut self, item: WireItem<'_>) -> Result<()> {
+        let type_name = item.type_name.trim();
+        let value_type =
+            cpp_name_to_value_type(type_name).ok_or_else(|| ContainerError::WireFormat {
+                offset: item.type_offset,
+                message: format!("Unknown C++ type name: {}", type_name),
+            })?;
+
+        if !matches!(value_type, ValueType::Container | ValueType::Array) {
+            let value = parse_scalar_cpp(&item.name, value_type, &item.data).map_err(|e| {
+                ContainerError::WireFormat {
+                    offset: item.data_offset,
+                    message: match e {
+                        ContainerError::InvalidDataFormat(message) => message,
+                        other => other.to_string(),
+                    },
                 }
+            })?;
+            self.attach(value);
+            return Ok(());
+        }
+
+        if self.open.len() >= MAX_NESTING_DEPTH {
+            return Err(ContainerError::WireFormat {
+                offset: item.offset,
+                message: format!(
+                    "Nesting depth exceeds maximum of {} at '{}'",
+                    MAX_NESTING_DEPTH, item.name
+                ),
+            });
+        }
+
+        let expected: usize = if item.data.trim().is_empty() {
+            0
+        } else {
+            item.data
+                .trim()
+                .parse()
+                .map_err(|e| ContainerError::WireFormat {
+                    offset: item.data_offset,
+                    message: format!("Invalid child count for '{}': {}", item.name, e),
+                })?
+        };
+
+        let nested = OpenNested {
+            name: item.name.into_owned(),
+            value_type,
+            expected,
+            children: Vec::new(),
+            offset: item.offset,
+        };
+        if expected == 0 {
+            self.attach(nested.build());
+        } else {
+            self.open.push(nested);
+        }
+        Ok(())
+    }
+
+    /// Add a finished value to its parent, closing every parent it completes
+    fn attach(&mut self, mut value: Arc<dyn Value>) {
+        while let Some(parent) = self.open.last_mut() {
+            parent.children.push(value);
+            if parent.children.len() < parent.expected {
+                return;
+            }
+            value = match self.open.pop() {
+                Some(done) => done.build(),
+                None => return,
             };
+        }
+        self.values.push(value);
+    }
+
+    pub(crate) fn finish(self) -> Result<(ValueContainer, WireReport)> {
+        if let Some(nested) = self.open.last() {
+            return Err(ContainerError::WireFormat {
+                offset: nested.offset,
+                message: format!(
+                    "'{}' declares {} children but only {} follow",
+                    nested.name,
+                    nested.expected,
+                    nested.children.len()
+                ),
+            });
+        }
 
-            container.add_value(parsed_value)?;
+        // Create container with header
+        let [target_id, target_sub_id, source_id, source_sub_id] = self.routing;
+        let mut container = ValueContainer::new();
+        container.set_source(source_id, source_sub_id);
+        container.set_target(target_id, target_sub_id);
+        container.set_message_type(self.message_type);
+        container.set_version(self.version);
+        for value in self.values {
+            container.add_value(value)?;
         }
+
+        Ok((container, self.report))
     }
+}
+
+/// Parse a non-nested value from its wire data string
+///
+/// String data is taken verbatim; surrounding whitespace is ignored for all other types.
+fn parse_scalar_cpp(name: &str, value_type: ValueType, data_str: &str) -> Result<Arc<dyn Value>> {
+    use crate::values::*;
+
+    let raw = data_str;
+    let data_str = data_str.trim();
+
+    let parsed_value: Arc<dyn Value> = match value_type {
+        ValueType::Bool => {
+            let val = data_str == "true";
+            Arc::new(BoolValue::new(name, val))
+        }
+        ValueType::Short => {
+            let val: i16 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid short: {}", e)))?;
+            Arc::new(ShortValue::new(name, val))
+        }
+        ValueType::UShort => {
+            let val: u16 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid ushort: {}", e)))?;
+            Arc::new(UShortValue::new(name, val))
+        }
+        ValueType::Int => {
+            let val: i32 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid int: {}", e)))?;
+            Arc::new(IntValue::new(name, val))
+        }
+        ValueType::UInt => {
+            let val: u32 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid uint: {}", e)))?;
+            Arc::new(UIntValue::new(name, val))
+        }
+        ValueType::Long => {
+            let val: i64 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid long: {}", e)))?;
+            Arc::new(LongValue::new(name, val)?)
+        }
+        ValueType::LLong => {
+            let val: i64 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid llong: {}", e)))?;
+            Arc::new(LLongValue::new(name, val))
+        }
+        ValueType::ULong => {
+            let val: u64 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid ulong: {}", e)))?;
+            Arc::new(ULongValue::new(name, val)?)
+        }
+        ValueType::ULLong => {
+            let val: u64 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid ullong: {}", e)))?;
+            Arc::new(ULLongValue::new(name, val))
+        }
+        ValueType::Float => {
+            let val: f32 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid float: {}", e)))?;
+            Arc::new(FloatValue::new(name, val))
+        }
+        ValueType::Double => {
+            let val: f64 = data_str
+                .parse()
+                .map_err(|e| ContainerError::InvalidDataFormat(format!("Invalid double: {}", e)))?;
+            Arc::new(DoubleValue::new(name, val))
+        }
+        ValueType::Decimal => {
+            let val: Decimal = data_str.parse().map_err(|_| {
+                ContainerError::InvalidDataFormat(format!("Invalid decimal: {}", data_str))
+            })?;
+            Arc::new(DecimalValue::new(name, val))
+        }
+        ValueType::Timestamp => {
+            let val: Timestamp = data_str.parse().map_err(|_| {
+                ContainerError::InvalidDataFormat(format!("Invalid timestamp: {}", data_str))
+            })?;
+            Arc::new(TimestampValue::new(name, val))
+        }
+        ValueType::Duration => Arc::new(DurationValue::parse(name, data_str).map_err(|_| {
+            ContainerError::InvalidDataFormat(format!("Invalid duration: {}", data_str))
+        })?),
+        ValueType::Uuid => Arc::new(UuidValue::parse(name, data_str).map_err(|_| {
+            ContainerError::InvalidDataFormat(format!("Invalid uuid: {}", data_str))
+        })?),
+        ValueType::String => Arc::new(StringValue::new(name, raw)),
+        ValueType::Bytes => {
+            let bytes = hex_to_bytes(data_str)?;
+            Arc::new(BytesValue::new(name, bytes))
+        }
+        ValueType::Null => {
+            // Null values are represented as empty containers
+            Arc::new(ContainerValue::new(name, vec![]))
+        }
+        ValueType::Container | ValueType::Array => {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "Nested value '{}' must be decoded with its children",
+                name
+            )));
+        }
+    };
 
-    Ok(container)
+    Ok(parsed_value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::test_support::Trickle;
     use crate::values::*;
 
     #[test]
@@ -533,6 +1536,51 @@ mod tests {
         assert!(flag.to_bool().unwrap());
     }
 
+    #[test]
+    fn test_version_roundtrip() {
+        let mut container = ValueContainer::new();
+        container.set_message_type("versioned");
+        container.set_version("2.3.0-rc.1");
+
+        let wire_data = serialize_cpp_wire(&container).unwrap();
+        assert!(wire_data.contains("[6,2.3.0-rc.1];"));
+
+        let restored = deserialize_cpp_wire(&wire_data).unwrap();
+        assert_eq!(restored.version(), "2.3.0-rc.1");
+
+        // Missing version header falls back to the default
+        let restored = deserialize_cpp_wire("@header={{[5,t];}};@data={{}};").unwrap();
+        assert_eq!(restored.version(), "1.0.0.0");
+    }
+
+    #[test]
+    fn test_roundtrip_all_numeric_types() {
+        let mut container = ValueContainer::new();
+        let values: Vec<Arc<dyn Value>> = vec![
+            Arc::new(ShortValue::new("short", -12)),
+            Arc::new(UShortValue::new("ushort", 65000)),
+            Arc::new(UIntValue::new("uint", 4_000_000_000)),
+            Arc::new(ULongValue::new("ulong", 4_000_000_000).unwrap()),
+            Arc::new(LLongValue::new("llong", i64::MIN)),
+            Arc::new(ULLongValue::new("ullong", u64::MAX)),
+        ];
+        for value in values {
+            container.add_value(value).unwrap();
+        }
+
+        let (wire_data, report) =
+            serialize_cpp_wire_with_mode(&container, WireMode::Strict).unwrap();
+        assert!(report.is_clean());
+
+        let restored = deserialize_cpp_wire(&wire_data).unwrap();
+        assert_eq!(restored.value_count(), 6);
+        for value in container.values() {
+            let other = restored.get_value(value.name()).unwrap();
+            assert_eq!(other.value_type(), value.value_type());
+            assert_eq!(other.to_string(), value.to_string());
+        }
+    }
+
     #[test]
     fn test_bytes_hex_encoding() {
         use crate::values::BytesValue;
@@ -553,4 +1601,581 @@ mod tests {
         let bytes_val = data.as_any().downcast_ref::<BytesValue>().unwrap();
         assert_eq!(bytes_val.data(), &test_bytes[..]);
     }
+
+    #[test]
+    fn test_bytes_invalid_hex() {
+        for data in ["aé1", "é", "0g", "+f", "abc"] {
+            let wire_data = format!("@header={{{{}}}};@data={{{{[b,bytes_value,{}];}}}};", data);
+            match deserialize_cpp_wire(&wire_data) {
+                Err(ContainerError::WireFormat { offset, .. }) => {
+                    assert_eq!(offset, wire_data.find(data).unwrap(), "{data:?}");
+                }
+                other => panic!(
+                    "expected wire format error for {data:?}, got {:?}",
+                    other.map(|_| ())
+                ),
+            }
+        }
+    }
+
+    /// Build a value nested `depth` levels deep, alternating containers and arrays
+    fn build_nested(depth: usize) -> Arc<dyn Value> {
+        let mut current: Arc<dyn Value> = Arc::new(StringValue::new("leaf", "bottom"));
+        for level in (0..depth).rev() {
+            let marker = Arc::new(IntValue::new("level", level as i32)) as Arc<dyn Value>;
+            current = if level % 2 == 0 {
+                Arc::new(ContainerValue::new(
+                    format!("node{}", level),
+                    vec![marker, current],
+                ))
+            } else {
+                Arc::new(ArrayValue::new(
+                    format!("list{}", level),
+                    vec![marker, current],
+                ))
+            };
+        }
+        current
+    }
+
+    /// Walk a value produced by `build_nested` and return the leaf
+    fn walk_nested(value: &Arc<dyn Value>, depth: usize) -> Arc<dyn Value> {
+        let mut current = Arc::clone(value);
+        for level in 0..depth {
+            let children = if level % 2 == 0 {
+                let cv = current
+                    .as_any()
+                    .downcast_ref::<ContainerValue>()
+                    .expect("expected ContainerValue");
+                assert_eq!(cv.name(), format!("node{}", level));
+                cv.children().to_vec()
+            } else {
+                let av = current
+                    .as_any()
+                    .downcast_ref::<ArrayValue>()
+                    .expect("expected ArrayValue");
+                assert_eq!(av.name(), format!("list{}", level));
+                av.elements().to_vec()
+            };
+            assert_eq!(children.len(), 2);
+            assert_eq!(children[0].to_int().unwrap(), level as i32);
+            current = Arc::clone(&children[1]);
+        }
+        current
+    }
+
+    #[test]
+    fn test_nested_roundtrip_depths_1_to_10() {
+        for depth in 1..=10 {
+            let mut original = ValueContainer::new();
+            original.set_message_type("nested");
+            original.add_value(build_nested(depth)).unwrap();
+            original
+                .add_value(Arc::new(IntValue::new("after", 99)))
+                .unwrap();
+
+            let wire_data = serialize_cpp_wire(&original).unwrap();
+            let restored = deserialize_cpp_wire(&wire_data).unwrap();
+
+            assert_eq!(restored.value_count(), 2, "depth {}", depth);
+            let root = restored.values()[0].clone();
+            let leaf = walk_nested(&root, depth);
+            assert_eq!(leaf.name(), "leaf");
+            assert_eq!(
+                restored.get_value("after").unwrap().to_int().unwrap(),
+                99,
+                "depth {}",
+                depth
+            );
+
+            // Re-serializing must yield identical bytes
+            assert_eq!(serialize_cpp_wire(&restored).unwrap(), wire_data);
+        }
+    }
+
+    #[test]
+    fn test_nested_container_wire_layout() {
+        let tags = Arc::new(ArrayValue::new(
+            "tags",
+            vec![Arc::new(StringValue::new("", "admin")) as Arc<dyn Value>],
+        ));
+        let user = Arc::new(ContainerValue::new(
+            "user",
+            vec![Arc::new(IntValue::new("id", 7)) as Arc<dyn Value>, tags],
+        ));
+        let mut container = ValueContainer::new();
+        container.add_value(user).unwrap();
+
+        let wire_data = serialize_cpp_wire(&container).unwrap();
+        assert!(wire_data.contains(
+            "@data={{[user,container_value,2];[id,int_value,7];[tags,array_value,1];[,string_value,admin];}};"
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_cpp_nested_container() {
+        let wire_data = "@header={{[5,nested];[6,1.0.0.0];}};@data={{[outer,container_value,2];[inner,container_value,1];[x,int_value,1];[y,int_value,2];[z,int_value,3];}};";
+
+        let container = deserialize_cpp_wire(wire_data).unwrap();
+        assert_eq!(container.value_count(), 2);
+
+        let outer = container.get_value("outer").unwrap();
+        let outer = outer.as_any().downcast_ref::<ContainerValue>().unwrap();
+        assert_eq!(outer.child_count(), 2);
+
+        let inner = outer.get_child("inner", 0).unwrap();
+        let inner = inner.as_any().downcast_ref::<ContainerValue>().unwrap();
+        assert_eq!(inner.get_child("x", 0).unwrap().to_int().unwrap(), 1);
+        assert_eq!(outer.get_child("y", 0).unwrap().to_int().unwrap(), 2);
+
+        assert_eq!(container.get_value("z").unwrap().to_int().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_deserialize_nested_count_exceeds_items() {
+        let wire_data =
+            "@header={{[5,t];[6,1.0.0.0];}};@data={{[outer,container_value,3];[x,int_value,1];}};";
+        assert!(deserialize_cpp_wire(wire_data).is_err());
+    }
+
+    fn wire_error_offset(wire_data: &str) -> usize {
+        match deserialize_cpp_wire(wire_data) {
+            Err(ContainerError::WireFormat { offset, .. }) => offset,
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_escape_wire() {
+        assert!(matches!(escape_wire("plain text"), Cow::Borrowed(_)));
+        assert_eq!(escape_wire(r"a\b"), r"a\\b");
+        assert_eq!(escape_wire("x];[y,z"), r"x\]\;\[y\,z");
+        assert_eq!(escape_wire("line1\r\nline2"), r"line1\r\nline2");
+    }
+
+    #[test]
+    fn test_roundtrip_delimiters_in_strings() {
+        let texts = [
+            "ends with ];",
+            "a,b,c",
+            "[bracketed]",
+            r"C:\path\to\file",
+            "multi\nline\r\ntext",
+            "}};@data={{[evil,int_value,1];",
+            "",
+        ];
+
+        let mut container = ValueContainer::new();
+        container.set_source("client, inc", "session;1");
+        container.set_target("server[0]", r"handler\main");
+        container.set_message_type("free,text");
+        for (i, text) in texts.iter().enumerate() {
+            container
+                .add_value(Arc::new(StringValue::new(format!("s{}", i), *text)))
+                .unwrap();
+        }
+
+        let wire_data = serialize_cpp_wire(&container).unwrap();
+        let restored = deserialize_cpp_wire(&wire_data).unwrap();
+
+        assert_eq!(restored.source_id(), "client, inc");
+        assert_eq!(restored.source_sub_id(), "session;1");
+        assert_eq!(restored.target_id(), "server[0]");
+        assert_eq!(restored.target_sub_id(), r"handler\main");
+        assert_eq!(restored.message_type(), "free,text");
+        assert_eq!(restored.value_count(), texts.len());
+        for (i, text) in texts.iter().enumerate() {
+            let value = restored.get_value(&format!("s{}", i)).unwrap();
+            assert_eq!(value.to_string(), *text);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_names_with_punctuation() {
+        let mut container = ValueContainer::new();
+        for name in [
+            "user-id",
+            "config.timeout",
+            "a,b",
+            "k;v",
+            "[0]",
+            "with space",
+        ] {
+            container
+                .add_value(Arc::new(IntValue::new(name, 1)))
+                .unwrap();
+        }
+
+        let wire_data = serialize_cpp_wire(&container).unwrap();
+        let restored = deserialize_cpp_wire(&wire_data).unwrap();
+
+        for name in [
+            "user-id",
+            "config.timeout",
+            "a,b",
+            "k;v",
+            "[0]",
+            "with space",
+        ] {
+            assert_eq!(restored.get_value(name).unwrap().to_int().unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn test_nested_roundtrip_with_delimiters() {
+        let leaf = Arc::new(StringValue::new("note", "bottom; [x,y];")) as Arc<dyn Value>;
+        let inner = Arc::new(ArrayValue::new("items,list", vec![leaf])) as Arc<dyn Value>;
+        let outer = Arc::new(ContainerValue::new("outer.box", vec![inner]));
+        let mut container = ValueContainer::new();
+        container.add_value(outer).unwrap();
+
+        let wire_data = serialize_cpp_wire(&container).unwrap();
+        let restored = deserialize_cpp_wire(&wire_data).unwrap();
+
+        let outer = restored.get_value("outer.box").unwrap();
+        let outer = outer.as_any().downcast_ref::<ContainerValue>().unwrap();
+        let inner = outer.get_child("items,list", 0).unwrap();
+        let inner = inner.as_any().downcast_ref::<ArrayValue>().unwrap();
+        assert_eq!(inner.elements()[0].to_string(), "bottom; [x,y];");
+        assert_eq!(serialize_cpp_wire(&restored).unwrap(), wire_data);
+    }
+
+    #[test]
+    fn test_deserialize_unescaped_cpp_output() {
+        // Unescaped C++ output: raw commas and brackets in the last field are literal
+        let wire_data = "@header={{[5,legacy];[6,1.0.0.0];}};\n@data={{[msg,string_value,a,b [c]];[n, int_value, 5 ];}};";
+        let container = deserialize_cpp_wire(wire_data).unwrap();
+
+        assert_eq!(container.message_type(), "legacy");
+        assert_eq!(container.get_value("msg").unwrap().to_string(), "a,b [c]");
+        assert_eq!(container.get_value("n").unwrap().to_int().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_deserialize_keeps_raw_line_breaks() {
+        let wire_data =
+            "@header={{[5,t];}};@data={{[msg,string_value,line1\r\nline2\n];[n,int_value,5\n];}};";
+        let container = deserialize_cpp_wire(wire_data).unwrap();
+        assert_eq!(
+            container.get_value("msg").unwrap().to_string(),
+            "line1\r\nline2\n"
+        );
+        assert_eq!(container.get_value("n").unwrap().to_int().unwrap(), 5);
+
+        let mut streamed = wire_data.as_bytes();
+        let container = read_cpp_wire(&mut streamed).unwrap();
+        assert_eq!(
+            container.get_value("msg").unwrap().to_string(),
+            "line1\r\nline2\n"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_error_offsets() {
+        let prefix = "@header={{[5,t];}};@data={{";
+
+        // Invalid escape sequence points at the backslash
+        let wire_data = format!("{}[s,string_value,bad\\q];}}}};", prefix);
+        assert_eq!(wire_error_offset(&wire_data), wire_data.find('\\').unwrap());
+
+        // Unterminated item points at its opening bracket
+        let wire_data = format!("{}[ok,int_value,1];[s,string_value,open", prefix);
+        assert_eq!(wire_error_offset(&wire_data), wire_data.rfind('[').unwrap());
+
+        // Unparseable data points at the data field
+        let wire_data = format!("{}[n,int_value,abc];}}}};", prefix);
+        assert_eq!(
+            wire_error_offset(&wire_data),
+            wire_data.find("abc").unwrap()
+        );
+
+        // Unknown type points at the type field
+        let wire_data = format!("{}[n,mystery_value,1];}}}};", prefix);
+        assert_eq!(
+            wire_error_offset(&wire_data),
+            wire_data.find("mystery").unwrap()
+        );
+
+        // Invalid header ID points at the header item
+        let wire_data = "@header={{[x,t];}};";
+        assert_eq!(wire_error_offset(wire_data), wire_data.find('[').unwrap());
+
+        // Garbage between sections
+        let wire_data = "@header={{[5,t];}};junk";
+        assert_eq!(
+            wire_error_offset(wire_data),
+            wire_data.find("junk").unwrap()
+        );
+
+        // Mismatched section braces
+        let wire_data = "@data={{[n,int_value,1];};";
+        assert_eq!(wire_error_offset(wire_data), wire_data.rfind('}').unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_empty_input() {
+        let container = deserialize_cpp_wire("").unwrap();
+        assert_eq!(container.value_count(), 0);
+        assert_eq!(container.message_type(), "data_container");
+    }
+
+    /// A value that claims to be a container but is not a `ContainerValue`
+    #[derive(Debug)]
+    struct FakeContainer;
+
+    impl Value for FakeContainer {
+        fn name(&self) -> &str {
+            "fake"
+        }
+        fn value_type(&self) -> ValueType {
+            ValueType::Container
+        }
+        fn size(&self) -> usize {
+            0
+        }
+        fn to_string(&self) -> String {
+            String::new()
+        }
+        fn to_bytes(&self) -> Vec<u8> {
+            Vec::new()
+        }
+        fn to_json(&self) -> Result<String> {
+            Ok("null".to_string())
+        }
+        fn to_xml(&self) -> Result<String> {
+            Ok(String::new())
+        }
+        fn clone_value(&self) -> Arc<dyn Value> {
+            Arc::new(FakeContainer)
+        }
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    fn container_with_bad_value() -> ValueContainer {
+        let mut container = ValueContainer::new();
+        container
+            .add_value(Arc::new(IntValue::new("a", 1)))
+            .unwrap();
+        container.add_value(Arc::new(FakeContainer)).unwrap();
+        container
+            .add_value(Arc::new(IntValue::new("b", 2)))
+            .unwrap();
+        container
+    }
+
+    #[test]
+    fn test_serialize_strict_names_failing_value() {
+        let container = container_with_bad_value();
+
+        let err = serialize_cpp_wire_with_mode(&container, WireMode::Strict).unwrap_err();
+        assert!(matches!(err, ContainerError::SerializationError(_)));
+        assert!(err.to_string().contains("'fake'"), "{}", err);
+    }
+
+    #[test]
+    fn test_serialize_lenient_reports_skipped_value() {
+        let container = container_with_bad_value();
+
+        let (wire_data, report) =
+            serialize_cpp_wire_with_mode(&container, WireMode::Lenient).unwrap();
+        assert!(wire_data.contains("[a,int_value,1];[b,int_value,2];"));
+        assert_eq!(report.warnings().len(), 1);
+        assert!(matches!(
+            &report.warnings()[0],
+            WireWarning::SkippedValue { name, .. } if name == "fake"
+        ));
+
+        // The plain entry point keeps its lenient behavior
+        assert_eq!(serialize_cpp_wire(&container).unwrap(), wire_data);
+    }
+
+    #[test]
+    fn test_deserialize_unknown_header_id_modes() {
+        let wire_data = "@header={{[5,t];[42,x];[6,1.0.0.0];[7,y];}};@data={{[n,int_value,1];}};";
+
+        match deserialize_cpp_wire_with_mode(wire_data, WireMode::Strict) {
+            Err(ContainerError::WireFormat { offset, message }) => {
+                assert_eq!(offset, wire_data.find("[42").unwrap());
+                assert!(message.contains("42"), "{}", message);
+            }
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+
+        let (container, report) =
+            deserialize_cpp_wire_with_mode(wire_data, WireMode::Lenient).unwrap();
+        assert_eq!(container.value_count(), 1);
+        assert_eq!(
+            report.warnings(),
+            &[
+                WireWarning::UnknownHeaderId {
+                    id: 42,
+                    offset: wire_data.find("[42").unwrap()
+                },
+                WireWarning::UnknownHeaderId {
+                    id: 7,
+                    offset: wire_data.find("[7").unwrap()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_out_of_range_header_id() {
+        let wire_data = "@header={{[300,x];[5,t];}};@data={{[n,int_value,1];}};";
+
+        match deserialize_cpp_wire_with_mode(wire_data, WireMode::Strict) {
+            Err(ContainerError::WireFormat { message, .. }) => {
+                assert!(message.contains("Unknown header ID 300"), "{}", message);
+            }
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+
+        let (container, report) =
+            deserialize_cpp_wire_with_mode(wire_data, WireMode::Lenient).unwrap();
+        assert_eq!(container.message_type(), "t");
+        assert_eq!(
+            report.warnings(),
+            &[WireWarning::UnknownHeaderId {
+                id: 300,
+                offset: wire_data.find("[300").unwrap()
+            }]
+        );
+
+        // Still not a number at all
+        assert!(deserialize_cpp_wire_with_mode(
+            "@header={{[x,y];}};@data={{}};",
+            WireMode::Lenient
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_strict_roundtrip_is_clean() {
+        let mut container = ValueContainer::new();
+        container.set_source("client", "session");
+        container.set_target("server", "handler");
+        container.set_message_type("audit");
+        container
+            .add_value(Arc::new(StringValue::new("event", "login")))
+            .unwrap();
+
+        let (wire_data, report) =
+            serialize_cpp_wire_with_mode(&container, WireMode::Strict).unwrap();
+        assert!(report.is_clean());
+
+        let (restored, report) =
+            deserialize_cpp_wire_with_mode(&wire_data, WireMode::Strict).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(restored.get_value("event").unwrap().to_string(), "login");
+    }
+
+    fn streaming_sample() -> ValueContainer {
+        let mut container = ValueContainer::new();
+        container.set_source("client, inc", "session;1");
+        container.set_target("server", "main");
+        container.set_message_type("stream\ntest");
+        container.set_version("3.1");
+        container
+            .add_value(Arc::new(StringValue::new("text", "a];b,[c]\\\r\n")))
+            .unwrap();
+        container
+            .add_value(Arc::new(BytesValue::new(
+                "blob",
+                (0..5000).map(|i| i as u8).collect(),
+            )))
+            .unwrap();
+        container.add_value(build_nested(4)).unwrap();
+        container
+    }
+
+    #[test]
+    fn test_write_cpp_wire_matches_serialize() {
+        let container = streaming_sample();
+        let mut out = Vec::new();
+        write_cpp_wire(&container, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            serialize_cpp_wire(&container).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_cpp_wire_one_byte_at_a_time() {
+        let wire_data = serialize_cpp_wire(&streaming_sample()).unwrap();
+
+        let restored = read_cpp_wire(&mut Trickle(wire_data.as_bytes())).unwrap();
+        assert_eq!(restored.source_id(), "client, inc");
+        assert_eq!(restored.message_type(), "stream\ntest");
+        assert_eq!(restored.version(), "3.1");
+        assert_eq!(serialize_cpp_wire(&restored).unwrap(), wire_data);
+
+        let restored = read_cpp_wire(&mut wire_data.as_bytes()).unwrap();
+        assert_eq!(serialize_cpp_wire(&restored).unwrap(), wire_data);
+    }
+
+    #[test]
+    fn test_read_cpp_wire_errors_match_deserialize() {
+        let prefix = "@header={{[5,t];}};@data={{";
+        let inputs = [
+            format!("{}[s,string_value,bad\\q];}}}};", prefix),
+            format!("{}[ok,int_value,1];[s,string_value,open", prefix),
+            format!("{}[n,int_value,abc];}}}};", prefix),
+            format!("{}[s,string_value,x]", prefix),
+            format!("{}[s,string_value,x\\", prefix),
+            format!("{}[outer,container_value,2];[x,int_value,1];}}}};", prefix),
+            "@header={{[x,t];}};".to_string(),
+            "@header={{[5,t];}};junk".to_string(),
+            "@header={{[5,t];}};@".to_string(),
+            "@data={{[n,int_value,1];};".to_string(),
+            "@data={".to_string(),
+        ];
+
+        for wire_data in &inputs {
+            let expected = wire_error_offset(wire_data);
+            match read_cpp_wire(&mut Trickle(wire_data.as_bytes())) {
+                Err(ContainerError::WireFormat { offset, .. }) => {
+                    assert_eq!(offset, expected, "{}", wire_data)
+                }
+                other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+            }
+        }
+
+        // Invalid UTF-8 is rejected with the offset of its field
+        let mut bytes = format!("{}[s,string_value,", prefix).into_bytes();
+        let field_offset = bytes.len();
+        bytes.extend_from_slice(&[b'o', b'k', 0xFF, b']', b';']);
+        match read_cpp_wire(&mut bytes.as_slice()) {
+            Err(ContainerError::WireFormat { offset, .. }) => assert_eq!(offset, field_offset),
+            other => panic!("expected wire format error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_write_cpp_wire_lenient_skips_nested_failures_cleanly() {
+        let mut container = container_with_bad_value();
+        container
+            .add_value(Arc::new(ContainerValue::new(
+                "wrapper",
+                vec![
+                    Arc::new(IntValue::new("before", 1)) as Arc<dyn Value>,
+                    Arc::new(FakeContainer),
+                ],
+            )))
+            .unwrap();
+
+        let mut out = Vec::new();
+        let report = write_cpp_wire_with_mode(&container, &mut out, WireMode::Lenient).unwrap();
+        assert_eq!(report.warnings().len(), 2);
+
+        // Nothing of a skipped value reaches the writer
+        let wire_data = String::from_utf8(out).unwrap();
+        assert!(!wire_data.contains("wrapper"), "{}", wire_data);
+        let restored = deserialize_cpp_wire(&wire_data).unwrap();
+        assert_eq!(restored.value_count(), 2);
+
+        let mut out = Vec::new();
+        assert!(write_cpp_wire_with_mode(&container, &mut out, WireMode::Strict).is_err());
+    }
 }
@@ -84,9 +84,9 @@ pub mod prelude {
     pub use crate::kcenon::{ArcContainerProvider, ContainerFactory, DefaultContainerFactory};
     pub use crate::messaging::MessagingContainerBuilder;
     pub use crate::values::{
-        ArrayValue, BoolValue, BytesValue, ContainerValue, DoubleValue, FloatValue, IntValue,
-        LLongValue, LongValue, ShortValue, StringValue, UIntValue, ULLongValue, ULongValue,
-        UShortValue,
+        ArrayValue, BoolValue, BytesValue, ContainerValue, DecimalValue, DoubleValue,
+        DurationValue, FloatValue, IntValue, LLongValue, LongValue, ShortValue, StringValue,
+        TimestampValue, UIntValue, ULLongValue, ULongValue, UShortValue, UuidValue,
     };
 }
 
@@ -0,0 +1,804 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Typed message marshalling.
+//!
+//! [`ContainerMessage`] maps a Rust struct to the values of a `ValueContainer` and
+//! back, and [`MessageField`] maps a single field to a single value. Both are
+//! normally implemented with `#[derive(ContainerMessage)]` (enable the `derive`
+//! feature) rather than by hand.
+//!
+//! # Field Types
+//!
+//! | Rust | Default value type | Allowed `value_type` overrides |
+//! |------|--------------------|--------------------------------|
+//! | `bool` | `bool_value` | `bool`, `string` |
+//! | `i8`, `i16` / `u8`, `u16` | `short_value` / `ushort_value` | any integer type, `float`, `double`, `string` |
+//! | `i32` / `u32` | `int_value` / `uint_value` | any integer type, `float`, `double`, `string` |
+//! | `i64` / `u64` | `llong_value` / `ullong_value` | any integer type, `float`, `double`, `string` |
+//! | `f32` / `f64` | `float_value` / `double_value` | `float`, `double`, `string` |
+//! | `String` | `string_value` | `string` |
+//! | `Vec<u8>` | `bytes_value` | `bytes` |
+//! | `Option<T>` | `null_value` for `None`, otherwise as `T` | as `T` |
+//! | `T: ContainerMessage` | `container_value` | `container` |
+//! | `Vec<T>` with `T: ContainerMessage` | `array_value` of `container_value` | `array` |
+//!
+//! Overrides never lose data: an `i64` written as `long_value` (32 bits on the wire)
+//! fails instead of truncating, an integer written as `float` or `double` must be
+//! exactly representable (so `u64::MAX` cannot be sent as a `double`), and an `f64`
+//! written as `float` must survive the narrowing.
+//!
+//! Every override also reads back. Integer fields accept any integer value that fits,
+//! a `float` or `double` holding an exact integer, or its decimal string; `bool`,
+//! `f32` and `f64` fields accept their string form, and floats accept either width
+//! as long as the number is exact. A missing or null value reads as `None` for
+//! `Option` fields, and so does an empty `container_value`, which is what the wire
+//! protocol and JSON v2.0 rebuild a `null_value` as.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rust_container_system::messaging::{read_field, ContainerMessage, MessageField};
+//! use rust_container_system::{Result, Value, ValueType};
+//! use std::sync::Arc;
+//!
+//! struct Login {
+//!     user_id: i64,
+//!     nickname: Option<String>,
+//! }
+//!
+//! impl ContainerMessage for Login {
+//!     const MESSAGE_TYPE: &'static str = "login";
+//!
+//!     fn to_values(&self) -> Result<Vec<Arc<dyn Value>>> {
+//!         Ok(vec![
+//!             self.user_id.to_value("uid", Some(ValueType::Long))?,
+//!             self.nickname.to_value("nickname", None)?,
+//!         ])
+//!     }
+//!
+//!     fn from_values(values: &[Arc<dyn Value>]) -> Result<Self> {
+//!         Ok(Self {
+//!             user_id: read_field(values, "uid")?,
+//!             nickname: read_field(values, "nickname")?,
+//!         })
+//!     }
+//! }
+//!
+//! let container = Login { user_id: 42, nickname: None }.to_container().unwrap();
+//! assert_eq!(container.message_type(), "login");
+//! assert_eq!(container.get_value("uid").unwrap().value_type(), ValueType::Long);
+//! assert!(container.get_value("nickname").unwrap().is_null());
+//!
+//! let login = Login::from_container(&container).unwrap();
+//! assert_eq!(login.user_id, 42);
+//! assert_eq!(login.nickname, None);
+//! ```
+
+use crate::core::{ContainerError, Result, Value, ValueContainer, ValueType};
+use crate::messaging::MessagingContainerBuilder;
+use crate::values::time_values::{duration_of, format_duration, timestamp_of};
+use crate::values::uuid_value::uuid_of;
+use crate::values::{
+    ArrayValue, BoolValue, BytesValue, ContainerValue, Decimal, DecimalValue, DoubleValue,
+    DurationValue, FloatValue, IntValue, LLongValue, LongValue, NullValue, ShortValue, StringValue,
+    Timestamp, TimestampValue, UIntValue, ULLongValue, ULongValue, UShortValue, Uuid, UuidValue,
+};
+use std::sync::Arc;
+
+/// A Rust type that is exchanged as a whole container.
+///
+/// Implementors list their fields as values with [`to_values`](Self::to_values) and
+/// rebuild themselves with [`from_values`](Self::from_values); the provided methods
+/// add the container header on top.
+pub trait ContainerMessage: Sized {
+    /// Value of the `message_type` header written and expected by this message
+    const MESSAGE_TYPE: &'static str;
+
+    /// Convert every field into a named value.
+    fn to_values(&self) -> Result<Vec<Arc<dyn Value>>>;
+
+    /// Rebuild the message from named values, usually with [`read_field`].
+    fn from_values(values: &[Arc<dyn Value>]) -> Result<Self>;
+
+    /// Build a container whose message type is [`MESSAGE_TYPE`](Self::MESSAGE_TYPE)
+    /// and whose values are the message fields.
+    ///
+    /// Source and target headers are left empty for the caller to fill in.
+    fn to_container(&self) -> Result<ValueContainer> {
+        let mut container = MessagingContainerBuilder::new()
+            .with_type(Self::MESSAGE_TYPE)
+            .build();
+        for value in self.to_values()? {
+            container.add_value(value)?;
+        }
+        Ok(container)
+    }
+
+    /// Read the message back from a container.
+    ///
+    /// Fails with `InvalidDataFormat` when the container's message type is not
+    /// [`MESSAGE_TYPE`](Self::MESSAGE_TYPE).
+    fn from_container(container: &ValueContainer) -> Result<Self> {
+        let message_type = container.message_type();
+        if message_type != Self::MESSAGE_TYPE {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "expected message type '{}', found '{}'",
+                Self::MESSAGE_TYPE,
+                message_type
+            )));
+        }
+        Self::from_values(&container.values())
+    }
+}
+
+/// A Rust type that is exchanged as a single value.
+///
+/// See the [module documentation](self) for the supported types and overrides.
+pub trait MessageField: Sized {
+    /// Convert to a value named `name`.
+    ///
+    /// `value_type` overrides the default value type for this Rust type; an
+    /// override the type cannot be represented as fails with
+    /// `InvalidTypeConversion`.
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>>;
+
+    /// Convert back from a value.
+    fn from_value(value: &dyn Value) -> Result<Self>;
+
+    /// Result for a field that is absent from the container.
+    ///
+    /// Fails with `ValueNotFound` unless overridden, as `Option` does.
+    fn missing(name: &str) -> Result<Self> {
+        Err(ContainerError::ValueNotFound(name.to_string()))
+    }
+}
+
+/// Read the first value called `name` as a `T`, falling back to
+/// [`MessageField::missing`] when there is none.
+pub fn read_field<T: MessageField>(values: &[Arc<dyn Value>], name: &str) -> Result<T> {
+    match values.iter().find(|value| value.name() == name) {
+        Some(value) => T::from_value(value.as_ref()),
+        None => T::missing(name),
+    }
+}
+
+fn unsupported_override(rust_type: &str, value_type: ValueType) -> ContainerError {
+    ContainerError::InvalidTypeConversion {
+        from: rust_type.to_string(),
+        to: value_type.to_string(),
+    }
+}
+
+fn unexpected_value(value: &dyn Value, rust_type: &str) -> ContainerError {
+    ContainerError::InvalidTypeConversion {
+        from: format!("{} '{}'", value.value_type(), value.name()),
+        to: rust_type.to_string(),
+    }
+}
+
+fn out_of_range(
+    name: &str,
+    number: impl std::fmt::Display,
+    target: impl std::fmt::Display,
+) -> ContainerError {
+    ContainerError::InvalidTypeConversion {
+        from: format!("'{name}' ({number})"),
+        to: format!("{target} (out of range)"),
+    }
+}
+
+fn inexact(
+    name: &str,
+    number: impl std::fmt::Display,
+    target: impl std::fmt::Display,
+) -> ContainerError {
+    ContainerError::InvalidTypeConversion {
+        from: format!("'{name}' ({number})"),
+        to: format!("{target} (not exact)"),
+    }
+}
+
+/// Parse the text of a `StringValue`, the fallback form of every scalar field.
+fn parse_text<T: std::str::FromStr>(value: &dyn Value, rust_type: &str) -> Result<T> {
+    value
+        .as_any()
+        .downcast_ref::<StringValue>()
+        .and_then(|text| text.value().trim().parse().ok())
+        .ok_or_else(|| unexpected_value(value, rust_type))
+}
+
+/// Narrow an `f64` to `f32`, rejecting numbers that would be rounded or overflow.
+fn narrow_f64(name: &str, number: f64) -> Result<f32> {
+    let narrowed = number as f32;
+    if f64::from(narrowed) == number || number.is_nan() {
+        Ok(narrowed)
+    } else {
+        Err(inexact(name, number, ValueType::Float))
+    }
+}
+
+/// Build an integer-typed value of `value_type`, rejecting numbers that do not fit
+/// or that a `float` / `double` would round.
+fn integer_value(
+    name: &str,
+    number: i128,
+    value_type: ValueType,
+    rust_type: &str,
+) -> Result<Arc<dyn Value>> {
+    fn fit<T: TryFrom<i128>>(name: &str, number: i128, value_type: ValueType) -> Result<T> {
+        T::try_from(number).map_err(|_| out_of_range(name, number, value_type))
+    }
+
+    Ok(match value_type {
+        ValueType::Short => Arc::new(ShortValue::new(name, fit(name, number, value_type)?)),
+        ValueType::UShort => Arc::new(UShortValue::new(name, fit(name, number, value_type)?)),
+        ValueType::Int => Arc::new(IntValue::new(name, fit(name, number, value_type)?)),
+        ValueType::UInt => Arc::new(UIntValue::new(name, fit(name, number, value_type)?)),
+        ValueType::Long => Arc::new(LongValue::new(name, fit(name, number, value_type)?)?),
+        ValueType::ULong => Arc::new(ULongValue::new(name, fit(name, number, value_type)?)?),
+        ValueType::LLong => Arc::new(LLongValue::new(name, fit(name, number, value_type)?)),
+        ValueType::ULLong => Arc::new(ULLongValue::new(name, fit(name, number, value_type)?)),
+        ValueType::Float => {
+            let float = number as f32;
+            if float as i128 != number {
+                return Err(inexact(name, number, value_type));
+            }
+            Arc::new(FloatValue::new(name, float))
+        }
+        ValueType::Double => {
+            let double = number as f64;
+            if double as i128 != number {
+                return Err(inexact(name, number, value_type));
+            }
+            Arc::new(DoubleValue::new(name, double))
+        }
+        ValueType::String => Arc::new(StringValue::new(name, number.to_string())),
+        other => return Err(unsupported_override(rust_type, other)),
+    })
+}
+
+/// Read an integer as an `i128`, which holds every supported width exactly.
+///
+/// Accepts every form [`integer_value`] writes: any integer type, a `float` or
+/// `double` with no fractional part, or a decimal string.
+fn integer_of(value: &dyn Value, rust_type: &str) -> Result<i128> {
+    match value.value_type() {
+        ValueType::Short | ValueType::Int | ValueType::Long | ValueType::LLong => {
+            Ok(i128::from(value.to_long()?))
+        }
+        ValueType::UShort | ValueType::UInt | ValueType::ULong | ValueType::ULLong => {
+            Ok(i128::from(value.to_ulong()?))
+        }
+        ValueType::Float | ValueType::Double => {
+            let number = value.to_double()?;
+            // NaN and infinities have no fractional part of zero either
+            if number.fract() != 0.0 || number.abs() >= i128::MAX as f64 {
+                return Err(inexact(value.name(), number, rust_type));
+            }
+            Ok(number as i128)
+        }
+        ValueType::String => parse_text(value, rust_type),
+        _ => Err(unexpected_value(value, rust_type)),
+    }
+}
+
+macro_rules! integer_field {
+    ($($ty:ty => $natural:ident),* $(,)?) => {$(
+        impl MessageField for $ty {
+            fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+                let value_type = value_type.unwrap_or(ValueType::$natural);
+                integer_value(name, i128::from(*self), value_type, stringify!($ty))
+            }
+
+            fn from_value(value: &dyn Value) -> Result<Self> {
+                let number = integer_of(value, stringify!($ty))?;
+                <$ty>::try_from(number).map_err(|_| out_of_range(value.name(), number, stringify!($ty)))
+            }
+        }
+    )*};
+}
+
+integer_field! {
+    i8 => Short,
+    i16 => Short,
+    i32 => Int,
+    i64 => LLong,
+    u8 => UShort,
+    u16 => UShort,
+    u32 => UInt,
+    u64 => ULLong,
+}
+
+impl MessageField for bool {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Bool) {
+            ValueType::Bool => Ok(Arc::new(BoolValue::new(name, *self))),
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.to_string()))),
+            other => Err(unsupported_override("bool", other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Bool => value.to_bool(),
+            ValueType::String => parse_text(value, "bool"),
+            _ => Err(unexpected_value(value, "bool")),
+        }
+    }
+}
+
+impl MessageField for f32 {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Float) {
+            ValueType::Float => Ok(Arc::new(FloatValue::new(name, *self))),
+            ValueType::Double => Ok(Arc::new(DoubleValue::new(name, f64::from(*self)))),
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.to_string()))),
+            other => Err(unsupported_override("f32", other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Float => value.to_float(),
+            ValueType::Double => narrow_f64(value.name(), value.to_double()?),
+            ValueType::String => parse_text(value, "f32"),
+            _ => Err(unexpected_value(value, "f32")),
+        }
+    }
+}
+
+impl MessageField for f64 {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Double) {
+            ValueType::Float => Ok(Arc::new(FloatValue::new(name, narrow_f64(name, *self)?))),
+            ValueType::Double => Ok(Arc::new(DoubleValue::new(name, *self))),
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.to_string()))),
+            other => Err(unsupported_override("f64", other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        match value.value_type() {
+            ValueType::Float => Ok(f64::from(value.to_float()?)),
+            ValueType::Double => value.to_double(),
+            ValueType::String => parse_text(value, "f64"),
+            _ => Err(unexpected_value(value, "f64")),
+        }
+    }
+}
+
+impl MessageField for Decimal {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Decimal) {
+            ValueType::Decimal => Ok(Arc::new(DecimalValue::new(name, *self))),
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.to_string()))),
+            other => Err(unsupported_override("Decimal", other)),
+        }
+    }
+
+    /// Also accepts the string a peer without `decimal_value` support sends
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        if let Some(decimal) = value.as_any().downcast_ref::<DecimalValue>() {
+            return Ok(decimal.value());
+        }
+        match value.value_type() {
+            ValueType::Decimal | ValueType::String => value
+                .to_string()
+                .parse()
+                .map_err(|_| unexpected_value(value, "Decimal")),
+            _ => Err(unexpected_value(value, "Decimal")),
+        }
+    }
+}
+
+impl MessageField for Timestamp {
+    /// The `llong_value` override writes epoch milliseconds, so it fails for a
+    /// timestamp with a UTC offset or a sub-millisecond part
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Timestamp) {
+            ValueType::Timestamp => Ok(Arc::new(TimestampValue::new(name, *self))),
+            ValueType::LLong => {
+                let millis = self.unix_millis();
+                if Timestamp::from_unix_millis(millis).ok() != Some(*self) {
+                    return Err(inexact(name, self, ValueType::LLong));
+                }
+                Ok(Arc::new(LLongValue::new(name, millis)))
+            }
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.to_string()))),
+            other => Err(unsupported_override("Timestamp", other)),
+        }
+    }
+
+    /// Also accepts RFC 3339 strings and `llong_value` epoch milliseconds
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        match value.value_type() {
+            ValueType::LLong => Timestamp::from_unix_millis(value.to_long()?),
+            _ => timestamp_of(value),
+        }
+    }
+}
+
+impl MessageField for std::time::Duration {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Duration) {
+            ValueType::Duration => Ok(Arc::new(DurationValue::new(name, *self))),
+            ValueType::String => Ok(Arc::new(StringValue::new(name, format_duration(*self)))),
+            other => Err(unsupported_override("Duration", other)),
+        }
+    }
+
+    /// Also accepts ISO 8601 duration strings
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        duration_of(value)
+    }
+}
+
+impl MessageField for Uuid {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Uuid) {
+            ValueType::Uuid => Ok(Arc::new(UuidValue::new(name, *self))),
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.to_string()))),
+            other => Err(unsupported_override("Uuid", other)),
+        }
+    }
+
+    /// Also accepts the string a peer without `uuid_value` support sends
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        uuid_of(value)
+    }
+}
+
+impl MessageField for String {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::String) {
+            ValueType::String => Ok(Arc::new(StringValue::new(name, self.as_str()))),
+            other => Err(unsupported_override("String", other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        value
+            .as_any()
+            .downcast_ref::<StringValue>()
+            .map(|text| text.value().to_string())
+            .ok_or_else(|| unexpected_value(value, "String"))
+    }
+}
+
+impl MessageField for Vec<u8> {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Bytes) {
+            ValueType::Bytes => Ok(Arc::new(BytesValue::new(name, self.clone()))),
+            other => Err(unsupported_override("Vec<u8>", other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        value
+            .as_any()
+            .downcast_ref::<BytesValue>()
+            .map(|bytes| bytes.data().to_vec())
+            .ok_or_else(|| unexpected_value(value, "Vec<u8>"))
+    }
+}
+
+impl<T: MessageField> MessageField for Option<T> {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match self {
+            Some(inner) => inner.to_value(name, value_type),
+            None => Ok(Arc::new(NullValue::new(name))),
+        }
+    }
+
+    /// Also reads the empty container that older formats use for `null_value`
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        let empty_container = value
+            .as_any()
+            .downcast_ref::<ContainerValue>()
+            .is_some_and(|container| container.children().is_empty());
+        if value.is_null() || empty_container {
+            Ok(None)
+        } else {
+            T::from_value(value).map(Some)
+        }
+    }
+
+    fn missing(_name: &str) -> Result<Self> {
+        Ok(None)
+    }
+}
+
+impl<T: ContainerMessage> MessageField for T {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Container) {
+            ValueType::Container => Ok(Arc::new(ContainerValue::new(name, self.to_values()?))),
+            other => Err(unsupported_override(std::any::type_name::<T>(), other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        let container = value
+            .as_any()
+            .downcast_ref::<ContainerValue>()
+            .ok_or_else(|| unexpected_value(value, std::any::type_name::<T>()))?;
+        T::from_values(container.children())
+    }
+}
+
+impl<T: ContainerMessage> MessageField for Vec<T> {
+    fn to_value(&self, name: &str, value_type: Option<ValueType>) -> Result<Arc<dyn Value>> {
+        match value_type.unwrap_or(ValueType::Array) {
+            ValueType::Array => {
+                let elements = self
+                    .iter()
+                    .map(|element| element.to_value("", None))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Arc::new(ArrayValue::new(name, elements)))
+            }
+            other => Err(unsupported_override(std::any::type_name::<Self>(), other)),
+        }
+    }
+
+    fn from_value(value: &dyn Value) -> Result<Self> {
+        let array = value
+            .as_any()
+            .downcast_ref::<ArrayValue>()
+            .ok_or_else(|| unexpected_value(value, std::any::type_name::<Self>()))?;
+        array
+            .elements()
+            .iter()
+            .map(|element| T::from_value(element.as_ref()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl ContainerMessage for Point {
+        const MESSAGE_TYPE: &'static str = "point";
+
+        fn to_values(&self) -> Result<Vec<Arc<dyn Value>>> {
+            Ok(vec![
+                self.x.to_value("x", None)?,
+                self.y.to_value("y", None)?,
+            ])
+        }
+
+        fn from_values(values: &[Arc<dyn Value>]) -> Result<Self> {
+            Ok(Self {
+                x: read_field(values, "x")?,
+                y: read_field(values, "y")?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_integer_defaults_and_overrides() {
+        assert_eq!(
+            7i64.to_value("n", None).unwrap().value_type(),
+            ValueType::LLong
+        );
+        assert_eq!(
+            7u8.to_value("n", None).unwrap().value_type(),
+            ValueType::UShort
+        );
+
+        let long = 7i64.to_value("n", Some(ValueType::Long)).unwrap();
+        assert_eq!(long.value_type(), ValueType::Long);
+        assert_eq!(i64::from_value(long.as_ref()).unwrap(), 7);
+
+        let text = 7i64.to_value("n", Some(ValueType::String)).unwrap();
+        assert_eq!(text.to_string(), "7");
+    }
+
+    #[test]
+    fn test_overrides_read_back() {
+        use ValueType::*;
+
+        for value_type in [
+            Short, UShort, Int, UInt, Long, ULong, LLong, ULLong, Float, Double, String,
+        ] {
+            let value = 7i64.to_value("n", Some(value_type)).unwrap();
+            assert_eq!(value.value_type(), value_type);
+            assert_eq!(
+                i64::from_value(value.as_ref()).unwrap(),
+                7,
+                "{}",
+                value_type
+            );
+            assert_eq!(u8::from_value(value.as_ref()).unwrap(), 7, "{}", value_type);
+        }
+        let big = u64::MAX.to_value("n", Some(String)).unwrap();
+        assert_eq!(u64::from_value(big.as_ref()).unwrap(), u64::MAX);
+
+        for value_type in [Bool, String] {
+            let value = true.to_value("b", Some(value_type)).unwrap();
+            assert!(bool::from_value(value.as_ref()).unwrap(), "{}", value_type);
+        }
+
+        for value_type in [Float, Double, String] {
+            let value = 0.1f32.to_value("f", Some(value_type)).unwrap();
+            assert_eq!(
+                f32::from_value(value.as_ref()).unwrap(),
+                0.1,
+                "{}",
+                value_type
+            );
+            let value = 0.5f64.to_value("f", Some(value_type)).unwrap();
+            assert_eq!(
+                f64::from_value(value.as_ref()).unwrap(),
+                0.5,
+                "{}",
+                value_type
+            );
+        }
+        let value = 0.1f64.to_value("f", Some(String)).unwrap();
+        assert_eq!(f64::from_value(value.as_ref()).unwrap(), 0.1);
+    }
+
+    #[test]
+    fn test_overrides_reject_rounding() {
+        use ValueType::*;
+
+        assert!(16_777_216i32.to_value("n", Some(Float)).is_ok());
+        assert!(16_777_217i32.to_value("n", Some(Float)).is_err());
+        assert!(((1i64 << 53) + 1).to_value("n", Some(Double)).is_err());
+        assert!(u64::MAX.to_value("n", Some(Double)).is_err());
+        assert!(0.1f64.to_value("f", Some(Float)).is_err());
+
+        assert!(i64::from_value(&DoubleValue::new("n", 2.5)).is_err());
+        assert!(i64::from_value(&DoubleValue::new("n", f64::NAN)).is_err());
+        assert!(i64::from_value(&DoubleValue::new("n", 1e300)).is_err());
+        assert!(u8::from_value(&FloatValue::new("n", 256.0)).is_err());
+        assert!(f32::from_value(&DoubleValue::new("f", 0.1)).is_err());
+        assert!(bool::from_value(&StringValue::new("b", "yes")).is_err());
+    }
+
+    #[test]
+    fn test_integer_range_checks() {
+        let err = i64::MAX.to_value("n", Some(ValueType::Long)).unwrap_err();
+        assert!(matches!(err, ContainerError::InvalidTypeConversion { .. }));
+        assert!(300i32.to_value("n", Some(ValueType::UShort)).is_ok());
+        assert!((-1i32).to_value("n", Some(ValueType::UInt)).is_err());
+
+        let wide = ULLongValue::new("n", u64::MAX);
+        assert_eq!(u64::from_value(&wide).unwrap(), u64::MAX);
+        assert!(i64::from_value(&wide).is_err());
+        assert_eq!(u8::from_value(&IntValue::new("n", 255)).unwrap(), 255);
+        assert!(u8::from_value(&IntValue::new("n", 256)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_types() {
+        assert!(true.to_value("b", Some(ValueType::Int)).is_err());
+        assert!("x"
+            .to_string()
+            .to_value("s", Some(ValueType::Bytes))
+            .is_err());
+        assert!(String::from_value(&IntValue::new("s", 1)).is_err());
+        assert!(i32::from_value(&StringValue::new("n", "one")).is_err());
+        assert!(Vec::<u8>::from_value(&StringValue::new("b", "x")).is_err());
+    }
+
+    #[test]
+    fn test_time_fields() {
+        let sent = Timestamp::from_unix_millis(1_700_000_000_123).unwrap();
+        let value = sent.to_value("sent_at", None).unwrap();
+        assert_eq!(value.value_type(), ValueType::Timestamp);
+        assert_eq!(Timestamp::from_value(value.as_ref()).unwrap(), sent);
+
+        // Epoch milliseconds, as older messages store them
+        let millis = sent.to_value("sent_at", Some(ValueType::LLong)).unwrap();
+        assert_eq!(millis.to_long().unwrap(), 1_700_000_000_123);
+        assert_eq!(Timestamp::from_value(millis.as_ref()).unwrap(), sent);
+
+        let text = sent.to_value("sent_at", Some(ValueType::String)).unwrap();
+        assert_eq!(Timestamp::from_value(text.as_ref()).unwrap(), sent);
+
+        // Milliseconds cannot carry an offset or nanoseconds
+        let local = sent.with_offset(120).unwrap();
+        assert!(local.to_value("sent_at", Some(ValueType::LLong)).is_err());
+        let precise: Timestamp = "2023-11-14T22:13:20.123456Z".parse().unwrap();
+        assert!(precise.to_value("sent_at", Some(ValueType::LLong)).is_err());
+        let text = local.to_value("sent_at", Some(ValueType::String)).unwrap();
+        assert_eq!(Timestamp::from_value(text.as_ref()).unwrap(), local);
+
+        let ttl = std::time::Duration::from_secs(90);
+        let value = ttl.to_value("ttl", Some(ValueType::String)).unwrap();
+        assert_eq!(value.to_string(), "PT1M30S");
+        assert_eq!(
+            std::time::Duration::from_value(value.as_ref()).unwrap(),
+            ttl
+        );
+        assert!(ttl.to_value("ttl", Some(ValueType::Int)).is_err());
+    }
+
+    #[test]
+    fn test_option_maps_to_null() {
+        let none: Option<u32> = None;
+        assert!(none.to_value("o", None).unwrap().is_null());
+        assert_eq!(
+            Some(5u32).to_value("o", None).unwrap().value_type(),
+            ValueType::UInt
+        );
+        assert_eq!(
+            Option::<u32>::from_value(&NullValue::new("o")).unwrap(),
+            None
+        );
+        assert_eq!(
+            Option::<u32>::from_value(&ContainerValue::new("o", vec![])).unwrap(),
+            None
+        );
+        assert_eq!(read_field::<Option<u32>>(&[], "o").unwrap(), None);
+        assert!(matches!(
+            read_field::<u32>(&[], "o"),
+            Err(ContainerError::ValueNotFound(name)) if name == "o"
+        ));
+    }
+
+    #[test]
+    fn test_container_round_trip_and_type_check() {
+        let container = Point { x: 1, y: -2 }.to_container().unwrap();
+        assert_eq!(container.message_type(), "point");
+        assert_eq!(container.value_count(), 2);
+
+        let point = Point::from_container(&container).unwrap();
+        assert_eq!((point.x, point.y), (1, -2));
+
+        let mut other = container.copy(true);
+        other.set_message_type("shape");
+        assert!(matches!(
+            Point::from_container(&other),
+            Err(ContainerError::InvalidDataFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_nested_messages() {
+        let points = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+        let value = points.to_value("points", None).unwrap();
+        assert_eq!(value.value_type(), ValueType::Array);
+
+        let back = Vec::<Point>::from_value(value.as_ref()).unwrap();
+        assert_eq!(back.len(), 2);
+        assert_eq!((back[1].x, back[1].y), (3, 4));
+
+        let single = Point { x: 5, y: 6 }.to_value("origin", None).unwrap();
+        assert_eq!(single.value_type(), ValueType::Container);
+        assert_eq!(Point::from_value(single.as_ref()).unwrap().y, 6);
+    }
+}
@@ -52,5 +52,15 @@
 /// Builder module for fluent container construction
 pub mod builder;
 
+/// Typed message marshalling
+pub mod message;
+
 /// Re-export MessagingContainerBuilder for convenient access
 pub use builder::MessagingContainerBuilder;
+
+/// Re-export the message traits for convenient access
+pub use message::{read_field, ContainerMessage, MessageField};
+
+/// Derive macro for `ContainerMessage` (requires the `derive` feature)
+#[cfg(feature = "derive")]
+pub use rust_container_system_derive::ContainerMessage;
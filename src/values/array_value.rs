@@ -31,11 +31,9 @@
 
 use crate::core::error::Result;
 use crate::core::value::Value;
+use crate::core::value_codec::{encode_value, ValueDecoder};
 use crate::core::value_types::ValueType;
-use crate::values::bytes_value::BytesValue;
-use crate::values::primitive_values::{
-    BoolValue, DoubleValue, FloatValue, ShortValue, UIntValue, ULongValue, UShortValue,
-};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
@@ -66,9 +64,10 @@ use std::sync::Arc;
 ///
 /// assert_eq!(array.count(), 2);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArrayValue {
     name: String,
+    #[serde(with = "crate::core::value_serde::values")]
     elements: Vec<Arc<dyn Value>>,
 }
 
@@ -176,6 +175,48 @@ impl ArrayValue {
         self.elements.get(index).cloned()
     }
 
+    /// Replace the element at index, returning the previous one
+    ///
+    /// Returns `None` and leaves the array unchanged if the index is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::values::{ArrayValue, IntValue};
+    /// use std::sync::Arc;
+    ///
+    /// let mut array = ArrayValue::new("data", vec![Arc::new(IntValue::new("", 1))]);
+    /// let old = array.set(0, Arc::new(IntValue::new("", 2))).unwrap();
+    /// assert_eq!(old.to_int().unwrap(), 1);
+    /// assert_eq!(array.at(0).unwrap().to_int().unwrap(), 2);
+    /// assert!(array.set(5, Arc::new(IntValue::new("", 3))).is_none());
+    /// ```
+    pub fn set(&mut self, index: usize, element: Arc<dyn Value>) -> Option<Arc<dyn Value>> {
+        self.elements
+            .get_mut(index)
+            .map(|slot| std::mem::replace(slot, element))
+    }
+
+    /// Remove the element at index, shifting later elements down
+    ///
+    /// Returns `None` if the index is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::values::{ArrayValue, IntValue};
+    /// use std::sync::Arc;
+    ///
+    /// let elem1 = Arc::new(IntValue::new("", 1));
+    /// let elem2 = Arc::new(IntValue::new("", 2));
+    /// let mut array = ArrayValue::new("data", vec![elem1, elem2]);
+    ///
+    /// assert_eq!(array.remove(0).unwrap().to_int().unwrap(), 1);
+    /// assert_eq!(array.at(0).unwrap().to_int().unwrap(), 2);
+    /// assert!(array.remove(1).is_none());
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<Arc<dyn Value>> {
+        (index < self.elements.len()).then(|| self.elements.remove(index))
+    }
+
     /// Clear all elements
     ///
     /// # Example
@@ -215,16 +256,38 @@ impl ArrayValue {
     /// `[type:1=15][name_len:4 LE][name:UTF-8][value_size:4 LE][count:4 LE][element1_bytes][element2_bytes]...`
     ///
     /// This produces byte-for-byte compatible output with C++ ArrayValue::serialize()
+    ///
+    /// An element the shared codec cannot encode (such as a custom `Value` claiming
+    /// to be a container) is written with its own `to_bytes()`, which the decoder may
+    /// not be able to read back; use
+    /// [`try_to_binary_bytes`](Self::try_to_binary_bytes) to get an error instead.
     pub fn to_binary_bytes(&self) -> Vec<u8> {
-        // Serialize all elements first to calculate total size
-        let mut serialized_elements: Vec<Vec<u8>> = Vec::with_capacity(self.elements.len());
-        let mut total_elements_size = 0usize;
+        self.assemble_binary(
+            self.elements
+                .iter()
+                .map(|element| encode_value(element).unwrap_or_else(|_| element.to_bytes()))
+                .collect(),
+        )
+    }
 
-        for element in &self.elements {
-            let elem_bytes = element.to_bytes();
-            total_elements_size += elem_bytes.len();
-            serialized_elements.push(elem_bytes);
-        }
+    /// Serialize to complete binary format with header, like
+    /// [`to_binary_bytes`](Self::to_binary_bytes)
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`encode_value`] error for the first element the codec cannot encode.
+    pub fn try_to_binary_bytes(&self) -> Result<Vec<u8>> {
+        let serialized_elements = self
+            .elements
+            .iter()
+            .map(encode_value)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.assemble_binary(serialized_elements))
+    }
+
+    /// Prefix already encoded elements with the array header
+    fn assemble_binary(&self, serialized_elements: Vec<Vec<u8>>) -> Vec<u8> {
+        let total_elements_size: usize = serialized_elements.iter().map(Vec::len).sum();
 
         // value_size = count(4) + all element bytes
         let value_size = 4 + total_elements_size;
@@ -273,6 +336,14 @@ impl ArrayValue {
     /// - Type byte is not 15 (ArrayValue)
     /// - Element deserialization fails
     pub fn deserialize_binary(data: &[u8]) -> Result<Self> {
+        Self::deserialize_binary_with_decoder(data, &ValueDecoder::new())
+    }
+
+    /// Deserialize ArrayValue from complete binary format, decoding elements through `decoder`
+    ///
+    /// Use this when elements may be custom `Value` implementations registered with a
+    /// [`ValueDecoder`].
+    pub fn deserialize_binary_with_decoder(data: &[u8], decoder: &ValueDecoder) -> Result<Self> {
         use crate::core::error::ContainerError;
 
         if data.len() < 13 {
@@ -360,9 +431,7 @@ impl ArrayValue {
             // Extract remaining data for element deserialization
             let element_data = &data[offset..];
 
-            // Deserialize element using factory
-            // TODO: Implement full value factory for all types
-            let (element, bytes_read) = Self::deserialize_value(element_data)?;
+            let (element, bytes_read) = decoder.decode(element_data)?;
 
             elements.push(element);
             offset += bytes_read;
@@ -370,512 +439,6 @@ impl ArrayValue {
 
         Ok(ArrayValue::new(name, elements))
     }
-
-    /// Helper function to deserialize a single value from binary data
-    ///
-    /// Returns the deserialized value and the number of bytes consumed.
-    ///
-    /// # TODO
-    /// This is a minimal implementation. Full Value factory pattern needed for all types.
-    fn deserialize_value(data: &[u8]) -> Result<(Arc<dyn Value>, usize)> {
-        use crate::core::error::ContainerError;
-        use crate::values::{IntValue, LongValue, StringValue};
-
-        if data.is_empty() {
-            return Err(ContainerError::InvalidDataFormat(
-                "Empty data for value deserialization".to_string(),
-            ));
-        }
-
-        // Read type ID - manually map u8 to ValueType
-        let type_byte = data[0];
-        let type_id = match type_byte {
-            0 => ValueType::Null,
-            1 => ValueType::Bool,
-            2 => ValueType::Short,
-            3 => ValueType::UShort,
-            4 => ValueType::Int,
-            5 => ValueType::UInt,
-            6 => ValueType::Long,
-            7 => ValueType::ULong,
-            8 => ValueType::LLong,
-            9 => ValueType::ULLong,
-            10 => ValueType::Float,
-            11 => ValueType::Double,
-            12 => ValueType::String, // Matches C++ string_value = 12
-            13 => ValueType::Bytes,  // Matches C++ bytes_value = 13
-            14 => ValueType::Container,
-            15 => ValueType::Array,
-            _ => {
-                return Err(ContainerError::InvalidDataFormat(format!(
-                    "Unknown value type: {}",
-                    type_byte
-                )));
-            }
-        };
-
-        match type_id {
-            ValueType::Int => {
-                // Deserialize IntValue (type 3)
-                // Format: [type:1][name_len:4][name][value_size:4][value:4]
-                if data.len() < 13 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for IntValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                // Name length
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                // Name
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                // Skip value_size (4 bytes)
-                offset += 4;
-
-                // Read int value (4 bytes, little-endian)
-                let value = i32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]);
-                offset += 4;
-
-                Ok((Arc::new(IntValue::new(name, value)), offset))
-            }
-
-            ValueType::Long | ValueType::LLong => {
-                // Deserialize LongValue (type 5 or 7)
-                // Format: [type:1][name_len:4][name][value_size:4][value:8]
-                if data.len() < 17 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for LongValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                // Name length
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                // Name
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                // Skip value_size (4 bytes)
-                offset += 4;
-
-                // Read long value (8 bytes, little-endian)
-                let value = i64::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                    data[offset + 4],
-                    data[offset + 5],
-                    data[offset + 6],
-                    data[offset + 7],
-                ]);
-                offset += 8;
-
-                Ok((Arc::new(LongValue::new(name, value)?), offset))
-            }
-
-            ValueType::Bool => {
-                // Deserialize BoolValue (type 1)
-                // Format: [type:1][name_len:4][name][value_size:4][value:1]
-                if data.len() < 10 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for BoolValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                if offset + name_len + 4 + 1 > data.len() {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Data too short for BoolValue".to_string(),
-                    ));
-                }
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let value = data[offset] != 0;
-                offset += 1;
-
-                Ok((Arc::new(BoolValue::new(name, value)), offset))
-            }
-
-            ValueType::Short => {
-                // Deserialize ShortValue (type 2)
-                // Format: [type:1][name_len:4][name][value_size:4][value:2]
-                if data.len() < 11 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for ShortValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                if offset + name_len + 4 + 2 > data.len() {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Data too short for ShortValue".to_string(),
-                    ));
-                }
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let value = i16::from_le_bytes([data[offset], data[offset + 1]]);
-                offset += 2;
-
-                Ok((Arc::new(ShortValue::new(name, value)), offset))
-            }
-
-            ValueType::UShort => {
-                // Deserialize UShortValue (type 3)
-                // Format: [type:1][name_len:4][name][value_size:4][value:2]
-                if data.len() < 11 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for UShortValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                if offset + name_len + 4 + 2 > data.len() {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Data too short for UShortValue".to_string(),
-                    ));
-                }
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let value = u16::from_le_bytes([data[offset], data[offset + 1]]);
-                offset += 2;
-
-                Ok((Arc::new(UShortValue::new(name, value)), offset))
-            }
-
-            ValueType::UInt => {
-                // Deserialize UIntValue (type 5)
-                // Format: [type:1][name_len:4][name][value_size:4][value:4]
-                if data.len() < 13 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for UIntValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let value = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]);
-                offset += 4;
-
-                Ok((Arc::new(UIntValue::new(name, value)), offset))
-            }
-
-            ValueType::ULong | ValueType::ULLong => {
-                // Deserialize ULongValue or ULLongValue (type 7 or 9)
-                // Format: [type:1][name_len:4][name][value_size:4][value:8]
-                if data.len() < 17 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for ULongValue/ULLongValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let value = u64::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                    data[offset + 4],
-                    data[offset + 5],
-                    data[offset + 6],
-                    data[offset + 7],
-                ]);
-                offset += 8;
-
-                Ok((Arc::new(ULongValue::new(name, value)?), offset))
-            }
-
-            ValueType::Float => {
-                // Deserialize FloatValue (type 10)
-                // Format: [type:1][name_len:4][name][value_size:4][value:4]
-                if data.len() < 13 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for FloatValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let bits = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]);
-                let value = f32::from_bits(bits);
-                offset += 4;
-
-                Ok((Arc::new(FloatValue::new(name, value)), offset))
-            }
-
-            ValueType::Double => {
-                // Deserialize DoubleValue (type 11)
-                // Format: [type:1][name_len:4][name][value_size:4][value:8]
-                if data.len() < 17 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for DoubleValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                offset += 4; // Skip value_size
-
-                let bits = u64::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                    data[offset + 4],
-                    data[offset + 5],
-                    data[offset + 6],
-                    data[offset + 7],
-                ]);
-                let value = f64::from_bits(bits);
-                offset += 8;
-
-                Ok((Arc::new(DoubleValue::new(name, value)), offset))
-            }
-
-            ValueType::Bytes => {
-                // Deserialize BytesValue (type 12)
-                // Format: [type:1][name_len:4][name][value_size:4][bytes]
-                if data.len() < 13 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for BytesValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                let value_size = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                let value = data[offset..offset + value_size].to_vec();
-                offset += value_size;
-
-                Ok((Arc::new(BytesValue::new(name, value)), offset))
-            }
-
-            ValueType::String => {
-                // Deserialize StringValue (type 12)
-                // Format: [type:1][name_len:4][name][value_size:4][string_bytes]
-                if data.len() < 13 {
-                    return Err(ContainerError::InvalidDataFormat(
-                        "Insufficient data for StringValue".to_string(),
-                    ));
-                }
-
-                let mut offset = 1;
-
-                // Name length
-                let name_len = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                // Name
-                let name =
-                    String::from_utf8(data[offset..offset + name_len].to_vec()).map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!("Invalid UTF-8: {}", e))
-                    })?;
-                offset += name_len;
-
-                // Value size
-                let value_size = u32::from_le_bytes([
-                    data[offset],
-                    data[offset + 1],
-                    data[offset + 2],
-                    data[offset + 3],
-                ]) as usize;
-                offset += 4;
-
-                // String bytes
-                let str_value = String::from_utf8(data[offset..offset + value_size].to_vec())
-                    .map_err(|e| {
-                        ContainerError::InvalidDataFormat(format!(
-                            "Invalid UTF-8 in string value: {}",
-                            e
-                        ))
-                    })?;
-                offset += value_size;
-
-                Ok((Arc::new(StringValue::new(name, str_value)), offset))
-            }
-
-            _ => Err(ContainerError::InvalidDataFormat(format!(
-                "Unsupported value type for deserialization: {:?}",
-                type_id
-            ))),
-        }
-    }
 }
 
 impl Value for ArrayValue {
@@ -1082,6 +645,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_binary_roundtrip_mixed_and_nested() {
+        use crate::values::{LLongValue, LongValue, NullValue, ShortValue, ULLongValue};
+
+        let inner = ArrayValue::new(
+            "",
+            vec![Arc::new(ShortValue::new("", -3)) as Arc<dyn Value>],
+        );
+        let original = ArrayValue::new(
+            "mixed",
+            vec![
+                Arc::new(LongValue::new("", -70_000).unwrap()) as Arc<dyn Value>,
+                Arc::new(LLongValue::new("", i64::MAX)),
+                Arc::new(ULLongValue::new("", u64::MAX)),
+                Arc::new(NullValue::new("")),
+                Arc::new(inner),
+            ],
+        );
+
+        let restored = ArrayValue::deserialize_binary(&original.to_binary_bytes()).unwrap();
+        assert_eq!(restored.count(), 5);
+        for (a, b) in original.elements().iter().zip(restored.elements()) {
+            assert_eq!(a.value_type(), b.value_type());
+            assert_eq!(a.to_string(), b.to_string());
+        }
+    }
+
     #[test]
     fn test_binary_empty_array() {
         let empty = ArrayValue::new("empty", vec![]);
@@ -1178,4 +768,55 @@ mod tests {
         let elem1 = restored.at(1).unwrap();
         assert_eq!(elem1.to_string(), "blue");
     }
+
+    /// A value that claims to be a container but is not a `ContainerValue`
+    #[derive(Debug)]
+    struct FakeContainer;
+
+    impl Value for FakeContainer {
+        fn name(&self) -> &str {
+            ""
+        }
+        fn value_type(&self) -> ValueType {
+            ValueType::Container
+        }
+        fn size(&self) -> usize {
+            0
+        }
+        fn to_string(&self) -> String {
+            String::new()
+        }
+        fn to_bytes(&self) -> Vec<u8> {
+            Vec::new()
+        }
+        fn to_json(&self) -> Result<String> {
+            Ok("null".to_string())
+        }
+        fn to_xml(&self) -> Result<String> {
+            Ok(String::new())
+        }
+        fn clone_value(&self) -> Arc<dyn Value> {
+            Arc::new(FakeContainer)
+        }
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_binary_rejects_unencodable_element() {
+        let array = ArrayValue::new(
+            "mixed",
+            vec![Arc::new(IntValue::new("", 1)), Arc::new(FakeContainer)],
+        );
+        assert!(matches!(
+            array.try_to_binary_bytes(),
+            Err(crate::core::ContainerError::SerializationError(_))
+        ));
+        // The infallible form falls back to the element's own bytes
+        assert!(!array.to_binary_bytes().is_empty());
+
+        let ints = ArrayValue::new("ints", vec![Arc::new(IntValue::new("", 1))]);
+        assert_eq!(ints.try_to_binary_bytes().unwrap(), ints.to_binary_bytes());
+    }
 }
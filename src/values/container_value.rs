@@ -32,6 +32,7 @@
 use crate::core::error::{ContainerError, Result};
 use crate::core::value::Value;
 use crate::core::value_types::ValueType;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
@@ -54,9 +55,10 @@ use std::sync::Arc;
 ///
 /// assert_eq!(container.child_count(), 2);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerValue {
     name: String,
+    #[serde(with = "crate::core::value_serde::values")]
     children: Vec<Arc<dyn Value>>,
 }
 
@@ -183,6 +185,65 @@ impl ContainerValue {
             .collect()
     }
 
+    /// Replace the nth child with the given name, returning the previous one
+    ///
+    /// Returns `None` and leaves the container unchanged if there is no such child.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::values::{ContainerValue, IntValue};
+    /// use std::sync::Arc;
+    ///
+    /// let child1 = Arc::new(IntValue::new("value", 1));
+    /// let child2 = Arc::new(IntValue::new("value", 2));
+    /// let mut container = ContainerValue::new("data", vec![child1, child2]);
+    ///
+    /// let old = container.set_child("value", 1, Arc::new(IntValue::new("value", 3)));
+    /// assert_eq!(old.unwrap().to_int().unwrap(), 2);
+    /// assert_eq!(container.get_child("value", 1).unwrap().to_int().unwrap(), 3);
+    /// ```
+    pub fn set_child(
+        &mut self,
+        name: &str,
+        index: usize,
+        child: Arc<dyn Value>,
+    ) -> Option<Arc<dyn Value>> {
+        let position = self.position(name, index)?;
+        Some(std::mem::replace(&mut self.children[position], child))
+    }
+
+    /// Remove the nth child with the given name
+    ///
+    /// Returns the removed child, or `None` if there is no such child.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_container_system::values::{ContainerValue, IntValue};
+    /// use std::sync::Arc;
+    ///
+    /// let child1 = Arc::new(IntValue::new("tag", 1));
+    /// let child2 = Arc::new(IntValue::new("tag", 2));
+    /// let mut container = ContainerValue::new("data", vec![child1, child2]);
+    ///
+    /// assert_eq!(container.remove_child_at("tag", 0).unwrap().to_int().unwrap(), 1);
+    /// assert_eq!(container.child_count(), 1);
+    /// assert!(container.remove_child_at("tag", 1).is_none());
+    /// ```
+    pub fn remove_child_at(&mut self, name: &str, index: usize) -> Option<Arc<dyn Value>> {
+        let position = self.position(name, index)?;
+        Some(self.children.remove(position))
+    }
+
+    /// Position in `children` of the nth child with the given name
+    fn position(&self, name: &str, index: usize) -> Option<usize> {
+        self.children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| child.name() == name)
+            .nth(index)
+            .map(|(position, _)| position)
+    }
+
     /// Remove all children with the given name
     ///
     /// Returns true if any children were removed.
@@ -0,0 +1,812 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Exact decimal value implementation.
+//!
+//! [`DecimalValue`] stores a [`Decimal`]: a 128-bit integer mantissa with a
+//! decimal scale (up to 38 digits, like SQL `DECIMAL(38, s)`), so amounts such
+//! as `0.10` are kept exactly instead of being approximated by `f64`.
+//!
+//! `decimal_value` (type code 16) is an extended type. C++ peers that do not
+//! know it receive it as a `string_value` holding the canonical text (see
+//! [`TypeSupport`](crate::core::value_types::TypeSupport)).
+//!
+//! | Format | Encoding |
+//! |--------|----------|
+//! | Wire protocol | canonical text (`[amount,decimal_value,-12.50]`) |
+//! | Binary | 17 bytes: `[scale:1][mantissa:16 LE]` |
+//! | JSON v2 | canonical text as a JSON string (no float rounding) |
+//!
+//! Conversions to integers round with the value's [`RoundingMode`]
+//! (half-even by default) and fail if the result does not fit.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rust_container_system::values::{Decimal, DecimalValue, RoundingMode};
+//! use rust_container_system::core::Value;
+//!
+//! let price: Decimal = "19.99".parse().unwrap();
+//! let quantity = Decimal::from(3);
+//! let total = price.checked_mul(quantity).unwrap();
+//! assert_eq!(total.to_string(), "59.97");
+//!
+//! let value = DecimalValue::new("total", total).with_rounding(RoundingMode::Down);
+//! assert_eq!(value.to_long().unwrap(), 59);
+//! assert_eq!(value.to_string(), "59.97");
+//! ```
+
+use crate::core::{ContainerError, Result, Value, ValueType};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Largest scale (digits after the decimal point)
+pub const MAX_SCALE: u32 = 38;
+
+/// Largest mantissa magnitude (38 nines)
+const MAX_MANTISSA: u128 = 10u128.pow(38) - 1;
+
+/// Size of the binary payload: scale byte and 16-byte mantissa
+pub const DECIMAL_PAYLOAD_SIZE: usize = 17;
+
+fn pow10(exp: u32) -> u128 {
+    10u128.pow(exp)
+}
+
+/// How to round when digits are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// Toward zero (truncate)
+    Down,
+    /// Away from zero
+    Up,
+    /// Toward negative infinity
+    Floor,
+    /// Toward positive infinity
+    Ceiling,
+    /// To nearest, ties away from zero
+    HalfUp,
+    /// To nearest, ties toward zero
+    HalfDown,
+    /// To nearest, ties to the even neighbour (banker's rounding)
+    #[default]
+    HalfEven,
+}
+
+impl RoundingMode {
+    /// Round a truncated magnitude `q` given how the dropped part compares to
+    /// one half and whether anything non-zero was dropped
+    fn apply(self, q: u128, half: Ordering, inexact: bool, negative: bool) -> u128 {
+        let away = match self {
+            RoundingMode::Down => false,
+            RoundingMode::Up => inexact,
+            RoundingMode::Floor => inexact && negative,
+            RoundingMode::Ceiling => inexact && !negative,
+            RoundingMode::HalfUp => half != Ordering::Less,
+            RoundingMode::HalfDown => half == Ordering::Greater,
+            RoundingMode::HalfEven => {
+                half == Ordering::Greater || (half == Ordering::Equal && q % 2 == 1)
+            }
+        };
+        q + u128::from(away)
+    }
+}
+
+/// `(10 * r / d, 10 * r % d)` for `r < d` without overflowing
+fn times_ten_div(r: u128, d: u128) -> (u128, u128) {
+    if r <= u128::MAX / 10 {
+        return (r * 10 / d, r * 10 % d);
+    }
+    let (mut digit, mut rem) = (0, 0u128);
+    for _ in 0..10 {
+        if rem >= d - r {
+            rem -= d - r;
+            digit += 1;
+        } else {
+            rem += r;
+        }
+    }
+    (digit, rem)
+}
+
+fn scale_error(scale: u32) -> ContainerError {
+    ContainerError::InvalidDataFormat(format!("decimal scale {} exceeds {}", scale, MAX_SCALE))
+}
+
+fn overflow(operation: &str) -> ContainerError {
+    ContainerError::InvalidDataFormat(format!("decimal overflow in {}", operation))
+}
+
+/// Exact decimal number: `mantissa / 10^scale`
+///
+/// The mantissa holds at most 38 digits and the scale is at most 38.
+/// Comparison is by numeric value (`1.5 == 1.50`); the scale is kept for
+/// display and encoding.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Zero with scale 0
+    pub const ZERO: Decimal = Decimal {
+        mantissa: 0,
+        scale: 0,
+    };
+
+    /// `mantissa / 10^scale`
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the scale exceeds [`MAX_SCALE`] or the
+    /// mantissa has more than 38 digits.
+    pub fn new(mantissa: i128, scale: u32) -> Result<Self> {
+        if scale > MAX_SCALE {
+            return Err(scale_error(scale));
+        }
+        Self::checked(mantissa.unsigned_abs(), mantissa < 0, scale)
+            .ok_or_else(|| overflow("construction"))
+    }
+
+    /// Decimal from a magnitude and sign, if the magnitude fits
+    fn checked(magnitude: u128, negative: bool, scale: u32) -> Option<Self> {
+        if magnitude > MAX_MANTISSA {
+            return None;
+        }
+        let mantissa = magnitude as i128;
+        Some(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale,
+        })
+    }
+
+    /// The integer mantissa
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Digits after the decimal point
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Whether the value is zero
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Whether the value is below zero
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// The same value with a different scale, rounding if digits are dropped
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the scale exceeds [`MAX_SCALE`] or the
+    /// result needs more than 38 digits.
+    pub fn rescale(&self, scale: u32, mode: RoundingMode) -> Result<Self> {
+        if scale > MAX_SCALE {
+            return Err(scale_error(scale));
+        }
+        let magnitude = self.mantissa.unsigned_abs();
+        let negative = self.is_negative();
+        let result = if scale >= self.scale {
+            magnitude
+                .checked_mul(pow10(scale - self.scale))
+                .and_then(|m| Self::checked(m, negative, scale))
+        } else {
+            let divisor = pow10(self.scale - scale);
+            let (q, r) = (magnitude / divisor, magnitude % divisor);
+            let q = mode.apply(q, r.cmp(&(divisor - r)), r != 0, negative);
+            Self::checked(q, negative, scale)
+        };
+        result.ok_or_else(|| overflow("rescale"))
+    }
+
+    /// Round to `scale` digits after the point (never adds digits)
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if rounding up overflows 38 digits.
+    pub fn round(&self, scale: u32, mode: RoundingMode) -> Result<Self> {
+        if scale >= self.scale {
+            return Ok(*self);
+        }
+        self.rescale(scale, mode)
+    }
+
+    /// The same value without trailing fractional zeros (`1.500` to `1.5`)
+    pub fn normalize(&self) -> Self {
+        let mut result = *self;
+        while result.scale > 0 && result.mantissa % 10 == 0 {
+            result.mantissa /= 10;
+            result.scale -= 1;
+        }
+        result
+    }
+
+    /// Exact sum, or `None` if it needs more than 38 digits
+    pub fn checked_add(self, rhs: Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(rhs.scale);
+        let left = self.rescale(scale, RoundingMode::Down).ok()?;
+        let right = rhs.rescale(scale, RoundingMode::Down).ok()?;
+        let sum = left.mantissa.checked_add(right.mantissa)?;
+        Self::checked(sum.unsigned_abs(), sum < 0, scale)
+    }
+
+    /// Exact difference, or `None` if it needs more than 38 digits
+    pub fn checked_sub(self, rhs: Decimal) -> Option<Decimal> {
+        self.checked_add(-rhs)
+    }
+
+    /// Exact product (scales add up), or `None` if it needs more than 38
+    /// digits or a scale above [`MAX_SCALE`]
+    pub fn checked_mul(self, rhs: Decimal) -> Option<Decimal> {
+        let scale = self.scale + rhs.scale;
+        if scale > MAX_SCALE {
+            return None;
+        }
+        let product = self
+            .mantissa
+            .unsigned_abs()
+            .checked_mul(rhs.mantissa.unsigned_abs())?;
+        Self::checked(product, self.is_negative() != rhs.is_negative(), scale)
+    }
+
+    /// Quotient rounded to `scale` digits, or `None` when dividing by zero or
+    /// if the result needs more than 38 digits
+    pub fn checked_div(self, rhs: Decimal, scale: u32, mode: RoundingMode) -> Option<Decimal> {
+        if rhs.is_zero() || scale > MAX_SCALE {
+            return None;
+        }
+        let negative = self.is_negative() != rhs.is_negative();
+        let (n, d) = (self.mantissa.unsigned_abs(), rhs.mantissa.unsigned_abs());
+        let (mut q, mut r) = (n / d, n % d);
+
+        // Result mantissa is n / d * 10^shift
+        let shift = i64::from(scale) + i64::from(rhs.scale) - i64::from(self.scale);
+        if shift >= 0 {
+            for _ in 0..shift {
+                let (digit, rem) = times_ten_div(r, d);
+                q = q.checked_mul(10)?.checked_add(digit)?;
+                if q > MAX_MANTISSA {
+                    return None;
+                }
+                r = rem;
+            }
+            let q = mode.apply(q, r.cmp(&(d - r)), r != 0, negative);
+            return Self::checked(q, negative, scale);
+        }
+
+        // Drop the last -shift digits of the integer quotient
+        let divisor = pow10(u32::try_from(-shift).ok()?);
+        let (kept, dropped) = (q / divisor, q % divisor);
+        let half = match dropped.cmp(&(divisor / 2)) {
+            Ordering::Equal if r > 0 => Ordering::Greater,
+            half => half,
+        };
+        let q = mode.apply(kept, half, dropped != 0 || r != 0, negative);
+        Self::checked(q, negative, scale)
+    }
+
+    /// Integer value, rounding the fraction with `mode`
+    ///
+    /// # Errors
+    ///
+    /// Never fails for valid decimals; the `Result` matches [`rescale`](Self::rescale).
+    pub fn to_i128(&self, mode: RoundingMode) -> Result<i128> {
+        Ok(self.rescale(0, mode)?.mantissa)
+    }
+
+    /// Nearest `f64`
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Binary payload: `[scale:1][mantissa:16 LE]`
+    pub fn to_payload(&self) -> [u8; DECIMAL_PAYLOAD_SIZE] {
+        let mut out = [0; DECIMAL_PAYLOAD_SIZE];
+        out[0] = self.scale as u8;
+        out[1..].copy_from_slice(&self.mantissa.to_le_bytes());
+        out
+    }
+
+    /// Parse a binary payload written by [`to_payload`](Self::to_payload)
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the payload is not 17 bytes or holds an
+    /// out-of-range scale or mantissa.
+    pub fn from_payload(payload: &[u8]) -> Result<Self> {
+        if payload.len() != DECIMAL_PAYLOAD_SIZE {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "decimal payload must be {} bytes, got {}",
+                DECIMAL_PAYLOAD_SIZE,
+                payload.len()
+            )));
+        }
+        let mut mantissa = [0; 16];
+        mantissa.copy_from_slice(&payload[1..]);
+        Self::new(i128::from_le_bytes(mantissa), u32::from(payload[0]))
+    }
+}
+
+impl From<i32> for Decimal {
+    fn from(n: i32) -> Self {
+        Self {
+            mantissa: n.into(),
+            scale: 0,
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(n: i64) -> Self {
+        Self {
+            mantissa: n.into(),
+            scale: 0,
+        }
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(n: u64) -> Self {
+        Self {
+            mantissa: n.into(),
+            scale: 0,
+        }
+    }
+}
+
+impl std::ops::Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal {
+            mantissa: -self.mantissa,
+            scale: self.scale,
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Integer parts, then fractions widened to 38 digits (both fit in i128)
+        let split = |d: &Decimal| {
+            let unit = pow10(d.scale) as i128;
+            let fraction = (d.mantissa % unit) * pow10(MAX_SCALE - d.scale) as i128;
+            (d.mantissa / unit, fraction)
+        };
+        split(self).cmp(&split(other))
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalize();
+        normalized.mantissa.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!(
+            "{:0width$}",
+            self.mantissa.unsigned_abs(),
+            width = scale + 1
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        let sign = if self.is_negative() { "-" } else { "" };
+        if fraction.is_empty() {
+            write!(f, "{}{}", sign, integer)
+        } else {
+            write!(f, "{}{}.{}", sign, integer, fraction)
+        }
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ContainerError;
+
+    /// Parse `[-+]digits[.digits]`, keeping the number of fraction digits as
+    /// the scale
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || ContainerError::ParseError(format!("invalid decimal '{}'", s));
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) if !fraction.is_empty() => (integer, fraction),
+            Some(_) => return Err(invalid()),
+            None => (unsigned, ""),
+        };
+        if integer.is_empty()
+            || !(integer.bytes().chain(fraction.bytes())).all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let scale = u32::try_from(fraction.len()).map_err(|_| invalid())?;
+        let mut magnitude: u128 = 0;
+        for digit in integer.bytes().chain(fraction.bytes()) {
+            magnitude = magnitude
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(u128::from(digit - b'0')))
+                .filter(|&m| m <= MAX_MANTISSA)
+                .ok_or_else(invalid)?;
+        }
+        if scale > MAX_SCALE {
+            return Err(invalid());
+        }
+        Self::checked(magnitude, negative, scale).ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Exact decimal value (`decimal_value`, type code 16)
+///
+/// Carries the [`RoundingMode`] used when converting to integer types; the
+/// mode is a local setting and is not serialized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecimalValue {
+    name: String,
+    value: Decimal,
+    #[serde(skip)]
+    rounding: RoundingMode,
+}
+
+impl DecimalValue {
+    /// Create a decimal value that rounds half-even when converted to integers
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rust_container_system::values::{Decimal, DecimalValue};
+    ///
+    /// let fee = DecimalValue::new("fee", Decimal::new(250, 2).unwrap());
+    /// assert_eq!(fee.value().to_string(), "2.50");
+    /// ```
+    pub fn new(name: impl Into<String>, value: Decimal) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    /// Parse a decimal value from its text form (`"-12.50"`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the text is not a decimal number.
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self> {
+        Ok(Self::new(name, text.parse()?))
+    }
+
+    /// Set the rounding used by integer conversions
+    #[must_use = "builder methods return a new value"]
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// The decimal number
+    pub fn value(&self) -> Decimal {
+        self.value
+    }
+
+    /// The rounding used by integer conversions
+    pub fn rounding(&self) -> RoundingMode {
+        self.rounding
+    }
+
+    fn integer<T: TryFrom<i128>>(&self, to: &str) -> Result<T> {
+        self.value
+            .to_i128(self.rounding)
+            .ok()
+            .and_then(|n| T::try_from(n).ok())
+            .ok_or_else(|| ContainerError::InvalidTypeConversion {
+                from: format!("decimal({})", self.value),
+                to: to.to_string(),
+            })
+    }
+}
+
+impl Value for DecimalValue {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Decimal
+    }
+
+    fn size(&self) -> usize {
+        DECIMAL_PAYLOAD_SIZE
+    }
+
+    fn to_short(&self) -> Result<i16> {
+        self.integer("i16")
+    }
+
+    fn to_ushort(&self) -> Result<u16> {
+        self.integer("u16")
+    }
+
+    fn to_int(&self) -> Result<i32> {
+        self.integer("i32")
+    }
+
+    fn to_uint(&self) -> Result<u32> {
+        self.integer("u32")
+    }
+
+    fn to_long(&self) -> Result<i64> {
+        self.integer("i64")
+    }
+
+    fn to_ulong(&self) -> Result<u64> {
+        self.integer("u64")
+    }
+
+    fn to_float(&self) -> Result<f32> {
+        self.value
+            .to_string()
+            .parse()
+            .map_err(|_| ContainerError::InvalidTypeConversion {
+                from: format!("decimal({})", self.value),
+                to: "f32".to_string(),
+            })
+    }
+
+    fn to_double(&self) -> Result<f64> {
+        Ok(self.value.to_f64())
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_string()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // Format: [type:1][name_len:4][name][value_size:4][scale:1][mantissa:16]
+        let name_bytes = self.name.as_bytes();
+        let mut result = Vec::with_capacity(9 + name_bytes.len() + DECIMAL_PAYLOAD_SIZE);
+        result.push(ValueType::Decimal as u8);
+        result.extend_from_slice(&(name_bytes.len() as u32).to_le_bytes());
+        result.extend_from_slice(name_bytes);
+        result.extend_from_slice(&(DECIMAL_PAYLOAD_SIZE as u32).to_le_bytes());
+        result.extend_from_slice(&self.value.to_payload());
+        result
+    }
+
+    fn to_json(&self) -> Result<String> {
+        // Text keeps every digit; a JSON number would be read back as a float
+        let tagged = serde_json::json!({
+            "type": "decimal",
+            "value": self.value.to_string()
+        });
+        serde_json::to_string(&tagged).map_err(Into::into)
+    }
+
+    fn to_xml(&self) -> Result<String> {
+        Ok(format!("<decimal>{}</decimal>", self.value))
+    }
+
+    fn clone_value(&self) -> Arc<dyn Value> {
+        Arc::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(text: &str) -> Decimal {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(dec("12.50").to_string(), "12.50");
+        assert_eq!(dec("-0.005").to_string(), "-0.005");
+        assert_eq!(dec("+7").to_string(), "7");
+        assert_eq!(dec("12.50").scale(), 2);
+        assert!("1.".parse::<Decimal>().is_err());
+        assert!(".5".parse::<Decimal>().is_err());
+        assert!("1e5".parse::<Decimal>().is_err());
+        assert!(Decimal::new(1, MAX_SCALE + 1).is_err());
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let sum = dec("0.1").checked_add(dec("0.2")).unwrap();
+        assert_eq!(sum, dec("0.3"));
+        assert_eq!(sum.to_string(), "0.3");
+
+        assert_eq!(
+            dec("19.99").checked_mul(dec("3")).unwrap().to_string(),
+            "59.97"
+        );
+        assert_eq!(
+            dec("1.5").checked_sub(dec("2.25")).unwrap().to_string(),
+            "-0.75"
+        );
+
+        let third = dec("10")
+            .checked_div(dec("3"), 4, RoundingMode::HalfEven)
+            .unwrap();
+        assert_eq!(third.to_string(), "3.3333");
+        assert!(dec("1")
+            .checked_div(Decimal::ZERO, 2, RoundingMode::HalfEven)
+            .is_none());
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let cases = [
+            (RoundingMode::Down, ["2.5", "-2.5", "2.6"], ["2", "-2", "2"]),
+            (RoundingMode::Up, ["2.1", "-2.1", "2.0"], ["3", "-3", "2"]),
+            (
+                RoundingMode::Floor,
+                ["2.5", "-2.1", "2.9"],
+                ["2", "-3", "2"],
+            ),
+            (
+                RoundingMode::Ceiling,
+                ["2.1", "-2.9", "2.0"],
+                ["3", "-2", "2"],
+            ),
+            (
+                RoundingMode::HalfUp,
+                ["2.5", "-2.5", "2.4"],
+                ["3", "-3", "2"],
+            ),
+            (
+                RoundingMode::HalfDown,
+                ["2.5", "-2.5", "2.6"],
+                ["2", "-2", "3"],
+            ),
+            (
+                RoundingMode::HalfEven,
+                ["2.5", "3.5", "-2.5"],
+                ["2", "4", "-2"],
+            ),
+        ];
+        for (mode, inputs, expected) in cases {
+            for (input, expected) in inputs.iter().zip(expected) {
+                assert_eq!(
+                    dec(input).round(0, mode).unwrap().to_string(),
+                    expected,
+                    "{} rounded {:?}",
+                    input,
+                    mode
+                );
+            }
+        }
+        assert_eq!(
+            dec("1.005")
+                .round(2, RoundingMode::HalfUp)
+                .unwrap()
+                .to_string(),
+            "1.01"
+        );
+    }
+
+    #[test]
+    fn test_equality_ignores_scale() {
+        use std::collections::HashSet;
+
+        assert_eq!(dec("1.5"), dec("1.500"));
+        assert!(dec("-1") < dec("0.01"));
+        let set: HashSet<Decimal> = [dec("2.0"), dec("2")].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_payload_roundtrip() {
+        for text in [
+            "0",
+            "12.50",
+            "-99999999999999999999999999999999999999",
+            "0.001",
+        ] {
+            let value = dec(text);
+            let decoded = Decimal::from_payload(&value.to_payload()).unwrap();
+            assert_eq!(decoded.to_string(), text);
+        }
+        let mut bad = dec("1").to_payload();
+        bad[0] = 99;
+        assert!(Decimal::from_payload(&bad).is_err());
+        assert!(Decimal::from_payload(&bad[..4]).is_err());
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        let value = DecimalValue::parse("amount", "2.5").unwrap();
+        assert_eq!(value.value_type(), ValueType::Decimal);
+        assert_eq!(value.to_int().unwrap(), 2);
+        assert_eq!(
+            value.with_rounding(RoundingMode::HalfUp).to_int().unwrap(),
+            3
+        );
+        assert!(value_of("-2.5").to_ulong().is_err());
+        assert_eq!(value_of("12.25").to_double().unwrap(), 12.25);
+        assert_eq!(
+            value_of("12.25").to_json().unwrap(),
+            r#"{"type":"decimal","value":"12.25"}"#
+        );
+        assert!(value_of("1").to_bool().is_err());
+    }
+
+    fn value_of(text: &str) -> DecimalValue {
+        DecimalValue::parse("amount", text).unwrap()
+    }
+}
@@ -37,6 +37,9 @@
 //! - `string_value`: UTF-8 string implementation
 //! - `bytes_value`: Binary data implementation
 //! - `container_value`: Nested container implementation
+//! - `decimal_value`: Exact decimal implementation
+//! - `time_values`: Timestamp and Duration implementations
+//! - `uuid_value`: UUID implementation
 
 /// Null value implementation
 pub mod null_value;
@@ -56,6 +59,15 @@ pub mod container_value;
 /// Array value implementation (arrays/lists)
 pub mod array_value;
 
+/// Exact decimal value implementation
+pub mod decimal_value;
+
+/// Timestamp and duration value implementations
+pub mod time_values;
+
+/// UUID value implementation
+pub mod uuid_value;
+
 /// Re-export null type
 ///
 /// ```rust
@@ -143,3 +155,38 @@ pub use container_value::ContainerValue;
 /// println!("Array has {} elements", array.count());
 /// ```
 pub use array_value::ArrayValue;
+
+/// Re-export decimal types
+///
+/// ```rust
+/// use rust_container_system::values::{Decimal, DecimalValue};
+/// use rust_container_system::core::Value;
+///
+/// let amount = DecimalValue::parse("amount", "0.10").unwrap();
+/// assert_eq!(amount.to_string(), "0.10");
+/// assert_eq!(amount.value(), Decimal::new(1, 1).unwrap());
+/// ```
+pub use decimal_value::{Decimal, DecimalValue, RoundingMode};
+
+/// Re-export time types
+///
+/// ```rust
+/// use rust_container_system::values::{Timestamp, TimestampValue};
+/// use rust_container_system::core::Value;
+///
+/// let sent = TimestampValue::parse("sent_at", "2024-03-01T09:30:00Z").unwrap();
+/// assert_eq!(sent.value().unix_seconds(), 1_709_285_400);
+/// assert_eq!(sent.to_string(), "2024-03-01T09:30:00Z");
+/// ```
+pub use time_values::{DurationValue, Timestamp, TimestampValue};
+
+/// Re-export UUID types
+///
+/// ```rust
+/// use rust_container_system::values::{Uuid, UuidValue};
+/// use rust_container_system::core::Value;
+///
+/// let id = UuidValue::parse("id", "67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+/// assert_eq!(id.size(), 16);
+/// ```
+pub use uuid_value::{Uuid, UuidValue};
@@ -428,6 +428,14 @@ impl LongValue {
         })
     }
 
+    /// Create from a value that is already 32 bits wide (cannot fail)
+    pub fn from_i32(name: impl Into<String>, value: i32) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+
     pub fn value(&self) -> i32 {
         self.value
     }
@@ -637,6 +645,10 @@ impl Value for ShortValue {
         2
     }
 
+    fn to_short(&self) -> Result<i16> {
+        Ok(self.value)
+    }
+
     fn to_int(&self) -> Result<i32> {
         Ok(self.value as i32)
     }
@@ -718,6 +730,18 @@ impl Value for UShortValue {
         2
     }
 
+    fn to_ushort(&self) -> Result<u16> {
+        Ok(self.value)
+    }
+
+    fn to_uint(&self) -> Result<u32> {
+        Ok(self.value as u32)
+    }
+
+    fn to_ulong(&self) -> Result<u64> {
+        Ok(self.value as u64)
+    }
+
     fn to_int(&self) -> Result<i32> {
         Ok(self.value as i32)
     }
@@ -799,6 +823,14 @@ impl Value for UIntValue {
         4
     }
 
+    fn to_uint(&self) -> Result<u32> {
+        Ok(self.value)
+    }
+
+    fn to_ulong(&self) -> Result<u64> {
+        Ok(self.value as u64)
+    }
+
     fn to_int(&self) -> Result<i32> {
         self.value
             .try_into()
@@ -874,6 +906,14 @@ impl ULongValue {
         })
     }
 
+    /// Create from a value that is already 32 bits wide (cannot fail)
+    pub fn from_u32(name: impl Into<String>, value: u32) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+
     pub fn value(&self) -> u32 {
         self.value
     }
@@ -892,6 +932,14 @@ impl Value for ULongValue {
         4 // Always 4 bytes
     }
 
+    fn to_uint(&self) -> Result<u32> {
+        Ok(self.value)
+    }
+
+    fn to_ulong(&self) -> Result<u64> {
+        Ok(self.value as u64)
+    }
+
     fn to_int(&self) -> Result<i32> {
         self.value
             .try_into()
@@ -979,6 +1027,10 @@ impl Value for ULLongValue {
         8
     }
 
+    fn to_ulong(&self) -> Result<u64> {
+        Ok(self.value)
+    }
+
     fn to_int(&self) -> Result<i32> {
         self.value
             .try_into()
@@ -1568,4 +1620,26 @@ mod tests {
         assert_eq!(value2.name(), "temp");
         assert_eq!(value2.value(), -40.5);
     }
+
+    #[test]
+    fn test_native_conversions() {
+        assert_eq!(ShortValue::new("s", -5).to_short().unwrap(), -5);
+        assert_eq!(UShortValue::new("us", 65535).to_ushort().unwrap(), 65535);
+        assert_eq!(UIntValue::new("ui", u32::MAX).to_uint().unwrap(), u32::MAX);
+        assert_eq!(
+            ULongValue::new("ul", 4_000_000_000)
+                .unwrap()
+                .to_ulong()
+                .unwrap(),
+            4_000_000_000
+        );
+        assert_eq!(
+            ULLongValue::new("ull", u64::MAX).to_ulong().unwrap(),
+            u64::MAX
+        );
+
+        // Unsigned values widen losslessly
+        assert_eq!(UShortValue::new("us", 7).to_ulong().unwrap(), 7);
+        assert_eq!(UIntValue::new("ui", 7).to_ulong().unwrap(), 7);
+    }
 }
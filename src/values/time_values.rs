@@ -0,0 +1,1101 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Timestamp and duration value implementations.
+//!
+//! [`TimestampValue`] stores a [`Timestamp`]: an instant with nanosecond
+//! precision plus an optional UTC offset, so the local time a message was
+//! stamped with survives the round trip. [`DurationValue`] stores a
+//! [`std::time::Duration`]. Calendar arithmetic is done here, without a time
+//! crate.
+//!
+//! `timestamp_value` (type code 17) and `duration_value` (type code 18) are
+//! extended types. C++ peers that do not know them receive them as a
+//! `string_value` holding the text form (see
+//! [`TypeSupport`](crate::core::value_types::TypeSupport)).
+//!
+//! | Format | Timestamp | Duration |
+//! |--------|-----------|----------|
+//! | Wire protocol, JSON v2 | RFC 3339 (`2024-03-01T09:30:00.25+09:00`) | ISO 8601 (`PT1H30M`) |
+//! | Binary | 14 bytes: `[seconds:8 LE i64][nanos:4 LE][offset minutes:2 LE i16]` | 12 bytes: `[seconds:8 LE u64][nanos:4 LE]` |
+//!
+//! A timestamp without an offset is UTC and is written with `Z`; the offset
+//! `i16::MIN` marks it in the binary layout.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rust_container_system::values::{Timestamp, TimestampValue, DurationValue};
+//! use rust_container_system::core::Value;
+//! use std::time::Duration;
+//!
+//! let sent: Timestamp = "2024-03-01T09:30:00.25+09:00".parse().unwrap();
+//! assert_eq!(sent.offset_minutes(), Some(540));
+//! assert_eq!(sent.unix_seconds(), 1_709_253_000);
+//!
+//! let expires = sent.checked_add(Duration::from_secs(90 * 60)).unwrap();
+//! assert_eq!(expires.to_string(), "2024-03-01T11:00:00.25+09:00");
+//!
+//! let value = TimestampValue::new("sent_at", sent);
+//! assert_eq!(value.to_long().unwrap(), 1_709_253_000_250);
+//!
+//! let ttl = DurationValue::parse("ttl", "PT1H30M").unwrap();
+//! assert_eq!(ttl.value(), Duration::from_secs(5400));
+//! ```
+
+use crate::core::{ContainerError, Result, Value, ValueType};
+use crate::values::StringValue;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Size of the timestamp binary payload
+pub const TIMESTAMP_PAYLOAD_SIZE: usize = 14;
+
+/// Size of the duration binary payload
+pub const DURATION_PAYLOAD_SIZE: usize = 12;
+
+/// `0000-01-01T00:00:00Z`, the first instant RFC 3339 can write
+const MIN_SECONDS: i64 = -62_167_219_200;
+
+/// `9999-12-31T23:59:59Z`, the last whole second RFC 3339 can write
+const MAX_SECONDS: i64 = 253_402_300_799;
+
+/// Largest offset magnitude in minutes (`±23:59`)
+const MAX_OFFSET_MINUTES: i16 = 23 * 60 + 59;
+
+/// Offset stored in the binary payload of a timestamp without one
+const NO_OFFSET: i16 = i16::MIN;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const NANOS_PER_SECOND: u32 = 1_000_000_000;
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = i64::from((month + 9) % 12);
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian `(year, month, day)` of a day count since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Fixed-width decimal field of `text` at `at`
+fn digits(text: &[u8], at: usize, len: usize) -> Option<u32> {
+    let field = text.get(at..at + len)?;
+    field.iter().try_fold(0u32, |n, &b| {
+        b.is_ascii_digit().then(|| n * 10 + u32::from(b - b'0'))
+    })
+}
+
+/// Fraction digits after a `.`: the nanoseconds and the number of bytes read
+fn fraction(text: &[u8]) -> Option<(u32, usize)> {
+    let len = text.iter().take_while(|b| b.is_ascii_digit()).count();
+    if len == 0 || len > 9 {
+        return None;
+    }
+    let nanos = digits(text, 0, len)? * 10u32.pow(9 - len as u32);
+    Some((nanos, len))
+}
+
+/// Nanoseconds as `.fff` with trailing zeros removed, or nothing
+fn write_fraction(f: &mut fmt::Formatter<'_>, nanos: u32) -> fmt::Result {
+    if nanos == 0 {
+        return Ok(());
+    }
+    let text = format!("{:09}", nanos);
+    write!(f, ".{}", text.trim_end_matches('0'))
+}
+
+/// An instant with nanosecond precision and an optional UTC offset
+///
+/// Years 0000 to 9999 are supported, as in RFC 3339. The offset only changes
+/// how the instant is written; [`unix_seconds`](Self::unix_seconds) is always
+/// UTC. Ordering compares the instant first and the offset last, so
+/// timestamps for the same instant with different offsets are not equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Timestamp {
+    seconds: i64,
+    nanos: u32,
+    offset: Option<i16>,
+}
+
+impl Timestamp {
+    /// `1970-01-01T00:00:00Z`
+    pub const UNIX_EPOCH: Timestamp = Timestamp {
+        seconds: 0,
+        nanos: 0,
+        offset: None,
+    };
+
+    /// UTC timestamp from seconds and nanoseconds since the Unix epoch
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if `nanos` is not below one second or the
+    /// instant is outside years 0000-9999.
+    pub fn new(seconds: i64, nanos: u32) -> Result<Self> {
+        if nanos >= NANOS_PER_SECOND {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "timestamp nanoseconds {} exceed one second",
+                nanos
+            )));
+        }
+        Self::checked(seconds, nanos, None)
+    }
+
+    /// UTC timestamp from milliseconds since the Unix epoch
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the instant is outside years 0000-9999.
+    pub fn from_unix_millis(millis: i64) -> Result<Self> {
+        let nanos = millis.rem_euclid(1000) as u32 * 1_000_000;
+        Self::new(millis.div_euclid(1000), nanos)
+    }
+
+    /// The current time in UTC
+    pub fn now() -> Self {
+        Self::try_from(SystemTime::now()).expect("system clock is within years 0000-9999")
+    }
+
+    /// The same instant written with a UTC offset in minutes
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the offset exceeds ±23:59 or the local
+    /// time is outside years 0000-9999.
+    pub fn with_offset(self, minutes: i16) -> Result<Self> {
+        if !(-MAX_OFFSET_MINUTES..=MAX_OFFSET_MINUTES).contains(&minutes) {
+            return Err(ContainerError::InvalidDataFormat(format!(
+                "UTC offset of {} minutes exceeds ±23:59",
+                minutes
+            )));
+        }
+        Self::checked(self.seconds, self.nanos, Some(minutes))
+    }
+
+    /// The same instant without an offset (written in UTC)
+    #[must_use]
+    pub fn to_utc(self) -> Self {
+        Self {
+            offset: None,
+            ..self
+        }
+    }
+
+    /// Whole seconds since the Unix epoch (UTC)
+    pub fn unix_seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Nanoseconds past [`unix_seconds`](Self::unix_seconds)
+    pub fn subsec_nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    /// Milliseconds since the Unix epoch, rounded toward negative infinity
+    pub fn unix_millis(&self) -> i64 {
+        self.seconds * 1000 + i64::from(self.nanos / 1_000_000)
+    }
+
+    /// Nanoseconds since the Unix epoch
+    pub fn unix_nanos(&self) -> i128 {
+        i128::from(self.seconds) * i128::from(NANOS_PER_SECOND) + i128::from(self.nanos)
+    }
+
+    /// UTC offset in minutes, if the timestamp has one
+    pub fn offset_minutes(&self) -> Option<i16> {
+        self.offset
+    }
+
+    /// The timestamp `duration` later, keeping the offset
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let nanos = self
+            .unix_nanos()
+            .checked_add(i128::try_from(duration.as_nanos()).ok()?)?;
+        Self::from_nanos(nanos, self.offset)
+    }
+
+    /// The timestamp `duration` earlier, keeping the offset
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let nanos = self
+            .unix_nanos()
+            .checked_sub(i128::try_from(duration.as_nanos()).ok()?)?;
+        Self::from_nanos(nanos, self.offset)
+    }
+
+    /// Time elapsed since `earlier`, or `None` if `earlier` is later
+    pub fn duration_since(&self, earlier: Timestamp) -> Option<Duration> {
+        let nanos = u128::try_from(self.unix_nanos() - earlier.unix_nanos()).ok()?;
+        let seconds = u64::try_from(nanos / u128::from(NANOS_PER_SECOND)).ok()?;
+        Some(Duration::new(
+            seconds,
+            (nanos % u128::from(NANOS_PER_SECOND)) as u32,
+        ))
+    }
+
+    /// Binary payload: `[seconds:8 LE][nanos:4 LE][offset:2 LE]`
+    pub fn to_payload(&self) -> [u8; TIMESTAMP_PAYLOAD_SIZE] {
+        let mut payload = [0; TIMESTAMP_PAYLOAD_SIZE];
+        payload[..8].copy_from_slice(&self.seconds.to_le_bytes());
+        payload[8..12].copy_from_slice(&self.nanos.to_le_bytes());
+        payload[12..].copy_from_slice(&self.offset.unwrap_or(NO_OFFSET).to_le_bytes());
+        payload
+    }
+
+    /// Read a payload written by [`to_payload`](Self::to_payload)
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the payload has the wrong size or holds
+    /// an invalid timestamp.
+    pub fn from_payload(payload: &[u8]) -> Result<Self> {
+        let payload: &[u8; TIMESTAMP_PAYLOAD_SIZE] = payload.try_into().map_err(|_| {
+            ContainerError::InvalidDataFormat(format!(
+                "timestamp payload must be {} bytes, got {}",
+                TIMESTAMP_PAYLOAD_SIZE,
+                payload.len()
+            ))
+        })?;
+        let seconds = i64::from_le_bytes(payload[..8].try_into().expect("8 bytes"));
+        let nanos = u32::from_le_bytes(payload[8..12].try_into().expect("4 bytes"));
+        let offset = i16::from_le_bytes(payload[12..].try_into().expect("2 bytes"));
+        let timestamp = Self::new(seconds, nanos)?;
+        match offset {
+            NO_OFFSET => Ok(timestamp),
+            minutes => timestamp.with_offset(minutes),
+        }
+    }
+
+    fn checked(seconds: i64, nanos: u32, offset: Option<i16>) -> Result<Self> {
+        let timestamp = Self {
+            seconds,
+            nanos,
+            offset,
+        };
+        let local = seconds.checked_add(timestamp.offset_seconds());
+        match local {
+            Some(local)
+                if (MIN_SECONDS..=MAX_SECONDS).contains(&seconds)
+                    && (MIN_SECONDS..=MAX_SECONDS).contains(&local) =>
+            {
+                Ok(timestamp)
+            }
+            _ => Err(ContainerError::InvalidDataFormat(format!(
+                "timestamp {}s is outside years 0000-9999",
+                seconds
+            ))),
+        }
+    }
+
+    fn from_nanos(nanos: i128, offset: Option<i16>) -> Option<Self> {
+        let per_second = i128::from(NANOS_PER_SECOND);
+        let seconds = i64::try_from(nanos.div_euclid(per_second)).ok()?;
+        Self::checked(seconds, nanos.rem_euclid(per_second) as u32, offset).ok()
+    }
+
+    fn offset_seconds(&self) -> i64 {
+        i64::from(self.offset.unwrap_or(0)) * 60
+    }
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = ContainerError;
+
+    fn try_from(time: SystemTime) -> Result<Self> {
+        let nanos = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(after) => i128::try_from(after.as_nanos()).ok(),
+            Err(before) => i128::try_from(before.duration().as_nanos())
+                .ok()
+                .map(|n| -n),
+        };
+        nanos
+            .and_then(|nanos| Self::from_nanos(nanos, None))
+            .ok_or_else(|| {
+                ContainerError::InvalidDataFormat(format!("{:?} is outside years 0000-9999", time))
+            })
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        let since_epoch = Duration::new(timestamp.seconds.unsigned_abs(), 0);
+        let base = if timestamp.seconds >= 0 {
+            SystemTime::UNIX_EPOCH + since_epoch
+        } else {
+            SystemTime::UNIX_EPOCH - since_epoch
+        };
+        base + Duration::from_nanos(u64::from(timestamp.nanos))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    /// RFC 3339, in the local time of the offset (`Z` without one)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let local = self.seconds + self.offset_seconds();
+        let (year, month, day) = civil_from_days(local.div_euclid(SECONDS_PER_DAY));
+        let time = local.rem_euclid(SECONDS_PER_DAY);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        )?;
+        write_fraction(f, self.nanos)?;
+        match self.offset {
+            None => f.write_str("Z"),
+            Some(minutes) => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ContainerError;
+
+    /// Parse an RFC 3339 / ISO 8601 date-time
+    ///
+    /// Accepts `YYYY-MM-DD`, optionally followed by `T` (or a space) and
+    /// `hh:mm:ss[.fraction]`, and an offset of `Z`, `±hh:mm` or none. A missing
+    /// offset, `Z` and the RFC 3339 "unknown offset" `-00:00` all mean UTC
+    /// without an offset. Leap seconds are rejected.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || ContainerError::ParseError(format!("invalid timestamp '{}'", s));
+        let text = s.as_bytes();
+        let field = |at, len| digits(text, at, len).ok_or_else(invalid);
+        let expect = |at: usize, separators: &[u8]| match text.get(at) {
+            Some(b) if separators.contains(b) => Ok(()),
+            _ => Err(invalid()),
+        };
+
+        let year = i64::from(field(0, 4)?);
+        expect(4, b"-")?;
+        let month = field(5, 2)?;
+        expect(7, b"-")?;
+        let day = field(8, 2)?;
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(invalid());
+        }
+
+        let (mut seconds, mut nanos, mut rest) = (0, 0, &text[10..]);
+        if !rest.is_empty() {
+            expect(10, b"Tt ")?;
+            let hour = field(11, 2)?;
+            expect(13, b":")?;
+            let minute = field(14, 2)?;
+            expect(16, b":")?;
+            let second = field(17, 2)?;
+            if hour > 23 || minute > 59 || second > 59 {
+                return Err(invalid());
+            }
+            seconds = i64::from(hour * 3600 + minute * 60 + second);
+            rest = &text[19..];
+            if let Some(after_dot) = rest.strip_prefix(b".") {
+                let (fraction_nanos, len) = fraction(after_dot).ok_or_else(invalid)?;
+                nanos = fraction_nanos;
+                rest = &after_dot[len..];
+            }
+        }
+
+        let offset = match rest {
+            b"" | b"Z" | b"z" | b"-00:00" => None,
+            [sign @ (b'+' | b'-'), ..] if rest.len() == 6 && rest[3] == b':' => {
+                let (hours, minutes) = (digits(rest, 1, 2), digits(rest, 4, 2));
+                match (hours, minutes) {
+                    (Some(h), Some(m)) if h < 24 && m < 60 => {
+                        let minutes = (h * 60 + m) as i16;
+                        Some(if *sign == b'-' { -minutes } else { minutes })
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(invalid()),
+        };
+
+        let local = days_from_civil(year, month, day) * SECONDS_PER_DAY + seconds;
+        let utc = local - i64::from(offset.unwrap_or(0)) * 60;
+        Self::checked(utc, nanos, offset).map_err(|_| invalid())
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Write a duration in ISO 8601 form with hours, minutes and seconds
+///
+/// Days are not used because a calendar day is not always 24 hours.
+///
+/// # Example
+///
+/// ```rust
+/// use rust_container_system::values::time_values::format_duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(format_duration(Duration::from_millis(5_400_250)), "PT1H30M0.25S");
+/// assert_eq!(format_duration(Duration::ZERO), "PT0S");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    DurationText(duration).to_string()
+}
+
+/// Parse an ISO 8601 duration (`PT1H30M`, `P2DT0.5S`, `P1W`)
+///
+/// Weeks, days, hours, minutes and seconds are accepted; a day counts as 24
+/// hours. Only the seconds may have a fraction, of up to 9 digits. Years and
+/// months are rejected because their length depends on the calendar.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the text is not such a duration or overflows.
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let invalid = || ContainerError::ParseError(format!("invalid duration '{}'", text));
+    let body = text
+        .strip_prefix('P')
+        .or_else(|| text.strip_prefix('p'))
+        .filter(|body| !body.is_empty())
+        .ok_or_else(invalid)?;
+    let (date, time) = match body.split_once(['T', 't']) {
+        Some((_, "")) => return Err(invalid()),
+        Some((date, time)) => (date, time),
+        None => (body, ""),
+    };
+
+    let mut seconds: u64 = 0;
+    let mut nanos: u32 = 0;
+    for (part, units) in [
+        (date, &[('W', 604_800), ('D', 86_400)][..]),
+        (time, &[('H', 3600), ('M', 60), ('S', 1)][..]),
+    ] {
+        let mut rest = part.as_bytes();
+        let mut next_unit = 0;
+        while !rest.is_empty() {
+            let len = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+            let number: u64 = std::str::from_utf8(&rest[..len])
+                .ok()
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(invalid)?;
+            rest = &rest[len..];
+            if let Some(after_dot) = rest.strip_prefix(b".") {
+                let (fraction_nanos, len) = fraction(after_dot).ok_or_else(invalid)?;
+                rest = &after_dot[len..];
+                if rest.first().map(u8::to_ascii_uppercase) != Some(b'S') {
+                    return Err(invalid());
+                }
+                nanos = fraction_nanos;
+            }
+            let designator = rest
+                .first()
+                .map(u8::to_ascii_uppercase)
+                .ok_or_else(invalid)?;
+            let position = units[next_unit..]
+                .iter()
+                .position(|(unit, _)| *unit as u8 == designator)
+                .ok_or_else(invalid)?;
+            let (_, unit_seconds) = units[next_unit + position];
+            next_unit += position + 1;
+            rest = &rest[1..];
+            seconds = number
+                .checked_mul(unit_seconds)
+                .and_then(|s| s.checked_add(seconds))
+                .ok_or_else(invalid)?;
+        }
+    }
+    Ok(Duration::new(seconds, nanos))
+}
+
+/// Binary payload of a duration: `[seconds:8 LE][nanos:4 LE]`
+pub(crate) fn duration_payload(duration: Duration) -> [u8; DURATION_PAYLOAD_SIZE] {
+    let mut payload = [0; DURATION_PAYLOAD_SIZE];
+    payload[..8].copy_from_slice(&duration.as_secs().to_le_bytes());
+    payload[8..].copy_from_slice(&duration.subsec_nanos().to_le_bytes());
+    payload
+}
+
+/// Read a payload written by [`duration_payload`]
+pub(crate) fn duration_from_payload(payload: &[u8]) -> Result<Duration> {
+    let invalid = || {
+        ContainerError::InvalidDataFormat(format!(
+            "duration payload must be {} bytes with nanoseconds below one second",
+            DURATION_PAYLOAD_SIZE
+        ))
+    };
+    let payload: &[u8; DURATION_PAYLOAD_SIZE] = payload.try_into().map_err(|_| invalid())?;
+    let seconds = u64::from_le_bytes(payload[..8].try_into().expect("8 bytes"));
+    let nanos = u32::from_le_bytes(payload[8..].try_into().expect("4 bytes"));
+    if nanos >= NANOS_PER_SECOND {
+        return Err(invalid());
+    }
+    Ok(Duration::new(seconds, nanos))
+}
+
+/// Displays a duration in ISO 8601 form
+struct DurationText(Duration);
+
+impl fmt::Display for DurationText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.0.as_secs();
+        let nanos = self.0.subsec_nanos();
+        let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+        f.write_str("PT")?;
+        if hours > 0 {
+            write!(f, "{}H", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}M", minutes)?;
+        }
+        if seconds > 0 || nanos > 0 || total == 0 {
+            write!(f, "{}", seconds)?;
+            write_fraction(f, nanos)?;
+            f.write_str("S")?;
+        }
+        Ok(())
+    }
+}
+
+/// Serde as ISO 8601 text, for `DurationValue`
+mod iso_duration {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(&DurationText(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Duration, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_duration(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Read a timestamp from a `timestamp_value`, or from the `string_value` a
+/// peer without timestamp support sends
+pub(crate) fn timestamp_of(value: &dyn Value) -> Result<Timestamp> {
+    if let Some(timestamp) = value.as_any().downcast_ref::<TimestampValue>() {
+        return Ok(timestamp.value());
+    }
+    match value.as_any().downcast_ref::<StringValue>() {
+        Some(text) => text.value().parse(),
+        None if value.value_type() == ValueType::Timestamp => value.to_string().parse(),
+        None => Err(unexpected(value, "timestamp")),
+    }
+}
+
+/// Read a duration from a `duration_value`, or from the `string_value` a peer
+/// without duration support sends
+pub(crate) fn duration_of(value: &dyn Value) -> Result<Duration> {
+    if let Some(duration) = value.as_any().downcast_ref::<DurationValue>() {
+        return Ok(duration.value());
+    }
+    match value.as_any().downcast_ref::<StringValue>() {
+        Some(text) => parse_duration(text.value()),
+        None if value.value_type() == ValueType::Duration => parse_duration(&value.to_string()),
+        None => Err(unexpected(value, "duration")),
+    }
+}
+
+fn unexpected(value: &dyn Value, to: &str) -> ContainerError {
+    ContainerError::InvalidTypeConversion {
+        from: format!("{} '{}'", value.value_type(), value.name()),
+        to: to.to_string(),
+    }
+}
+
+/// Header and payload of a fixed-size value, as `Value::to_bytes` writes them
+fn encode(value_type: ValueType, name: &str, payload: &[u8]) -> Vec<u8> {
+    // Format: [type:1][name_len:4][name][value_size:4][payload]
+    let name_bytes = name.as_bytes();
+    let mut result = Vec::with_capacity(9 + name_bytes.len() + payload.len());
+    result.push(value_type as u8);
+    result.extend_from_slice(&(name_bytes.len() as u32).to_le_bytes());
+    result.extend_from_slice(name_bytes);
+    result.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    result.extend_from_slice(payload);
+    result
+}
+
+/// Converts whole milliseconds to an integer type
+fn millis<T: TryFrom<i128>>(millis: i128, from: &str, to: &str) -> Result<T> {
+    T::try_from(millis).map_err(|_| ContainerError::InvalidTypeConversion {
+        from: from.to_string(),
+        to: to.to_string(),
+    })
+}
+
+/// Timestamp value (`timestamp_value`, type code 17)
+///
+/// Integer conversions give milliseconds since the Unix epoch, the unit of
+/// the `llong_value` fields this type replaces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampValue {
+    name: String,
+    value: Timestamp,
+}
+
+impl TimestampValue {
+    /// Create a timestamp value
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rust_container_system::values::{Timestamp, TimestampValue};
+    ///
+    /// let created = TimestampValue::new("created_at", Timestamp::UNIX_EPOCH);
+    /// assert_eq!(created.value().to_string(), "1970-01-01T00:00:00Z");
+    /// ```
+    pub fn new(name: impl Into<String>, value: Timestamp) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Parse a timestamp value from RFC 3339 / ISO 8601 text
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the text is not a timestamp (see
+    /// [`Timestamp::from_str`]).
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self> {
+        Ok(Self::new(name, text.parse()?))
+    }
+
+    /// The timestamp
+    pub fn value(&self) -> Timestamp {
+        self.value
+    }
+
+    fn millis<T: TryFrom<i128>>(&self, to: &str) -> Result<T> {
+        let from = format!("timestamp({})", self.value);
+        millis(self.value.unix_millis().into(), &from, to)
+    }
+}
+
+impl Value for TimestampValue {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Timestamp
+    }
+
+    fn size(&self) -> usize {
+        TIMESTAMP_PAYLOAD_SIZE
+    }
+
+    fn to_int(&self) -> Result<i32> {
+        self.millis("i32")
+    }
+
+    fn to_uint(&self) -> Result<u32> {
+        self.millis("u32")
+    }
+
+    fn to_long(&self) -> Result<i64> {
+        self.millis("i64")
+    }
+
+    fn to_ulong(&self) -> Result<u64> {
+        self.millis("u64")
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_string()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(ValueType::Timestamp, &self.name, &self.value.to_payload())
+    }
+
+    fn to_json(&self) -> Result<String> {
+        let tagged = serde_json::json!({
+            "type": "timestamp",
+            "value": self.value.to_string()
+        });
+        serde_json::to_string(&tagged).map_err(Into::into)
+    }
+
+    fn to_xml(&self) -> Result<String> {
+        Ok(format!("<timestamp>{}</timestamp>", self.value))
+    }
+
+    fn clone_value(&self) -> Arc<dyn Value> {
+        Arc::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Duration value (`duration_value`, type code 18)
+///
+/// Integer conversions give whole milliseconds, like
+/// [`TimestampValue`]'s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationValue {
+    name: String,
+    #[serde(with = "iso_duration")]
+    value: Duration,
+}
+
+impl DurationValue {
+    /// Create a duration value
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rust_container_system::values::DurationValue;
+    /// use rust_container_system::core::Value;
+    /// use std::time::Duration;
+    ///
+    /// let timeout = DurationValue::new("timeout", Duration::from_millis(1500));
+    /// assert_eq!(timeout.to_string(), "PT1.5S");
+    /// ```
+    pub fn new(name: impl Into<String>, value: Duration) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Parse a duration value from ISO 8601 text (see [`parse_duration`])
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the text is not a supported duration.
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self> {
+        Ok(Self::new(name, parse_duration(text)?))
+    }
+
+    /// The duration
+    pub fn value(&self) -> Duration {
+        self.value
+    }
+
+    fn millis<T: TryFrom<i128>>(&self, to: &str) -> Result<T> {
+        let from = format!("duration({})", DurationText(self.value));
+        let whole = i128::try_from(self.value.as_millis()).unwrap_or(i128::MAX);
+        millis(whole, &from, to)
+    }
+}
+
+impl Value for DurationValue {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Duration
+    }
+
+    fn size(&self) -> usize {
+        DURATION_PAYLOAD_SIZE
+    }
+
+    fn to_int(&self) -> Result<i32> {
+        self.millis("i32")
+    }
+
+    fn to_uint(&self) -> Result<u32> {
+        self.millis("u32")
+    }
+
+    fn to_long(&self) -> Result<i64> {
+        self.millis("i64")
+    }
+
+    fn to_ulong(&self) -> Result<u64> {
+        self.millis("u64")
+    }
+
+    fn to_string(&self) -> String {
+        format_duration(self.value)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        encode(
+            ValueType::Duration,
+            &self.name,
+            &duration_payload(self.value),
+        )
+    }
+
+    fn to_json(&self) -> Result<String> {
+        let tagged = serde_json::json!({
+            "type": "duration",
+            "value": format_duration(self.value)
+        });
+        serde_json::to_string(&tagged).map_err(Into::into)
+    }
+
+    fn to_xml(&self) -> Result<String> {
+        Ok(format!(
+            "<duration>{}</duration>",
+            format_duration(self.value)
+        ))
+    }
+
+    fn clone_value(&self) -> Arc<dyn Value> {
+        Arc::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(text: &str) -> Timestamp {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_civil_conversions() {
+        for (days, date) in [
+            (0, (1970, 1, 1)),
+            (-1, (1969, 12, 31)),
+            (11_016, (2000, 2, 29)),
+            (-719_528, (0, 1, 1)),
+            (2_932_896, (9999, 12, 31)),
+        ] {
+            assert_eq!(civil_from_days(days), date);
+            assert_eq!(days_from_civil(date.0, date.1, date.2), days);
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let sent = ts("2024-03-01T09:30:00.250+09:00");
+        assert_eq!(sent.unix_seconds(), 1_709_253_000);
+        assert_eq!(sent.subsec_nanos(), 250_000_000);
+        assert_eq!(sent.offset_minutes(), Some(540));
+        assert_eq!(sent.to_string(), "2024-03-01T09:30:00.25+09:00");
+        assert_eq!(sent.to_utc().to_string(), "2024-03-01T00:30:00.25Z");
+
+        assert_eq!(
+            ts("1969-12-31T23:59:59.000000001Z").unix_nanos(),
+            -999_999_999
+        );
+        assert_eq!(ts("2024-03-01").to_string(), "2024-03-01T00:00:00Z");
+        assert_eq!(ts("2024-03-01 12:00:00").offset_minutes(), None);
+        assert_eq!(ts("2024-03-01T12:00:00-00:00").offset_minutes(), None);
+        assert_eq!(
+            ts("2024-03-01T12:00:00+00:00").to_string(),
+            "2024-03-01T12:00:00+00:00"
+        );
+        assert_eq!(
+            ts("2024-03-01T12:00:00-05:30").unix_seconds(),
+            1_709_314_200
+        );
+
+        for bad in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-03-01T24:00:00Z",
+            "2024-03-01T23:59:60Z",
+            "2024-03-01T12:00Z",
+            "2024-03-01T12:00:00.Z",
+            "2024-03-01T12:00:00.1234567890Z",
+            "2024-03-01T12:00:00+24:00",
+            "2024-03-01T12:00:00 ",
+            "0000-01-01T00:00:00+01:00",
+        ] {
+            assert!(bad.parse::<Timestamp>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_range_and_offsets() {
+        assert!(Timestamp::new(MAX_SECONDS, 999_999_999).is_ok());
+        assert!(Timestamp::new(MAX_SECONDS + 1, 0).is_err());
+        assert!(Timestamp::new(MIN_SECONDS - 1, 0).is_err());
+        assert!(Timestamp::new(0, NANOS_PER_SECOND).is_err());
+        assert!(Timestamp::UNIX_EPOCH.with_offset(24 * 60).is_err());
+        assert_eq!(
+            Timestamp::from_unix_millis(-1).unwrap().to_string(),
+            "1969-12-31T23:59:59.999Z"
+        );
+
+        let utc = ts("2024-01-01T00:00:00Z");
+        let tokyo = utc.with_offset(540).unwrap();
+        assert_ne!(utc, tokyo);
+        assert_eq!(utc.unix_nanos(), tokyo.unix_nanos());
+        assert!(utc < tokyo);
+        assert!(utc < ts("2024-01-01T00:00:00.000000001Z"));
+    }
+
+    #[test]
+    fn test_arithmetic_and_system_time() {
+        let start = ts("2024-02-28T23:00:00Z");
+        let end = start.checked_add(Duration::from_secs(2 * 3600)).unwrap();
+        assert_eq!(end.to_string(), "2024-02-29T01:00:00Z");
+        assert_eq!(end.duration_since(start), Some(Duration::from_secs(7200)));
+        assert_eq!(start.duration_since(end), None);
+        assert_eq!(end.checked_sub(Duration::from_secs(7200)), Some(start));
+        assert!(ts("9999-12-31T23:59:59Z")
+            .checked_add(Duration::from_secs(1))
+            .is_none());
+
+        let before_epoch = ts("1960-06-01T12:00:00.5Z");
+        let system: SystemTime = before_epoch.into();
+        assert_eq!(Timestamp::try_from(system).unwrap(), before_epoch);
+        assert!(Timestamp::now() > ts("2020-01-01"));
+    }
+
+    #[test]
+    fn test_payload_roundtrip() {
+        for text in [
+            "2024-03-01T09:30:00.25+09:00",
+            "1900-01-01T00:00:00Z",
+            "2024-03-01T00:00:00-00:01",
+        ] {
+            let timestamp = ts(text);
+            assert_eq!(
+                Timestamp::from_payload(&timestamp.to_payload()).unwrap(),
+                timestamp
+            );
+        }
+        let mut bad = Timestamp::UNIX_EPOCH.to_payload();
+        bad[8..12].copy_from_slice(&NANOS_PER_SECOND.to_le_bytes());
+        assert!(Timestamp::from_payload(&bad).is_err());
+        assert!(Timestamp::from_payload(&bad[..8]).is_err());
+
+        let duration = Duration::new(u64::MAX, 999_999_999);
+        assert_eq!(
+            duration_from_payload(&duration_payload(duration)).unwrap(),
+            duration
+        );
+        assert!(duration_from_payload(&[0xff; DURATION_PAYLOAD_SIZE]).is_err());
+    }
+
+    #[test]
+    fn test_durations() {
+        for (text, duration, canonical) in [
+            ("PT1H30M", Duration::from_secs(5400), "PT1H30M"),
+            ("PT0.5S", Duration::from_millis(500), "PT0.5S"),
+            ("P1DT1S", Duration::from_secs(86_401), "PT24H1S"),
+            ("P2W", Duration::from_secs(14 * 86_400), "PT336H"),
+            ("PT90M", Duration::from_secs(5400), "PT1H30M"),
+            ("pt1m0.000000001s", Duration::new(60, 1), "PT1M0.000000001S"),
+            ("PT0S", Duration::ZERO, "PT0S"),
+        ] {
+            let parsed = parse_duration(text).unwrap();
+            assert_eq!(parsed, duration, "{}", text);
+            assert_eq!(format_duration(parsed), canonical);
+        }
+        for bad in [
+            "", "P", "PT", "1H", "P1Y", "P1M", "PT1S1M", "PT1.5M", "PT-1S", "PT1H1H",
+        ] {
+            assert!(parse_duration(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        let sent = TimestampValue::parse("sent_at", "2024-03-01T00:00:00.0015Z").unwrap();
+        assert_eq!(sent.value_type(), ValueType::Timestamp);
+        assert_eq!(sent.to_long().unwrap(), 1_709_251_200_001);
+        assert!(sent.to_int().is_err());
+        assert_eq!(
+            sent.to_json().unwrap(),
+            r#"{"type":"timestamp","value":"2024-03-01T00:00:00.0015Z"}"#
+        );
+
+        let ttl = DurationValue::parse("ttl", "PT1.5S").unwrap();
+        assert_eq!(ttl.value_type(), ValueType::Duration);
+        assert_eq!(ttl.to_int().unwrap(), 1500);
+        assert_eq!(ttl.to_xml().unwrap(), "<duration>PT1.5S</duration>");
+
+        let fallback = StringValue::new("sent_at", "2024-03-01T00:00:00Z");
+        assert_eq!(
+            timestamp_of(&fallback).unwrap().unix_seconds(),
+            1_709_251_200
+        );
+        assert!(duration_of(&fallback).is_err());
+        assert!(timestamp_of(&ttl).is_err());
+    }
+}
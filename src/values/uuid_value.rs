@@ -0,0 +1,361 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! UUID value implementation.
+//!
+//! [`UuidValue`] stores a [`Uuid`] as its 16 bytes. Text is validated when
+//! it is parsed, so a container never holds a malformed ID, and the binary
+//! layout needs 16 bytes instead of the 36 of a `string_value`.
+//!
+//! `uuid_value` (type code 19) is an extended type. C++ peers that do not know
+//! it receive it as a `string_value` holding the canonical text (see
+//! [`TypeSupport`](crate::core::value_types::TypeSupport)).
+//!
+//! | Format | Encoding |
+//! |--------|----------|
+//! | Wire protocol, JSON v2 | canonical text (`67e55044-10b1-426f-9247-bb680e5fe0c8`) |
+//! | Binary | the 16 bytes, most significant first |
+//!
+//! # Example
+//!
+//! ```rust
+//! use rust_container_system::values::{Uuid, UuidValue};
+//! use rust_container_system::core::Value;
+//!
+//! let id = UuidValue::parse("order_id", "67E55044-10B1-426F-9247-BB680E5FE0C8").unwrap();
+//! assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+//! assert_eq!(id.value().version(), 4);
+//!
+//! assert!(UuidValue::parse("order_id", "not-a-uuid").is_err());
+//! assert!(Uuid::NIL.is_nil());
+//! ```
+
+use crate::core::{ContainerError, Result, Value, ValueType};
+use crate::values::StringValue;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Size of the binary payload
+pub const UUID_PAYLOAD_SIZE: usize = 16;
+
+/// Byte offsets of the hyphens in the canonical text
+const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+/// A 128-bit universally unique identifier (RFC 9562)
+///
+/// Any 16 bytes are a valid `Uuid`; parsing checks the text form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Uuid([u8; UUID_PAYLOAD_SIZE]);
+
+impl Uuid {
+    /// The nil UUID (all zeros)
+    pub const NIL: Uuid = Uuid([0; UUID_PAYLOAD_SIZE]);
+
+    /// UUID from its 16 bytes, most significant first
+    pub const fn from_bytes(bytes: [u8; UUID_PAYLOAD_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    /// UUID from a 128-bit integer
+    pub const fn from_u128(value: u128) -> Self {
+        Self(value.to_be_bytes())
+    }
+
+    /// The 16 bytes, most significant first
+    pub fn as_bytes(&self) -> &[u8; UUID_PAYLOAD_SIZE] {
+        &self.0
+    }
+
+    /// The UUID as a 128-bit integer
+    pub fn as_u128(&self) -> u128 {
+        u128::from_be_bytes(self.0)
+    }
+
+    /// Whether this is the nil UUID
+    pub fn is_nil(&self) -> bool {
+        *self == Self::NIL
+    }
+
+    /// The version number (the high nibble of byte 6), e.g. 4 for random UUIDs
+    pub fn version(&self) -> u8 {
+        self.0[6] >> 4
+    }
+
+    /// Read a UUID from a 16-byte slice
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDataFormat` if the slice is not 16 bytes long.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        bytes.try_into().map(Self).map_err(|_| {
+            ContainerError::InvalidDataFormat(format!(
+                "UUID must be {} bytes, got {}",
+                UUID_PAYLOAD_SIZE,
+                bytes.len()
+            ))
+        })
+    }
+}
+
+impl From<[u8; UUID_PAYLOAD_SIZE]> for Uuid {
+    fn from(bytes: [u8; UUID_PAYLOAD_SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Display for Uuid {
+    /// Canonical lowercase hyphenated form
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = ContainerError;
+
+    /// Parse the hyphenated form (`8-4-4-4-12` hex digits) or 32 hex digits,
+    /// in either case
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || ContainerError::ParseError(format!("invalid UUID '{}'", s));
+        let text = s.as_bytes();
+        let hex: Vec<u8> = match text.len() {
+            32 => text.to_vec(),
+            36 if HYPHENS.iter().all(|&i| text[i] == b'-') => text
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !HYPHENS.contains(i))
+                .map(|(_, &b)| b)
+                .collect(),
+            _ => return Err(invalid()),
+        };
+
+        let mut bytes = [0; UUID_PAYLOAD_SIZE];
+        for (byte, pair) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            if !pair.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for Uuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uuid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Read a UUID from a `uuid_value`, or from the `string_value` a peer without
+/// UUID support sends
+pub(crate) fn uuid_of(value: &dyn Value) -> Result<Uuid> {
+    if let Some(uuid) = value.as_any().downcast_ref::<UuidValue>() {
+        return Ok(uuid.value());
+    }
+    match value.as_any().downcast_ref::<StringValue>() {
+        Some(text) => text.value().parse(),
+        None if value.value_type() == ValueType::Uuid => value.to_string().parse(),
+        None => Err(ContainerError::InvalidTypeConversion {
+            from: format!("{} '{}'", value.value_type(), value.name()),
+            to: "uuid".to_string(),
+        }),
+    }
+}
+
+/// UUID value (`uuid_value`, type code 19)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UuidValue {
+    name: String,
+    value: Uuid,
+}
+
+impl UuidValue {
+    /// Create a UUID value
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rust_container_system::values::{Uuid, UuidValue};
+    ///
+    /// let id = UuidValue::new("id", Uuid::from_u128(1));
+    /// assert_eq!(id.value().to_string(), "00000000-0000-0000-0000-000000000001");
+    /// ```
+    pub fn new(name: impl Into<String>, value: Uuid) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Parse a UUID value from text, rejecting malformed IDs
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if the text is not a UUID (see [`Uuid::from_str`]).
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self> {
+        Ok(Self::new(name, text.parse()?))
+    }
+
+    /// The UUID
+    pub fn value(&self) -> Uuid {
+        self.value
+    }
+}
+
+impl Value for UuidValue {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Uuid
+    }
+
+    fn size(&self) -> usize {
+        UUID_PAYLOAD_SIZE
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_string()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // Format: [type:1][name_len:4][name][value_size:4][uuid:16]
+        let name_bytes = self.name.as_bytes();
+        let mut result = Vec::with_capacity(9 + name_bytes.len() + UUID_PAYLOAD_SIZE);
+        result.push(ValueType::Uuid as u8);
+        result.extend_from_slice(&(name_bytes.len() as u32).to_le_bytes());
+        result.extend_from_slice(name_bytes);
+        result.extend_from_slice(&(UUID_PAYLOAD_SIZE as u32).to_le_bytes());
+        result.extend_from_slice(self.value.as_bytes());
+        result
+    }
+
+    fn to_json(&self) -> Result<String> {
+        let tagged = serde_json::json!({
+            "type": "uuid",
+            "value": self.value.to_string()
+        });
+        serde_json::to_string(&tagged).map_err(Into::into)
+    }
+
+    fn to_xml(&self) -> Result<String> {
+        Ok(format!("<uuid>{}</uuid>", self.value))
+    }
+
+    fn clone_value(&self) -> Arc<dyn Value> {
+        Arc::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    #[test]
+    fn test_parse_and_display() {
+        let id: Uuid = TEXT.parse().unwrap();
+        assert_eq!(id.to_string(), TEXT);
+        assert_eq!(id.as_u128(), 0x67e5504410b1426f9247bb680e5fe0c8);
+        assert_eq!(id.version(), 4);
+        assert_eq!(TEXT.to_uppercase().parse::<Uuid>().unwrap(), id);
+        assert_eq!(TEXT.replace('-', "").parse::<Uuid>().unwrap(), id);
+        assert_eq!(
+            Uuid::NIL.to_string(),
+            "00000000-0000-0000-0000-000000000000"
+        );
+
+        for bad in [
+            "",
+            "67e55044-10b1-426f-9247-bb680e5fe0c",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8a",
+            "67e5504410b1-426f-9247-bb680e5fe0c8-",
+            "67e55044-10b1-426f-9247-bb680e5fe0cg",
+            "{67e55044-10b1-426f-9247-bb680e5fe0c}",
+            "+7e55044-10b1-426f-9247-bb680e5fe0c8",
+            "67e55044-10b1-426f-9247-bb680e5fé0c",
+        ] {
+            assert!(bad.parse::<Uuid>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        let id = Uuid::from_u128(0x0102030405060708090a0b0c0d0e0f10);
+        assert_eq!(id.as_bytes()[0], 1);
+        assert_eq!(Uuid::from_slice(id.as_bytes()).unwrap(), id);
+        assert!(Uuid::from_slice(&[0; 15]).is_err());
+
+        let value = UuidValue::new("id", id);
+        let bytes = value.to_bytes();
+        assert_eq!(bytes.len(), 9 + 2 + UUID_PAYLOAD_SIZE);
+        assert_eq!(&bytes[bytes.len() - UUID_PAYLOAD_SIZE..], id.as_bytes());
+    }
+
+    #[test]
+    fn test_value() {
+        let value = UuidValue::parse("id", TEXT).unwrap();
+        assert_eq!(value.value_type(), ValueType::Uuid);
+        assert_eq!(
+            value.to_json().unwrap(),
+            format!(r#"{{"type":"uuid","value":"{}"}}"#, TEXT)
+        );
+        assert!(value.to_long().is_err());
+        assert!(UuidValue::parse("id", "12345").is_err());
+
+        assert_eq!(
+            uuid_of(&StringValue::new("id", TEXT)).unwrap(),
+            value.value()
+        );
+        assert!(uuid_of(&StringValue::new("id", "nope")).is_err());
+    }
+}
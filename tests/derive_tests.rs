@@ -0,0 +1,134 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Round-trip tests for `#[derive(ContainerMessage)]` (requires the `derive` feature)
+
+use rust_container_system::messaging::ContainerMessage;
+use rust_container_system::prelude::*;
+
+#[derive(Debug, PartialEq, ContainerMessage)]
+#[container(message_type = "order_line")]
+struct Line {
+    sku: String,
+    qty: u16,
+    #[container(value_type = "string")]
+    price_cents: u64,
+}
+
+#[derive(Debug, PartialEq, ContainerMessage)]
+#[container(message_type = "order")]
+struct Order {
+    #[container(name = "id", value_type = "long")]
+    order_id: i64,
+    #[container(value_type = "double")]
+    total_cents: i64,
+    paid: bool,
+    #[container(value_type = "string")]
+    discount: f64,
+    note: Option<String>,
+    attachment: Option<Vec<u8>>,
+    shipping: Line,
+    lines: Vec<Line>,
+}
+
+fn line(sku: &str, qty: u16) -> Line {
+    Line {
+        sku: sku.to_string(),
+        qty,
+        price_cents: u64::MAX,
+    }
+}
+
+fn order() -> Order {
+    Order {
+        order_id: 7,
+        total_cents: 1_999,
+        paid: true,
+        discount: 0.15,
+        note: None,
+        attachment: Some(vec![0, 1, 2]),
+        shipping: line("ship", 1),
+        lines: vec![line("a", 2), line("b", 3)],
+    }
+}
+
+#[test]
+fn test_derived_value_types() {
+    let container = order().to_container().unwrap();
+    assert_eq!(container.message_type(), "order");
+
+    let type_of = |name: &str| container.get_value(name).unwrap().value_type();
+    assert_eq!(type_of("id"), ValueType::Long);
+    assert_eq!(type_of("total_cents"), ValueType::Double);
+    assert_eq!(type_of("paid"), ValueType::Bool);
+    assert_eq!(type_of("discount"), ValueType::String);
+    assert_eq!(type_of("note"), ValueType::Null);
+    assert_eq!(type_of("attachment"), ValueType::Bytes);
+    assert_eq!(type_of("shipping"), ValueType::Container);
+    assert_eq!(type_of("lines"), ValueType::Array);
+}
+
+#[test]
+fn test_derived_roundtrip() {
+    let original = order();
+    let container = original.to_container().unwrap();
+    assert_eq!(Order::from_container(&container).unwrap(), original);
+
+    let binary = container.to_binary().unwrap();
+    let restored = ValueContainer::from_binary(&binary).unwrap();
+    assert_eq!(Order::from_container(&restored).unwrap(), original);
+
+    let json = JsonV2Adapter::to_v2_json(&container, false).unwrap();
+    let restored = JsonV2Adapter::from_v2_json(&json).unwrap();
+    assert_eq!(Order::from_container(&restored).unwrap(), original);
+
+    // The wire protocol reads `null_value` back as an empty container
+    let wire = container.serialize_cpp_wire().unwrap();
+    let restored = ValueContainer::deserialize_cpp_wire(&wire).unwrap();
+    assert_eq!(Order::from_container(&restored).unwrap(), original);
+}
+
+#[test]
+fn test_derived_errors() {
+    let mut container = order().to_container().unwrap();
+    container.set_message_type("invoice");
+    assert!(Order::from_container(&container).is_err());
+
+    // An override that would round the number fails instead
+    let mut lossy = order();
+    lossy.total_cents = (1 << 53) + 1;
+    assert!(lossy.to_container().is_err());
+
+    let mut missing = ValueContainer::new();
+    missing.set_message_type("order_line");
+    missing
+        .add_value(std::sync::Arc::new(StringValue::new("sku", "a")))
+        .unwrap();
+    assert!(Line::from_container(&missing).is_err());
+}
@@ -0,0 +1,223 @@
+// BSD 3-Clause License
+//
+// Copyright (c) 2021-2025, 🍀☀🌕🌥 🌊
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this
+//    list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice,
+//    this list of conditions and the following disclaimer in the documentation
+//    and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its
+//    contributors may be used to endorse or promote products derived from
+//    this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+// CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+// OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+// OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Framing tests over real byte streams
+//!
+//! These tests push framed containers through channel-backed pipes and Unix domain socket pairs,
+//! with the writer and reader on different threads, to check that frames survive
+//! arbitrary read boundaries and that the reader stops cleanly when the writer closes.
+
+use rust_container_system::core::framing::{FrameReader, FrameWriter};
+use rust_container_system::prelude::*;
+use rust_container_system::values::ArrayValue;
+use std::io::{BufWriter, Read, Write};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread;
+
+const MESSAGES: i32 = 50;
+
+const FORMATS: [SerializationFormat; 3] = [
+    SerializationFormat::WireProtocol,
+    SerializationFormat::Binary,
+    SerializationFormat::JsonV2,
+];
+
+fn message(id: i32) -> ValueContainer {
+    let mut container = ValueContainer::builder()
+        .source("producer", "1")
+        .target("consumer", "1")
+        .message_type("stream_test")
+        .build();
+    container
+        .add_value(Arc::new(IntValue::new("id", id)))
+        .unwrap();
+    container
+        .add_value(Arc::new(StringValue::new(
+            "text",
+            format!("message {}", id),
+        )))
+        .unwrap();
+    // Large enough payloads to span several pipe reads
+    container
+        .add_value(Arc::new(BytesValue::new("blob", vec![id as u8; 20_000])))
+        .unwrap();
+    container
+        .add_value(Arc::new(ArrayValue::new(
+            "list",
+            vec![Arc::new(IntValue::new("", id * 2)) as Arc<dyn Value>],
+        )))
+        .unwrap();
+    container
+}
+
+/// Largest chunk handed out by a single [`PipeReader::read`], to force split frames
+const PIPE_READ_LIMIT: usize = 4096;
+
+/// Writing half of [`pipe`]; each write becomes one chunk on a bounded channel
+struct PipeWriter(SyncSender<Vec<u8>>);
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| std::io::ErrorKind::BrokenPipe)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reading half of [`pipe`]; reports end of stream once the writer is dropped
+struct PipeReader {
+    chunks: Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.pending.len() {
+            match self.chunks.recv() {
+                Ok(chunk) => {
+                    self.pending = chunk;
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = buf
+            .len()
+            .min(self.pending.len() - self.pos)
+            .min(PIPE_READ_LIMIT);
+        buf[..n].copy_from_slice(&self.pending[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// A blocking in-process pipe, like `std::io::pipe` but without needing Rust 1.87
+fn pipe() -> (PipeReader, PipeWriter) {
+    let (sender, chunks) = sync_channel(4);
+    let reader = PipeReader {
+        chunks,
+        pending: Vec::new(),
+        pos: 0,
+    };
+    (reader, PipeWriter(sender))
+}
+
+fn produce<W: Write>(writer: W, format: SerializationFormat) {
+    let mut writer = FrameWriter::new(BufWriter::new(writer), format)
+        .unwrap()
+        .with_checksum(true);
+    for id in 0..MESSAGES {
+        writer.write_container(&message(id)).unwrap();
+    }
+    writer.flush().unwrap();
+}
+
+fn consume<R: Read>(reader: R) {
+    let mut count = 0;
+    for (id, container) in FrameReader::new(reader).enumerate() {
+        let container = container.unwrap();
+        let id = id as i32;
+        assert_eq!(container.message_type(), "stream_test");
+        assert_eq!(container.get_value("id").unwrap().to_int().unwrap(), id);
+        assert_eq!(
+            container.get_value("text").unwrap().to_string(),
+            format!("message {}", id)
+        );
+        let blob = container.get_value("blob").unwrap();
+        let blob = blob.as_any().downcast_ref::<BytesValue>().unwrap();
+        assert_eq!(blob.data(), &vec![id as u8; 20_000][..]);
+        count += 1;
+    }
+    assert_eq!(count, MESSAGES);
+}
+
+#[test]
+fn test_frames_over_pipe() {
+    for format in FORMATS {
+        let (reader, writer) = pipe();
+        let producer = thread::spawn(move || produce(writer, format));
+        consume(reader);
+        producer.join().unwrap();
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_frames_over_unix_socket_pair() {
+    use std::os::unix::net::UnixStream;
+
+    for format in FORMATS {
+        let (reader, writer) = UnixStream::pair().unwrap();
+        let producer = thread::spawn(move || produce(writer, format));
+        consume(reader);
+        producer.join().unwrap();
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bidirectional_socket_exchange() {
+    use std::os::unix::net::UnixStream;
+
+    let (client, server) = UnixStream::pair().unwrap();
+    let echo = thread::spawn(move || {
+        let mut writer =
+            FrameWriter::new(server.try_clone().unwrap(), SerializationFormat::Binary).unwrap();
+        for request in FrameReader::new(server) {
+            let mut reply = request.unwrap().copy(true);
+            reply.swap_header();
+            reply.set_message_type("reply");
+            writer.write_container(&reply).unwrap();
+        }
+    });
+
+    let mut writer = FrameWriter::new(
+        client.try_clone().unwrap(),
+        SerializationFormat::WireProtocol,
+    )
+    .unwrap();
+    let mut replies = FrameReader::new(client.try_clone().unwrap());
+    for id in 0..5 {
+        writer.write_container(&message(id)).unwrap();
+        let reply = replies.read_container().unwrap().unwrap();
+        assert_eq!(reply.message_type(), "reply");
+        assert_eq!(reply.target_id(), "producer");
+        assert_eq!(reply.get_value("id").unwrap().to_int().unwrap(), id);
+    }
+    client.shutdown(std::net::Shutdown::Write).unwrap();
+    assert!(replies.read_container().unwrap().is_none());
+    echo.join().unwrap();
+}
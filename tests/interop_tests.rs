@@ -325,3 +325,44 @@ fn test_special_characters_in_strings() {
     );
     assert_eq!(restored.get_value("expr").unwrap().to_string(), "a=b+c");
 }
+
+#[test]
+fn test_nested_roundtrip_has_no_diff() {
+    let items: Vec<Arc<dyn Value>> = (0..3)
+        .map(|i| -> Arc<dyn Value> {
+            Arc::new(ContainerValue::new(
+                "",
+                vec![
+                    Arc::new(StringValue::new("sku", format!("item-{}", i))),
+                    Arc::new(IntValue::new("qty", i * 2)),
+                ],
+            ))
+        })
+        .collect();
+
+    let mut container = ValueContainer::new();
+    container.set_source("rust_client", "diff");
+    container.set_message_type("order_sync");
+    container
+        .add_value(Arc::new(ContainerValue::new(
+            "order",
+            vec![
+                Arc::new(ArrayValue::new("items", items)),
+                Arc::new(DoubleValue::new("total", 12.5)),
+            ],
+        )))
+        .unwrap();
+    container
+        .add_value(Arc::new(StringValue::new("tag", "first")))
+        .unwrap();
+    container
+        .add_value(Arc::new(StringValue::new("tag", "second")))
+        .unwrap();
+
+    let wire_data = container.serialize_cpp_wire().unwrap();
+    let restored = ValueContainer::deserialize_cpp_wire(&wire_data).unwrap();
+
+    // The report names every value that did not survive the round trip
+    let changes = container.diff(&restored);
+    assert!(changes.is_empty(), "wire round trip changed:\n{}", changes);
+}